@@ -0,0 +1,126 @@
+//! Compares the cost of the `TryFutureExt` context combinators.
+//!
+//! Run with `cargo bench --features futures --bench futures_context`.
+//! Each form is measured when the future is dropped without being
+//! polled and when it is polled to an error.
+
+use snafu::{futures::TryFutureExt as _, prelude::*};
+use std::{
+    future::{self, Future},
+    hint::black_box,
+    pin::Pin,
+    ptr,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+    time::Instant,
+};
+
+const ITERATIONS: u32 = 1_000_000;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Loading { snapshot: String, source: ApiError },
+}
+
+#[derive(Debug, Snafu)]
+struct ApiError;
+
+fn failing() -> impl Future<Output = Result<i32, ApiError>> {
+    future::ready(Err(ApiError))
+}
+
+fn snapshot() -> String {
+    black_box("a large snapshot of the current state").to_string()
+}
+
+fn noop_waker() -> Waker {
+    fn raw() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            raw()
+        }
+        fn noop(_: *const ()) {}
+
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(ptr::null(), &VTABLE)
+    }
+
+    unsafe { Waker::from_raw(raw()) }
+}
+
+fn poll_once<F: Future>(fut: F, waker: &Waker) -> F::Output {
+    let mut ctx = Context::from_waker(waker);
+    let mut fut = fut;
+    // SAFETY: `fut` is not moved again before it is dropped.
+    let fut = unsafe { Pin::new_unchecked(&mut fut) };
+
+    match fut.poll(&mut ctx) {
+        Poll::Ready(v) => v,
+        Poll::Pending => unreachable!("The future is always ready"),
+    }
+}
+
+fn measure(name: &str, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<40} {:>8.1} ns/iter",
+        name,
+        elapsed.as_nanos() as f64 / f64::from(ITERATIONS),
+    );
+}
+
+fn main() {
+    let waker = noop_waker();
+    let borrowed = "a borrowed snapshot";
+
+    measure("context (borrowed field), unpolled", || {
+        drop(black_box(
+            failing().context(LoadingSnafu { snapshot: borrowed }),
+        ));
+    });
+    measure("context (owned field), unpolled", || {
+        let snapshot = snapshot();
+        drop(black_box(failing().context(LoadingSnafu { snapshot })));
+    });
+    measure("with_context, unpolled", || {
+        let fut = failing().with_context(|_| LoadingSnafu {
+            snapshot: snapshot(),
+        });
+        drop(black_box(fut));
+    });
+    measure("lazy_context, unpolled", || {
+        let fut = failing().lazy_context(|| LoadingSnafu {
+            snapshot: snapshot(),
+        });
+        drop(black_box(fut));
+    });
+
+    measure("context (borrowed field), error", || {
+        let fut = failing().context(LoadingSnafu { snapshot: borrowed });
+        let result: Result<i32, Error> = poll_once(fut, &waker);
+        drop(black_box(result));
+    });
+    measure("context (owned field), error", || {
+        let snapshot = snapshot();
+        let fut = failing().context(LoadingSnafu { snapshot });
+        let result: Result<i32, Error> = poll_once(fut, &waker);
+        drop(black_box(result));
+    });
+    measure("with_context, error", || {
+        let fut = failing().with_context(|_| LoadingSnafu {
+            snapshot: snapshot(),
+        });
+        let result: Result<i32, Error> = poll_once(fut, &waker);
+        drop(black_box(result));
+    });
+    measure("lazy_context, error", || {
+        let fut = failing().lazy_context(|| LoadingSnafu {
+            snapshot: snapshot(),
+        });
+        let result: Result<i32, Error> = poll_once(fut, &waker);
+        drop(black_box(result));
+    });
+}
@@ -0,0 +1,98 @@
+#![cfg(test)]
+
+// Both traits have `context` and `with_context` methods, so calling
+// them by those names on a `Result` or `Option` is ambiguous. The
+// SNAFU methods are called by their alternate names instead.
+use anyhow::Context;
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("could not read {path}"))]
+    Read {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("no user with id {id}"))]
+    MissingUser { id: u32 },
+}
+
+fn io_failure() -> Result<(), std::io::Error> {
+    Err(std::io::Error::new(std::io::ErrorKind::Other, "disk gone"))
+}
+
+#[test]
+fn result_aliases_can_be_used_alongside_anyhow() {
+    let error = io_failure()
+        .snafu_context(ReadSnafu { path: "a.txt" })
+        .unwrap_err();
+    assert_eq!(error.to_string(), "could not read a.txt");
+
+    let error = io_failure()
+        .with_snafu_context(|_| ReadSnafu { path: "b.txt" })
+        .unwrap_err();
+    assert_eq!(error.to_string(), "could not read b.txt");
+
+    let error = Context::context(io_failure(), "anyhow context").unwrap_err();
+    assert_eq!(error.to_string(), "anyhow context");
+
+    let error = Context::with_context(io_failure(), || "lazy anyhow context").unwrap_err();
+    assert_eq!(error.to_string(), "lazy anyhow context");
+}
+
+#[test]
+fn option_aliases_can_be_used_alongside_anyhow() {
+    let error = None::<i32>
+        .snafu_context(MissingUserSnafu { id: 1u32 })
+        .unwrap_err();
+    assert_eq!(error.to_string(), "no user with id 1");
+
+    let error = None::<i32>
+        .with_snafu_context(|| MissingUserSnafu { id: 2u32 })
+        .unwrap_err();
+    assert_eq!(error.to_string(), "no user with id 2");
+
+    let error = Context::context(None::<i32>, "anyhow context").unwrap_err();
+    assert_eq!(error.to_string(), "anyhow context");
+}
+
+mod futures_aliases {
+    use super::*;
+    use futures::{executor::block_on, future, stream, TryStreamExt as _};
+
+    #[test]
+    fn future_aliases_add_context() {
+        let error =
+            block_on(future::ready(io_failure()).snafu_context(ReadSnafu { path: "d.txt" }))
+                .unwrap_err();
+        assert_eq!(error.to_string(), "could not read d.txt");
+
+        let error = block_on(
+            future::ready(io_failure()).with_snafu_context(|_| ReadSnafu { path: "e.txt" }),
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "could not read e.txt");
+    }
+
+    #[test]
+    fn stream_aliases_add_context() {
+        let failing = || stream::iter(vec![io_failure()]);
+
+        let error = block_on(
+            failing()
+                .snafu_context(ReadSnafu { path: "f.txt" })
+                .try_collect::<Vec<()>>(),
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "could not read f.txt");
+
+        let error = block_on(
+            failing()
+                .with_snafu_context(|_| ReadSnafu { path: "g.txt" })
+                .try_collect::<Vec<()>>(),
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "could not read g.txt");
+    }
+}
@@ -33,3 +33,27 @@ fn backtrace_contains_function_names() {
         .map(|n| n.to_string());
     assert!(names.any(|n| n.contains("::example::")));
 }
+
+#[test]
+fn backtrace_can_be_displayed() {
+    let error = example().unwrap_err();
+    let backtrace = ErrorCompat::backtrace(&error).unwrap();
+    let text = snafu::DisplayBacktrace(backtrace).to_string();
+    assert!(
+        text.contains("backtraces_impl_backtrace_crate::example"),
+        "{}",
+        text
+    );
+}
+
+#[test]
+fn frames_are_bridged_to_the_crate_type() {
+    let error = example().unwrap_err();
+    let backtrace = ErrorCompat::backtrace(&error).unwrap();
+
+    let frames = snafu::backtrace::frames(backtrace).unwrap();
+    assert_eq!(frames.len(), backtrace.frames().len());
+
+    let converted = snafu::backtrace::to_backtrace_crate(backtrace).unwrap();
+    assert_eq!(converted.frames().len(), frames.len());
+}
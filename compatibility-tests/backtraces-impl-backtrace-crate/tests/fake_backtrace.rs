@@ -0,0 +1,22 @@
+use snafu::{
+    env::{self, BacktracePolicy, FAKE_BACKTRACE},
+    prelude::*,
+    Backtrace, DisplayBacktrace, ErrorCompat,
+};
+
+#[derive(Debug, Snafu)]
+struct Error {
+    backtrace: Backtrace,
+}
+
+// The policy is global, so this is the only test in this binary.
+#[test]
+fn fake_backtraces_have_no_frames() {
+    env::set_backtrace_policy(BacktracePolicy::Fake);
+
+    let error = Snafu.build();
+    let backtrace = ErrorCompat::backtrace(&error).unwrap();
+
+    assert!(backtrace.frames().is_empty());
+    assert_eq!(DisplayBacktrace(backtrace).to_string(), FAKE_BACKTRACE);
+}
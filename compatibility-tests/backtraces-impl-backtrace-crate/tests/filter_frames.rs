@@ -0,0 +1,75 @@
+use snafu::{
+    backtrace::{filter_frames, in_crate},
+    prelude::*,
+    Backtrace, ErrorCompat,
+};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    WithBacktrace {
+        backtrace: Backtrace,
+    },
+
+    WithSource {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+}
+
+fn example() -> Result<(), Error> {
+    WithBacktraceSnafu.fail()
+}
+
+fn example_with_source() -> Result<(), Error> {
+    let source = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+    Err(source).context(WithSourceSnafu)
+}
+
+fn symbol_names(frames: &[&backtrace::BacktraceFrame]) -> Vec<String> {
+    frames
+        .iter()
+        .flat_map(|f| f.symbols())
+        .flat_map(|s| s.name())
+        .map(|n| n.to_string())
+        .collect()
+}
+
+#[test]
+fn excluded_frames_are_not_displayed() {
+    let error = example().unwrap_err();
+    let backtrace = ErrorCompat::backtrace(&error).unwrap();
+
+    let unfiltered = filter_frames(backtrace, |_| false).to_string();
+    assert!(unfiltered.contains("snafu::"), "{}", unfiltered);
+
+    let filtered = filter_frames(backtrace, in_crate("snafu"));
+    let text = filtered.to_string();
+    assert!(!text.contains("snafu::"), "{}", text);
+    assert!(text.contains("filter_frames::example"), "{}", text);
+}
+
+#[test]
+fn frames_from_snafu_traits_are_excluded() {
+    let error = example_with_source().unwrap_err();
+    let backtrace = ErrorCompat::backtrace(&error).unwrap();
+
+    let all: Vec<_> = backtrace.frames().iter().collect();
+    let names = symbol_names(&all);
+    assert!(names.iter().any(|n| n.contains(" as snafu::IntoError<")));
+    assert!(names.iter().any(|n| n.contains(" as snafu::ResultExt<")));
+
+    let filtered = filter_frames(backtrace, in_crate("snafu"));
+    let kept: Vec<_> = filtered.frames().collect();
+    assert!(kept.len() < all.len());
+    assert!(symbol_names(&kept).iter().all(|n| !n.contains("snafu::")));
+}
+
+#[test]
+fn crate_names_must_match_a_whole_path_segment() {
+    let backtrace = backtrace::Backtrace::new();
+
+    let kept = filter_frames(&backtrace, in_crate("filter"))
+        .frames()
+        .count();
+    assert_eq!(kept, backtrace.frames().len());
+}
@@ -0,0 +1,28 @@
+use snafu::{prelude::*, Backtrace};
+
+#[derive(Debug, Snafu)]
+enum EnumError {
+    #[snafu(absorb_whatever)]
+    NotWhatever { message: String },
+
+    #[snafu(whatever, absorb_whatever, display("{message}"))]
+    NoSource {
+        message: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(whatever, absorb_whatever, display("{message}"))]
+    NoBacktrace {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(whatever, absorb_whatever, display("{message}"))]
+struct StructError {
+    message: String,
+}
+
+fn main() {}
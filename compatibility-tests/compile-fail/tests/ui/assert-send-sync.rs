@@ -0,0 +1,36 @@
+use snafu::prelude::*;
+use std::{cell::Cell, rc::Rc};
+
+#[derive(Debug, Snafu)]
+#[snafu(assert_send_sync)]
+enum EnumError {
+    Alpha { counter: Rc<u32> },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(assert_send_sync)]
+struct StructError {
+    seen: Cell<bool>,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(assert_send_sync)]
+struct GenericError<T> {
+    value: T,
+    counter: Rc<u32>,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(assert_send_sync, assert_send_sync)]
+enum DuplicateError {
+    #[snafu(assert_send_sync)]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+struct FieldError {
+    #[snafu(assert_send_sync)]
+    value: i32,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum ContextFieldError {
+    #[snafu(auto_from)]
+    Alpha {
+        name: String,
+        source: std::io::Error,
+    },
+}
+
+#[derive(Debug, Snafu)]
+enum NoSourceError {
+    #[snafu(auto_from)]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum ContextFalseError {
+    #[snafu(auto_from, context(false))]
+    Alpha { source: std::io::Error },
+}
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    Alpha {
+        #[snafu(auto_from)]
+        source: std::io::Error,
+    },
+}
+
+fn main() {}
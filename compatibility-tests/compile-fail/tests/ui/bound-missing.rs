@@ -0,0 +1,14 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error<S>
+where
+    S: std::error::Error + 'static,
+{
+    Wrap {
+        #[snafu(backtrace)]
+        source: S,
+    },
+}
+
+fn main() {}
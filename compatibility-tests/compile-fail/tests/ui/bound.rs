@@ -0,0 +1,25 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum EnumError {
+    #[snafu(bound(String: Clone))]
+    Alpha {
+        #[snafu(bound(String: Clone))]
+        value: String,
+    },
+}
+
+#[derive(Debug, Snafu)]
+struct NamedError {
+    #[snafu(bound(String: Clone))]
+    value: String,
+}
+
+#[derive(Debug, Snafu)]
+struct TupleError(#[snafu(bound(String: Clone))] std::io::Error);
+
+#[derive(Debug, Snafu)]
+#[snafu(bound(not a bound))]
+struct UnparseableError;
+
+fn main() {}
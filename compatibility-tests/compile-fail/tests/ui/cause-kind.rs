@@ -0,0 +1,23 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(cause_kind = "transient")]
+enum EnumError {
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum VariantError {
+    #[snafu(cause_kind = "sometimes")]
+    Beta,
+}
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    Gamma {
+        #[snafu(cause_kind = "permanent")]
+        value: i32,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(compat_std_error, compat_std_error)]
+enum EnumError {
+    #[snafu(compat_std_error)]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+struct FieldError {
+    #[snafu(compat_std_error)]
+    value: i32,
+}
+
+#[derive(Debug, Snafu)]
+struct TupleError(#[snafu(compat_std_error)] EnumError);
+
+fn main() {}
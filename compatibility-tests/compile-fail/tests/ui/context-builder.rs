@@ -0,0 +1,37 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(context(builder), context(false))]
+    Beta { source: std::io::Error },
+
+    #[snafu(whatever, context(builder), display("{message}"))]
+    Gamma { message: String },
+
+    Delta {
+        #[snafu(context(builder))]
+        id: i32,
+    },
+
+    #[snafu(context(builder))]
+    Epsilon,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(context(builder))]
+struct TupleError(Error);
+
+mod opt_in {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        Alpha { id: i32 },
+    }
+
+    fn example() {
+        let _ = AlphaSnafu::<i32>::builder();
+    }
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(context(derive(Deserialize)), context(false))]
+    Beta { source: std::io::Error },
+
+    #[snafu(whatever, context(derive(Deserialize)), display("{message}"))]
+    Gamma { message: String },
+
+    Delta {
+        #[snafu(context(derive(Deserialize)))]
+        id: i32,
+    },
+
+    #[snafu(context(derive(Serialize)))]
+    Epsilon,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(context(derive(Deserialize)))]
+struct TupleError(Error);
+
+fn main() {}
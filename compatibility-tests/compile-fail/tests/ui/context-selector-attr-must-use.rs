@@ -0,0 +1,22 @@
+#![deny(unused_must_use)]
+
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(context_selector_attr(must_use = "handle this error"))]
+    Alpha { id: i32 },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(context_selector_attr(must_use))]
+struct StructError {
+    id: i32,
+}
+
+fn unused_selectors() {
+    AlphaSnafu { id: 42 };
+    StructSnafu { id: 42 };
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(context_selector_attr(must_use), context(false))]
+    Beta { source: std::io::Error },
+
+    #[snafu(whatever, context_selector_attr(must_use), display("{message}"))]
+    Gamma { message: String },
+
+    Delta {
+        #[snafu(context_selector_attr(must_use))]
+        id: i32,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(context_selector_attr(must_use))]
+struct TupleError(Error);
+
+fn main() {}
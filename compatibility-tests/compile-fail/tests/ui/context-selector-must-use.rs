@@ -0,0 +1,32 @@
+#![deny(unused_must_use)]
+#![allow(path_statements)]
+
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Alpha { id: i32 },
+
+    Beta,
+
+    Gamma { source: std::io::Error },
+}
+
+#[derive(Debug, Snafu)]
+struct StructError {
+    id: i32,
+}
+
+fn unused_selectors() {
+    AlphaSnafu { id: 42 };
+    BetaSnafu;
+    GammaSnafu;
+    StructSnafu { id: 42 };
+}
+
+fn consumed_selectors() -> Result<(), Error> {
+    let _ = AlphaSnafu { id: 42 };
+    BetaSnafu.fail()
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+#[snafu::defaults(module)]
+fn not_a_module() {}
+
+#[snafu::defaults(not_an_attribute)]
+mod unknown_attribute {}
+
+#[snafu::defaults(source(from(String, Into::into)))]
+mod invalid_on_enums {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        Alpha,
+    }
+}
+
+#[snafu::defaults(module)]
+mod duplicated {
+    #[snafu::defaults(module, module)]
+    mod nested {
+        use snafu::prelude::*;
+
+        #[derive(Debug, Snafu)]
+        enum Error {
+            Alpha,
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+extern crate snafu;
+
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[repr(i32)]
+enum Error {
+    NotFound = -2,
+
+    Busy { retries: u8 } = -3,
+
+    Invalid(String) = -4,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(display_context)]
+enum EnumError {
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    Alpha {
+        #[snafu(display_context)]
+        name: String,
+    },
+}
+
+#[derive(Debug, Snafu)]
+enum TransparentError {
+    #[snafu(transparent, display_context)]
+    Alpha { source: std::io::Error },
+}
+
+#[derive(Debug, Snafu)]
+enum DuplicateError {
+    #[snafu(display_context, display_context)]
+    Alpha,
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+use snafu::prelude::*;
+
+const PREFIX: &str = "prefix: ";
+
+#[derive(Debug, Snafu)]
+#[snafu(display_inherit(parent = PREFIX))]
+enum EnumError {
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    Alpha {
+        #[snafu(display_inherit(parent = PREFIX))]
+        name: String,
+    },
+}
+
+#[derive(Debug, Snafu)]
+enum TransparentError {
+    #[snafu(transparent, display_inherit(parent = PREFIX))]
+    Alpha { source: std::io::Error },
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+use snafu::{prelude::*, Location};
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    Alpha {
+        #[snafu(display_location)]
+        #[snafu(implicit)]
+        location: Location,
+    },
+}
+
+#[derive(Debug, Snafu)]
+enum MissingLocationError {
+    #[snafu(display_location)]
+    Alpha { name: String },
+}
+
+#[derive(Debug, Snafu)]
+enum TransparentError {
+    #[snafu(transparent, display_location)]
+    Alpha {
+        source: std::io::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display_location)]
+struct MissingStructLocationError {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("Failed: {source}"), display(no_source))]
+    Shorthand { source: std::io::Error },
+
+    /// Failed: {source}
+    #[snafu(display(no_source))]
+    DocComment { source: std::io::Error },
+
+    #[snafu(transparent, display(no_source))]
+    Transparent { source: std::fmt::Error },
+}
+
+#[derive(Debug, Snafu)]
+struct TupleStruct(#[snafu(display(no_source))] std::io::Error);
+
+fn main() {}
@@ -0,0 +1,37 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(display_prefix(false))]
+enum EnumFlagError {
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum VariantPrefixError {
+    #[snafu(display_prefix = "prefix: ")]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    Alpha {
+        #[snafu(display_prefix(false))]
+        name: String,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display_prefix = "prefix: ")]
+struct StructError;
+
+#[derive(Debug, Snafu)]
+#[snafu(display_prefix(false))]
+struct StructFlagError;
+
+#[derive(Debug, Snafu)]
+#[snafu(display_prefix = "one: ", display_prefix = "two: ")]
+enum DuplicateError {
+    Alpha,
+}
+
+fn main() {}
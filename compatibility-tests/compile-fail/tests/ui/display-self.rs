@@ -0,0 +1,35 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Positional {
+    #[snafu(display("{}", self))]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum Inline {
+    #[snafu(display("failed: {self}"))]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum Named {
+    #[snafu(display("failed: {me:>10}", me = &self))]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum Indexed {
+    #[snafu(display("{0:?} {0}", *self))]
+    Alpha,
+}
+
+const MSG: &str = "failed: {me}";
+
+#[derive(Debug, Snafu)]
+enum Template {
+    #[snafu(display(template = MSG, me = self))]
+    Alpha,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use snafu::prelude::*;
+
+const MESSAGE: &str = "Could not open {path}";
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display(template = MESSAGE, "{path}"))]
+    FormatString { path: String },
+
+    #[snafu(display(template = MESSAGE, path.len()))]
+    Positional { path: String },
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+use snafu::prelude::*;
+use std::time::Duration;
+
+fn by_value(d: Duration) -> u64 {
+    d.as_secs()
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("timed out after {timeout}"))]
+    Timeout {
+        #[snafu(display(with = by_value))]
+        timeout: Duration,
+    },
+}
+
+#[derive(Debug, Snafu)]
+enum FormatError {
+    Alpha {
+        #[snafu(display("not a function"))]
+        name: String,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use snafu::prelude::*;
+
+fn describe(f: &mut std::fmt::Formatter, id: &u32) -> std::fmt::Result {
+    write!(f, "{id}")
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display(with = describe, "{id}"))]
+    WithFormat { id: u32 },
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use snafu::prelude::*;
+use std::fmt;
+
+fn swapped(f: &mut fmt::Formatter, name: &String, id: &u32) -> fmt::Result {
+    write!(f, "{name} {id}")
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display(with = swapped))]
+    WrongOrder { id: u32, name: String },
+}
+
+fn main() {}
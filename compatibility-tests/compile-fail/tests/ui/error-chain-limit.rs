@@ -0,0 +1,39 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(error_chain_limit = 3)]
+enum EnumError {
+    #[snafu(error_chain_limit = 3)]
+    NotWhatever { source: std::io::Error },
+
+    #[snafu(whatever, error_chain_limit = 3, display("{message}"))]
+    NoSource { message: String },
+
+    #[snafu(whatever, error_chain_limit = 0, display("{message}"))]
+    Zero {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+    },
+
+    #[snafu(whatever, error_chain_limit = 1, error_chain_limit = 2, display("{message}"))]
+    Duplicate {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+    },
+}
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    Gamma {
+        #[snafu(error_chain_limit = 3)]
+        value: i32,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(error_chain_limit = 3)]
+struct TupleError(EnumError);
+
+fn main() {}
@@ -0,0 +1,22 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(error_description = "enum")]
+enum EnumError {
+    #[snafu(error_description = "one", error_description = "two")]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    Gamma {
+        #[snafu(error_description = "field")]
+        value: i32,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(error_description = "tuple")]
+struct TupleError(EnumError);
+
+fn main() {}
@@ -0,0 +1,27 @@
+mod errors {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = crate::errors::impls::forgotten)]
+    pub enum ForgottenError {
+        Alpha,
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = crate::errors::impls::misplaced)]
+    pub enum MisplacedError {
+        Beta,
+    }
+
+    pub mod impls {
+        pub mod forgotten {}
+
+        pub mod misplaced {}
+
+        pub mod elsewhere {
+            crate::errors::misplaced_error_impls!(crate::errors);
+        }
+    }
+}
+
+fn main() {}
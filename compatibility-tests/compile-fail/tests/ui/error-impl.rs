@@ -0,0 +1,11 @@
+extern crate snafu;
+
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum OtherError {
+    #[snafu(error_impl = impls)]
+    Beta,
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum MissingError {
+    #[snafu(ffi_code(1))]
+    Alpha,
+
+    Beta,
+
+    #[snafu(ffi_code(-1))]
+    Gamma,
+
+    Delta,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(ffi_code(1))]
+enum EnumError {
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    #[snafu(ffi_code(1))]
+    Alpha {
+        #[snafu(ffi_code(2))]
+        value: i32,
+    },
+}
+
+#[derive(Debug, Snafu)]
+enum WrongTypeError {
+    #[snafu(ffi_code(404u16))]
+    Alpha,
+}
+
+fn main() {}
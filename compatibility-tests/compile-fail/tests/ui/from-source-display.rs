@@ -0,0 +1,23 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    WithoutSource {
+        #[snafu(from_source_display)]
+        message: String,
+    },
+
+    #[snafu(whatever, display("{message}"))]
+    Whatever {
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        message: String,
+        #[snafu(from_source_display)]
+        snapshot: String,
+    },
+
+    #[snafu(from_source_display)]
+    OnVariant { source: std::io::Error },
+}
+
+fn main() {}
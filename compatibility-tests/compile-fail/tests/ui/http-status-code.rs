@@ -0,0 +1,9 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(http_status(1000))]
+    Alpha,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(http_status(NOT_A_STATUS))]
+    Alpha,
+}
+
+fn main() {}
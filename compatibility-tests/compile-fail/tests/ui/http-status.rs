@@ -0,0 +1,31 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum MissingError {
+    #[snafu(http_status(NOT_FOUND))]
+    Alpha,
+
+    Beta,
+
+    #[snafu(http_status(500))]
+    Gamma,
+
+    Delta,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(http_status(BAD_REQUEST))]
+enum EnumError {
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    #[snafu(http_status(BAD_REQUEST))]
+    Alpha {
+        #[snafu(http_status(NOT_FOUND))]
+        value: i32,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Alpha {
+        #[snafu(ignore_field)]
+        value: i32,
+    },
+}
+
+fn main() {
+    let _ = AlphaSnafu { value: 1 };
+}
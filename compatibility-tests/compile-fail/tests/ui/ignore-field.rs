@@ -0,0 +1,23 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(ignore_field)]
+enum EnumError {
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum VariantError {
+    #[snafu(ignore_field)]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum CombinedError {
+    Alpha {
+        #[snafu(ignore_field, implicit)]
+        value: i32,
+    },
+}
+
+fn main() {}
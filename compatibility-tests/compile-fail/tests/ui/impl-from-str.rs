@@ -0,0 +1,71 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(impl_from_str)]
+enum SourceError {
+    #[snafu(display("could not read"))]
+    Read { source: std::io::Error },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(impl_from_str)]
+enum MissingFieldError {
+    #[snafu(display("user was not found"))]
+    UserNotFound { id: u32 },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(impl_from_str)]
+enum ArgumentsError {
+    #[snafu(display("user {} was not found", id))]
+    UserNotFound { id: u32 },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(impl_from_str)]
+enum FormatSpecError {
+    #[snafu(display("user {id:>5} was not found"))]
+    UserNotFound { id: u32 },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(impl_from_str)]
+enum AdjacentError {
+    #[snafu(display("{first}{second}"))]
+    Pair { first: u32, second: u32 },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(impl_from_str)]
+enum UnknownNameError {
+    #[snafu(display("{id} is not {SOMETHING}"))]
+    Unknown { id: u32 },
+}
+
+const SOMETHING: &str = "something";
+
+#[derive(Debug, Snafu)]
+enum ParseWithoutImplError {
+    #[snafu(parse_with = parse)]
+    Alpha,
+}
+
+fn parse(_: &str) -> Option<ParseWithoutImplError> {
+    None
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(impl_from_str, impl_from_str, parse_with = parse)]
+enum MisplacedError {
+    #[snafu(impl_from_str)]
+    Alpha {
+        #[snafu(parse_with = parse)]
+        value: i32,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(impl_from_str, parse_with = parse)]
+struct TupleError(std::io::Error);
+
+fn main() {}
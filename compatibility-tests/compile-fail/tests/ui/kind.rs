@@ -0,0 +1,23 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(kind = "network")]
+enum EnumError {
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum VariantError {
+    #[snafu(kind = "database")]
+    Beta,
+}
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    Gamma {
+        #[snafu(kind = "io")]
+        value: i32,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,46 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum CodeWithoutKindsError {
+    #[snafu(code = "alpha")]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(kinds)]
+enum DuplicateCodeError {
+    #[snafu(code = "Beta")]
+    Alpha,
+
+    Beta,
+
+    #[snafu(code = "Beta")]
+    Gamma,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(kinds)]
+enum KindConflictError {
+    #[snafu(kind = "network")]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(kinds, kinds, code = "outer")]
+enum MisplacedError {
+    #[snafu(kinds, code = "alpha", code = "again")]
+    Alpha {
+        #[snafu(kinds, code = "field")]
+        value: i32,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(kinds, code = "named")]
+struct NamedError;
+
+#[derive(Debug, Snafu)]
+#[snafu(kinds, code = "tuple")]
+struct TupleError(std::io::Error);
+
+fn main() {}
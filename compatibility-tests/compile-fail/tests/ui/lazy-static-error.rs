@@ -0,0 +1,13 @@
+use snafu::prelude::*;
+use std::rc::Rc;
+
+#[derive(Debug, Snafu)]
+struct NotSyncError {
+    value: Rc<u8>,
+}
+
+snafu::lazy_static_error! {
+    static NOT_SYNC: NotSyncError = NotSyncError { value: Rc::new(1) };
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(opaque(transparent_source))]
+enum Error {
+    #[snafu(opaque(transparent_source))]
+    Alpha {
+        #[snafu(opaque(transparent_source))]
+        value: i32,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(opaque(transparent_source), opaque(transparent_source))]
+struct TupleError(#[snafu(opaque(transparent_source))] Error);
+
+fn main() {}
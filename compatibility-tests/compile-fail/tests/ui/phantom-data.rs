@@ -0,0 +1,24 @@
+use snafu::prelude::*;
+use std::marker::PhantomData;
+
+#[derive(Debug, Snafu)]
+#[snafu(phantom_data)]
+enum EnumError {
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum VariantError {
+    #[snafu(phantom_data)]
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum CombinedError<T> {
+    Alpha {
+        #[snafu(phantom_data, ignore_field)]
+        marker: PhantomData<T>,
+    },
+}
+
+fn main() {}
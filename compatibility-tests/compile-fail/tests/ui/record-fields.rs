@@ -0,0 +1,17 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(record_fields, record_fields)]
+enum Error {
+    #[snafu(record_fields)]
+    Alpha {
+        #[snafu(record_fields)]
+        value: i32,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(record_fields)]
+struct TupleError(Error);
+
+fn main() {}
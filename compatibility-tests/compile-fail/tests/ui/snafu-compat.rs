@@ -0,0 +1,18 @@
+use snafu::{Backtrace, SnafuCompat};
+
+#[derive(Debug, SnafuCompat)]
+#[snafu(display("not generated"))]
+enum Error {
+    Alpha {
+        #[snafu(source)]
+        cause: std::io::Error,
+    },
+
+    Beta {
+        backtrace: Backtrace,
+        #[snafu(backtrace)]
+        other: Backtrace,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(source(accessor))]
+enum Error {
+    NoFrom {
+        #[snafu(source(accessor))]
+        source: std::io::Error,
+    },
+
+    Arc {
+        #[snafu(source(arc, accessor))]
+        source: std::sync::Arc<std::io::Error>,
+    },
+
+    NotSource {
+        #[snafu(source(accessor))]
+        value: i32,
+    },
+
+    Duplicate {
+        #[snafu(source(from(std::io::Error, Box::new), accessor, accessor))]
+        source: Box<std::io::Error>,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(source(accessor))]
+struct TupleError(Error);
+
+fn main() {}
@@ -0,0 +1,16 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    NotAnArc {
+        #[snafu(source(arc))]
+        source: Box<std::io::Error>,
+    },
+
+    OptedOut {
+        #[snafu(source(arc, false))]
+        source: std::sync::Arc<std::io::Error>,
+    },
+}
+
+fn main() {}
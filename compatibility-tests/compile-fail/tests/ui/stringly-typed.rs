@@ -33,15 +33,4 @@ mod double_message {
     }
 }
 
-mod with_context_fields {
-    use snafu::prelude::*;
-
-    #[derive(Debug, Snafu)]
-    #[snafu(whatever)]
-    struct Error {
-        message: String,
-        user_id: i32,
-    }
-}
-
 fn main() {}
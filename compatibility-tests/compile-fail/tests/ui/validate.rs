@@ -0,0 +1,23 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(validate(field = name, check = |_: &String| true))]
+enum EnumError {
+    Alpha { name: String },
+}
+
+#[derive(Debug, Snafu)]
+enum UnknownFieldError {
+    #[snafu(validate(field = nmae, check = |_: &String| true))]
+    Alpha { name: String },
+}
+
+#[derive(Debug, Snafu)]
+enum FieldError {
+    Alpha {
+        #[snafu(validate(field = name, check = |_: &String| true))]
+        name: String,
+    },
+}
+
+fn main() {}
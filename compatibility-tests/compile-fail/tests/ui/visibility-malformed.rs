@@ -0,0 +1,15 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(visibility(foo))]
+    NotAVisibility,
+
+    #[snafu(visibility("pub"))]
+    StringVisibility,
+
+    #[snafu(visibility(pub(in)))]
+    MissingPath,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(default = 1)]
+enum EnumError {
+    #[snafu(default = 1)]
+    Alpha {
+        #[snafu(default = 1)]
+        value: i32,
+    },
+
+    #[snafu(whatever, display("{message}"))]
+    Whatever {
+        message: String,
+        #[snafu(default = 1, default = 2)]
+        code: i32,
+        #[snafu(default = None, source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+    },
+}
+
+#[derive(Debug, Snafu)]
+struct ContextError {
+    #[snafu(default = 1)]
+    value: i32,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(default = 1)]
+struct TupleError(#[snafu(default = 1)] std::io::Error);
+
+fn main() {}
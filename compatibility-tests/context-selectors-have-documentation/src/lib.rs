@@ -11,3 +11,22 @@ pub enum Error {
     /// Variant docs
     Variant,
 }
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub), context(builder))]
+/// Enum docs
+pub enum BuilderError {
+    /// Variant docs
+    WithSource {
+        /// Field docs
+        id: i32,
+        /// Field docs
+        source: std::io::Error,
+    },
+
+    /// Variant docs
+    Leaf {
+        /// Field docs
+        id: i32,
+    },
+}
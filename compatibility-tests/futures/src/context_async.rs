@@ -0,0 +1,110 @@
+// Uses `TryFutureExt::context_async` with a context store that takes
+// several polls to answer.
+use futures::executor::block_on;
+use snafu::{futures::TryFutureExt as _, prelude::*};
+use std::{
+    cell::Cell,
+    future::{self, Future},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Loading { request_id: u64, source: ApiError },
+}
+
+#[derive(Debug, Snafu)]
+struct ApiError;
+
+/// Stands in for an asynchronous store of request-scoped data.
+struct ContextStore {
+    request_id: u64,
+    lookups: Cell<usize>,
+    polls: Cell<usize>,
+}
+
+impl ContextStore {
+    fn new(request_id: u64) -> Self {
+        ContextStore {
+            request_id,
+            lookups: Cell::new(0),
+            polls: Cell::new(0),
+        }
+    }
+
+    fn request_id(&self) -> RequestId<'_> {
+        self.lookups.set(self.lookups.get() + 1);
+        RequestId {
+            store: self,
+            remaining: 2,
+        }
+    }
+}
+
+/// Resolves after being polled a few times.
+struct RequestId<'a> {
+    store: &'a ContextStore,
+    remaining: usize,
+}
+
+impl Future for RequestId<'_> {
+    type Output = u64;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<u64> {
+        self.store.polls.set(self.store.polls.get() + 1);
+
+        if self.remaining == 0 {
+            Poll::Ready(self.store.request_id)
+        } else {
+            self.remaining -= 1;
+            ctx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn the_context_future_is_awaited_on_error() {
+    let store = ContextStore::new(42);
+
+    let fut = future::ready(Err::<i32, _>(ApiError)).context_async(|| async {
+        LoadingSnafu {
+            request_id: store.request_id().await,
+        }
+    });
+    let error = block_on(fut).unwrap_err();
+
+    assert!(matches!(error, Error::Loading { request_id: 42, .. }));
+    assert_eq!(store.lookups.get(), 1);
+    assert_eq!(store.polls.get(), 3);
+}
+
+#[test]
+fn the_context_future_is_not_created_on_success() {
+    let store = ContextStore::new(42);
+
+    let fut = future::ready(Ok::<_, ApiError>(7)).context_async(|| async {
+        LoadingSnafu {
+            request_id: store.request_id().await,
+        }
+    });
+    let value: Result<i32, Error> = block_on(fut);
+
+    assert_eq!(value.unwrap(), 7);
+    assert_eq!(store.lookups.get(), 0);
+    assert_eq!(store.polls.get(), 0);
+}
+
+#[test]
+fn the_context_future_is_not_created_before_polling() {
+    let store = ContextStore::new(42);
+
+    let fut = future::ready(Err::<i32, _>(ApiError)).context_async(|| {
+        store.lookups.set(store.lookups.get() + 1);
+        future::ready(LoadingSnafu { request_id: 1u64 })
+    });
+    drop::<snafu::futures::try_future::ContextFutureAsync<_, _, _, Error>>(fut);
+
+    assert_eq!(store.lookups.get(), 0);
+}
@@ -0,0 +1,101 @@
+// Checks the work done by each `TryFutureExt` context combinator
+// before the future is polled. See also the `futures_context`
+// benchmark of the `snafu` crate.
+
+use futures::executor::block_on;
+use snafu::{futures::TryFutureExt as _, prelude::*};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    future::{self, Future},
+};
+
+// Counts allocations per thread so that tests running in parallel
+// do not affect each other.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = Cell::new(0);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Loading { snapshot: String, source: ApiError },
+}
+
+#[derive(Debug, Snafu)]
+struct ApiError;
+
+fn failing() -> impl Future<Output = Result<i32, ApiError>> {
+    future::ready(Err(ApiError))
+}
+
+#[test]
+fn combinators_do_no_work_until_an_error_occurs() {
+    let snapshot = "a large snapshot of the current state";
+
+    let eager = allocations_during(|| {
+        let fut = failing().context(LoadingSnafu { snapshot });
+        drop(fut);
+    });
+    assert_eq!(eager, 0, "`context` allocated before being polled");
+
+    let with = allocations_during(|| {
+        let fut = failing().with_context(|_| LoadingSnafu {
+            snapshot: snapshot.to_string(),
+        });
+        drop(fut);
+    });
+    assert_eq!(with, 0, "`with_context` allocated before being polled");
+
+    let called = Cell::new(false);
+    let lazy = allocations_during(|| {
+        let fut = failing().lazy_context(|| {
+            called.set(true);
+            LoadingSnafu {
+                snapshot: snapshot.to_string(),
+            }
+        });
+        drop(fut);
+    });
+    assert_eq!(lazy, 0, "`lazy_context` allocated before being polled");
+    assert!(
+        !called.get(),
+        "`lazy_context` ran its closure before being polled"
+    );
+
+    let error = block_on(failing().lazy_context(|| LoadingSnafu { snapshot })).unwrap_err();
+    assert!(matches!(error, Error::Loading { snapshot: s, .. } if s == snapshot));
+}
+
+#[test]
+fn lazy_context_is_not_called_on_success() {
+    let called = Cell::new(false);
+    let value = block_on(future::ready(Ok::<_, ApiError>(42)).lazy_context(|| {
+        called.set(true);
+        LoadingSnafu { snapshot: "" }
+    }));
+
+    assert_eq!(value.unwrap(), 42);
+    assert!(!called.get());
+}
@@ -0,0 +1,97 @@
+// Uses `TryFutureExt::boxed_error` and `TryFutureExt::err_into` to
+// satisfy service-like signatures without another extension trait.
+
+use futures::executor::block_on;
+use snafu::{futures::TryFutureExt as _, prelude::*};
+use std::{
+    future::{self, Future},
+    pin::Pin,
+};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+type ResponseFuture = Pin<Box<dyn Future<Output = Result<String, BoxError>> + Send>>;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("could not look up user {user_id}"))]
+    Lookup { user_id: u64, source: DbError },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("the database is offline"))]
+struct DbError;
+
+#[derive(Debug, Snafu)]
+#[snafu(context(false), display("the request failed"))]
+struct RequestError {
+    source: Error,
+}
+
+/// Stands in for a `tower::Service`-style handler.
+struct UserService {
+    online: bool,
+}
+
+impl UserService {
+    fn lookup(&self, user_id: u64) -> impl Future<Output = Result<String, DbError>> {
+        future::ready(if self.online {
+            Ok(format!("user-{}", user_id))
+        } else {
+            Err(DbError)
+        })
+    }
+
+    fn call(&self, user_id: u64) -> ResponseFuture {
+        Box::pin(
+            self.lookup(user_id)
+                .context(LookupSnafu { user_id })
+                .boxed_error(),
+        )
+    }
+
+    fn call_typed(&self, user_id: u64) -> impl Future<Output = Result<String, RequestError>> {
+        self.lookup(user_id)
+            .context(LookupSnafu { user_id })
+            .err_into::<RequestError>()
+    }
+}
+
+#[test]
+fn boxed_error_passes_success_through() {
+    let service = UserService { online: true };
+
+    assert_eq!(block_on(service.call(7)).unwrap(), "user-7");
+}
+
+#[test]
+fn boxed_error_keeps_the_error_and_its_source() {
+    let service = UserService { online: false };
+
+    let error = block_on(service.call(7)).unwrap_err();
+    assert_eq!(error.to_string(), "could not look up user 7");
+
+    let error = error
+        .downcast::<Error>()
+        .expect("Must be the context error");
+    assert!(matches!(*error, Error::Lookup { user_id: 7, .. }));
+
+    let source = std::error::Error::source(&*error).expect("Must have a source");
+    assert!(source.is::<DbError>());
+}
+
+#[test]
+fn err_into_uses_the_from_implementation() {
+    let service = UserService { online: true };
+    assert_eq!(block_on(service.call_typed(3)).unwrap(), "user-3");
+
+    let service = UserService { online: false };
+    let error = block_on(service.call_typed(3)).unwrap_err();
+
+    assert_eq!(error.to_string(), "the request failed");
+    assert!(matches!(
+        error,
+        RequestError {
+            source: Error::Lookup { user_id: 3, .. }
+        }
+    ));
+}
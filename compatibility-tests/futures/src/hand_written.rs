@@ -0,0 +1,91 @@
+// Uses the `futures` extension traits with hand-written futures and
+// streams, relying on nothing beyond the standard library and the
+// `Stream` trait.
+
+use futures::{
+    executor::{block_on, block_on_stream},
+    Stream,
+};
+use snafu::{
+    futures::{TryFutureExt as _, TryStreamExt as _},
+    prelude::*,
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Reading { attempt: u8, source: ApiError },
+}
+
+#[derive(Debug, Snafu)]
+struct ApiError;
+
+// Resolves to the stored result once it has been polled `remaining`
+// more times.
+struct Delayed {
+    remaining: u8,
+    result: Option<Result<i32, ApiError>>,
+}
+
+impl Future for Delayed {
+    type Output = Result<i32, ApiError>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.remaining == 0 {
+            Poll::Ready(self.result.take().expect("Polled after completion"))
+        } else {
+            self.remaining -= 1;
+            ctx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+// Yields each of the stored results in order.
+struct Results(Vec<Result<i32, ApiError>>);
+
+impl Stream for Results {
+    type Item = Result<i32, ApiError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, _ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.0.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Ready(Some(self.0.remove(0)))
+        }
+    }
+}
+
+#[test]
+fn hand_written_future_can_have_context_added() {
+    let fut = Delayed {
+        remaining: 2,
+        result: Some(Err(ApiError)),
+    };
+
+    let error = block_on(fut.context(ReadingSnafu { attempt: 1 })).unwrap_err();
+    assert!(matches!(error, Error::Reading { attempt: 1, .. }));
+
+    let fut = Delayed {
+        remaining: 1,
+        result: Some(Ok(42)),
+    };
+
+    let value = block_on(fut.with_context(|_| ReadingSnafu { attempt: 2 }));
+    assert_eq!(value.unwrap(), 42);
+}
+
+#[test]
+fn hand_written_stream_can_have_context_added() {
+    let stream = Results(vec![Ok(1), Err(ApiError)]).context(ReadingSnafu { attempt: 3 });
+    let items: Vec<_> = block_on_stream(Box::pin(stream)).collect();
+
+    assert!(matches!(
+        items[..],
+        [Ok(1), Err(Error::Reading { attempt: 3, .. })]
+    ));
+}
@@ -1,7 +1,13 @@
 #![cfg(test)]
 
+mod context_async;
+mod context_cost;
+mod err_into;
+mod hand_written;
 mod location;
+mod map_source;
 mod report;
+mod stream_error_context;
 
 mod api {
     use futures::{stream, StreamExt, TryStream};
@@ -139,6 +145,29 @@ async fn load_stock_data_series() -> Result<String, Error> {
         .await
 }
 
+// Closures can be used as context selectors with the combinators
+async fn load_stock_data_from_fn() -> Result<String, Error> {
+    let apple = api::fetch_page("apple")
+        .context(snafu::from_fn(|source| Error::UnableToLoadAppleStock {
+            source,
+        }))
+        .await?;
+
+    let name = String::from("stream");
+    let google = api::keep_fetching_page("google")
+        .context(snafu::from_fn(move |source| {
+            Error::UnableToLoadGoogleStock {
+                source,
+                name: name.clone(),
+            }
+        }))
+        .take(1)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    Ok(format!("{apple}+{google:?}"))
+}
+
 #[test]
 fn implements_error() {
     fn check<T: std::error::Error>() {}
@@ -157,4 +186,7 @@ fn implements_error() {
 
     let d = block_on(load_stock_data_series());
     d.unwrap_err();
+
+    let e = block_on(load_stock_data_from_fn());
+    e.unwrap_err();
 }
@@ -108,6 +108,25 @@ mod try_future {
         });
     }
 
+    #[test]
+    fn track_caller_is_applied_on_lazy_context_poll() {
+        block_on(async {
+            let base_line = line!();
+            let error_future = async { InnerSnafu.fail::<()>() };
+            let wrapped_error_future = error_future.lazy_context(|| WrappedSnafu);
+            let wrapped_error = wrapped_error_future.await.unwrap_err();
+
+            // `.await` calls our implementation of `poll`, so the
+            // location corresponds to that line.
+            assert_eq!(
+                wrapped_error.location.line,
+                base_line + 3,
+                "Actual location: {}",
+                wrapped_error.location,
+            );
+        });
+    }
+
     #[test]
     fn track_caller_is_applied_on_whatever_context_poll() {
         block_on(async {
@@ -0,0 +1,74 @@
+use futures::{
+    executor::{block_on, block_on_stream},
+    future, stream,
+};
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(display("database failure: {message}"))]
+struct DbError {
+    message: String,
+}
+
+impl From<ApiError> for DbError {
+    fn from(e: ApiError) -> Self {
+        DbError {
+            message: format!("code {}", e.code),
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+struct ApiError {
+    code: i32,
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Query { table: String, source: DbError },
+}
+
+#[test]
+fn future_errors_are_converted_before_context_is_added() {
+    let future = future::ready(ApiSnafu { code: 5 }.fail::<i32>())
+        .map_source(DbError::from)
+        .context(QuerySnafu { table: "orders" });
+
+    let error = block_on(future).unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::Query { source: DbError { ref message }, .. } if message == "code 5"
+    ));
+}
+
+#[test]
+fn future_success_is_unchanged() {
+    let future = future::ready(Ok::<_, ApiError>(1)).map_source(DbError::from);
+
+    assert!(matches!(block_on(future), Ok(1)));
+}
+
+#[test]
+fn stream_errors_are_each_converted_before_context_is_added() {
+    let stream = stream::iter(vec![
+        Ok(1),
+        ApiSnafu { code: 2 }.fail(),
+        ApiSnafu { code: 3 }.fail(),
+    ])
+    .map_source(DbError::from)
+    .context(QuerySnafu { table: "events" });
+
+    let items: Vec<_> = block_on_stream(stream).collect();
+
+    assert_eq!(items.len(), 3);
+    assert!(matches!(items[0], Ok(1)));
+    assert!(matches!(
+        items[1],
+        Err(Error::Query { source: DbError { ref message }, .. }) if message == "code 2"
+    ));
+    assert!(matches!(
+        items[2],
+        Err(Error::Query { source: DbError { ref message }, .. }) if message == "code 3"
+    ));
+}
@@ -0,0 +1,104 @@
+use futures::{executor::block_on_stream, Stream};
+use snafu::{futures::StreamErrorContext, prelude::*};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Fetching { page: u8, source: ApiError },
+}
+
+#[derive(Debug, Snafu)]
+struct ApiError {
+    code: i32,
+}
+
+// Emits `count` items, alternating between success and failure.
+struct Alternating {
+    next: i32,
+    count: i32,
+}
+
+impl Stream for Alternating {
+    type Item = Result<i32, ApiError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, _ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.next == self.count {
+            return Poll::Ready(None);
+        }
+
+        let n = self.next;
+        self.next += 1;
+
+        let item = if n % 2 == 0 {
+            Ok(n)
+        } else {
+            ApiSnafu { code: n }.fail()
+        };
+        Poll::Ready(Some(item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.count - self.next) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+fn collect<S: Stream>(stream: S) -> Vec<S::Item> {
+    block_on_stream(Box::pin(stream)).collect()
+}
+
+#[test]
+fn context_is_added_to_each_error_item() {
+    let stream =
+        StreamErrorContext::context(Alternating { next: 0, count: 4 }, FetchingSnafu { page: 7 });
+
+    let items = collect(stream);
+    assert_eq!(items.len(), 4);
+
+    assert!(matches!(items[0], Ok(0)));
+    assert!(matches!(
+        items[1],
+        Err(Error::Fetching {
+            page: 7,
+            source: ApiError { code: 1 }
+        })
+    ));
+    assert!(matches!(items[2], Ok(2)));
+    assert!(matches!(
+        items[3],
+        Err(Error::Fetching {
+            page: 7,
+            source: ApiError { code: 3 }
+        })
+    ));
+}
+
+#[test]
+fn size_hint_is_forwarded() {
+    let stream =
+        StreamErrorContext::context(Alternating { next: 0, count: 3 }, FetchingSnafu { page: 1 });
+
+    assert_eq!(stream.size_hint(), (3, Some(3)));
+}
+
+#[test]
+fn works_with_generic_streams() {
+    fn paged<S>(stream: S, page: u8) -> impl Stream<Item = Result<i32, Error>>
+    where
+        S: Stream<Item = Result<i32, ApiError>>,
+    {
+        StreamErrorContext::context(stream, FetchingSnafu { page })
+    }
+
+    let items = collect(paged(Alternating { next: 1, count: 2 }, 3));
+    assert!(matches!(
+        items[..],
+        [Err(Error::Fetching {
+            page: 3,
+            source: ApiError { code: 1 }
+        })]
+    ));
+}
@@ -0,0 +1,170 @@
+#![cfg(test)]
+
+use log::{
+    kv::{Key, Value, VisitSource},
+    Level, Log, Metadata, Record,
+};
+use snafu::{prelude::*, IntoError};
+use std::sync::Mutex;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("could not load {name}"))]
+    Load { name: String, source: ConfigError },
+
+    #[snafu(display("the service is unavailable"))]
+    Unavailable,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("the configuration is invalid"))]
+struct ConfigError {
+    source: std::io::Error,
+}
+
+#[derive(Debug, PartialEq)]
+struct Logged {
+    level: Level,
+    target: String,
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+/// Records everything it is asked to log.
+#[derive(Default)]
+struct MockLogger {
+    enabled: bool,
+    records: Mutex<Vec<Logged>>,
+}
+
+impl MockLogger {
+    fn enabled() -> Self {
+        MockLogger {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn records(&self) -> Vec<Logged> {
+        std::mem::take(&mut self.records.lock().unwrap())
+    }
+}
+
+impl Log for MockLogger {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        self.enabled
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        struct Collect(Vec<(String, String)>);
+
+        impl<'kvs> VisitSource<'kvs> for Collect {
+            fn visit_pair(
+                &mut self,
+                key: Key<'kvs>,
+                value: Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                self.0.push((key.to_string(), value.to_string()));
+                Ok(())
+            }
+        }
+
+        let mut fields = Collect(Vec::new());
+        record.key_values().visit(&mut fields).unwrap();
+
+        self.records.lock().unwrap().push(Logged {
+            level: record.level(),
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+            fields: fields.0,
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+fn load_error() -> Error {
+    let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+    let config_error = ConfigSnafu.into_error(io_error);
+    LoadSnafu { name: "settings" }.into_error(config_error)
+}
+
+fn fields(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|&(k, v)| (k.to_owned(), v.to_owned()))
+        .collect()
+}
+
+#[test]
+fn each_source_is_a_field() {
+    let logger = MockLogger::enabled();
+
+    snafu::error_info!(&logger, load_error());
+
+    assert_eq!(
+        logger.records(),
+        [Logged {
+            level: Level::Error,
+            target: module_path!().to_owned(),
+            message: "could not load settings".to_owned(),
+            fields: fields(&[
+                ("cause_0", "the configuration is invalid"),
+                ("cause_1", "no such file"),
+            ]),
+        }],
+    );
+}
+
+#[test]
+fn additional_fields_come_before_the_causes() {
+    let logger = MockLogger::enabled();
+    let user = "alice";
+
+    snafu::error_info!(&logger, load_error(); user = user, attempt = 3,);
+
+    let records = logger.records();
+    assert_eq!(
+        records[0].fields,
+        fields(&[
+            ("user", "alice"),
+            ("attempt", "3"),
+            ("cause_0", "the configuration is invalid"),
+            ("cause_1", "no such file"),
+        ]),
+    );
+}
+
+#[test]
+fn errors_without_a_source_have_no_causes() {
+    let logger = MockLogger::enabled();
+    let error = UnavailableSnafu.build();
+
+    snafu::error_info!(&logger, error; region = "eu");
+    snafu::error_info!(&logger, error);
+
+    let records = logger.records();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].message, "the service is unavailable");
+    assert_eq!(records[0].fields, fields(&[("region", "eu")]));
+    assert_eq!(records[1].fields, []);
+}
+
+#[test]
+fn boxed_errors_can_be_logged() {
+    let logger = MockLogger::enabled();
+    let error: Box<dyn std::error::Error + Send + Sync> = Box::new(load_error());
+
+    snafu::error_info!(&logger, error);
+
+    assert_eq!(logger.records()[0].fields.len(), 2);
+}
+
+#[test]
+fn nothing_is_logged_when_the_logger_is_disabled() {
+    let logger = MockLogger::default();
+
+    snafu::error_info!(&logger, load_error());
+
+    assert_eq!(logger.records(), []);
+}
@@ -17,6 +17,13 @@ struct OpaqueError(EnumError);
 #[snafu(crate_root(a_rose))]
 struct StructError;
 
+a_rose::derive_impl! {
+    WrapperError {
+        source: std::io::Error,
+    }
+    display = "wrapped: {source}"
+}
+
 #[test]
 fn implements_std_error() {
     fn expects_std_trait<E: std::error::Error>() {}
@@ -24,4 +31,5 @@ fn implements_std_error() {
     expects_std_trait::<EnumError>();
     expects_std_trait::<OpaqueError>();
     expects_std_trait::<StructError>();
+    expects_std_trait::<WrapperError>();
 }
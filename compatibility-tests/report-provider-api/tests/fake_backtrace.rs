@@ -0,0 +1,39 @@
+#![feature(error_generic_member_access)]
+
+use snafu::{
+    env::{self, BacktracePolicy},
+    prelude::*,
+    Backtrace, IntoError, Report,
+};
+
+#[derive(Debug, Snafu)]
+#[snafu(display("outer failure"))]
+struct OuterError {
+    source: InnerError,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("inner failure"))]
+struct InnerError {
+    backtrace: Backtrace,
+}
+
+// The policy is global, so this is the only test in this binary.
+#[test]
+fn fake_backtrace_is_printed_deterministically() {
+    env::set_backtrace_policy(BacktracePolicy::Fake);
+
+    let error = OuterSnafu.into_error(InnerSnafu.build());
+    let msg = Report::from_error(error).to_string();
+
+    assert_eq!(
+        msg,
+        "outer failure\n\
+         \n\
+         Caused by this error:\n  \
+         1: inner failure\n\
+         \n\
+         Backtrace:\n\
+         <backtrace captured: 0 frames>\n",
+    );
+}
@@ -0,0 +1,67 @@
+#![cfg(test)]
+
+use snafu::{prelude::*, Backtrace, IntoError};
+
+#[derive(Debug, Snafu)]
+#[snafu(context(derive(Deserialize)))]
+enum Error {
+    #[snafu(display("timed out after {seconds} seconds"))]
+    Timeout { seconds: u64, backtrace: Backtrace },
+
+    #[snafu(display("could not open {path}"))]
+    Open {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("the service is unavailable"))]
+    Unavailable,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("user {name} has no access"))]
+#[snafu(context(derive(Deserialize)))]
+struct AccessError {
+    name: String,
+}
+
+#[test]
+fn selectors_can_be_deserialized_and_built() {
+    let selector: TimeoutSnafu<u64> = serde_json::from_str(r#"{ "seconds": 30 }"#).unwrap();
+    let error = selector.build();
+
+    assert!(matches!(error, Error::Timeout { seconds: 30, .. }));
+    assert_eq!(error.to_string(), "timed out after 30 seconds");
+}
+
+#[test]
+fn selectors_with_a_source_can_be_deserialized() {
+    let selector: OpenSnafu<String> = serde_json::from_str(r#"{ "path": "/etc/app" }"#).unwrap();
+    let source = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+    let error = selector.into_error(source);
+
+    assert_eq!(error.to_string(), "could not open /etc/app");
+}
+
+#[test]
+fn selectors_without_fields_can_be_deserialized() {
+    let selector: UnavailableSnafu = serde_json::from_str("null").unwrap();
+    let error: Result<(), _> = selector.fail();
+
+    assert!(matches!(error, Err(Error::Unavailable)));
+}
+
+#[test]
+fn struct_selectors_can_be_deserialized() {
+    let selector: AccessSnafu<String> = serde_json::from_str(r#"{ "name": "alice" }"#).unwrap();
+    let error = selector.build();
+
+    assert_eq!(error.to_string(), "user alice has no access");
+}
+
+#[test]
+fn missing_fields_are_reported() {
+    let error = serde_json::from_str::<TimeoutSnafu<u64>>("{}").unwrap_err();
+
+    assert!(error.to_string().contains("missing field `seconds`"));
+}
@@ -0,0 +1,211 @@
+//! The attribute parsing used by [`snafu-derive`][snafu-derive],
+//! for crates that provide their own derive macros on top of types
+//! that also derive `Snafu`.
+//!
+//! **This crate is not covered by semantic versioning.** It follows
+//! the version of `snafu-derive`, and any release may change these
+//! types in a breaking manner. Depend on an exact version with `=`.
+//!
+//! Most extensions should start with the read-only [`model`], which
+//! gathers the display specifications, source and backtrace fields,
+//! and visibilities of an error type. The individual
+//! [`SnafuAttribute`]s can be read with
+//! [`parse::attributes_from_syn`].
+//!
+//! [snafu-derive]: https://docs.rs/snafu-derive
+
+use std::collections::BTreeSet;
+
+pub mod model;
+pub mod parse;
+
+/// The result of an operation that reports every problem found, not
+/// just the first.
+pub type MultiSynResult<T> = std::result::Result<T, Vec<syn::Error>>;
+
+/// Some arbitrary tokens we treat as a black box
+pub type UserInput = Box<dyn quote::ToTokens>;
+
+/// The module from `#[snafu(module)]` or `#[snafu(module(name))]`.
+pub enum ModuleName {
+    Default,
+    Custom(syn::Ident),
+}
+
+/// A value from `#[snafu(provide(...))]`.
+pub struct Provide {
+    pub is_chain: bool,
+    pub is_opt: bool,
+    pub is_priority: bool,
+    pub is_ref: bool,
+    pub ty: syn::Type,
+    pub expr: syn::Expr,
+}
+
+/// The suffix of a context selector's name.
+pub enum SuffixKind {
+    Default,
+    None,
+    Some(syn::Ident),
+}
+
+impl SuffixKind {
+    pub fn resolve_with_default<'a>(&'a self, def: &'a Self) -> &'a Self {
+        use SuffixKind::*;
+
+        match self {
+            Default => def,
+            None => self,
+            Some(_) => self,
+        }
+    }
+}
+
+/// A check from `validate(...)` that is run on a context selector's
+/// field before the error is constructed.
+pub struct Validation {
+    pub field: syn::Ident,
+    pub check: syn::Expr,
+    pub msg: Option<syn::LitStr>,
+}
+
+/// The argument of `#[snafu(provide)]` on a field.
+#[allow(clippy::large_enum_variant)]
+pub enum ProvideKind {
+    Flag(bool),
+    Expression(Provide),
+}
+
+/// The argument of `#[snafu(display_prefix(...))]`.
+pub enum DisplayPrefix {
+    Prefix(syn::LitStr),
+    Flag(bool),
+}
+
+/// The argument of `#[snafu(context(...))]`.
+pub enum Context {
+    Flag(bool),
+    Suffix(SuffixKind),
+}
+
+impl Context {
+    pub fn into_enabled(self) -> (bool, SuffixKind) {
+        match self {
+            Context::Flag(b) => (b, SuffixKind::None),
+            Context::Suffix(suffix) => (true, suffix),
+        }
+    }
+}
+
+/// The argument of `#[snafu(cause_kind(...))]`.
+#[derive(Copy, Clone)]
+pub enum CauseKind {
+    Transient,
+    Permanent,
+}
+
+/// The argument of `#[snafu(kind(...))]`.
+#[derive(Copy, Clone)]
+pub enum ErrorKind {
+    Network,
+    Io,
+    Logic,
+}
+
+/// The HTTP status code from `#[snafu(http_status(...))]`: either
+/// the name of a constant in `http_status` or a numeric code.
+#[derive(Clone)]
+pub enum HttpStatus {
+    Named(syn::Ident),
+    Code(syn::LitInt),
+}
+
+/// One argument of `#[snafu(source(...))]`.
+#[allow(clippy::large_enum_variant)]
+pub enum Source {
+    Flag(bool),
+    From(syn::Type, syn::Expr),
+    Arc,
+    Accessor,
+}
+
+/// The message from `#[snafu(display(...))]`.
+///
+/// `exprs` are the format string and its arguments, as written.
+/// `shorthand_names` are the fields the format string refers to by
+/// name and `assigned_names` are those given an explicit value.
+pub struct Display {
+    pub exprs: Vec<syn::Expr>,
+    pub with: Option<syn::Expr>,
+    pub template: Option<DisplayTemplate>,
+    pub shorthand_names: BTreeSet<syn::Ident>,
+    pub assigned_names: BTreeSet<syn::Ident>,
+}
+
+/// A message template stored in a constant, from
+/// `display(template = ...)`, along with the named arguments it may
+/// refer to.
+pub struct DisplayTemplate {
+    pub template: syn::Expr,
+    pub args: Vec<(syn::Ident, syn::Expr)>,
+}
+
+/// A SnafuAttribute represents one SNAFU-specific attribute inside of `#[snafu(...)]`.  For
+/// example, in `#[snafu(visibility(pub), display("hi"))]`, `visibility(pub)` and `display("hi")`
+/// are each a SnafuAttribute.
+///
+/// We store the location in the source where we found the attribute (as a `TokenStream`) along
+/// with the data.  The location can be used to give accurate error messages in case there was a
+/// problem with the use of the attribute.
+pub enum SnafuAttribute {
+    AbsorbWhatever(proc_macro2::TokenStream),
+    AutoFrom(proc_macro2::TokenStream),
+    Backtrace(proc_macro2::TokenStream, bool),
+    Bound(proc_macro2::TokenStream, Vec<syn::WherePredicate>),
+    CauseKind(proc_macro2::TokenStream, CauseKind),
+    Kind(proc_macro2::TokenStream, ErrorKind),
+    Kinds(proc_macro2::TokenStream),
+    Code(proc_macro2::TokenStream, syn::LitStr),
+    CompatStdError(proc_macro2::TokenStream),
+    AssertSendSync(proc_macro2::TokenStream),
+    ImplFromStr(proc_macro2::TokenStream),
+    ParseWith(proc_macro2::TokenStream, syn::Expr),
+    Context(proc_macro2::TokenStream, Context),
+    ContextSelectorAttr(proc_macro2::TokenStream, syn::Meta),
+    ContextDerive(proc_macro2::TokenStream),
+    ContextBuilder(proc_macro2::TokenStream),
+    CrateRoot(proc_macro2::TokenStream, UserInput),
+    Debug(proc_macro2::TokenStream),
+    DefaultValue(proc_macro2::TokenStream, syn::Expr),
+    ErrorChainLimit(proc_macro2::TokenStream, syn::LitInt),
+    ErrorDescription(proc_macro2::TokenStream, syn::LitStr),
+    ForwardSourceBacktrace(proc_macro2::TokenStream, bool),
+    FromSourceDisplay(proc_macro2::TokenStream),
+    HttpStatus(proc_macro2::TokenStream, HttpStatus),
+    FfiCode(proc_macro2::TokenStream, syn::Expr),
+    IgnoreField(proc_macro2::TokenStream, Option<syn::Expr>),
+    Display(proc_macro2::TokenStream, Display),
+    DisplayContext(proc_macro2::TokenStream),
+    DisplayNoSource(proc_macro2::TokenStream),
+    DisplayInherit(proc_macro2::TokenStream, syn::Path),
+    DisplayLocation(proc_macro2::TokenStream, bool),
+    DisplayPrefix(proc_macro2::TokenStream, DisplayPrefix),
+    DocComment(proc_macro2::TokenStream, String),
+    ErrorImpl(proc_macro2::TokenStream, syn::Path),
+    Implicit(proc_macro2::TokenStream, bool),
+    Module(proc_macro2::TokenStream, ModuleName),
+    Opaque(proc_macro2::TokenStream),
+    PhantomData(proc_macro2::TokenStream),
+    Provide(proc_macro2::TokenStream, ProvideKind),
+    RecordFields(proc_macro2::TokenStream),
+    Source(proc_macro2::TokenStream, Vec<Source>),
+    Transparent(proc_macro2::TokenStream, bool),
+    Validate(proc_macro2::TokenStream, Validation),
+    Visibility(proc_macro2::TokenStream, syn::Visibility),
+    Whatever(proc_macro2::TokenStream),
+}
+
+/// The visibility of items when none is given.
+pub fn private_visibility() -> syn::Visibility {
+    syn::Visibility::Inherited
+}
@@ -0,0 +1,616 @@
+//! A read-only view of an error type that derives `Snafu`.
+//!
+//! [`Container::from_derive_input`] reads the same attributes as the
+//! `Snafu` derive and records where the messages come from, what
+//! each field is used for, and the visibility of the context
+//! selectors. The derive decides what a field is used for with the
+//! same [`FieldAttributes::role`]. The model does not validate the
+//! attributes; the `Snafu` derive reports any misuse when it runs on
+//! the same type.
+//!
+//! ```rust
+//! use quote::quote;
+//! use snafu_derive_internals::model::Container;
+//!
+//! // The body of a derive macro that lists the source field of each
+//! // variant.
+//! fn source_fields(input: &syn::DeriveInput) -> proc_macro2::TokenStream {
+//!     let container = match Container::from_derive_input(input) {
+//!         Ok(container) => container,
+//!         Err(errors) => return errors.iter().map(syn::Error::to_compile_error).collect(),
+//!     };
+//!
+//!     let entries = container.variants().iter().map(|variant| {
+//!         let name = variant.ident.to_string();
+//!         match variant.source_field() {
+//!             Some(field) => {
+//!                 let field = field.ident.to_string();
+//!                 quote! { (#name, ::core::option::Option::Some(#field)) }
+//!             }
+//!             None => quote! { (#name, ::core::option::Option::None) },
+//!         }
+//!     });
+//!
+//!     let name = &container.ident;
+//!     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+//!
+//!     quote! {
+//!         impl #impl_generics #name #ty_generics #where_clause {
+//!             pub const SOURCE_FIELDS: &'static [(&'static str, ::core::option::Option<&'static str>)] =
+//!                 &[#(#entries),*];
+//!         }
+//!     }
+//! }
+//!
+//! let input = syn::parse_quote! {
+//!     #[snafu(visibility(pub(crate)))]
+//!     enum Error {
+//!         #[snafu(display("could not open {path}"))]
+//!         Open { path: String, source: std::io::Error },
+//!
+//!         /// The configuration was empty
+//!         Empty { backtrace: snafu::Backtrace },
+//!     }
+//! };
+//!
+//! let container = Container::from_derive_input(&input).unwrap();
+//! let variants = container.variants();
+//! assert!(container.visibility.is_some());
+//! assert!(variants[0].display.is_some());
+//! assert_eq!(variants[1].doc_comment.as_deref(), Some("The configuration was empty"));
+//! assert!(variants[1].backtrace_field().is_some());
+//!
+//! let output = source_fields(&input).to_string();
+//! assert!(output.contains("\"Open\" , :: core :: option :: Option :: Some (\"source\")"));
+//! ```
+
+use crate::{parse::attributes_from_syn, Display, MultiSynResult, SnafuAttribute, Source};
+
+/// An error type that derives `Snafu`.
+pub struct Container {
+    /// The name of the type.
+    pub ident: syn::Ident,
+    /// The variants or fields of the type.
+    pub data: Data,
+    /// The default visibility of the context selectors of an enum,
+    /// from `#[snafu(visibility(...))]` on the enum. Structs record
+    /// their visibility in their [`Variant`].
+    pub visibility: Option<syn::Visibility>,
+}
+
+/// The shape of an error type.
+#[allow(clippy::large_enum_variant)]
+pub enum Data {
+    /// An enum, with one error per variant.
+    Enum(Vec<Variant>),
+    /// A struct with named fields, whose attributes are read the same
+    /// way as those of an enum variant.
+    Struct(Variant),
+    /// A struct with one unnamed field, which is the type of the
+    /// error being wrapped.
+    Opaque(syn::Type),
+}
+
+/// An enum variant or a struct with named fields.
+pub struct Variant {
+    /// The name of the variant or struct.
+    pub ident: syn::Ident,
+    /// The message from `#[snafu(display(...))]`.
+    pub display: Option<Display>,
+    /// The summary of the doc comment, which is the message when
+    /// there is no `display` attribute.
+    pub doc_comment: Option<String>,
+    /// The visibility of the context selector, from
+    /// `#[snafu(visibility(...))]`.
+    pub visibility: Option<syn::Visibility>,
+    /// The fields, in the order they were written.
+    pub fields: Vec<Field>,
+}
+
+/// A named field of a [`Variant`].
+pub struct Field {
+    /// The name of the field.
+    pub ident: syn::Ident,
+    /// The type of the field.
+    pub ty: syn::Type,
+    /// What the field is used for.
+    pub role: FieldRole,
+}
+
+/// What the `Snafu` derive uses a named field for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FieldRole {
+    /// The source of the error, either because it is named `source`
+    /// or because of `#[snafu(source)]`. With `backtrace`, the
+    /// backtrace is taken from the source.
+    Source { backtrace: bool },
+    /// Provides the backtrace, either because it is named `backtrace`
+    /// or because of `#[snafu(backtrace)]`.
+    Backtrace,
+    /// The `Display` text of the source, from
+    /// `#[snafu(from_source_display)]`.
+    SourceDisplay,
+    /// Created by `#[snafu(implicit)]`.
+    Implicit,
+    /// Filled in from `#[snafu(default = ...)]`.
+    Defaulted,
+    /// Filled in because of `#[snafu(ignore_field)]`.
+    Ignored,
+    /// A `PhantomData`, either by its type or because of
+    /// `#[snafu(phantom_data)]`.
+    PhantomData,
+    /// Given to the context selector.
+    User,
+}
+
+/// The attributes of a named field that decide its [`FieldRole`].
+///
+/// The `Snafu` derive fills this in while it checks the attributes,
+/// so both agree on what each field is used for.
+#[derive(Default)]
+pub struct FieldAttributes {
+    /// `source`, `source(from(...))`, or `source(arc)`.
+    pub source: bool,
+    /// `source(false)`.
+    pub source_opt_out: bool,
+    /// `backtrace`.
+    pub backtrace: bool,
+    /// `backtrace(false)`.
+    pub backtrace_opt_out: bool,
+    /// `implicit`.
+    pub implicit: bool,
+    /// `from_source_display`.
+    pub from_source_display: bool,
+    /// `ignore_field`.
+    pub ignore_field: bool,
+    /// `phantom_data`.
+    pub phantom_data: bool,
+    /// `default = ...`.
+    pub default: bool,
+}
+
+impl FieldAttributes {
+    /// Gathers the attributes of a field without checking them.
+    pub fn from_attributes(attrs: &[SnafuAttribute]) -> Self {
+        let mut this = Self::default();
+
+        for attr in attrs {
+            match attr {
+                SnafuAttribute::Source(_, args) => {
+                    for arg in args {
+                        match arg {
+                            Source::Flag(false) => this.source_opt_out = true,
+                            Source::Flag(true) | Source::From(..) | Source::Arc => {
+                                this.source = true
+                            }
+                            Source::Accessor => {}
+                        }
+                    }
+                }
+                SnafuAttribute::Backtrace(_, true) => this.backtrace = true,
+                SnafuAttribute::Backtrace(_, false) => this.backtrace_opt_out = true,
+                SnafuAttribute::Implicit(_, i) => this.implicit |= *i,
+                SnafuAttribute::FromSourceDisplay(_) => this.from_source_display = true,
+                SnafuAttribute::IgnoreField(..) => this.ignore_field = true,
+                SnafuAttribute::PhantomData(_) => this.phantom_data = true,
+                SnafuAttribute::DefaultValue(..) => this.default = true,
+                _ => {}
+            }
+        }
+
+        this
+    }
+
+    /// If any attribute gives the field a role other than being
+    /// ignored.
+    pub fn has_other_role(&self) -> bool {
+        self.source || self.backtrace || self.implicit || self.from_source_display
+    }
+
+    /// What a field with these attributes is used for.
+    pub fn role(&self, name: &syn::Ident, ty: &syn::Type) -> FieldRole {
+        if self.default {
+            return FieldRole::Defaulted;
+        }
+
+        // An ignored field is never a source or backtrace, even when
+        // it has one of those names.
+        if self.ignore_field {
+            return FieldRole::Ignored;
+        }
+
+        // Type aliases can't be seen through, so those fields need
+        // the attribute.
+        if self.phantom_data || (!self.has_other_role() && is_phantom_data(ty)) {
+            return FieldRole::PhantomData;
+        }
+
+        let source = self.source || (is_implicit_source(name) && !self.source_opt_out);
+        let backtrace = self.backtrace || (is_implicit_backtrace(name) && !self.backtrace_opt_out);
+
+        if source {
+            FieldRole::Source { backtrace }
+        } else if backtrace {
+            FieldRole::Backtrace
+        } else if self.from_source_display {
+            FieldRole::SourceDisplay
+        } else if self.implicit {
+            FieldRole::Implicit
+        } else {
+            FieldRole::User
+        }
+    }
+}
+
+/// If a field with this name is the source unless it opts out.
+pub fn is_implicit_source(name: &syn::Ident) -> bool {
+    name == "source"
+}
+
+/// If a field with this name provides the backtrace unless it opts
+/// out.
+pub fn is_implicit_backtrace(name: &syn::Ident) -> bool {
+    name == "backtrace"
+}
+
+fn is_phantom_data(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(p) if p.qself.is_none() => p
+            .path
+            .segments
+            .last()
+            .map_or(false, |s| s.ident == "PhantomData"),
+        _ => false,
+    }
+}
+
+impl Container {
+    /// Reads the attributes of a type that derives `Snafu`.
+    pub fn from_derive_input(input: &syn::DeriveInput) -> MultiSynResult<Self> {
+        let mut errors = Vec::new();
+        let mut visibility = None;
+
+        let data = match &input.data {
+            syn::Data::Enum(data) => {
+                for attr in collect(&mut errors, &input.attrs) {
+                    if let SnafuAttribute::Visibility(_, v) = attr {
+                        visibility = Some(v);
+                    }
+                }
+
+                let variants = data
+                    .variants
+                    .iter()
+                    .filter_map(|variant| {
+                        let fields = match &variant.fields {
+                            syn::Fields::Named(fields) => fields.named.iter().collect(),
+                            syn::Fields::Unit => Vec::new(),
+                            syn::Fields::Unnamed(fields) => {
+                                errors.push(syn::Error::new_spanned(
+                                    fields,
+                                    "Can only derive `Snafu` for enums with struct-like and unit enum variants",
+                                ));
+                                return None;
+                            }
+                        };
+
+                        Some(Variant::new(
+                            &mut errors,
+                            &variant.ident,
+                            &variant.attrs,
+                            fields,
+                        ))
+                    })
+                    .collect();
+
+                Data::Enum(variants)
+            }
+
+            syn::Data::Struct(data) => match &data.fields {
+                syn::Fields::Named(fields) => Data::Struct(Variant::new(
+                    &mut errors,
+                    &input.ident,
+                    &input.attrs,
+                    fields.named.iter().collect(),
+                )),
+                syn::Fields::Unit => Data::Struct(Variant::new(
+                    &mut errors,
+                    &input.ident,
+                    &input.attrs,
+                    Vec::new(),
+                )),
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    Data::Opaque(fields.unnamed[0].ty.clone())
+                }
+                syn::Fields::Unnamed(_) => {
+                    return Err(vec![syn::Error::new_spanned(
+                        input,
+                        "Can only derive `Snafu` for tuple structs with exactly one field",
+                    )]);
+                }
+            },
+
+            syn::Data::Union(_) => {
+                return Err(vec![syn::Error::new_spanned(
+                    input,
+                    "Can only derive `Snafu` for an enum or a newtype",
+                )]);
+            }
+        };
+
+        if errors.is_empty() {
+            Ok(Container {
+                ident: input.ident.clone(),
+                data,
+                visibility,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The variants of an enum, or the struct itself when it has named
+    /// fields.
+    pub fn variants(&self) -> &[Variant] {
+        match &self.data {
+            Data::Enum(variants) => variants,
+            Data::Struct(variant) => std::slice::from_ref(variant),
+            Data::Opaque(_) => &[],
+        }
+    }
+}
+
+impl Variant {
+    fn new(
+        errors: &mut Vec<syn::Error>,
+        ident: &syn::Ident,
+        attrs: &[syn::Attribute],
+        fields: Vec<&syn::Field>,
+    ) -> Self {
+        let mut display = None;
+        let mut doc_comment = String::new();
+        let mut reached_end_of_doc_comment = false;
+        let mut visibility = None;
+
+        for attr in collect(errors, attrs) {
+            match attr {
+                SnafuAttribute::Display(_, d) => display = Some(d),
+                SnafuAttribute::Visibility(_, v) => visibility = Some(v),
+                SnafuAttribute::DocComment(_, line) => {
+                    let trimmed = line.trim();
+                    if trimmed.is_empty() {
+                        reached_end_of_doc_comment = true;
+                    } else if !reached_end_of_doc_comment {
+                        if !doc_comment.is_empty() {
+                            doc_comment.push(' ');
+                        }
+                        doc_comment.push_str(trimmed);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let fields = fields
+            .into_iter()
+            .filter_map(|field| {
+                let ident = field.ident.clone()?;
+                let attrs = collect(errors, &field.attrs);
+                let role = FieldAttributes::from_attributes(&attrs).role(&ident, &field.ty);
+
+                Some(Field {
+                    ident,
+                    ty: field.ty.clone(),
+                    role,
+                })
+            })
+            .collect();
+
+        Variant {
+            ident: ident.clone(),
+            display,
+            doc_comment: Some(doc_comment).filter(|d| !d.is_empty()),
+            visibility,
+            fields,
+        }
+    }
+
+    /// The field that is the source of the error.
+    pub fn source_field(&self) -> Option<&Field> {
+        self.fields
+            .iter()
+            .find(|f| matches!(f.role, FieldRole::Source { .. }))
+    }
+
+    /// The field that provides the backtrace of the error. This is
+    /// the source field when the backtrace is taken from the source.
+    pub fn backtrace_field(&self) -> Option<&Field> {
+        self.fields.iter().find(|f| {
+            matches!(
+                f.role,
+                FieldRole::Backtrace | FieldRole::Source { backtrace: true }
+            )
+        })
+    }
+}
+
+fn collect(errors: &mut Vec<syn::Error>, attrs: &[syn::Attribute]) -> Vec<SnafuAttribute> {
+    attributes_from_syn(attrs.to_vec()).unwrap_or_else(|errs| {
+        errors.extend(errs);
+        Vec::new()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn model(input: syn::DeriveInput) -> Container {
+        Container::from_derive_input(&input).unwrap_or_else(|_| panic!("invalid attributes"))
+    }
+
+    #[test]
+    fn finds_explicit_and_implicit_sources() {
+        let container = model(syn::parse_quote! {
+            enum Error {
+                Implicit { source: std::io::Error },
+                Explicit { #[snafu(source(from(Other, Box::new)))] cause: Box<Other> },
+                Disabled { #[snafu(source(false))] source: String },
+            }
+        });
+
+        let sources: Vec<_> = container
+            .variants()
+            .iter()
+            .map(|v| v.source_field().map(|f| f.ident.to_string()))
+            .collect();
+        assert_eq!(sources, [Some("source".into()), Some("cause".into()), None]);
+    }
+
+    #[test]
+    fn a_source_can_provide_the_backtrace() {
+        let container = model(syn::parse_quote! {
+            struct Error {
+                #[snafu(backtrace)]
+                source: Inner,
+            }
+        });
+
+        let variant = &container.variants()[0];
+        assert_eq!(variant.ident, "Error");
+        assert_eq!(
+            variant.source_field().unwrap().role,
+            FieldRole::Source { backtrace: true }
+        );
+        assert_eq!(variant.backtrace_field().unwrap().ident, "source");
+    }
+
+    #[test]
+    fn reads_every_shape_the_derive_accepts() {
+        let enum_ = model(syn::parse_quote! {
+            enum Error {
+                Named { source: std::io::Error },
+                Unit,
+            }
+        });
+        let named_struct = model(syn::parse_quote! {
+            struct Error { source: std::io::Error }
+        });
+        let unit_struct = model(syn::parse_quote! {
+            struct Error;
+        });
+        let tuple_struct = model(syn::parse_quote! {
+            struct Error(InnerError);
+        });
+
+        let shape = |c: &Container| {
+            c.variants()
+                .iter()
+                .map(|v| (v.ident.to_string(), v.fields.len()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shape(&enum_), [("Named".into(), 1), ("Unit".into(), 0)]);
+        assert_eq!(shape(&named_struct), [("Error".into(), 1)]);
+        assert_eq!(shape(&unit_struct), [("Error".into(), 0)]);
+        assert!(matches!(unit_struct.data, Data::Struct(_)));
+        assert!(matches!(tuple_struct.data, Data::Opaque(_)));
+    }
+
+    #[test]
+    fn rejects_shapes_the_derive_rejects() {
+        let inputs: [syn::DeriveInput; 3] = [
+            syn::parse_quote! { enum Error { Tuple(i32) } },
+            syn::parse_quote! { struct Error(i32, i32); },
+            syn::parse_quote! { union Error { a: i32 } },
+        ];
+
+        for input in &inputs {
+            assert!(Container::from_derive_input(input).is_err());
+        }
+    }
+
+    #[test]
+    fn classifies_fields_like_the_derive() {
+        let container = model(syn::parse_quote! {
+            enum Error {
+                Alpha {
+                    #[snafu(ignore_field)]
+                    source: String,
+                    #[snafu(backtrace(false))]
+                    backtrace: String,
+                    marker: std::marker::PhantomData<u8>,
+                    #[snafu(implicit)]
+                    location: Location,
+                    #[snafu(from_source_display)]
+                    text: String,
+                    #[snafu(default = 0)]
+                    count: u8,
+                    user: u8,
+                },
+                Beta {
+                    #[snafu(implicit)]
+                    backtrace: Backtrace,
+                },
+            }
+        });
+
+        let roles: Vec<Vec<_>> = container
+            .variants()
+            .iter()
+            .map(|v| v.fields.iter().map(|f| f.role).collect())
+            .collect();
+        assert_eq!(
+            roles,
+            [
+                vec![
+                    FieldRole::Ignored,
+                    FieldRole::User,
+                    FieldRole::PhantomData,
+                    FieldRole::Implicit,
+                    FieldRole::SourceDisplay,
+                    FieldRole::Defaulted,
+                    FieldRole::User,
+                ],
+                vec![FieldRole::Backtrace],
+            ]
+        );
+    }
+
+    #[test]
+    fn variant_visibility_is_separate_from_the_default() {
+        let container = model(syn::parse_quote! {
+            #[snafu(visibility(pub))]
+            enum Error {
+                #[snafu(visibility(pub(crate)))]
+                Alpha,
+                Beta,
+            }
+        });
+
+        assert!(container.visibility.is_some());
+        assert!(container.variants()[0].visibility.is_some());
+        assert!(container.variants()[1].visibility.is_none());
+    }
+
+    #[test]
+    fn opaque_structs_have_no_variants() {
+        let container = model(syn::parse_quote! {
+            struct Error(InnerError);
+        });
+
+        assert!(matches!(container.data, Data::Opaque(_)));
+        assert!(container.variants().is_empty());
+    }
+
+    #[test]
+    fn reports_invalid_attributes() {
+        let input = syn::parse_quote! {
+            enum Error {
+                #[snafu(not_an_attribute)]
+                Alpha,
+            }
+        };
+
+        assert!(Container::from_derive_input(&input).is_err());
+    }
+}
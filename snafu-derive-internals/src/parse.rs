@@ -0,0 +1,2523 @@
+use std::collections::BTreeSet;
+
+use crate::{ModuleName, ProvideKind, SnafuAttribute};
+use proc_macro2::TokenStream;
+use quote::{format_ident, ToTokens};
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream, Result},
+    punctuated::Punctuated,
+    token, Expr, Ident, Lit, LitBool, LitInt, LitStr, Path, Type, WherePredicate,
+};
+
+mod kw {
+    use syn::custom_keyword;
+
+    custom_keyword!(absorb_whatever);
+    custom_keyword!(assert_send_sync);
+    custom_keyword!(auto_from);
+    custom_keyword!(backtrace);
+    custom_keyword!(bound);
+    custom_keyword!(cause_kind);
+    custom_keyword!(code);
+    custom_keyword!(compat_std_error);
+    custom_keyword!(context);
+    custom_keyword!(context_selector_attr);
+    custom_keyword!(crate_root);
+    custom_keyword!(debug);
+    custom_keyword!(display);
+    custom_keyword!(display_context);
+    custom_keyword!(display_inherit);
+    custom_keyword!(display_location);
+    custom_keyword!(display_prefix);
+    custom_keyword!(error_chain_limit);
+    custom_keyword!(error_description);
+    custom_keyword!(error_impl);
+    custom_keyword!(ffi_code);
+    custom_keyword!(forward_source_backtrace);
+    custom_keyword!(from_source_display);
+    custom_keyword!(http_status);
+    custom_keyword!(ignore_field);
+    custom_keyword!(impl_from_str);
+    custom_keyword!(implicit);
+    custom_keyword!(kind);
+    custom_keyword!(kinds);
+    custom_keyword!(module);
+    custom_keyword!(opaque);
+    custom_keyword!(parse_with);
+    custom_keyword!(phantom_data);
+    custom_keyword!(provide);
+    custom_keyword!(record_fields);
+    custom_keyword!(source);
+    custom_keyword!(transparent);
+    custom_keyword!(validate);
+    custom_keyword!(visibility);
+    custom_keyword!(whatever);
+
+    custom_keyword!(from);
+    custom_keyword!(arc);
+    custom_keyword!(accessor);
+
+    custom_keyword!(with);
+
+    custom_keyword!(parent);
+
+    custom_keyword!(field);
+    custom_keyword!(check);
+    custom_keyword!(msg);
+
+    custom_keyword!(suffix);
+    custom_keyword!(derive);
+    custom_keyword!(builder);
+    custom_keyword!(Deserialize);
+
+    custom_keyword!(compact_backtrace);
+
+    custom_keyword!(transparent_source);
+
+    custom_keyword!(chain);
+    custom_keyword!(opt);
+    custom_keyword!(priority);
+}
+
+/// Parses every `#[snafu(...)]` attribute and doc comment in
+/// `attrs`, ignoring any other attributes.
+pub fn attributes_from_syn(
+    attrs: Vec<syn::Attribute>,
+) -> super::MultiSynResult<Vec<SnafuAttribute>> {
+    let mut ours = Vec::new();
+    let mut errs = Vec::new();
+
+    for attr in attrs {
+        if attr.path().is_ident("snafu") {
+            let attr_list = Punctuated::<Attribute, token::Comma>::parse_terminated;
+
+            match attr.parse_args_with(attr_list) {
+                Ok(attrs) => {
+                    ours.extend(attrs.into_iter().map(Into::into));
+                }
+                Err(e) => errs.push(e),
+            }
+        } else if attr.path().is_ident("doc") {
+            // Ignore any errors that occur while parsing the doc
+            // comment. This isn't our attribute so we shouldn't
+            // assume that we know what values are acceptable.
+            if let Ok(comment) = syn::parse2::<DocComment>(attr.meta.to_token_stream()) {
+                ours.push(comment.into());
+            }
+        }
+    }
+
+    for attr in &ours {
+        if let SnafuAttribute::Provide(tt, ProvideKind::Expression(p)) = attr {
+            if p.is_chain && !p.is_ref {
+                errs.push(syn::Error::new_spanned(
+                    tt,
+                    "May only chain to references; please add `ref` flag",
+                ));
+            }
+        }
+    }
+
+    if errs.is_empty() {
+        Ok(ours)
+    } else {
+        Err(errs)
+    }
+}
+
+/// Parses a single attribute that was written outside of
+/// `#[snafu(...)]`, such as one of the arguments to
+/// `#[snafu::defaults(...)]`.
+pub fn attribute_from_tokens(tokens: TokenStream) -> Result<SnafuAttribute> {
+    syn::parse2::<Attribute>(tokens).map(Into::into)
+}
+
+enum Attribute {
+    AbsorbWhatever(AbsorbWhatever),
+    AssertSendSync(AssertSendSync),
+    AutoFrom(AutoFrom),
+    Backtrace(Backtrace),
+    Bound(Bound),
+    CauseKind(CauseKind),
+    Code(Code),
+    CompatStdError(CompatStdError),
+    Context(Context),
+    ContextSelectorAttr(ContextSelectorAttr),
+    CrateRoot(CrateRoot),
+    Debug(Debug),
+    DefaultValue(DefaultValue),
+    Display(Display),
+    DisplayContext(DisplayContext),
+    DisplayInherit(DisplayInherit),
+    DisplayLocation(DisplayLocation),
+    DisplayPrefix(DisplayPrefix),
+    ErrorChainLimit(ErrorChainLimit),
+    ErrorDescription(ErrorDescription),
+    ErrorImpl(ErrorImpl),
+    FfiCode(FfiCode),
+    ForwardSourceBacktrace(ForwardSourceBacktrace),
+    FromSourceDisplay(FromSourceDisplay),
+    HttpStatus(HttpStatus),
+    IgnoreField(IgnoreField),
+    ImplFromStr(ImplFromStr),
+    Implicit(Implicit),
+    Kind(Kind),
+    Kinds(Kinds),
+    Module(Module),
+    Opaque(Opaque),
+    ParseWith(ParseWith),
+    PhantomData(PhantomData),
+    Provide(Provide),
+    RecordFields(RecordFields),
+    Source(Source),
+    Transparent(Transparent),
+    Validate(Validate),
+    Visibility(Visibility),
+    Whatever(Whatever),
+}
+
+impl From<Attribute> for SnafuAttribute {
+    fn from(other: Attribute) -> Self {
+        use self::Attribute::*;
+
+        match other {
+            AbsorbWhatever(a) => SnafuAttribute::AbsorbWhatever(a.to_token_stream()),
+            AssertSendSync(a) => SnafuAttribute::AssertSendSync(a.to_token_stream()),
+            AutoFrom(a) => SnafuAttribute::AutoFrom(a.to_token_stream()),
+            Backtrace(b) => SnafuAttribute::Backtrace(b.to_token_stream(), b.into_bool()),
+            Bound(b) => SnafuAttribute::Bound(b.to_token_stream(), b.into_predicates()),
+            CauseKind(c) => SnafuAttribute::CauseKind(c.to_token_stream(), c.into_value()),
+            Code(c) => SnafuAttribute::Code(c.to_token_stream(), c.into_value()),
+            CompatStdError(c) => SnafuAttribute::CompatStdError(c.to_token_stream()),
+            Context(c) if c.is_derive() => SnafuAttribute::ContextDerive(c.to_token_stream()),
+            Context(c) if c.is_builder() => SnafuAttribute::ContextBuilder(c.to_token_stream()),
+            Context(c) => SnafuAttribute::Context(c.to_token_stream(), c.into_component()),
+            ContextSelectorAttr(c) => {
+                SnafuAttribute::ContextSelectorAttr(c.to_token_stream(), c.into_meta())
+            }
+            CrateRoot(cr) => SnafuAttribute::CrateRoot(cr.to_token_stream(), cr.into_arbitrary()),
+            Debug(d) => SnafuAttribute::Debug(d.to_token_stream()),
+            DefaultValue(d) => SnafuAttribute::DefaultValue(d.to_token_stream(), d.into_value()),
+            Display(d) if d.is_no_source() => SnafuAttribute::DisplayNoSource(d.to_token_stream()),
+            Display(d) => SnafuAttribute::Display(d.to_token_stream(), d.into_display()),
+            DisplayContext(d) => SnafuAttribute::DisplayContext(d.to_token_stream()),
+            DisplayInherit(d) => {
+                SnafuAttribute::DisplayInherit(d.to_token_stream(), d.into_parent())
+            }
+            DisplayLocation(d) => {
+                SnafuAttribute::DisplayLocation(d.to_token_stream(), d.into_bool())
+            }
+            DisplayPrefix(d) => SnafuAttribute::DisplayPrefix(d.to_token_stream(), d.into_value()),
+            ErrorChainLimit(e) => {
+                SnafuAttribute::ErrorChainLimit(e.to_token_stream(), e.into_value())
+            }
+            ErrorDescription(e) => {
+                SnafuAttribute::ErrorDescription(e.to_token_stream(), e.into_value())
+            }
+            ErrorImpl(e) => SnafuAttribute::ErrorImpl(e.to_token_stream(), e.into_module_path()),
+            ForwardSourceBacktrace(f) => {
+                SnafuAttribute::ForwardSourceBacktrace(f.to_token_stream(), f.into_bool())
+            }
+            FromSourceDisplay(f) => SnafuAttribute::FromSourceDisplay(f.to_token_stream()),
+            HttpStatus(h) => SnafuAttribute::HttpStatus(h.to_token_stream(), h.into_value()),
+            FfiCode(c) => SnafuAttribute::FfiCode(c.to_token_stream(), c.into_value()),
+            IgnoreField(i) => SnafuAttribute::IgnoreField(i.to_token_stream(), i.into_value()),
+            ImplFromStr(i) => SnafuAttribute::ImplFromStr(i.to_token_stream()),
+            Implicit(d) => SnafuAttribute::Implicit(d.to_token_stream(), d.into_bool()),
+            Kind(k) => SnafuAttribute::Kind(k.to_token_stream(), k.into_value()),
+            Kinds(k) => SnafuAttribute::Kinds(k.to_token_stream()),
+            Module(v) => SnafuAttribute::Module(v.to_token_stream(), v.into_value()),
+            Opaque(o) => SnafuAttribute::Opaque(o.to_token_stream()),
+            ParseWith(p) => SnafuAttribute::ParseWith(p.to_token_stream(), p.into_value()),
+            PhantomData(p) => SnafuAttribute::PhantomData(p.to_token_stream()),
+            Provide(v) => SnafuAttribute::Provide(v.to_token_stream(), v.into_value()),
+            RecordFields(r) => SnafuAttribute::RecordFields(r.to_token_stream()),
+            Source(s) => SnafuAttribute::Source(s.to_token_stream(), s.into_components()),
+            Transparent(t) => SnafuAttribute::Transparent(t.to_token_stream(), t.into_bool()),
+            Validate(v) => SnafuAttribute::Validate(v.to_token_stream(), v.into_value()),
+            Visibility(v) => SnafuAttribute::Visibility(v.to_token_stream(), v.into_visibility()),
+            Whatever(o) => SnafuAttribute::Whatever(o.to_token_stream()),
+        }
+    }
+}
+
+impl Parse for Attribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::absorb_whatever) {
+            input.parse().map(Attribute::AbsorbWhatever)
+        } else if lookahead.peek(kw::assert_send_sync) {
+            input.parse().map(Attribute::AssertSendSync)
+        } else if lookahead.peek(kw::auto_from) {
+            input.parse().map(Attribute::AutoFrom)
+        } else if lookahead.peek(kw::backtrace) {
+            input.parse().map(Attribute::Backtrace)
+        } else if lookahead.peek(kw::bound) {
+            input.parse().map(Attribute::Bound)
+        } else if lookahead.peek(kw::cause_kind) {
+            input.parse().map(Attribute::CauseKind)
+        } else if lookahead.peek(kw::code) {
+            input.parse().map(Attribute::Code)
+        } else if lookahead.peek(kw::compat_std_error) {
+            input.parse().map(Attribute::CompatStdError)
+        } else if lookahead.peek(kw::context) {
+            input.parse().map(Attribute::Context)
+        } else if lookahead.peek(kw::context_selector_attr) {
+            input.parse().map(Attribute::ContextSelectorAttr)
+        } else if lookahead.peek(kw::crate_root) {
+            input.parse().map(Attribute::CrateRoot)
+        } else if lookahead.peek(kw::debug) {
+            input.parse().map(Attribute::Debug)
+        } else if lookahead.peek(token::Default) {
+            input.parse().map(Attribute::DefaultValue)
+        } else if lookahead.peek(kw::display) {
+            input.parse().map(Attribute::Display)
+        } else if lookahead.peek(kw::display_context) {
+            input.parse().map(Attribute::DisplayContext)
+        } else if lookahead.peek(kw::display_inherit) {
+            input.parse().map(Attribute::DisplayInherit)
+        } else if lookahead.peek(kw::display_location) {
+            input.parse().map(Attribute::DisplayLocation)
+        } else if lookahead.peek(kw::display_prefix) {
+            input.parse().map(Attribute::DisplayPrefix)
+        } else if lookahead.peek(kw::error_chain_limit) {
+            input.parse().map(Attribute::ErrorChainLimit)
+        } else if lookahead.peek(kw::error_description) {
+            input.parse().map(Attribute::ErrorDescription)
+        } else if lookahead.peek(kw::error_impl) {
+            input.parse().map(Attribute::ErrorImpl)
+        } else if lookahead.peek(kw::ffi_code) {
+            input.parse().map(Attribute::FfiCode)
+        } else if lookahead.peek(kw::forward_source_backtrace) {
+            input.parse().map(Attribute::ForwardSourceBacktrace)
+        } else if lookahead.peek(kw::from_source_display) {
+            input.parse().map(Attribute::FromSourceDisplay)
+        } else if lookahead.peek(kw::http_status) {
+            input.parse().map(Attribute::HttpStatus)
+        } else if lookahead.peek(kw::ignore_field) {
+            input.parse().map(Attribute::IgnoreField)
+        } else if lookahead.peek(kw::impl_from_str) {
+            input.parse().map(Attribute::ImplFromStr)
+        } else if lookahead.peek(kw::implicit) {
+            input.parse().map(Attribute::Implicit)
+        } else if lookahead.peek(kw::kind) {
+            input.parse().map(Attribute::Kind)
+        } else if lookahead.peek(kw::kinds) {
+            input.parse().map(Attribute::Kinds)
+        } else if lookahead.peek(kw::module) {
+            input.parse().map(Attribute::Module)
+        } else if lookahead.peek(kw::opaque) {
+            input.parse().map(Attribute::Opaque)
+        } else if lookahead.peek(kw::parse_with) {
+            input.parse().map(Attribute::ParseWith)
+        } else if lookahead.peek(kw::phantom_data) {
+            input.parse().map(Attribute::PhantomData)
+        } else if lookahead.peek(kw::provide) {
+            input.parse().map(Attribute::Provide)
+        } else if lookahead.peek(kw::record_fields) {
+            input.parse().map(Attribute::RecordFields)
+        } else if lookahead.peek(kw::source) {
+            input.parse().map(Attribute::Source)
+        } else if lookahead.peek(kw::transparent) {
+            input.parse().map(Attribute::Transparent)
+        } else if lookahead.peek(kw::validate) {
+            input.parse().map(Attribute::Validate)
+        } else if lookahead.peek(kw::visibility) {
+            input.parse().map(Attribute::Visibility)
+        } else if lookahead.peek(kw::whatever) {
+            input.parse().map(Attribute::Whatever)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+struct AbsorbWhatever {
+    absorb_whatever_token: kw::absorb_whatever,
+}
+
+impl Parse for AbsorbWhatever {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            absorb_whatever_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for AbsorbWhatever {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.absorb_whatever_token.to_tokens(tokens);
+    }
+}
+
+struct AutoFrom {
+    auto_from_token: kw::auto_from,
+}
+
+impl Parse for AutoFrom {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            auto_from_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for AutoFrom {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.auto_from_token.to_tokens(tokens);
+    }
+}
+
+struct AssertSendSync {
+    assert_send_sync_token: kw::assert_send_sync,
+}
+
+impl Parse for AssertSendSync {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            assert_send_sync_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for AssertSendSync {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.assert_send_sync_token.to_tokens(tokens);
+    }
+}
+
+struct CompatStdError {
+    compat_std_error_token: kw::compat_std_error,
+}
+
+impl Parse for CompatStdError {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            compat_std_error_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for CompatStdError {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.compat_std_error_token.to_tokens(tokens);
+    }
+}
+
+struct RecordFields {
+    record_fields_token: kw::record_fields,
+}
+
+impl Parse for RecordFields {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            record_fields_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for RecordFields {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.record_fields_token.to_tokens(tokens);
+    }
+}
+
+struct Backtrace {
+    backtrace_token: kw::backtrace,
+    arg: MaybeArg<LitBool>,
+}
+
+impl Backtrace {
+    fn into_bool(self) -> bool {
+        self.arg.into_option().map_or(true, |a| a.value)
+    }
+}
+
+impl Parse for Backtrace {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            backtrace_token: input.parse()?,
+            arg: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Backtrace {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.backtrace_token.to_tokens(tokens);
+        self.arg.to_tokens(tokens);
+    }
+}
+
+struct Bound {
+    bound_token: kw::bound,
+    paren_token: token::Paren,
+    where_token: Option<token::Where>,
+    predicates: Punctuated<WherePredicate, token::Comma>,
+}
+
+impl Bound {
+    fn into_predicates(self) -> Vec<WherePredicate> {
+        self.predicates.into_iter().collect()
+    }
+}
+
+impl Parse for Bound {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            bound_token: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            where_token: content.parse()?,
+            predicates: Punctuated::parse_terminated(&content)?,
+        })
+    }
+}
+
+impl ToTokens for Bound {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.bound_token.to_tokens(tokens);
+        self.paren_token.surround(tokens, |tokens| {
+            self.where_token.to_tokens(tokens);
+            self.predicates.to_tokens(tokens);
+        });
+    }
+}
+
+struct CauseKind {
+    cause_kind_token: kw::cause_kind,
+    eq_token: token::Eq,
+    value: LitStr,
+    kind: crate::CauseKind,
+}
+
+impl CauseKind {
+    fn into_value(self) -> crate::CauseKind {
+        self.kind
+    }
+}
+
+impl Parse for CauseKind {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let cause_kind_token = input.parse()?;
+        let eq_token = input.parse()?;
+        let value: LitStr = input.parse()?;
+
+        let kind = match &*value.value() {
+            "transient" => crate::CauseKind::Transient,
+            "permanent" => crate::CauseKind::Permanent,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &value,
+                    r#"`cause_kind` must be "transient" or "permanent""#,
+                ))
+            }
+        };
+
+        Ok(Self {
+            cause_kind_token,
+            eq_token,
+            value,
+            kind,
+        })
+    }
+}
+
+impl ToTokens for CauseKind {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.cause_kind_token.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+struct Kind {
+    kind_token: kw::kind,
+    eq_token: token::Eq,
+    value: LitStr,
+    kind: crate::ErrorKind,
+}
+
+impl Kind {
+    fn into_value(self) -> crate::ErrorKind {
+        self.kind
+    }
+}
+
+impl Parse for Kind {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let kind_token = input.parse()?;
+        let eq_token = input.parse()?;
+        let value: LitStr = input.parse()?;
+
+        let kind = match &*value.value() {
+            "network" => crate::ErrorKind::Network,
+            "io" => crate::ErrorKind::Io,
+            "logic" => crate::ErrorKind::Logic,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &value,
+                    r#"`kind` must be "network", "io", or "logic""#,
+                ))
+            }
+        };
+
+        Ok(Self {
+            kind_token,
+            eq_token,
+            value,
+            kind,
+        })
+    }
+}
+
+impl ToTokens for Kind {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.kind_token.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+struct Kinds {
+    kinds_token: kw::kinds,
+}
+
+impl Parse for Kinds {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            kinds_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Kinds {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.kinds_token.to_tokens(tokens);
+    }
+}
+
+struct ErrorChainLimit {
+    error_chain_limit_token: kw::error_chain_limit,
+    eq_token: token::Eq,
+    value: LitInt,
+}
+
+impl ErrorChainLimit {
+    fn into_value(self) -> LitInt {
+        self.value
+    }
+}
+
+impl Parse for ErrorChainLimit {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let error_chain_limit_token = input.parse()?;
+        let eq_token = input.parse()?;
+        let value: LitInt = input.parse()?;
+
+        match value.base10_parse::<usize>() {
+            Ok(n) if n > 0 => {}
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &value,
+                    "`error_chain_limit` must be a positive integer",
+                ))
+            }
+        }
+
+        Ok(Self {
+            error_chain_limit_token,
+            eq_token,
+            value,
+        })
+    }
+}
+
+impl ToTokens for ErrorChainLimit {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.error_chain_limit_token.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+struct DisplayContext {
+    display_context_token: kw::display_context,
+}
+
+impl Parse for DisplayContext {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            display_context_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for DisplayContext {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.display_context_token.to_tokens(tokens);
+    }
+}
+
+struct DisplayInherit {
+    display_inherit_token: kw::display_inherit,
+    paren_token: token::Paren,
+    parent_token: kw::parent,
+    eq_token: token::Eq,
+    parent: Path,
+}
+
+impl DisplayInherit {
+    fn into_parent(self) -> Path {
+        self.parent
+    }
+}
+
+impl Parse for DisplayInherit {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            display_inherit_token: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            parent_token: content.parse()?,
+            eq_token: content.parse()?,
+            parent: content.parse()?,
+        })
+    }
+}
+
+impl ToTokens for DisplayInherit {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.display_inherit_token.to_tokens(tokens);
+        self.paren_token.surround(tokens, |tokens| {
+            self.parent_token.to_tokens(tokens);
+            self.eq_token.to_tokens(tokens);
+            self.parent.to_tokens(tokens);
+        });
+    }
+}
+
+struct DisplayLocation {
+    display_location_token: kw::display_location,
+    arg: MaybeArg<LitBool>,
+}
+
+impl DisplayLocation {
+    fn into_bool(self) -> bool {
+        self.arg.into_option().map_or(true, |a| a.value)
+    }
+}
+
+impl Parse for DisplayLocation {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            display_location_token: input.parse()?,
+            arg: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for DisplayLocation {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.display_location_token.to_tokens(tokens);
+        self.arg.to_tokens(tokens);
+    }
+}
+
+struct DisplayPrefix {
+    display_prefix_token: kw::display_prefix,
+    arg: DisplayPrefixArg,
+}
+
+impl DisplayPrefix {
+    fn into_value(self) -> super::DisplayPrefix {
+        match self.arg {
+            DisplayPrefixArg::Prefix { value, .. } => super::DisplayPrefix::Prefix(value),
+            DisplayPrefixArg::Flag { value, .. } => super::DisplayPrefix::Flag(value.value),
+        }
+    }
+}
+
+impl Parse for DisplayPrefix {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            display_prefix_token: input.parse()?,
+            arg: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for DisplayPrefix {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.display_prefix_token.to_tokens(tokens);
+        self.arg.to_tokens(tokens);
+    }
+}
+
+enum DisplayPrefixArg {
+    Prefix {
+        eq_token: token::Eq,
+        value: LitStr,
+    },
+    Flag {
+        paren_token: token::Paren,
+        value: LitBool,
+    },
+}
+
+impl Parse for DisplayPrefixArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(token::Eq) {
+            Ok(DisplayPrefixArg::Prefix {
+                eq_token: input.parse()?,
+                value: input.parse()?,
+            })
+        } else if lookahead.peek(token::Paren) {
+            let content;
+            Ok(DisplayPrefixArg::Flag {
+                paren_token: parenthesized!(content in input),
+                value: content.parse()?,
+            })
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl ToTokens for DisplayPrefixArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            DisplayPrefixArg::Prefix { eq_token, value } => {
+                eq_token.to_tokens(tokens);
+                value.to_tokens(tokens);
+            }
+            DisplayPrefixArg::Flag { paren_token, value } => {
+                paren_token.surround(tokens, |tokens| {
+                    value.to_tokens(tokens);
+                });
+            }
+        }
+    }
+}
+
+struct ErrorDescription {
+    error_description_token: kw::error_description,
+    eq_token: token::Eq,
+    value: LitStr,
+}
+
+impl ErrorDescription {
+    fn into_value(self) -> LitStr {
+        self.value
+    }
+}
+
+impl Parse for ErrorDescription {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            error_description_token: input.parse()?,
+            eq_token: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for ErrorDescription {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.error_description_token.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+struct Code {
+    code_token: kw::code,
+    eq_token: token::Eq,
+    value: LitStr,
+}
+
+impl Code {
+    fn into_value(self) -> LitStr {
+        self.value
+    }
+}
+
+impl Parse for Code {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            code_token: input.parse()?,
+            eq_token: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Code {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.code_token.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+struct Context {
+    context_token: kw::context,
+    arg: MaybeArg<ContextArg>,
+}
+
+impl Context {
+    fn is_derive(&self) -> bool {
+        matches!(
+            self.arg,
+            MaybeArg::Some {
+                content: ContextArg::Derive { .. },
+                ..
+            }
+        )
+    }
+
+    fn is_builder(&self) -> bool {
+        matches!(
+            self.arg,
+            MaybeArg::Some {
+                content: ContextArg::Builder { .. },
+                ..
+            }
+        )
+    }
+
+    fn into_component(self) -> super::Context {
+        use super::{Context::*, SuffixKind};
+
+        match self.arg.into_option() {
+            None => Flag(true),
+            Some(arg) => match arg {
+                ContextArg::Flag { value } => Flag(value.value),
+                ContextArg::Suffix {
+                    suffix:
+                        SuffixArg::Flag {
+                            value: LitBool { value: true, .. },
+                        },
+                    ..
+                } => Suffix(SuffixKind::Default),
+                ContextArg::Suffix {
+                    suffix:
+                        SuffixArg::Flag {
+                            value: LitBool { value: false, .. },
+                        },
+                    ..
+                } => Suffix(SuffixKind::None),
+                ContextArg::Suffix {
+                    suffix: SuffixArg::Suffix { suffix, .. },
+                    ..
+                } => Suffix(SuffixKind::Some(suffix)),
+                ContextArg::Derive { .. } => unreachable!("derives are a separate attribute"),
+                ContextArg::Builder { .. } => unreachable!("builders are a separate attribute"),
+            },
+        }
+    }
+}
+
+impl Parse for Context {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            context_token: input.parse()?,
+            arg: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Context {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.context_token.to_tokens(tokens);
+        self.arg.to_tokens(tokens);
+    }
+}
+
+enum ContextArg {
+    Flag {
+        value: LitBool,
+    },
+    Suffix {
+        suffix_token: kw::suffix,
+        paren_token: token::Paren,
+        suffix: SuffixArg,
+    },
+    Derive {
+        derive_token: kw::derive,
+        paren_token: token::Paren,
+        deserialize_token: kw::Deserialize,
+    },
+    Builder {
+        builder_token: kw::builder,
+    },
+}
+
+impl Parse for ContextArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(LitBool) {
+            Ok(ContextArg::Flag {
+                value: input.parse()?,
+            })
+        } else if lookahead.peek(kw::suffix) {
+            let content;
+            Ok(ContextArg::Suffix {
+                suffix_token: input.parse()?,
+                paren_token: parenthesized!(content in input),
+                suffix: content.parse()?,
+            })
+        } else if lookahead.peek(kw::derive) {
+            let derive_token: kw::derive = input.parse()?;
+
+            if !cfg!(feature = "serde") {
+                return Err(syn::Error::new_spanned(
+                    derive_token,
+                    "`context(derive(...))` requires the `serde` feature of SNAFU",
+                ));
+            }
+
+            let content;
+            let paren_token = parenthesized!(content in input);
+            let deserialize_token = content.parse().map_err(|e| {
+                syn::Error::new(
+                    e.span(),
+                    "only `Deserialize` can be derived for context selectors",
+                )
+            })?;
+
+            Ok(ContextArg::Derive {
+                derive_token,
+                paren_token,
+                deserialize_token,
+            })
+        } else if lookahead.peek(kw::builder) {
+            Ok(ContextArg::Builder {
+                builder_token: input.parse()?,
+            })
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl ToTokens for ContextArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            ContextArg::Flag { value } => {
+                value.to_tokens(tokens);
+            }
+            ContextArg::Suffix {
+                suffix_token,
+                paren_token,
+                suffix,
+            } => {
+                suffix_token.to_tokens(tokens);
+                paren_token.surround(tokens, |tokens| {
+                    suffix.to_tokens(tokens);
+                })
+            }
+            ContextArg::Derive {
+                derive_token,
+                paren_token,
+                deserialize_token,
+            } => {
+                derive_token.to_tokens(tokens);
+                paren_token.surround(tokens, |tokens| {
+                    deserialize_token.to_tokens(tokens);
+                })
+            }
+            ContextArg::Builder { builder_token } => {
+                builder_token.to_tokens(tokens);
+            }
+        }
+    }
+}
+
+enum SuffixArg {
+    Flag { value: LitBool },
+    Suffix { suffix: Ident },
+}
+
+impl Parse for SuffixArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(LitBool) {
+            Ok(SuffixArg::Flag {
+                value: input.parse()?,
+            })
+        } else if lookahead.peek(syn::Ident) {
+            Ok(SuffixArg::Suffix {
+                suffix: input.parse()?,
+            })
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl ToTokens for SuffixArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            SuffixArg::Flag { value } => {
+                value.to_tokens(tokens);
+            }
+            SuffixArg::Suffix { suffix } => {
+                suffix.to_tokens(tokens);
+            }
+        }
+    }
+}
+
+struct ContextSelectorAttr {
+    context_selector_attr_token: kw::context_selector_attr,
+    paren_token: token::Paren,
+    meta: syn::Meta,
+}
+
+impl ContextSelectorAttr {
+    fn into_meta(self) -> syn::Meta {
+        self.meta
+    }
+}
+
+impl Parse for ContextSelectorAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            context_selector_attr_token: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            meta: content.parse()?,
+        })
+    }
+}
+
+impl ToTokens for ContextSelectorAttr {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.context_selector_attr_token.to_tokens(tokens);
+        self.paren_token.surround(tokens, |tokens| {
+            self.meta.to_tokens(tokens);
+        });
+    }
+}
+
+struct CrateRoot {
+    crate_root_token: kw::crate_root,
+    paren_token: token::Paren,
+    arg: Path,
+}
+
+impl CrateRoot {
+    // TODO: Remove boxed trait object
+    fn into_arbitrary(self) -> Box<dyn ToTokens> {
+        Box::new(self.arg)
+    }
+}
+
+impl Parse for CrateRoot {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            crate_root_token: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            arg: content.parse()?,
+        })
+    }
+}
+
+impl ToTokens for CrateRoot {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.crate_root_token.to_tokens(tokens);
+        self.paren_token.surround(tokens, |tokens| {
+            self.arg.to_tokens(tokens);
+        });
+    }
+}
+
+struct Display {
+    display_token: kw::display,
+    paren_token: token::Paren,
+    args: Punctuated<Expr, token::Comma>,
+}
+
+impl Display {
+    fn is_no_source(&self) -> bool {
+        match self.args.first() {
+            Some(Expr::Path(p)) if self.args.len() == 1 => p.path.is_ident("no_source"),
+            _ => false,
+        }
+    }
+
+    fn with_function(args: &Punctuated<Expr, token::Comma>) -> Option<&Expr> {
+        args.iter().find_map(|arg| match arg {
+            Expr::Assign(a) => match &*a.left {
+                Expr::Path(p) if p.path.is_ident("with") => Some(&*a.right),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    fn template_string(args: &Punctuated<Expr, token::Comma>) -> Option<&Expr> {
+        args.iter().find_map(|arg| match arg {
+            Expr::Assign(a) => match &*a.left {
+                Expr::Path(p) if p.path.is_ident("template") => Some(&*a.right),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    /// The arguments made available to a `display(template = ...)`:
+    /// either a bare field name or `name = value`.
+    fn template_argument(arg: &Expr) -> Option<(Ident, Expr)> {
+        match arg {
+            Expr::Path(p) => {
+                let name = p.path.get_ident()?;
+                Some((name.clone(), arg.clone()))
+            }
+            Expr::Assign(a) => match &*a.left {
+                Expr::Path(p) => {
+                    let name = p.path.get_ident()?;
+                    Some((name.clone(), (*a.right).clone()))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Finds an argument that formats `self` with `Display`, which
+    /// would call the implementation being generated forever.
+    fn recursive_self_argument(args: &Punctuated<Expr, token::Comma>) -> Option<&Expr> {
+        if Self::template_string(args).is_some() {
+            return args
+                .iter()
+                .filter(|arg| Self::template_argument(arg).is_some())
+                .find(|arg| match arg {
+                    Expr::Assign(a) => is_self_expr(&a.right),
+                    _ => is_self_expr(arg),
+                });
+        }
+
+        let (format_str, rest) = match args.first() {
+            Some(Expr::Lit(l)) => match &l.lit {
+                Lit::Str(s) => (s.value(), args.iter().skip(1)),
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        let mut positional = Vec::new();
+        let mut named = Vec::new();
+        for arg in rest {
+            match arg {
+                Expr::Assign(a) => {
+                    if let Expr::Path(p) = &*a.left {
+                        named.extend(p.path.get_ident().map(|n| (n, &*a.right)));
+                    }
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        let first = args.first();
+        let mut next_positional = 0;
+
+        let found = format_placeholders(&format_str).find_map(|(name, spec)| {
+            // `.*` takes the precision from the next positional argument.
+            if spec.contains(".*") {
+                next_positional += 1;
+            }
+
+            let arg = if name.is_empty() {
+                next_positional += 1;
+                positional.get(next_positional - 1).copied()
+            } else if let Ok(index) = name.parse::<usize>() {
+                positional.get(index).copied()
+            } else {
+                match named.iter().find(|(n, _)| *n == name) {
+                    Some((_, value)) => Some(*value),
+                    // An implicitly captured `{self}` is reported at
+                    // the format string.
+                    None if name == "self" => return first.filter(|_| is_display_spec(spec)),
+                    None => None,
+                }
+            };
+
+            arg.filter(|arg| is_self_expr(arg) && is_display_spec(spec))
+        });
+        found
+    }
+
+    fn into_display(self) -> crate::Display {
+        if let Some(with) = Self::with_function(&self.args) {
+            return crate::Display {
+                exprs: Vec::new(),
+                with: Some(with.clone()),
+                template: None,
+                shorthand_names: BTreeSet::new(),
+                assigned_names: BTreeSet::new(),
+            };
+        }
+
+        if let Some(template) = Self::template_string(&self.args) {
+            // Arguments were validated when parsing.
+            let args = self
+                .args
+                .iter()
+                .filter_map(Self::template_argument)
+                .filter(|(name, _)| name != "template")
+                .collect();
+
+            return crate::Display {
+                exprs: Vec::new(),
+                with: None,
+                template: Some(crate::DisplayTemplate {
+                    template: template.clone(),
+                    args,
+                }),
+                shorthand_names: BTreeSet::new(),
+                assigned_names: BTreeSet::new(),
+            };
+        }
+
+        let exprs: Vec<_> = self.args.into_iter().collect();
+        let mut shorthand_names = BTreeSet::new();
+        let mut assigned_names = BTreeSet::new();
+
+        // Do a best-effort parsing here; if we fail, the compiler
+        // will likely spit out something more useful when it tries to
+        // parse it.
+        if let Some((Expr::Lit(l), args)) = exprs.split_first() {
+            if let Lit::Str(s) = &l.lit {
+                let format_str = s.value();
+                let names = extract_field_names(&format_str).map(|n| format_ident!("{}", n));
+                shorthand_names.extend(names);
+            }
+
+            for arg in args {
+                if let Expr::Assign(a) = arg {
+                    if let Expr::Path(p) = &*a.left {
+                        assigned_names.extend(p.path.get_ident().cloned());
+                    }
+                }
+            }
+        }
+
+        crate::Display {
+            exprs,
+            with: None,
+            template: None,
+            shorthand_names,
+            assigned_names,
+        }
+    }
+}
+
+/// Finds the named arguments referenced by a format string, both as
+/// the argument itself (`{name}`) and as a width or precision
+/// parameter (`{:width$.precision$}`). Positional arguments are
+/// skipped.
+pub fn extract_field_names(s: &str) -> impl Iterator<Item = &str> {
+    let mut names = Vec::new();
+
+    for (name, spec) in format_placeholders(s) {
+        if is_identifier(name) {
+            names.push(name);
+        }
+
+        names.extend(count_parameter_names(spec));
+    }
+
+    names.into_iter()
+}
+
+/// Splits each placeholder of a format string into the argument
+/// (empty for the next positional argument) and the format spec.
+fn format_placeholders(mut s: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut placeholders = Vec::new();
+
+    while let Some(open_curly) = s.find('{') {
+        s = &s[open_curly + '{'.len_utf8()..];
+
+        if s.starts_with('{') {
+            s = &s['{'.len_utf8()..];
+            continue;
+        }
+
+        let end_curly = match s.find('}') {
+            Some(idx) => idx,
+            None => break,
+        };
+        let format_contents = &s[..end_curly];
+        s = &s[end_curly..];
+
+        let (name, spec) = match format_contents.find(':') {
+            Some(idx) => (&format_contents[..idx], &format_contents[idx + 1..]),
+            None => (format_contents, ""),
+        };
+
+        placeholders.push((name.trim(), spec));
+    }
+
+    placeholders.into_iter()
+}
+
+/// Whether a format spec uses the `Display` trait, rather than
+/// `Debug` or one of the numeric formatting traits.
+fn is_display_spec(spec: &str) -> bool {
+    !spec.ends_with(|c| matches!(c, '?' | 'x' | 'X' | 'o' | 'b' | 'e' | 'E' | 'p'))
+}
+
+/// Whether an expression is `self`, possibly borrowed, dereferenced,
+/// or parenthesized.
+fn is_self_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(p) => p.qself.is_none() && p.path.is_ident("self"),
+        Expr::Reference(r) => is_self_expr(&r.expr),
+        Expr::Unary(u) => matches!(u.op, syn::UnOp::Deref(_)) && is_self_expr(&u.expr),
+        Expr::Paren(p) => is_self_expr(&p.expr),
+        _ => false,
+    }
+}
+
+/// Finds the `name$` parameters of a format spec. Anything else
+/// (fill characters, flags, literal counts, and the type) is
+/// ignored.
+fn count_parameter_names(spec: &str) -> impl Iterator<Item = &str> {
+    spec.match_indices('$').filter_map(move |(dollar, _)| {
+        let before = &spec[..dollar];
+        let prefix = before.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_');
+        let name = &before[prefix.len()..];
+
+        if is_identifier(name) {
+            Some(name)
+        } else {
+            None
+        }
+    })
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+
+    s != "_" && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+impl Parse for Display {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let display = Self {
+            display_token: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            args: Punctuated::parse_terminated(&content)?,
+        };
+
+        if display.args.len() > 1 && Self::with_function(&display.args).is_some() {
+            return Err(syn::Error::new_spanned(
+                &display.args,
+                "`display(with = ...)` cannot be combined with a format string",
+            ));
+        }
+
+        if let Some(arg) = Self::recursive_self_argument(&display.args) {
+            return Err(syn::Error::new_spanned(
+                arg,
+                "`self` cannot be formatted with `Display` inside its own `Display` implementation, as that recurses forever",
+            ));
+        }
+
+        if Self::template_string(&display.args).is_some() {
+            if let Some(arg) = display
+                .args
+                .iter()
+                .find(|arg| Self::template_argument(arg).is_none())
+            {
+                return Err(syn::Error::new_spanned(
+                    arg,
+                    "`display(template = ...)` only accepts field names or `name = value` arguments",
+                ));
+            }
+        }
+
+        Ok(display)
+    }
+}
+
+impl ToTokens for Display {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.display_token.to_tokens(tokens);
+        self.paren_token.surround(tokens, |tokens| {
+            self.args.to_tokens(tokens);
+        });
+    }
+}
+
+struct DocComment {
+    doc_ident: Ident,
+    eq_token: token::Eq,
+    str: LitStr,
+}
+
+impl DocComment {
+    fn into_value(self) -> String {
+        self.str.value()
+    }
+}
+
+impl From<DocComment> for SnafuAttribute {
+    fn from(other: DocComment) -> Self {
+        SnafuAttribute::DocComment(other.to_token_stream(), other.into_value())
+    }
+}
+
+impl Parse for DocComment {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            doc_ident: input.parse()?,
+            eq_token: input.parse()?,
+            str: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for DocComment {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.doc_ident.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.str.to_tokens(tokens);
+    }
+}
+
+struct FromSourceDisplay {
+    from_source_display_token: kw::from_source_display,
+}
+
+impl Parse for FromSourceDisplay {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            from_source_display_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for FromSourceDisplay {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.from_source_display_token.to_tokens(tokens);
+    }
+}
+
+struct ImplFromStr {
+    impl_from_str_token: kw::impl_from_str,
+}
+
+impl Parse for ImplFromStr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            impl_from_str_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for ImplFromStr {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.impl_from_str_token.to_tokens(tokens);
+    }
+}
+
+struct Implicit {
+    implicit_token: kw::implicit,
+    arg: MaybeArg<LitBool>,
+}
+
+impl Implicit {
+    fn into_bool(self) -> bool {
+        self.arg.into_option().map_or(true, |a| a.value)
+    }
+}
+
+impl Parse for Implicit {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            implicit_token: input.parse()?,
+            arg: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Implicit {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.implicit_token.to_tokens(tokens);
+        self.arg.to_tokens(tokens);
+    }
+}
+
+struct FfiCode {
+    ffi_code_token: kw::ffi_code,
+    paren_token: token::Paren,
+    value: Expr,
+}
+
+impl FfiCode {
+    fn into_value(self) -> Expr {
+        self.value
+    }
+}
+
+impl Parse for FfiCode {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            ffi_code_token: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            value: content.parse()?,
+        })
+    }
+}
+
+impl ToTokens for FfiCode {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.ffi_code_token.to_tokens(tokens);
+        self.paren_token
+            .surround(tokens, |tokens| self.value.to_tokens(tokens));
+    }
+}
+
+struct HttpStatus {
+    http_status_token: kw::http_status,
+    paren_token: token::Paren,
+    value: crate::HttpStatus,
+}
+
+impl HttpStatus {
+    fn into_value(self) -> crate::HttpStatus {
+        self.value
+    }
+}
+
+impl Parse for HttpStatus {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let http_status_token: kw::http_status = input.parse()?;
+
+        if !cfg!(feature = "http-status") {
+            return Err(syn::Error::new_spanned(
+                http_status_token,
+                "`http_status` requires the `http-status` feature of SNAFU",
+            ));
+        }
+
+        let content;
+        let paren_token = parenthesized!(content in input);
+
+        let lookahead = content.lookahead1();
+        let value = if lookahead.peek(LitInt) {
+            let code: LitInt = content.parse()?;
+            match code.base10_parse::<u16>() {
+                Ok(100..=999) => crate::HttpStatus::Code(code),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &code,
+                        "`http_status` codes must be between 100 and 999",
+                    ))
+                }
+            }
+        } else if lookahead.peek(Ident) {
+            crate::HttpStatus::Named(content.parse()?)
+        } else {
+            return Err(lookahead.error());
+        };
+
+        Ok(Self {
+            http_status_token,
+            paren_token,
+            value,
+        })
+    }
+}
+
+impl ToTokens for HttpStatus {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.http_status_token.to_tokens(tokens);
+        self.paren_token
+            .surround(tokens, |tokens| match &self.value {
+                crate::HttpStatus::Named(name) => name.to_tokens(tokens),
+                crate::HttpStatus::Code(code) => code.to_tokens(tokens),
+            });
+    }
+}
+
+struct IgnoreField {
+    ignore_field_token: kw::ignore_field,
+    arg: Option<IgnoreFieldArg>,
+}
+
+struct IgnoreFieldArg {
+    paren_token: token::Paren,
+    with_token: kw::with,
+    eq_token: token::Eq,
+    function: Expr,
+}
+
+impl IgnoreField {
+    fn into_value(self) -> Option<Expr> {
+        self.arg.map(|a| a.function)
+    }
+}
+
+impl Parse for IgnoreField {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ignore_field_token = input.parse()?;
+
+        let arg = if input.peek(token::Paren) {
+            let content;
+            Some(IgnoreFieldArg {
+                paren_token: parenthesized!(content in input),
+                with_token: content.parse()?,
+                eq_token: content.parse()?,
+                function: content.parse()?,
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            ignore_field_token,
+            arg,
+        })
+    }
+}
+
+impl ToTokens for IgnoreField {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.ignore_field_token.to_tokens(tokens);
+        if let Some(arg) = &self.arg {
+            arg.paren_token.surround(tokens, |tokens| {
+                arg.with_token.to_tokens(tokens);
+                arg.eq_token.to_tokens(tokens);
+                arg.function.to_tokens(tokens);
+            });
+        }
+    }
+}
+
+struct Validate {
+    validate_token: kw::validate,
+    paren_token: token::Paren,
+    field_token: kw::field,
+    field_eq_token: token::Eq,
+    field: Ident,
+    field_comma_token: token::Comma,
+    check_token: kw::check,
+    check_eq_token: token::Eq,
+    check: Expr,
+    msg: Option<ValidateMsg>,
+}
+
+struct ValidateMsg {
+    comma_token: token::Comma,
+    msg_token: kw::msg,
+    eq_token: token::Eq,
+    msg: LitStr,
+}
+
+impl Validate {
+    fn into_value(self) -> crate::Validation {
+        crate::Validation {
+            field: self.field,
+            check: self.check,
+            msg: self.msg.map(|m| m.msg),
+        }
+    }
+}
+
+impl Parse for Validate {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let validate_token = input.parse()?;
+        let paren_token = parenthesized!(content in input);
+        let field_token = content.parse()?;
+        let field_eq_token = content.parse()?;
+        let field = content.parse()?;
+        let field_comma_token = content.parse()?;
+        let check_token = content.parse()?;
+        let check_eq_token = content.parse()?;
+        let check = content.parse()?;
+
+        let msg = if content.is_empty() {
+            None
+        } else {
+            Some(ValidateMsg {
+                comma_token: content.parse()?,
+                msg_token: content.parse()?,
+                eq_token: content.parse()?,
+                msg: content.parse()?,
+            })
+        };
+
+        Ok(Self {
+            validate_token,
+            paren_token,
+            field_token,
+            field_eq_token,
+            field,
+            field_comma_token,
+            check_token,
+            check_eq_token,
+            check,
+            msg,
+        })
+    }
+}
+
+impl ToTokens for Validate {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.validate_token.to_tokens(tokens);
+        self.paren_token.surround(tokens, |tokens| {
+            self.field_token.to_tokens(tokens);
+            self.field_eq_token.to_tokens(tokens);
+            self.field.to_tokens(tokens);
+            self.field_comma_token.to_tokens(tokens);
+            self.check_token.to_tokens(tokens);
+            self.check_eq_token.to_tokens(tokens);
+            self.check.to_tokens(tokens);
+            if let Some(msg) = &self.msg {
+                msg.comma_token.to_tokens(tokens);
+                msg.msg_token.to_tokens(tokens);
+                msg.eq_token.to_tokens(tokens);
+                msg.msg.to_tokens(tokens);
+            }
+        });
+    }
+}
+
+struct Debug {
+    debug_token: kw::debug,
+    paren_token: token::Paren,
+    compact_backtrace_token: kw::compact_backtrace,
+}
+
+impl Parse for Debug {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            debug_token: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            compact_backtrace_token: content.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Debug {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.debug_token.to_tokens(tokens);
+        self.paren_token.surround(tokens, |tokens| {
+            self.compact_backtrace_token.to_tokens(tokens);
+        });
+    }
+}
+
+struct DefaultValue {
+    default_token: token::Default,
+    eq_token: token::Eq,
+    value: Expr,
+}
+
+impl DefaultValue {
+    fn into_value(self) -> Expr {
+        self.value
+    }
+}
+
+impl Parse for DefaultValue {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            default_token: input.parse()?,
+            eq_token: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for DefaultValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.default_token.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+struct Opaque {
+    opaque_token: kw::opaque,
+    paren_token: token::Paren,
+    transparent_source_token: kw::transparent_source,
+}
+
+impl Parse for Opaque {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            opaque_token: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            transparent_source_token: content.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Opaque {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.opaque_token.to_tokens(tokens);
+        self.paren_token.surround(tokens, |tokens| {
+            self.transparent_source_token.to_tokens(tokens);
+        });
+    }
+}
+
+struct ParseWith {
+    parse_with_token: kw::parse_with,
+    eq_token: token::Eq,
+    value: Expr,
+}
+
+impl ParseWith {
+    fn into_value(self) -> Expr {
+        self.value
+    }
+}
+
+impl Parse for ParseWith {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            parse_with_token: input.parse()?,
+            eq_token: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for ParseWith {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.parse_with_token.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+struct PhantomData {
+    phantom_data_token: kw::phantom_data,
+}
+
+impl Parse for PhantomData {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            phantom_data_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for PhantomData {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.phantom_data_token.to_tokens(tokens);
+    }
+}
+
+struct ErrorImpl {
+    error_impl_token: kw::error_impl,
+    eq_token: token::Eq,
+    module_path: Path,
+}
+
+impl ErrorImpl {
+    fn into_module_path(self) -> Path {
+        self.module_path
+    }
+}
+
+impl Parse for ErrorImpl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            error_impl_token: input.parse()?,
+            eq_token: input.parse()?,
+            module_path: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for ErrorImpl {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.error_impl_token.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.module_path.to_tokens(tokens);
+    }
+}
+
+struct ForwardSourceBacktrace {
+    forward_source_backtrace_token: kw::forward_source_backtrace,
+    arg: MaybeArg<LitBool>,
+}
+
+impl ForwardSourceBacktrace {
+    fn into_bool(self) -> bool {
+        self.arg.into_option().map_or(true, |a| a.value)
+    }
+}
+
+impl Parse for ForwardSourceBacktrace {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            forward_source_backtrace_token: input.parse()?,
+            arg: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for ForwardSourceBacktrace {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.forward_source_backtrace_token.to_tokens(tokens);
+        self.arg.to_tokens(tokens);
+    }
+}
+
+struct Module {
+    module_token: kw::module,
+    arg: MaybeArg<Ident>,
+}
+
+impl Module {
+    fn into_value(self) -> ModuleName {
+        match self.arg.into_option() {
+            None => ModuleName::Default,
+            Some(name) => ModuleName::Custom(name),
+        }
+    }
+}
+
+impl Parse for Module {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            module_token: input.parse()?,
+            arg: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Module {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.module_token.to_tokens(tokens);
+        self.arg.to_tokens(tokens);
+    }
+}
+
+struct Provide {
+    provide_token: kw::provide,
+    arg: MaybeArg<ProvideArg>,
+}
+
+impl Provide {
+    fn into_value(self) -> ProvideKind {
+        match self.arg.into_option() {
+            None => ProvideKind::Flag(true),
+            Some(ProvideArg::Flag { value }) => ProvideKind::Flag(value.value),
+            Some(ProvideArg::Expression {
+                flags,
+                ty,
+                arrow: _,
+                expr,
+            }) => ProvideKind::Expression(crate::Provide {
+                is_chain: flags.is_chain(),
+                is_opt: flags.is_opt(),
+                is_priority: flags.is_priority(),
+                is_ref: flags.is_ref(),
+                ty,
+                expr,
+            }),
+        }
+    }
+}
+
+impl Parse for Provide {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            provide_token: input.parse()?,
+            arg: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Provide {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.provide_token.to_tokens(tokens);
+        self.arg.to_tokens(tokens);
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+enum ProvideArg {
+    Flag {
+        value: LitBool,
+    },
+    Expression {
+        flags: ProvideFlags,
+        ty: Type,
+        arrow: token::FatArrow,
+        expr: Expr,
+    },
+}
+
+impl Parse for ProvideArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(LitBool) {
+            Ok(ProvideArg::Flag {
+                value: input.parse()?,
+            })
+        } else {
+            Ok(ProvideArg::Expression {
+                flags: input.parse()?,
+                ty: input.parse()?,
+                arrow: input.parse()?,
+                expr: input.parse()?,
+            })
+        }
+    }
+}
+
+impl ToTokens for ProvideArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            ProvideArg::Flag { value } => {
+                value.to_tokens(tokens);
+            }
+            ProvideArg::Expression {
+                flags,
+                ty,
+                arrow,
+                expr,
+            } => {
+                flags.to_tokens(tokens);
+                ty.to_tokens(tokens);
+                arrow.to_tokens(tokens);
+                expr.to_tokens(tokens);
+            }
+        }
+    }
+}
+
+struct ProvideFlags(Punctuated<ProvideFlag, token::Comma>);
+
+impl ProvideFlags {
+    fn is_chain(&self) -> bool {
+        self.0.iter().any(ProvideFlag::is_chain)
+    }
+
+    fn is_opt(&self) -> bool {
+        self.0.iter().any(ProvideFlag::is_opt)
+    }
+
+    fn is_priority(&self) -> bool {
+        self.0.iter().any(ProvideFlag::is_priority)
+    }
+
+    fn is_ref(&self) -> bool {
+        self.0.iter().any(ProvideFlag::is_ref)
+    }
+}
+
+impl Parse for ProvideFlags {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut flags = Punctuated::new();
+
+        while ProvideFlag::peek(input) {
+            flags.push_value(input.parse()?);
+            flags.push_punct(input.parse()?);
+        }
+
+        Ok(Self(flags))
+    }
+}
+
+impl ToTokens for ProvideFlags {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.0.to_tokens(tokens)
+    }
+}
+
+enum ProvideFlag {
+    Chain(kw::chain),
+    Opt(kw::opt),
+    Priority(kw::priority),
+    Ref(token::Ref),
+}
+
+impl ProvideFlag {
+    fn peek(input: ParseStream) -> bool {
+        input.peek(kw::chain)
+            || input.peek(kw::opt)
+            || input.peek(kw::priority)
+            || input.peek(token::Ref)
+    }
+
+    fn is_chain(&self) -> bool {
+        matches!(self, ProvideFlag::Chain(_))
+    }
+
+    fn is_opt(&self) -> bool {
+        matches!(self, ProvideFlag::Opt(_))
+    }
+
+    fn is_priority(&self) -> bool {
+        matches!(self, ProvideFlag::Priority(_))
+    }
+
+    fn is_ref(&self) -> bool {
+        matches!(self, ProvideFlag::Ref(_))
+    }
+}
+
+impl Parse for ProvideFlag {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+
+        if lookahead.peek(kw::chain) {
+            input.parse().map(ProvideFlag::Chain)
+        } else if lookahead.peek(kw::opt) {
+            input.parse().map(ProvideFlag::Opt)
+        } else if lookahead.peek(kw::priority) {
+            input.parse().map(ProvideFlag::Priority)
+        } else if lookahead.peek(token::Ref) {
+            input.parse().map(ProvideFlag::Ref)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl ToTokens for ProvideFlag {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            ProvideFlag::Chain(v) => v.to_tokens(tokens),
+            ProvideFlag::Opt(v) => v.to_tokens(tokens),
+            ProvideFlag::Priority(v) => v.to_tokens(tokens),
+            ProvideFlag::Ref(v) => v.to_tokens(tokens),
+        }
+    }
+}
+
+struct Source {
+    source_token: kw::source,
+    args: MaybeArg<Punctuated<SourceArg, token::Comma>>,
+}
+
+impl Source {
+    fn into_components(self) -> Vec<super::Source> {
+        match self.args.into_option() {
+            None => vec![super::Source::Flag(true)],
+            Some(args) => args
+                .into_iter()
+                .map(|sa| match sa {
+                    SourceArg::Flag { value } => super::Source::Flag(value.value),
+                    SourceArg::From { r#type, expr, .. } => super::Source::From(r#type, expr),
+                    SourceArg::Arc { .. } => super::Source::Arc,
+                    SourceArg::Accessor { .. } => super::Source::Accessor,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Parse for Source {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            source_token: input.parse()?,
+            args: MaybeArg::parse_with(input, Punctuated::parse_terminated)?,
+        })
+    }
+}
+
+impl ToTokens for Source {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.source_token.to_tokens(tokens);
+        self.args.to_tokens(tokens);
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+enum SourceArg {
+    Flag {
+        value: LitBool,
+    },
+    From {
+        from_token: kw::from,
+        paren_token: token::Paren,
+        r#type: Type,
+        comma_token: token::Comma,
+        expr: Expr,
+    },
+    Arc {
+        arc_token: kw::arc,
+    },
+    Accessor {
+        accessor_token: kw::accessor,
+    },
+}
+
+impl Parse for SourceArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(LitBool) {
+            Ok(SourceArg::Flag {
+                value: input.parse()?,
+            })
+        } else if lookahead.peek(kw::from) {
+            let content;
+            Ok(SourceArg::From {
+                from_token: input.parse()?,
+                paren_token: parenthesized!(content in input),
+                r#type: content.parse()?,
+                comma_token: content.parse()?,
+                expr: content.parse()?,
+            })
+        } else if lookahead.peek(kw::arc) {
+            Ok(SourceArg::Arc {
+                arc_token: input.parse()?,
+            })
+        } else if lookahead.peek(kw::accessor) {
+            Ok(SourceArg::Accessor {
+                accessor_token: input.parse()?,
+            })
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl ToTokens for SourceArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            SourceArg::Flag { value } => {
+                value.to_tokens(tokens);
+            }
+            SourceArg::From {
+                from_token,
+                paren_token,
+                r#type,
+                comma_token,
+                expr,
+            } => {
+                from_token.to_tokens(tokens);
+                paren_token.surround(tokens, |tokens| {
+                    r#type.to_tokens(tokens);
+                    comma_token.to_tokens(tokens);
+                    expr.to_tokens(tokens);
+                })
+            }
+            SourceArg::Arc { arc_token } => {
+                arc_token.to_tokens(tokens);
+            }
+            SourceArg::Accessor { accessor_token } => {
+                accessor_token.to_tokens(tokens);
+            }
+        }
+    }
+}
+
+struct Transparent {
+    transparent_token: kw::transparent,
+    arg: MaybeArg<LitBool>,
+}
+
+impl Transparent {
+    fn into_bool(self) -> bool {
+        self.arg.into_option().map_or(true, |a| a.value)
+    }
+}
+
+impl Parse for Transparent {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            transparent_token: input.parse()?,
+            arg: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Transparent {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.transparent_token.to_tokens(tokens);
+        self.arg.to_tokens(tokens);
+    }
+}
+
+struct Visibility {
+    visibility_token: kw::visibility,
+    visibility: MaybeArg<syn::Visibility>,
+}
+
+impl Visibility {
+    fn into_visibility(self) -> syn::Visibility {
+        // TODO: Move this default value out of parsing
+        self.visibility
+            .into_option()
+            .unwrap_or_else(super::private_visibility)
+    }
+}
+
+impl Parse for Visibility {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            visibility_token: input.parse()?,
+            visibility: MaybeArg::parse_with(input, |input| {
+                let visibility = input.parse()?;
+                if !input.is_empty() {
+                    return Err(input.error(
+                        "expected a visibility such as `pub`, `pub(crate)`, `pub(super)`, or `pub(in path)`",
+                    ));
+                }
+                Ok(visibility)
+            })?,
+        })
+    }
+}
+
+impl ToTokens for Visibility {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.visibility_token.to_tokens(tokens);
+        self.visibility.to_tokens(tokens);
+    }
+}
+
+struct Whatever {
+    whatever_token: kw::whatever,
+}
+
+impl Parse for Whatever {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            whatever_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Whatever {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.whatever_token.to_tokens(tokens);
+    }
+}
+
+enum MaybeArg<T> {
+    None,
+    Some {
+        paren_token: token::Paren,
+        content: T,
+    },
+}
+
+impl<T> MaybeArg<T> {
+    fn into_option(self) -> Option<T> {
+        match self {
+            MaybeArg::None => None,
+            MaybeArg::Some { content, .. } => Some(content),
+        }
+    }
+
+    fn parse_with<F>(input: ParseStream<'_>, parser: F) -> Result<Self>
+    where
+        F: FnOnce(ParseStream<'_>) -> Result<T>,
+    {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(token::Paren) {
+            let content;
+            Ok(MaybeArg::Some {
+                paren_token: parenthesized!(content in input),
+                content: parser(&content)?,
+            })
+        } else {
+            Ok(MaybeArg::None)
+        }
+    }
+}
+
+impl<T: Parse> Parse for MaybeArg<T> {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Self::parse_with(input, Parse::parse)
+    }
+}
+
+impl<T: ToTokens> ToTokens for MaybeArg<T> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if let MaybeArg::Some {
+            paren_token,
+            content,
+        } = self
+        {
+            paren_token.surround(tokens, |tokens| {
+                content.to_tokens(tokens);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn names(s: &str) -> Vec<&str> {
+        extract_field_names(s).collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn ignores_positional_arguments() {
+        assert_eq!(names("{}"), [] as [&str; 0]);
+    }
+
+    #[test]
+    fn finds_named_argument() {
+        assert_eq!(names("{a}"), ["a"]);
+    }
+
+    #[test]
+    fn finds_multiple_named_arguments() {
+        assert_eq!(names("{a} {b}"), ["a", "b"]);
+    }
+
+    #[test]
+    fn ignores_escaped_braces() {
+        assert_eq!(names("{{a}}"), [] as [&str; 0]);
+    }
+
+    #[test]
+    fn finds_named_arguments_around_escaped() {
+        assert_eq!(names("{a} {{b}} {c}"), ["a", "c"]);
+    }
+
+    #[test]
+    fn ignores_format_spec() {
+        assert_eq!(names("{a:?}"), ["a"]);
+    }
+
+    #[test]
+    fn ignores_width_and_precision() {
+        assert_eq!(names("{a:>10} {b:.3} {c:_^+#08.2e}"), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn finds_width_and_precision_parameters() {
+        assert_eq!(names("{a:w$.p$} {:>width$}"), ["a", "w", "p", "width"]);
+    }
+
+    #[test]
+    fn ignores_positional_width_and_precision() {
+        assert_eq!(names("{a:1$.*} {b:.0$}"), ["a", "b"]);
+    }
+
+    #[test]
+    fn ignores_indexed_arguments() {
+        assert_eq!(names("{0} {1:>5}"), [] as [&str; 0]);
+    }
+}
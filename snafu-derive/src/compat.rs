@@ -0,0 +1,163 @@
+use crate::{
+    attributes_from_syn, default_crate_root, is_implicit_backtrace, AtMostOne, ErrorLocation,
+    MultiSynResult, SnafuAttribute, SyntaxErrors, UserInput,
+};
+use quote::quote;
+use syn::{spanned::Spanned, Data, DeriveInput, Fields, Member};
+
+/// Generates `ErrorCompat` for a type that implements `Display` and
+/// `Error` itself.
+pub(crate) fn body(ty: DeriveInput) -> MultiSynResult<proc_macro2::TokenStream> {
+    let span = ty.span();
+    let DeriveInput {
+        ident,
+        generics,
+        data,
+        attrs,
+        ..
+    } = ty;
+
+    let mut errors = SyntaxErrors::default();
+
+    let (outer_location, inner_location) = match data {
+        Data::Enum(_) => (ErrorLocation::OnEnum, ErrorLocation::InVariant),
+        _ => (ErrorLocation::OnNamedStruct, ErrorLocation::InNamedStruct),
+    };
+
+    let mut crate_roots = AtMostOne::new("crate_root", outer_location);
+
+    for attr in snafu_attributes(attrs, &mut errors) {
+        match attr {
+            (SnafuAttribute::CrateRoot(tokens, root), _) => crate_roots.add(root, tokens),
+            (_, location) => errors.add(
+                location,
+                "`SnafuCompat` only supports `crate_root` on the type",
+            ),
+        }
+    }
+
+    let (crate_root, errs) = crate_roots.finish();
+    let crate_root = crate_root.unwrap_or_else(default_crate_root);
+    errors.extend(errs);
+
+    let arms = match data {
+        Data::Enum(enum_) => enum_
+            .variants
+            .into_iter()
+            .map(|variant| {
+                let variant_name = variant.ident;
+                backtrace_arm(
+                    &crate_root,
+                    quote! { Self::#variant_name },
+                    variant.fields,
+                    inner_location,
+                    &mut errors,
+                )
+            })
+            .collect(),
+        Data::Struct(struct_) => vec![backtrace_arm(
+            &crate_root,
+            quote! { Self },
+            struct_.fields,
+            inner_location,
+            &mut errors,
+        )],
+        Data::Union(_) => {
+            return Err(vec![syn::Error::new(
+                span,
+                "Can only derive `SnafuCompat` for an enum or a struct",
+            )])
+        }
+    };
+
+    errors.finish()?;
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #[allow(single_use_lifetimes)]
+        impl #impl_generics #crate_root::ErrorCompat for #ident #ty_generics #where_clause {
+            fn backtrace(&self) -> ::core::option::Option<&#crate_root::Backtrace> {
+                match *self {
+                    #(#arms),*
+                }
+            }
+        }
+    })
+}
+
+/// Finds the backtrace field of a variant or struct: the field
+/// marked with `#[snafu(backtrace)]` or the field named `backtrace`.
+fn backtrace_arm(
+    crate_root: &UserInput,
+    pattern_ident: proc_macro2::TokenStream,
+    fields: Fields,
+    location: ErrorLocation,
+    errors: &mut SyntaxErrors,
+) -> proc_macro2::TokenStream {
+    let mut backtrace_fields = AtMostOne::new("backtrace", location);
+
+    for (index, field) in fields.into_iter().enumerate() {
+        let member = match field.ident {
+            Some(name) => Member::Named(name),
+            None => Member::Unnamed(index.into()),
+        };
+
+        let mut opted_in = None;
+        let mut opted_out = false;
+
+        for attr in snafu_attributes(field.attrs, errors) {
+            match attr {
+                (SnafuAttribute::Backtrace(tokens, true), _) => opted_in = Some(tokens),
+                (SnafuAttribute::Backtrace(_, false), _) => opted_out = true,
+                (_, location) => errors.add(
+                    location,
+                    "`SnafuCompat` only supports `backtrace` on fields",
+                ),
+            }
+        }
+
+        let is_implicit = matches!(&member, Member::Named(name) if is_implicit_backtrace(name));
+
+        match opted_in {
+            Some(tokens) => backtrace_fields.add(member, tokens),
+            None if is_implicit && !opted_out => {
+                let tokens = quote! { #member };
+                backtrace_fields.add(member, tokens);
+            }
+            None => {}
+        }
+    }
+
+    let (backtrace_field, errs) = backtrace_fields.finish();
+    errors.extend(errs);
+
+    match backtrace_field {
+        Some(member) => quote! {
+            #pattern_ident { #member: ref backtrace, .. } => {
+                #crate_root::AsBacktrace::as_backtrace(backtrace)
+            }
+        },
+        None => quote! {
+            #pattern_ident { .. } => { ::core::option::Option::None }
+        },
+    }
+}
+
+/// Parses each `#[snafu(...)]` attribute, pairing the parsed values
+/// with the attribute they came from for error reporting.
+fn snafu_attributes(
+    attrs: Vec<syn::Attribute>,
+    errors: &mut SyntaxErrors,
+) -> Vec<(SnafuAttribute, syn::Attribute)> {
+    let mut parsed = Vec::new();
+
+    for attr in attrs.into_iter().filter(|a| a.path().is_ident("snafu")) {
+        match attributes_from_syn(vec![attr.clone()]) {
+            Ok(values) => parsed.extend(values.into_iter().map(|v| (v, attr.clone()))),
+            Err(errs) => errors.extend(errs),
+        }
+    }
+
+    parsed
+}
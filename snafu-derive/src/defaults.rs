@@ -0,0 +1,184 @@
+use crate::{
+    parse::{attribute_from_tokens, attributes_from_syn},
+    Context, SnafuAttribute,
+};
+use quote::{quote, ToTokens};
+use std::mem::{self, Discriminant};
+use syn::{
+    parse::Parser, parse_quote, punctuated::Punctuated, spanned::Spanned, token, Attribute, Fields,
+    Item, ItemMod, Meta,
+};
+
+pub fn body(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let defaults = Defaults::parse(attr.into())?;
+    let item = syn::parse::<Item>(item)?;
+
+    let mut module = match item {
+        Item::Mod(
+            module @ ItemMod {
+                content: Some(_), ..
+            },
+        ) => module,
+        _ => {
+            return Err(syn::Error::new(
+                item.span(),
+                "`#[snafu::defaults]` may only be used on inline modules",
+            ))
+        }
+    };
+
+    if let Some((_, items)) = &mut module.content {
+        defaults.apply(items);
+    }
+
+    Ok(quote! { #module })
+}
+
+/// Identifies the attributes that a default is overridden by. An
+/// enum may set only one context suffix, for example, so a default
+/// suffix is skipped when the enum has its own.
+#[derive(Clone, Copy, PartialEq)]
+struct OverrideKey(Discriminant<SnafuAttribute>, Option<Discriminant<Context>>);
+
+impl OverrideKey {
+    /// Attributes that may be repeated never override a default.
+    fn of(attr: &SnafuAttribute) -> Option<Self> {
+        match attr {
+            SnafuAttribute::ContextSelectorAttr(..)
+            | SnafuAttribute::DocComment(..)
+            | SnafuAttribute::Provide(..) => None,
+            SnafuAttribute::Context(_, c) => Some(OverrideKey(
+                mem::discriminant(attr),
+                Some(mem::discriminant(c)),
+            )),
+            _ => Some(OverrideKey(mem::discriminant(attr), None)),
+        }
+    }
+}
+
+struct DefaultAttribute {
+    meta: Meta,
+    key: Option<OverrideKey>,
+    valid_on_structs: bool,
+}
+
+struct Defaults(Vec<DefaultAttribute>);
+
+impl Defaults {
+    fn parse(tokens: proc_macro2::TokenStream) -> syn::Result<Self> {
+        let metas = Punctuated::<Meta, token::Comma>::parse_terminated.parse2(tokens)?;
+
+        metas
+            .into_iter()
+            .map(|meta| {
+                let attr = attribute_from_tokens(meta.to_token_stream())?;
+                let key = OverrideKey::of(&attr);
+                let valid_on_structs = matches!(attr, SnafuAttribute::ForwardSourceBacktrace(..));
+                Ok(DefaultAttribute {
+                    meta,
+                    key,
+                    valid_on_structs,
+                })
+            })
+            .collect::<syn::Result<_>>()
+            .map(Defaults)
+    }
+
+    fn not_overridden_by<'a>(
+        &'a self,
+        existing: &'a [OverrideKey],
+    ) -> impl Iterator<Item = &'a DefaultAttribute> + 'a {
+        self.0.iter().filter(move |d| match d.key {
+            Some(key) => !existing.contains(&key),
+            None => true,
+        })
+    }
+
+    fn apply(&self, items: &mut [Item]) {
+        for item in items {
+            match item {
+                Item::Enum(e) if derives_snafu(&e.attrs) => self.add_missing(&mut e.attrs, false),
+                // Many enum attributes are not valid on structs, so
+                // structs only receive the few that are.
+                Item::Struct(s)
+                    if derives_snafu(&s.attrs) && matches!(s.fields, Fields::Named(_)) =>
+                {
+                    self.add_missing(&mut s.attrs, true)
+                }
+                Item::Mod(m) => self.apply_to_module(m),
+                _ => {}
+            }
+        }
+    }
+
+    fn add_missing(&self, attrs: &mut Vec<Attribute>, is_struct: bool) {
+        // Invalid attributes are left for the derive to report.
+        let existing = match attributes_from_syn(attrs.clone()) {
+            Ok(existing) => existing,
+            Err(_) => return,
+        };
+        let existing: Vec<_> = existing.iter().filter_map(OverrideKey::of).collect();
+
+        // Appended so that the derive has already introduced the
+        // `snafu` helper attribute.
+        for default in self.not_overridden_by(&existing) {
+            if is_struct && !default.valid_on_structs {
+                continue;
+            }
+            let meta = &default.meta;
+            attrs.push(parse_quote! { #[snafu(#meta)] });
+        }
+    }
+
+    fn apply_to_module(&self, module: &mut ItemMod) {
+        // A nested `defaults` is expanded after this one, so it is
+        // given the defaults that it does not override instead.
+        if let Some(attr) = module.attrs.iter_mut().find(|a| is_defaults(a)) {
+            let inner = match &attr.meta {
+                Meta::Path(_) => Defaults(Vec::new()),
+                Meta::List(list) => match Defaults::parse(list.tokens.clone()) {
+                    Ok(inner) => inner,
+                    Err(_) => return,
+                },
+                Meta::NameValue(_) => return,
+            };
+            let existing: Vec<_> = inner.0.iter().filter_map(|d| d.key).collect();
+
+            let path = attr.path().clone();
+            let metas = inner
+                .0
+                .iter()
+                .chain(self.not_overridden_by(&existing))
+                .map(|d| &d.meta);
+
+            *attr = parse_quote! { #[#path(#(#metas),*)] };
+            return;
+        }
+
+        if let Some((_, items)) = &mut module.content {
+            self.apply(items);
+        }
+    }
+}
+
+fn derives_snafu(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::Path, token::Comma>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|path| path.segments.last().map_or(false, |s| s.ident == "Snafu"))
+}
+
+fn is_defaults(attr: &Attribute) -> bool {
+    attr.path()
+        .segments
+        .last()
+        .map_or(false, |s| s.ident == "defaults")
+}
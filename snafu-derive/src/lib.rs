@@ -5,10 +5,18 @@ extern crate proc_macro;
 use crate::parse::attributes_from_syn;
 use proc_macro::TokenStream;
 use quote::quote;
+use snafu_derive_internals::model::{
+    is_implicit_backtrace, is_implicit_source, FieldAttributes, FieldRole,
+};
+use snafu_derive_internals::{
+    parse, private_visibility, CauseKind, Context, Display, DisplayPrefix, DisplayTemplate,
+    ErrorKind, HttpStatus, ModuleName, MultiSynResult, Provide, ProvideKind, SnafuAttribute,
+    Source, SuffixKind, UserInput, Validation,
+};
 use std::collections::{BTreeSet, VecDeque};
 use std::fmt;
+use syn::ext::IdentExt;
 
-mod parse;
 mod shared;
 
 // The snafu crate re-exports this and adds useful documentation.
@@ -19,6 +27,14 @@ pub fn snafu_derive(input: TokenStream) -> TokenStream {
     impl_snafu_macro(ast)
 }
 
+mod compat;
+#[proc_macro_derive(SnafuCompat, attributes(snafu))]
+pub fn snafu_compat_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse(input).expect("Could not parse type to derive ErrorCompat for");
+
+    compat::body(ast).unwrap_or_else(to_compile_errors).into()
+}
+
 mod report;
 #[proc_macro_attribute]
 pub fn report(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -27,16 +43,15 @@ pub fn report(attr: TokenStream, item: TokenStream) -> TokenStream {
         .into()
 }
 
-type MultiSynResult<T> = std::result::Result<T, Vec<syn::Error>>;
-
-/// Some arbitrary tokens we treat as a black box
-type UserInput = Box<dyn quote::ToTokens>;
-
-enum ModuleName {
-    Default,
-    Custom(syn::Ident),
+mod defaults;
+#[proc_macro_attribute]
+pub fn defaults(attr: TokenStream, item: TokenStream) -> TokenStream {
+    defaults::body(attr, item)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
+#[allow(clippy::large_enum_variant)]
 enum SnafuInfo {
     Enum(EnumInfo),
     NamedStruct(NamedStructInfo),
@@ -48,26 +63,62 @@ struct EnumInfo {
     name: syn::Ident,
     generics: syn::Generics,
     variants: Vec<FieldContainer>,
-    default_visibility: Option<UserInput>,
+    default_visibility: Option<syn::Visibility>,
     default_suffix: SuffixKind,
     module: Option<ModuleName>,
+    error_impl_module: Option<syn::Path>,
+    discriminant_type: Option<syn::Ident>,
+    compact_backtrace_debug: bool,
+    record_fields: bool,
+    compat_std_error: bool,
+    assert_send_sync: bool,
+    impl_from_str: bool,
+    kinds: Option<syn::Visibility>,
+    bounds: Vec<syn::WherePredicate>,
 }
 
 /// A struct or enum variant, with named fields.
 struct FieldContainer {
     name: syn::Ident,
+    field_names: Vec<syn::Ident>,
     backtrace_field: Option<Field>,
     implicit_fields: Vec<Field>,
+    source_display_fields: Vec<Field>,
+    ignored_fields: Vec<IgnoredField>,
+    field_display_withs: Vec<(syn::Ident, syn::Expr)>,
     selector_kind: ContextSelectorKind,
     display_format: Option<Display>,
+    display_no_source: bool,
     doc_comment: Option<DocComment>,
-    visibility: Option<UserInput>,
+    visibility: Option<syn::Visibility>,
     module: Option<ModuleName>,
     provides: Vec<Provide>,
     is_transparent: bool,
+    cause_kind: Option<CauseKind>,
+    error_kind: Option<ErrorKind>,
+    error_description: Option<syn::LitStr>,
+    display_context: bool,
+    display_location: Option<syn::Ident>,
+    display_inherit: Option<syn::Path>,
+    display_prefix: Option<syn::LitStr>,
+    http_status: Option<HttpStatus>,
+    ffi_code: Option<syn::Expr>,
+    parse_with: Option<syn::Expr>,
+    code: Option<syn::LitStr>,
+    from_str_template: Option<FromStrTemplate>,
+    validations: Vec<Validation>,
 }
 
 impl FieldContainer {
+    /// The code of this variant's kind for `#[snafu(kinds)]`: either
+    /// the one from `#[snafu(code = "...")]` or the variant's name.
+    fn kind_code(&self) -> String {
+        match &self.code {
+            Some(code) => code.value(),
+            None => self.name.to_string(),
+        }
+    }
+
     fn user_fields(&self) -> &[Field] {
         self.selector_kind.user_fields()
     }
@@ -77,43 +128,23 @@ impl FieldContainer {
     }
 }
 
-struct Provide {
-    is_chain: bool,
-    is_opt: bool,
-    is_priority: bool,
-    is_ref: bool,
-    ty: syn::Type,
-    expr: syn::Expr,
-}
-
-enum SuffixKind {
-    Default,
-    None,
-    Some(syn::Ident),
-}
-
-impl SuffixKind {
-    fn resolve_with_default<'a>(&'a self, def: &'a Self) -> &'a Self {
-        use SuffixKind::*;
-
-        match self {
-            Default => def,
-            None => self,
-            Some(_) => self,
-        }
-    }
-}
-
+#[allow(clippy::large_enum_variant)]
 enum ContextSelectorKind {
     Context {
         suffix: SuffixKind,
         source_field: Option<SourceField>,
         user_fields: Vec<Field>,
+        auto_from: bool,
+        attrs: Vec<syn::Meta>,
+        derive_deserialize: bool,
+        builder: bool,
     },
 
     Whatever {
         source_field: Option<SourceField>,
         message_field: Field,
+        absorbs_whatever: bool,
+        error_chain_limit: Option<syn::LitInt>,
     },
 
     NoContext {
@@ -153,16 +184,29 @@ impl ContextSelectorKind {
 
 struct NamedStructInfo {
     crate_root: UserInput,
+    error_impl_module: Option<syn::Path>,
+    compact_backtrace_debug: bool,
+    record_fields: bool,
+    compat_std_error: bool,
+    assert_send_sync: bool,
+    impl_from_str: bool,
+    bounds: Vec<syn::WherePredicate>,
     field_container: FieldContainer,
     generics: syn::Generics,
 }
 
 struct TupleStructInfo {
     crate_root: UserInput,
+    error_impl_module: Option<syn::Path>,
     name: syn::Ident,
     generics: syn::Generics,
     transformation: Transformation,
     provides: Vec<Provide>,
+    inner_visibility: syn::Visibility,
+    transparent_source: bool,
+    compat_std_error: bool,
+    assert_send_sync: bool,
+    bounds: Vec<syn::WherePredicate>,
 }
 
 #[derive(Clone)]
@@ -179,11 +223,38 @@ impl Field {
     }
 }
 
+/// A field that is left out of the context selector and filled in
+/// when the error is created.
+pub(crate) struct IgnoredField {
+    field: Field,
+    fill: FieldFill,
+}
+
+/// The value used to fill in an ignored field.
+pub(crate) enum FieldFill {
+    Default,
+    Expression(syn::Expr),
+    Function(syn::Expr),
+    PhantomData,
+}
+
+/// How `impl_from_str` recreates a variant or struct from its
+/// display text.
+struct FromStrTemplate {
+    /// The literal text before, between, and after each placeholder.
+    literals: Vec<String>,
+    /// Each field to parse and the placeholder that holds its text.
+    fields: Vec<(Field, usize)>,
+}
+
 struct SourceField {
     name: syn::Ident,
     transformation: Transformation,
+    is_arc: bool,
+    is_reference: bool,
     backtrace_delegate: bool,
     provide: bool,
+    accessor: bool,
 }
 
 impl SourceField {
@@ -192,6 +263,7 @@ impl SourceField {
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 enum Transformation {
     None {
         ty: syn::Type,
@@ -226,11 +298,6 @@ impl Transformation {
     }
 }
 
-enum ProvideKind {
-    Flag(bool),
-    Expression(Provide),
-}
-
 /// SyntaxErrors is a convenience wrapper for a list of syntax errors discovered while parsing
 /// something that derives Snafu.  It makes it easier for developers to add and return syntax
 /// errors while walking through the parse tree.
@@ -492,7 +559,7 @@ where
     ///
     /// This can help with additional manual error checks beyond the duplication checks that
     /// `AtMostOne` handles for you.
-    fn iter(&self) -> std::collections::vec_deque::Iter<(T, U)> {
+    fn iter(&self) -> std::collections::vec_deque::Iter<'_, (T, U)> {
         self.values.iter()
     }
 
@@ -534,14 +601,16 @@ fn parse_snafu_information(ty: syn::DeriveInput) -> MultiSynResult<SnafuInfo> {
     let span = ty.span();
     let syn::DeriveInput {
         ident,
+        vis,
         generics,
         data,
         attrs,
-        ..
     } = ty;
 
     match data {
-        Data::Enum(enum_) => parse_snafu_enum(enum_, ident, generics, attrs).map(SnafuInfo::Enum),
+        Data::Enum(enum_) => {
+            parse_snafu_enum(enum_, ident, vis, generics, attrs).map(SnafuInfo::Enum)
+        }
         Data::Struct(struct_) => parse_snafu_struct(struct_, ident, generics, attrs, span),
         _ => Err(vec![syn::Error::new(
             span,
@@ -575,6 +644,16 @@ const ATTR_SOURCE_FROM: OnlyValidOn = OnlyValidOn {
     valid_on: "enum variant or struct fields with a name",
 };
 
+const ATTR_SOURCE_ARC: OnlyValidOn = OnlyValidOn {
+    attribute: "source(arc)",
+    valid_on: "enum variant or struct fields with a name",
+};
+
+const ATTR_SOURCE_ACCESSOR: OnlyValidOn = OnlyValidOn {
+    attribute: "source(accessor)",
+    valid_on: "enum variant or struct fields with a name",
+};
+
 const ATTR_BACKTRACE: OnlyValidOn = OnlyValidOn {
     attribute: "backtrace",
     valid_on: "enum variant or struct fields with a name",
@@ -590,6 +669,21 @@ const ATTR_IMPLICIT: OnlyValidOn = OnlyValidOn {
     valid_on: "enum variant or struct fields with a name",
 };
 
+const ATTR_IGNORE_FIELD: OnlyValidOn = OnlyValidOn {
+    attribute: "ignore_field",
+    valid_on: "enum variant or struct fields with a name",
+};
+
+const ATTR_PHANTOM_DATA: OnlyValidOn = OnlyValidOn {
+    attribute: "phantom_data",
+    valid_on: "enum variant or struct fields with a name",
+};
+
+const ATTR_FROM_SOURCE_DISPLAY: OnlyValidOn = OnlyValidOn {
+    attribute: "from_source_display",
+    valid_on: "enum variant or struct fields with a name",
+};
+
 const ATTR_IMPLICIT_FALSE: DoesNothing = DoesNothing {
     attribute: "implicit(false)",
 };
@@ -634,11 +728,161 @@ const ATTR_WHATEVER: OnlyValidOn = OnlyValidOn {
     valid_on: "enum variants or structs with named fields",
 };
 
+const ATTR_ABSORB_WHATEVER: OnlyValidOn = OnlyValidOn {
+    attribute: "absorb_whatever",
+    valid_on: "enum variants or structs with named fields",
+};
+
+const ATTR_AUTO_FROM: OnlyValidOn = OnlyValidOn {
+    attribute: "auto_from",
+    valid_on: "an enum, enum variants, or structs with named fields",
+};
+
+const ATTR_CONTEXT_SELECTOR_ATTR: OnlyValidOn = OnlyValidOn {
+    attribute: "context_selector_attr",
+    valid_on: "an enum, enum variants, or structs with named fields",
+};
+
+const ATTR_CONTEXT_DERIVE: OnlyValidOn = OnlyValidOn {
+    attribute: "context(derive(...))",
+    valid_on: "an enum, enum variants, or structs with named fields",
+};
+
+const ATTR_CONTEXT_BUILDER: OnlyValidOn = OnlyValidOn {
+    attribute: "context(builder)",
+    valid_on: "an enum, enum variants, or structs with named fields",
+};
+
 const ATTR_CRATE_ROOT: OnlyValidOn = OnlyValidOn {
     attribute: "crate_root",
     valid_on: "an enum or a struct",
 };
 
+const ATTR_BOUND: OnlyValidOn = OnlyValidOn {
+    attribute: "bound",
+    valid_on: "an enum or a struct",
+};
+
+const ATTR_CAUSE_KIND: OnlyValidOn = OnlyValidOn {
+    attribute: "cause_kind",
+    valid_on: "enum variants or structs with named fields",
+};
+
+const ATTR_KIND: OnlyValidOn = OnlyValidOn {
+    attribute: "kind",
+    valid_on: "enum variants or structs with named fields",
+};
+
+const ATTR_ERROR_CHAIN_LIMIT: OnlyValidOn = OnlyValidOn {
+    attribute: "error_chain_limit",
+    valid_on: "enum variants or structs with named fields",
+};
+
+const ATTR_ERROR_DESCRIPTION: OnlyValidOn = OnlyValidOn {
+    attribute: "error_description",
+    valid_on: "enum variants or structs with named fields",
+};
+
+const ATTR_DISPLAY_CONTEXT: OnlyValidOn = OnlyValidOn {
+    attribute: "display_context",
+    valid_on: "enum variants or structs with named fields",
+};
+
+const ATTR_DISPLAY_INHERIT: OnlyValidOn = OnlyValidOn {
+    attribute: "display_inherit",
+    valid_on: "enum variants or structs with named fields",
+};
+
+const ATTR_DISPLAY_LOCATION: OnlyValidOn = OnlyValidOn {
+    attribute: "display_location",
+    valid_on: "an enum, enum variants, or structs with named fields",
+};
+
+const ATTR_DISPLAY_PREFIX: OnlyValidOn = OnlyValidOn {
+    attribute: "display_prefix",
+    valid_on: "an enum",
+};
+
+const ATTR_DISPLAY_PREFIX_FLAG: OnlyValidOn = OnlyValidOn {
+    attribute: "display_prefix(bool)",
+    valid_on: "enum variants",
+};
+
+const ATTR_HTTP_STATUS: OnlyValidOn = OnlyValidOn {
+    attribute: "http_status",
+    valid_on: "enum variants or structs with named fields",
+};
+
+const ATTR_FFI_CODE: OnlyValidOn = OnlyValidOn {
+    attribute: "ffi_code",
+    valid_on: "enum variants or structs with named fields",
+};
+
+const ATTR_PARSE_WITH: OnlyValidOn = OnlyValidOn {
+    attribute: "parse_with",
+    valid_on: "enum variants or structs with named fields",
+};
+
+const ATTR_CODE: OnlyValidOn = OnlyValidOn {
+    attribute: "code",
+    valid_on: "enum variants",
+};
+
+const ATTR_DEBUG: OnlyValidOn = OnlyValidOn {
+    attribute: "debug",
+    valid_on: "an enum or a struct with named fields",
+};
+
+const ATTR_DEFAULT_VALUE: OnlyValidOn = OnlyValidOn {
+    attribute: "default",
+    valid_on: "fields of `whatever` errors",
+};
+
+const ATTR_OPAQUE: OnlyValidOn = OnlyValidOn {
+    attribute: "opaque",
+    valid_on: "tuple structs",
+};
+
+const ATTR_RECORD_FIELDS: OnlyValidOn = OnlyValidOn {
+    attribute: "record_fields",
+    valid_on: "an enum or a struct with named fields",
+};
+
+const ATTR_VALIDATE: OnlyValidOn = OnlyValidOn {
+    attribute: "validate",
+    valid_on: "enum variants or structs with named fields",
+};
+
+const ATTR_COMPAT_STD_ERROR: OnlyValidOn = OnlyValidOn {
+    attribute: "compat_std_error",
+    valid_on: "an enum or a struct",
+};
+
+const ATTR_ASSERT_SEND_SYNC: OnlyValidOn = OnlyValidOn {
+    attribute: "assert_send_sync",
+    valid_on: "an enum or a struct",
+};
+
+const ATTR_IMPL_FROM_STR: OnlyValidOn = OnlyValidOn {
+    attribute: "impl_from_str",
+    valid_on: "an enum or a struct with named fields",
+};
+
+const ATTR_KINDS: OnlyValidOn = OnlyValidOn {
+    attribute: "kinds",
+    valid_on: "an enum",
+};
+
+const ATTR_FORWARD_SOURCE_BACKTRACE: OnlyValidOn = OnlyValidOn {
+    attribute: "forward_source_backtrace",
+    valid_on: "an enum, enum variants, or structs with named fields",
+};
+
+const ATTR_ERROR_IMPL: OnlyValidOn = OnlyValidOn {
+    attribute: "error_impl",
+    valid_on: "an enum or a struct",
+};
+
 const ATTR_TRANSPARENT: OnlyValidOn = OnlyValidOn {
     attribute: "transparent",
     valid_on: "enum variants or structs with named fields",
@@ -651,9 +895,13 @@ const ATTR_TRANSPARENT_FALSE: DoesNothing = DoesNothing {
 const SOURCE_BOOL_FROM_INCOMPATIBLE: IncompatibleAttributes =
     IncompatibleAttributes(&["source(false)", "source(from)"]);
 
+const SOURCE_BOOL_ARC_INCOMPATIBLE: IncompatibleAttributes =
+    IncompatibleAttributes(&["source(false)", "source(arc)"]);
+
 fn parse_snafu_enum(
     enum_: syn::DataEnum,
     name: syn::Ident,
+    vis: syn::Visibility,
     generics: syn::Generics,
     attrs: Vec<syn::Attribute>,
 ) -> MultiSynResult<EnumInfo> {
@@ -666,23 +914,60 @@ fn parse_snafu_enum(
     let mut default_visibilities = AtMostOne::new("visibility", ErrorLocation::OnEnum);
     let mut default_suffixes = AtMostOne::new("context(suffix)", ErrorLocation::OnEnum);
     let mut crate_roots = AtMostOne::new("crate_root", ErrorLocation::OnEnum);
+    let mut error_impl_modules = AtMostOne::new("error_impl", ErrorLocation::OnEnum);
+    let mut debugs = AtMostOne::new("debug", ErrorLocation::OnEnum);
+    let mut record_fields = AtMostOne::new("record_fields", ErrorLocation::OnEnum);
+    let mut compat_std_errors = AtMostOne::new("compat_std_error", ErrorLocation::OnEnum);
+    let mut assert_send_syncs = AtMostOne::new("assert_send_sync", ErrorLocation::OnEnum);
+    let mut impl_from_strs = AtMostOne::new("impl_from_str", ErrorLocation::OnEnum);
+    let mut kinds = AtMostOne::new("kinds", ErrorLocation::OnEnum);
+    let mut forward_source_backtraces =
+        AtMostOne::new("forward_source_backtrace", ErrorLocation::OnEnum);
+    let mut display_locations = AtMostOne::new("display_location", ErrorLocation::OnEnum);
+    let mut display_no_sources = AtMostOne::new("display(no_source)", ErrorLocation::OnEnum);
+    let mut display_prefixes = AtMostOne::new("display_prefix", ErrorLocation::OnEnum);
+    let mut auto_froms = AtMostOne::new("auto_from", ErrorLocation::OnEnum);
+    let mut context_selector_attrs = Vec::new();
+    let mut bounds = Vec::new();
+    let mut context_derives = AtMostOne::new("context(derive(...))", ErrorLocation::OnEnum);
+    let mut context_builders = AtMostOne::new("context(builder)", ErrorLocation::OnEnum);
     let mut enum_errors = errors.scoped(ErrorLocation::OnEnum);
 
+    let repr_type = repr_integer_type(&attrs);
+
     for attr in attributes_from_syn(attrs)? {
         use SnafuAttribute as Att;
 
         match attr {
             Att::Visibility(tokens, v) => default_visibilities.add(v, tokens),
             Att::Display(tokens, ..) => enum_errors.add(tokens, ATTR_DISPLAY),
+            Att::DisplayNoSource(tokens) => display_no_sources.add((), tokens),
             Att::Source(tokens, ss) => {
                 for s in ss {
                     match s {
                         Source::Flag(..) => enum_errors.add(tokens.clone(), ATTR_SOURCE_BOOL),
                         Source::From(..) => enum_errors.add(tokens.clone(), ATTR_SOURCE_FROM),
+                        Source::Arc => enum_errors.add(tokens.clone(), ATTR_SOURCE_ARC),
+                        Source::Accessor => enum_errors.add(tokens.clone(), ATTR_SOURCE_ACCESSOR),
                     }
                 }
             }
             Att::CrateRoot(tokens, root) => crate_roots.add(root, tokens),
+            Att::Bound(_tokens, b) => bounds.extend(b),
+            Att::ErrorImpl(tokens, m) => error_impl_modules.add(m, tokens),
+            Att::Debug(tokens) => debugs.add((), tokens),
+            Att::DefaultValue(tokens, ..) => enum_errors.add(tokens, ATTR_DEFAULT_VALUE),
+            Att::RecordFields(tokens) => record_fields.add((), tokens),
+            Att::CompatStdError(tokens) => compat_std_errors.add((), tokens),
+            Att::AssertSendSync(tokens) => assert_send_syncs.add((), tokens),
+            Att::ImplFromStr(tokens) => impl_from_strs.add((), tokens),
+            Att::ParseWith(tokens, ..) => enum_errors.add(tokens, ATTR_PARSE_WITH),
+            Att::Kinds(tokens) => kinds.add((), tokens),
+            Att::Code(tokens, ..) => enum_errors.add(tokens, ATTR_CODE),
+            Att::Opaque(tokens) => enum_errors.add(tokens, ATTR_OPAQUE),
+            Att::PhantomData(tokens) => enum_errors.add(tokens, ATTR_PHANTOM_DATA),
+            Att::ForwardSourceBacktrace(tokens, f) => forward_source_backtraces.add(f, tokens),
+            Att::DisplayLocation(tokens, d) => display_locations.add(d, tokens),
             Att::Context(tokens, c) => match c {
                 Context::Suffix(s) => default_suffixes.add(s, tokens),
                 Context::Flag(_) => enum_errors.add(tokens, ATTR_CONTEXT_FLAG),
@@ -695,9 +980,29 @@ fn parse_snafu_enum(
                 enum_errors.add(tokens, ATTR_PROVIDE_EXPRESSION)
             }
             Att::Backtrace(tokens, ..) => enum_errors.add(tokens, ATTR_BACKTRACE),
+            Att::CauseKind(tokens, ..) => enum_errors.add(tokens, ATTR_CAUSE_KIND),
+            Att::Kind(tokens, ..) => enum_errors.add(tokens, ATTR_KIND),
+            Att::ErrorChainLimit(tokens, ..) => enum_errors.add(tokens, ATTR_ERROR_CHAIN_LIMIT),
+            Att::ErrorDescription(tokens, ..) => enum_errors.add(tokens, ATTR_ERROR_DESCRIPTION),
+            Att::DisplayContext(tokens) => enum_errors.add(tokens, ATTR_DISPLAY_CONTEXT),
+            Att::DisplayInherit(tokens, ..) => enum_errors.add(tokens, ATTR_DISPLAY_INHERIT),
+            Att::DisplayPrefix(tokens, p) => match p {
+                DisplayPrefix::Prefix(p) => display_prefixes.add(p, tokens),
+                DisplayPrefix::Flag(_) => enum_errors.add(tokens, ATTR_DISPLAY_PREFIX_FLAG),
+            },
+            Att::HttpStatus(tokens, ..) => enum_errors.add(tokens, ATTR_HTTP_STATUS),
+            Att::FfiCode(tokens, ..) => enum_errors.add(tokens, ATTR_FFI_CODE),
             Att::Implicit(tokens, ..) => enum_errors.add(tokens, ATTR_IMPLICIT),
+            Att::FromSourceDisplay(tokens) => enum_errors.add(tokens, ATTR_FROM_SOURCE_DISPLAY),
+            Att::IgnoreField(tokens, ..) => enum_errors.add(tokens, ATTR_IGNORE_FIELD),
             Att::Transparent(tokens, ..) => enum_errors.add(tokens, ATTR_TRANSPARENT),
+            Att::Validate(tokens, ..) => enum_errors.add(tokens, ATTR_VALIDATE),
             Att::Whatever(tokens) => enum_errors.add(tokens, ATTR_WHATEVER),
+            Att::AbsorbWhatever(tokens) => enum_errors.add(tokens, ATTR_ABSORB_WHATEVER),
+            Att::AutoFrom(tokens) => auto_froms.add((), tokens),
+            Att::ContextSelectorAttr(_tokens, m) => context_selector_attrs.push(m),
+            Att::ContextDerive(tokens) => context_derives.add((), tokens),
+            Att::ContextBuilder(tokens) => context_builders.add((), tokens),
             Att::DocComment(..) => { /* Just a regular doc comment. */ }
         }
     }
@@ -716,10 +1021,65 @@ fn parse_snafu_enum(
     let crate_root = maybe_crate_root.unwrap_or_else(default_crate_root);
     errors.extend(errs);
 
+    let (error_impl_module, errs) = error_impl_modules.finish();
+    errors.extend(errs);
+
+    let (compact_backtrace_debug, errs) = debugs.finish();
+    errors.extend(errs);
+
+    let (record_fields, errs) = record_fields.finish();
+    errors.extend(errs);
+
+    let (compat_std_error, errs) = compat_std_errors.finish();
+    errors.extend(errs);
+
+    let (assert_send_sync, errs) = assert_send_syncs.finish();
+    errors.extend(errs);
+
+    let (impl_from_str, errs) = impl_from_strs.finish();
+    let impl_from_str = impl_from_str.is_some();
+    errors.extend(errs);
+
+    let (kinds, errs) = kinds.finish();
+    let kinds = kinds.map(|()| vis);
+    errors.extend(errs);
+
+    let (forward_source_backtrace, errs) = forward_source_backtraces.finish();
+    let forward_source_backtrace = forward_source_backtrace.unwrap_or(false);
+    errors.extend(errs);
+
+    let (display_location, errs) = display_locations.finish();
+    let display_location = display_location.unwrap_or(false);
+    errors.extend(errs);
+
+    let (display_no_source, errs) = display_no_sources.finish();
+    errors.extend(errs);
+
+    let (display_prefix, errs) = display_prefixes.finish();
+    errors.extend(errs);
+
+    let (auto_from, errs) = auto_froms.finish();
+    errors.extend(errs);
+
+    let (derive_deserialize, errs) = context_derives.finish();
+    errors.extend(errs);
+
+    let (builder, errs) = context_builders.finish();
+    errors.extend(errs);
+
+    let has_discriminants = enum_.variants.iter().any(|v| v.discriminant.is_some());
+
     let variants: sponge::AllErrors<_, _> = enum_
         .variants
         .into_iter()
         .map(|variant| {
+            if has_discriminants && !matches!(variant.fields, Fields::Unit) {
+                return Err(vec![syn::Error::new(
+                    variant.fields.span(),
+                    "Can only derive `Snafu` for enums with explicit discriminants when every variant is a unit variant",
+                )]);
+            }
+
             let fields = match variant.fields {
                 Fields::Named(f) => f.named.into_iter().collect(),
                 Fields::Unnamed(_) => {
@@ -741,6 +1101,14 @@ fn parse_snafu_enum(
                 span,
                 attrs,
                 fields,
+                forward_source_backtrace,
+                display_location,
+                display_no_source.is_some(),
+                display_prefix.as_ref(),
+                auto_from.is_some(),
+                &context_selector_attrs,
+                derive_deserialize.is_some(),
+                builder.is_some(),
                 &mut errors,
                 ErrorLocation::OnVariant,
                 ErrorLocation::InVariant,
@@ -748,7 +1116,12 @@ fn parse_snafu_enum(
         })
         .collect();
 
-    let variants = errors.absorb(variants.into_result())?;
+    let mut variants: Vec<FieldContainer> = errors.absorb(variants.into_result())?;
+
+    require_on_every_variant(&name, &variants, "http_status", |v| v.http_status.is_some())?;
+    require_on_every_variant(&name, &variants, "ffi_code", |v| v.ffi_code.is_some())?;
+    prepare_from_str(&mut variants, impl_from_str)?;
+    validate_kinds(&variants, kinds.is_some())?;
 
     Ok(EnumInfo {
         crate_root,
@@ -758,14 +1131,58 @@ fn parse_snafu_enum(
         default_visibility,
         default_suffix,
         module,
+        error_impl_module,
+        discriminant_type: has_discriminants.then(|| {
+            repr_type.unwrap_or_else(|| syn::Ident::new("i32", proc_macro2::Span::call_site()))
+        }),
+        compact_backtrace_debug: compact_backtrace_debug.is_some(),
+        record_fields: record_fields.is_some(),
+        compat_std_error: compat_std_error.is_some(),
+        assert_send_sync: assert_send_sync.is_some(),
+        impl_from_str,
+        kinds,
+        bounds,
     })
 }
 
+/// Finds the integer type named in a `#[repr(...)]` attribute, if any.
+fn repr_integer_type(attrs: &[syn::Attribute]) -> Option<syn::Ident> {
+    const INTEGER_TYPES: &[&str] = &[
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    ];
+
+    let mut repr_type = None;
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        // Ignore any errors that occur while parsing the attribute;
+        // the compiler will report them.
+        let _ = attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                if INTEGER_TYPES.iter().any(|t| ident == t) {
+                    repr_type = Some(ident.clone());
+                }
+            }
+            Ok(())
+        });
+    }
+
+    repr_type
+}
+
+#[allow(clippy::too_many_arguments)]
 fn field_container(
     name: syn::Ident,
     variant_span: proc_macro2::Span,
     attrs: Vec<SnafuAttribute>,
     fields: Vec<syn::Field>,
+    default_forward_source_backtrace: bool,
+    default_display_location: bool,
+    default_display_no_source: bool,
+    default_display_prefix: Option<&syn::LitStr>,
+    default_auto_from: bool,
+    default_context_selector_attrs: &[syn::Meta],
+    default_derive_deserialize: bool,
+    default_builder: bool,
     errors: &mut SyntaxErrors,
     outer_error_location: ErrorLocation,
     inner_error_location: ErrorLocation,
@@ -777,12 +1194,33 @@ fn field_container(
 
     let mut modules = AtMostOne::new("module", outer_error_location);
     let mut display_formats = AtMostOne::new("display", outer_error_location);
+    let mut display_no_sources = AtMostOne::new("display(no_source)", outer_error_location);
     let mut visibilities = AtMostOne::new("visibility", outer_error_location);
     let mut provides = Vec::new();
+    let mut validations = Vec::new();
 
     let mut contexts = AtMostOne::new("context", outer_error_location);
     let mut whatevers = AtMostOne::new("whatever", outer_error_location);
+    let mut absorb_whatevers = AtMostOne::new("absorb_whatever", outer_error_location);
+    let mut error_chain_limits = AtMostOne::new("error_chain_limit", outer_error_location);
+    let mut auto_froms = AtMostOne::new("auto_from", outer_error_location);
+    let mut context_selector_attrs = Vec::new();
+    let mut context_derives = AtMostOne::new("context(derive(...))", outer_error_location);
+    let mut context_builders = AtMostOne::new("context(builder)", outer_error_location);
     let mut transparents = AtMostOne::new("transparent", outer_error_location);
+    let mut forward_source_backtraces =
+        AtMostOne::new("forward_source_backtrace", outer_error_location);
+    let mut display_locations = AtMostOne::new("display_location", outer_error_location);
+    let mut cause_kinds = AtMostOne::new("cause_kind", outer_error_location);
+    let mut error_kinds = AtMostOne::new("kind", outer_error_location);
+    let mut error_descriptions = AtMostOne::new("error_description", outer_error_location);
+    let mut display_contexts = AtMostOne::new("display_context", outer_error_location);
+    let mut display_inherits = AtMostOne::new("display_inherit", outer_error_location);
+    let mut display_prefix_flags = AtMostOne::new("display_prefix", outer_error_location);
+    let mut http_statuses = AtMostOne::new("http_status", outer_error_location);
+    let mut ffi_codes = AtMostOne::new("ffi_code", outer_error_location);
+    let mut parse_withs = AtMostOne::new("parse_with", outer_error_location);
+    let mut codes = AtMostOne::new("code", outer_error_location);
 
     let mut doc_comment = DocComment::default();
     let mut reached_end_of_doc_comment = false;
@@ -793,9 +1231,15 @@ fn field_container(
         match attr {
             Att::Module(tokens, n) => modules.add(n, tokens),
             Att::Display(tokens, d) => display_formats.add(d, tokens),
+            Att::DisplayNoSource(tokens) => display_no_sources.add((), tokens),
             Att::Visibility(tokens, v) => visibilities.add(v, tokens),
             Att::Context(tokens, c) => contexts.add(c, tokens),
             Att::Whatever(tokens) => whatevers.add((), tokens),
+            Att::AbsorbWhatever(tokens) => absorb_whatevers.add((), tokens),
+            Att::AutoFrom(tokens) => auto_froms.add((), tokens),
+            Att::ContextSelectorAttr(tokens, m) => context_selector_attrs.push((m, tokens)),
+            Att::ContextDerive(tokens) => context_derives.add((), tokens),
+            Att::ContextBuilder(tokens) => context_builders.add((), tokens),
             Att::Transparent(tokens, t) => {
                 if t {
                     transparents.add((), tokens)
@@ -805,8 +1249,47 @@ fn field_container(
             }
             Att::Source(tokens, ..) => outer_errors.add(tokens, ATTR_SOURCE),
             Att::Backtrace(tokens, ..) => outer_errors.add(tokens, ATTR_BACKTRACE),
+            Att::CauseKind(tokens, k) => cause_kinds.add(k, tokens),
+            Att::Kind(tokens, k) => error_kinds.add(k, tokens),
+            Att::ErrorChainLimit(tokens, l) => error_chain_limits.add(l, tokens),
+            Att::ErrorDescription(tokens, d) => error_descriptions.add(d, tokens),
+            Att::DisplayContext(tokens) => display_contexts.add((), tokens),
+            Att::DisplayInherit(tokens, p) => display_inherits.add(p, tokens),
+            Att::DisplayPrefix(tokens, DisplayPrefix::Flag(f))
+                if matches!(outer_error_location, ErrorLocation::OnVariant) =>
+            {
+                display_prefix_flags.add(f, tokens)
+            }
+            Att::DisplayPrefix(tokens, DisplayPrefix::Flag(_)) => {
+                outer_errors.add(tokens, ATTR_DISPLAY_PREFIX_FLAG)
+            }
+            Att::DisplayPrefix(tokens, DisplayPrefix::Prefix(_)) => {
+                outer_errors.add(tokens, ATTR_DISPLAY_PREFIX)
+            }
+            Att::HttpStatus(tokens, s) => http_statuses.add(s, tokens),
+            Att::FfiCode(tokens, c) => ffi_codes.add(c, tokens),
             Att::Implicit(tokens, ..) => outer_errors.add(tokens, ATTR_IMPLICIT),
+            Att::FromSourceDisplay(tokens) => outer_errors.add(tokens, ATTR_FROM_SOURCE_DISPLAY),
+            Att::IgnoreField(tokens, ..) => outer_errors.add(tokens, ATTR_IGNORE_FIELD),
             Att::CrateRoot(tokens, ..) => outer_errors.add(tokens, ATTR_CRATE_ROOT),
+            Att::Bound(tokens, ..) => outer_errors.add(tokens, ATTR_BOUND),
+            Att::ErrorImpl(tokens, ..) => outer_errors.add(tokens, ATTR_ERROR_IMPL),
+            Att::Debug(tokens) => outer_errors.add(tokens, ATTR_DEBUG),
+            Att::DefaultValue(tokens, ..) => outer_errors.add(tokens, ATTR_DEFAULT_VALUE),
+            Att::Opaque(tokens) => outer_errors.add(tokens, ATTR_OPAQUE),
+            Att::PhantomData(tokens) => outer_errors.add(tokens, ATTR_PHANTOM_DATA),
+            Att::RecordFields(tokens) => outer_errors.add(tokens, ATTR_RECORD_FIELDS),
+            Att::CompatStdError(tokens) => outer_errors.add(tokens, ATTR_COMPAT_STD_ERROR),
+            Att::AssertSendSync(tokens) => outer_errors.add(tokens, ATTR_ASSERT_SEND_SYNC),
+            Att::ImplFromStr(tokens) => outer_errors.add(tokens, ATTR_IMPL_FROM_STR),
+            Att::ParseWith(tokens, f) => parse_withs.add(f, tokens),
+            Att::Kinds(tokens) => outer_errors.add(tokens, ATTR_KINDS),
+            Att::Code(tokens, c) if matches!(outer_error_location, ErrorLocation::OnVariant) => {
+                codes.add(c, tokens)
+            }
+            Att::Code(tokens, ..) => outer_errors.add(tokens, ATTR_CODE),
+            Att::ForwardSourceBacktrace(tokens, f) => forward_source_backtraces.add(f, tokens),
+            Att::DisplayLocation(tokens, d) => display_locations.add(d, tokens),
             Att::Provide(tokens, ProvideKind::Flag(..)) => {
                 outer_errors.add(tokens, ATTR_PROVIDE_FLAG)
             }
@@ -814,6 +1297,7 @@ fn field_container(
                 // TODO: can we have improved error handling for obvious type duplicates?
                 provides.push(provide);
             }
+            Att::Validate(tokens, validation) => validations.push((validation, tokens)),
             Att::DocComment(_tts, doc_comment_line) => {
                 // We join all the doc comment attributes with a space,
                 // but end once the summary of the doc comment is
@@ -834,6 +1318,12 @@ fn field_container(
     let mut source_fields = AtMostOne::new("source", inner_error_location);
     let mut backtrace_fields = AtMostOne::new("backtrace", inner_error_location);
     let mut implicit_fields = Vec::new();
+    let mut source_display_fields = Vec::new();
+    let mut ignored_fields = Vec::new();
+    let mut defaulted_fields = Vec::new();
+    let mut field_display_withs = Vec::new();
+
+    let field_names = fields.iter().filter_map(|f| f.ident.clone()).collect();
 
     for syn_field in fields {
         let original = syn_field.clone();
@@ -854,11 +1344,20 @@ fn field_container(
         let mut source_attrs = AtMostOne::new("source", ErrorLocation::OnField);
         let mut backtrace_attrs = AtMostOne::new("backtrace", ErrorLocation::OnField);
         let mut implicit_attrs = AtMostOne::new("implicit", ErrorLocation::OnField);
+        let mut source_display_attrs =
+            AtMostOne::new("from_source_display", ErrorLocation::OnField);
         let mut provide_attrs = AtMostOne::new("provide", ErrorLocation::OnField);
+        let mut ignore_attrs = AtMostOne::new("ignore_field", ErrorLocation::OnField);
+        let mut phantom_attrs = AtMostOne::new("phantom_data", ErrorLocation::OnField);
+        let mut default_attrs = AtMostOne::new("default", ErrorLocation::OnField);
+        let mut accessor_attrs = AtMostOne::new("source(accessor)", ErrorLocation::OnField);
+        let mut display_with_attrs = AtMostOne::new("display(with)", ErrorLocation::OnField);
 
         // Keep track of the negative markers so we can check for inconsistencies and
         // exclude fields even if they have the "source" or "backtrace" name.
         let mut source_opt_out = false;
+        let mut source_arc = false;
+        let mut non_arc_source = None;
         let mut backtrace_opt_out = false;
         let mut provide_opt_out = false;
 
@@ -879,7 +1378,12 @@ fn field_container(
                                     .map(|(val, _location)| val)
                                     .any(Option::is_some);
                                 if !v && seen_source_from {
-                                    field_errors.add(tokens.clone(), SOURCE_BOOL_FROM_INCOMPATIBLE);
+                                    let incompatible = if source_arc {
+                                        SOURCE_BOOL_ARC_INCOMPATIBLE
+                                    } else {
+                                        SOURCE_BOOL_FROM_INCOMPATIBLE
+                                    };
+                                    field_errors.add(tokens.clone(), incompatible);
                                 }
                                 if v {
                                     source_attrs.add(None, tokens.clone());
@@ -895,6 +1399,23 @@ fn field_container(
                                 }
                                 source_attrs.add(Some((t, e)), tokens.clone());
                             }
+                            Source::Arc => {
+                                if source_opt_out {
+                                    field_errors.add(tokens.clone(), SOURCE_BOOL_ARC_INCOMPATIBLE);
+                                }
+                                match arc_source_type(&syn_field.ty) {
+                                    Some(source_ty) => {
+                                        // `From<T>` and `From<Box<T>>` are
+                                        // both implemented for `Arc<T>`.
+                                        let expr =
+                                            syn::parse_quote! { ::core::convert::From::from };
+                                        source_arc = true;
+                                        source_attrs.add(Some((source_ty, expr)), tokens.clone());
+                                    }
+                                    None => non_arc_source = Some(tokens.clone()),
+                                }
+                            }
+                            Source::Accessor => accessor_attrs.add((), tokens.clone()),
                         }
                     }
                 }
@@ -914,6 +1435,8 @@ fn field_container(
                         field_errors.add(tokens, ATTR_IMPLICIT_FALSE);
                     }
                 }
+                Att::FromSourceDisplay(tokens) => source_display_attrs.add((), tokens),
+                Att::IgnoreField(tokens, with) => ignore_attrs.add(with, tokens),
                 Att::Module(tokens, ..) => field_errors.add(tokens, ATTR_MODULE),
                 Att::Provide(tokens, ProvideKind::Flag(v)) => {
                     if v {
@@ -928,15 +1451,73 @@ fn field_container(
                     field_errors.add(tokens, ATTR_PROVIDE_EXPRESSION)
                 }
                 Att::Visibility(tokens, ..) => field_errors.add(tokens, ATTR_VISIBILITY),
-                Att::Display(tokens, ..) => field_errors.add(tokens, ATTR_DISPLAY),
+                Att::Display(
+                    tokens,
+                    Display {
+                        with: Some(with), ..
+                    },
+                ) => display_with_attrs.add(with, tokens),
+                Att::Display(tokens, ..) | Att::DisplayNoSource(tokens) => {
+                    field_errors.add(tokens, ATTR_DISPLAY)
+                }
                 Att::Context(tokens, ..) => field_errors.add(tokens, ATTR_CONTEXT),
+                Att::CauseKind(tokens, ..) => field_errors.add(tokens, ATTR_CAUSE_KIND),
+                Att::Kind(tokens, ..) => field_errors.add(tokens, ATTR_KIND),
+                Att::ErrorChainLimit(tokens, ..) => {
+                    field_errors.add(tokens, ATTR_ERROR_CHAIN_LIMIT)
+                }
+                Att::ErrorDescription(tokens, ..) => {
+                    field_errors.add(tokens, ATTR_ERROR_DESCRIPTION)
+                }
+                Att::HttpStatus(tokens, ..) => field_errors.add(tokens, ATTR_HTTP_STATUS),
+                Att::FfiCode(tokens, ..) => field_errors.add(tokens, ATTR_FFI_CODE),
                 Att::Transparent(tokens, ..) => field_errors.add(tokens, ATTR_TRANSPARENT),
+                Att::Validate(tokens, ..) => field_errors.add(tokens, ATTR_VALIDATE),
+                Att::DisplayContext(tokens) => field_errors.add(tokens, ATTR_DISPLAY_CONTEXT),
+                Att::DisplayInherit(tokens, ..) => field_errors.add(tokens, ATTR_DISPLAY_INHERIT),
+                Att::DisplayPrefix(tokens, DisplayPrefix::Prefix(_)) => {
+                    field_errors.add(tokens, ATTR_DISPLAY_PREFIX)
+                }
+                Att::DisplayPrefix(tokens, DisplayPrefix::Flag(_)) => {
+                    field_errors.add(tokens, ATTR_DISPLAY_PREFIX_FLAG)
+                }
                 Att::Whatever(tokens) => field_errors.add(tokens, ATTR_WHATEVER),
+                Att::AbsorbWhatever(tokens) => field_errors.add(tokens, ATTR_ABSORB_WHATEVER),
+                Att::AutoFrom(tokens) => field_errors.add(tokens, ATTR_AUTO_FROM),
+                Att::ContextSelectorAttr(tokens, ..) => {
+                    field_errors.add(tokens, ATTR_CONTEXT_SELECTOR_ATTR)
+                }
+                Att::ContextDerive(tokens) => field_errors.add(tokens, ATTR_CONTEXT_DERIVE),
+                Att::ContextBuilder(tokens) => field_errors.add(tokens, ATTR_CONTEXT_BUILDER),
                 Att::CrateRoot(tokens, ..) => field_errors.add(tokens, ATTR_CRATE_ROOT),
+                Att::Bound(tokens, ..) => field_errors.add(tokens, ATTR_BOUND),
+                Att::ErrorImpl(tokens, ..) => field_errors.add(tokens, ATTR_ERROR_IMPL),
+                Att::Debug(tokens) => field_errors.add(tokens, ATTR_DEBUG),
+                Att::DefaultValue(tokens, v) => default_attrs.add(v, tokens),
+                Att::Opaque(tokens) => field_errors.add(tokens, ATTR_OPAQUE),
+                Att::PhantomData(tokens) => phantom_attrs.add((), tokens),
+                Att::RecordFields(tokens) => field_errors.add(tokens, ATTR_RECORD_FIELDS),
+                Att::CompatStdError(tokens) => field_errors.add(tokens, ATTR_COMPAT_STD_ERROR),
+                Att::AssertSendSync(tokens) => field_errors.add(tokens, ATTR_ASSERT_SEND_SYNC),
+                Att::ImplFromStr(tokens) => field_errors.add(tokens, ATTR_IMPL_FROM_STR),
+                Att::ParseWith(tokens, ..) => field_errors.add(tokens, ATTR_PARSE_WITH),
+                Att::Kinds(tokens) => field_errors.add(tokens, ATTR_KINDS),
+                Att::Code(tokens, ..) => field_errors.add(tokens, ATTR_CODE),
+                Att::ForwardSourceBacktrace(tokens, ..) => {
+                    field_errors.add(tokens, ATTR_FORWARD_SOURCE_BACKTRACE)
+                }
+                Att::DisplayLocation(tokens, ..) => field_errors.add(tokens, ATTR_DISPLAY_LOCATION),
                 Att::DocComment(..) => { /* Just a regular doc comment. */ }
             }
         }
 
+        if let Some(tokens) = non_arc_source {
+            errors.add(
+                tokens,
+                "`source(arc)` requires the field type to be `Arc<T>`",
+            );
+        }
+
         // Add errors for any duplicated attributes on this field.
         let (source_attr, errs) = source_attrs.finish_with_location();
         errors.extend(errs);
@@ -946,9 +1527,43 @@ fn field_container(
         let (implicit_attr, errs) = implicit_attrs.finish();
         errors.extend(errs);
 
+        let (source_display_attr, errs) = source_display_attrs.finish_with_location();
+        errors.extend(errs);
+
         let (provide_attr, errs) = provide_attrs.finish();
         errors.extend(errs);
 
+        let (ignore_attr, errs) = ignore_attrs.finish_with_location();
+        errors.extend(errs);
+
+        let (phantom_attr, errs) = phantom_attrs.finish_with_location();
+        errors.extend(errs);
+
+        let (default_attr, errs) = default_attrs.finish_with_location();
+        errors.extend(errs);
+
+        let (accessor_attr, errs) = accessor_attrs.finish_with_location();
+        errors.extend(errs);
+
+        let (display_with_attr, errs) = display_with_attrs.finish();
+        errors.extend(errs);
+
+        if let Some(with) = display_with_attr {
+            field_display_withs.push((name.clone(), with));
+        }
+
+        // The accessor recovers the type the source was converted
+        // from, so there must be an explicit conversion.
+        if let Some((_, location)) = &accessor_attr {
+            let has_source_from = !source_arc && matches!(source_attr, Some((Some(_), _)));
+            if !has_source_from {
+                errors.add(
+                    location.clone(),
+                    "`source(accessor)` requires `source(from(...))` on the same field",
+                );
+            }
+        }
+
         let field = Field {
             name: name.clone(),
             ty: syn_field.ty.clone(),
@@ -956,57 +1571,114 @@ fn field_container(
             original,
         };
 
-        let source_attr = source_attr.or_else(|| {
-            if is_implicit_source(&field.name) && !source_opt_out {
-                Some((None, syn_field.clone().into_token_stream()))
-            } else {
-                None
+        let field_attributes = FieldAttributes {
+            source: source_attr.is_some(),
+            source_opt_out,
+            backtrace: backtrace_attr.is_some(),
+            backtrace_opt_out,
+            implicit: implicit_attr.is_some(),
+            from_source_display: source_display_attr.is_some(),
+            ignore_field: ignore_attr.is_some(),
+            phantom_data: phantom_attr.is_some(),
+            default: default_attr.is_some(),
+        };
+        let has_other_role = field_attributes.has_other_role();
+
+        if let Some((_, location)) = &phantom_attr {
+            if has_other_role || ignore_attr.is_some() {
+                errors.add(
+                    location.clone(),
+                    "`phantom_data` cannot be combined with `ignore_field`, `source`, `backtrace`, `implicit`, or `from_source_display`",
+                );
             }
-        });
+        }
 
-        let backtrace_attr = backtrace_attr.or_else(|| {
-            if is_implicit_backtrace(&field.name) && !backtrace_opt_out {
-                Some(((), syn_field.clone().into_token_stream()))
-            } else {
-                None
+        // Whether the field may have a default depends on the kind of
+        // selector, which isn't known until every field is seen.
+        if let Some((_, location)) = &default_attr {
+            if has_other_role || ignore_attr.is_some() || phantom_attr.is_some() {
+                errors.add(
+                    location.clone(),
+                    "`default` cannot be combined with `ignore_field`, `phantom_data`, `source`, `backtrace`, `implicit`, or `from_source_display`",
+                );
             }
-        });
+        } else if let Some((_, location)) = &ignore_attr {
+            if has_other_role {
+                errors.add(
+                    location.clone(),
+                    "`ignore_field` cannot be combined with `source`, `backtrace`, `implicit`, or `from_source_display`",
+                );
+            }
+        }
 
-        let implicit_attr = implicit_attr.is_some();
-
-        if let Some((maybe_transformation, location)) = source_attr {
-            let Field {
-                name, ty, provide, ..
-            } = field;
-            let transformation = maybe_transformation
-                .map(|(source_ty, expr)| Transformation::Transform {
-                    source_ty,
-                    target_ty: ty.clone(),
-                    expr,
-                })
-                .unwrap_or_else(|| Transformation::None { ty });
+        // Fields that are a source or backtrace only by their name
+        // are reported at the field itself.
+        let field_location = || syn_field.clone().into_token_stream();
 
-            source_fields.add(
-                SourceField {
-                    name,
-                    transformation,
-                    // Specifying `backtrace` on a source field is how you request
-                    // delegation of the backtrace to the source error type.
-                    backtrace_delegate: backtrace_attr.is_some(),
-                    provide,
-                },
-                location,
-            );
-        } else if let Some((_, location)) = backtrace_attr {
-            backtrace_fields.add(field, location);
-        } else if implicit_attr {
-            implicit_fields.push(field);
-        } else {
-            user_fields.push(field);
+        match field_attributes.role(name, &field.ty) {
+            FieldRole::Defaulted => {
+                if let Some((value, location)) = default_attr {
+                    let fill = FieldFill::Expression(value);
+                    defaulted_fields.push((IgnoredField { field, fill }, location));
+                }
+            }
+            FieldRole::Ignored => {
+                let fill = match ignore_attr {
+                    Some((Some(with), _)) => FieldFill::Function(with),
+                    _ => FieldFill::Default,
+                };
+                ignored_fields.push(IgnoredField { field, fill });
+            }
+            FieldRole::PhantomData => {
+                ignored_fields.push(IgnoredField {
+                    field,
+                    fill: FieldFill::PhantomData,
+                });
+            }
+            FieldRole::Source { backtrace } => {
+                let (maybe_transformation, location) =
+                    source_attr.unwrap_or_else(|| (None, field_location()));
+                let Field {
+                    name, ty, provide, ..
+                } = field;
+                let transformation = maybe_transformation
+                    .map(|(source_ty, expr)| Transformation::Transform {
+                        source_ty,
+                        target_ty: ty.clone(),
+                        expr,
+                    })
+                    .unwrap_or_else(|| Transformation::None { ty });
+                let is_reference = matches!(transformation.target_ty(), syn::Type::Reference(_));
+
+                source_fields.add(
+                    SourceField {
+                        name,
+                        transformation,
+                        is_arc: source_arc,
+                        is_reference,
+                        // Specifying `backtrace` on a source field is how you request
+                        // delegation of the backtrace to the source error type.
+                        backtrace_delegate: backtrace,
+                        provide,
+                        accessor: accessor_attr.is_some(),
+                    },
+                    location,
+                );
+            }
+            FieldRole::Backtrace => {
+                let location = backtrace_attr.map_or_else(field_location, |(_, l)| l);
+                backtrace_fields.add(field, location);
+            }
+            FieldRole::SourceDisplay => {
+                let location = source_display_attr.map_or_else(field_location, |(_, l)| l);
+                source_display_fields.push((field, location));
+            }
+            FieldRole::Implicit => implicit_fields.push(field),
+            FieldRole::User => user_fields.push(field),
         }
     }
 
-    let (source, errs) = source_fields.finish_with_location();
+    let (mut source, errs) = source_fields.finish_with_location();
     errors.extend(errs);
 
     let (backtrace, errs) = backtrace_fields.finish_with_location();
@@ -1045,26 +1717,174 @@ fn field_container(
     let (is_whatever, errs) = whatevers.finish_with_location();
     errors.extend(errs);
 
-    let (is_transparent, errs) = transparents.finish_with_location();
+    let (absorbs_whatever, errs) = absorb_whatevers.finish_with_location();
     errors.extend(errs);
 
-    if let (Some((_, d_tt)), Some((_, t_tt))) = (&display_format, &is_transparent) {
-        let txt = "`transparent` errors cannot have a display format because they delegate `Display` to their source";
-        errors.extend([
-            syn::Error::new_spanned(d_tt, txt),
-            syn::Error::new_spanned(t_tt, txt),
-        ]);
+    let (error_chain_limit, errs) = error_chain_limits.finish_with_location();
+    errors.extend(errs);
+
+    let (auto_from, errs) = auto_froms.finish_with_location();
+    errors.extend(errs);
+
+    let (context_derive, errs) = context_derives.finish_with_location();
+    errors.extend(errs);
+
+    let (context_builder, errs) = context_builders.finish_with_location();
+    errors.extend(errs);
+
+    if let (Some((_, a_tt)), None) = (&absorbs_whatever, &is_whatever) {
+        errors.add(
+            a_tt,
+            "`absorb_whatever` is only valid on errors that are also `whatever`",
+        );
     }
 
-    match (&is_context, &is_transparent) {
-        (Some(((true, _), c_tt)), Some((_, t_tt))) => {
-            let txt = "`transparent` errors cannot have context";
-            errors.extend([
-                syn::Error::new_spanned(c_tt, txt),
-                syn::Error::new_spanned(t_tt, txt),
-            ]);
-        }
-        (None, Some((_, t_tt))) => {
+    if let (Some((_, l_tt)), None) = (&error_chain_limit, &is_whatever) {
+        errors.add(
+            l_tt,
+            "`error_chain_limit` is only valid on errors that are also `whatever`",
+        );
+    }
+
+    let (forward_source_backtrace, errs) = forward_source_backtraces.finish();
+    let forward_source_backtrace =
+        forward_source_backtrace.unwrap_or(default_forward_source_backtrace);
+    errors.extend(errs);
+
+    let (cause_kind, errs) = cause_kinds.finish();
+    errors.extend(errs);
+
+    let (error_kind, errs) = error_kinds.finish();
+    errors.extend(errs);
+
+    let (error_description, errs) = error_descriptions.finish();
+    errors.extend(errs);
+
+    let (display_inherit, errs) = display_inherits.finish_with_location();
+    errors.extend(errs);
+
+    let (http_status, errs) = http_statuses.finish();
+    errors.extend(errs);
+
+    let (ffi_code, errs) = ffi_codes.finish();
+    errors.extend(errs);
+
+    let (parse_with, errs) = parse_withs.finish();
+    errors.extend(errs);
+
+    let (code, errs) = codes.finish();
+    errors.extend(errs);
+
+    // An explicit backtrace field captures its own backtrace, and the
+    // optional source of a `whatever` error cannot be delegated to.
+    if forward_source_backtrace && backtrace.is_none() && is_whatever.is_none() {
+        if let Some((source, _)) = &mut source {
+            source.backtrace_delegate = true;
+        }
+    }
+
+    let (is_transparent, errs) = transparents.finish_with_location();
+    errors.extend(errs);
+
+    let (display_no_source, errs) = display_no_sources.finish_with_location();
+    errors.extend(errs);
+
+    if let (Some((_, n_tt)), Some((_, t_tt))) = (&display_no_source, &is_transparent) {
+        let txt = "`transparent` errors cannot use `display(no_source)` because they delegate `Display` to their source";
+        errors.extend([
+            syn::Error::new_spanned(n_tt, txt),
+            syn::Error::new_spanned(t_tt, txt),
+        ]);
+    }
+
+    // `transparent` errors always display their source, so the
+    // enum-wide default does not apply to them.
+    let display_no_source =
+        display_no_source.is_some() || (default_display_no_source && is_transparent.is_none());
+
+    if let (Some((_, d_tt)), Some((_, t_tt))) = (&display_format, &is_transparent) {
+        let txt = "`transparent` errors cannot have a display format because they delegate `Display` to their source";
+        errors.extend([
+            syn::Error::new_spanned(d_tt, txt),
+            syn::Error::new_spanned(t_tt, txt),
+        ]);
+    }
+
+    if let (Some((_, d_tt)), Some((_, t_tt))) = (&display_inherit, &is_transparent) {
+        let txt = "`transparent` errors cannot use `display_inherit` because they delegate `Display` to their source";
+        errors.extend([
+            syn::Error::new_spanned(d_tt, txt),
+            syn::Error::new_spanned(t_tt, txt),
+        ]);
+    }
+
+    let display_inherit = display_inherit.map(|(p, _)| p);
+
+    let (display_context, errs) = display_contexts.finish_with_location();
+    errors.extend(errs);
+
+    if let (Some((_, d_tt)), Some((_, t_tt))) = (&display_context, &is_transparent) {
+        let txt = "`transparent` errors cannot use `display_context` because they delegate `Display` to their source";
+        errors.extend([
+            syn::Error::new_spanned(d_tt, txt),
+            syn::Error::new_spanned(t_tt, txt),
+        ]);
+    }
+
+    let display_context = display_context.is_some();
+
+    let (display_location, errs) = display_locations.finish_with_location();
+    errors.extend(errs);
+
+    let location_field = implicit_fields
+        .iter()
+        .find(|f| is_location(&f.ty))
+        .map(|f| f.name.clone());
+
+    // The enum-wide setting skips variants without a location and
+    // `transparent` variants, which display only their source.
+    let display_location = match display_location {
+        Some((true, d_tt)) => {
+            if let Some((_, t_tt)) = &is_transparent {
+                let txt = "`transparent` errors cannot use `display_location` because they delegate `Display` to their source";
+                errors.extend([
+                    syn::Error::new_spanned(&d_tt, txt),
+                    syn::Error::new_spanned(t_tt, txt),
+                ]);
+            }
+            if location_field.is_none() {
+                errors.add(
+                    d_tt,
+                    "`display_location` requires a `#[snafu(implicit)]` field of type `Location`",
+                );
+            }
+            location_field
+        }
+        Some((false, _)) => None,
+        None if default_display_location && is_transparent.is_none() => location_field,
+        None => None,
+    };
+
+    let (display_prefix_flag, errs) = display_prefix_flags.finish();
+    errors.extend(errs);
+
+    // `transparent` errors display only their source, so the
+    // enum-wide prefix does not apply to them.
+    let display_prefix = if display_prefix_flag.unwrap_or(true) && is_transparent.is_none() {
+        default_display_prefix.cloned()
+    } else {
+        None
+    };
+
+    match (&is_context, &is_transparent) {
+        (Some(((true, _), c_tt)), Some((_, t_tt))) => {
+            let txt = "`transparent` errors cannot have context";
+            errors.extend([
+                syn::Error::new_spanned(c_tt, txt),
+                syn::Error::new_spanned(t_tt, txt),
+            ]);
+        }
+        (None, Some((_, t_tt))) => {
             // `transparent` implies `context(false)`. Treat `transparent` as if setting
             // `context(false)` as well.
             is_context = Some(((false, SuffixKind::Default), t_tt.clone()));
@@ -1075,6 +1895,65 @@ fn field_container(
 
     let source_field = source.map(|(val, _tts)| val);
 
+    // Only errors that need nothing beyond the source can be created
+    // from it alone. The enum-wide setting skips any other variants.
+    let can_auto_from = source_field.is_some() && user_fields.is_empty();
+    let auto_from = match auto_from {
+        Some((_, a_tt)) => {
+            if matches!(is_context, Some(((false, _), _))) {
+                errors.add(
+                    a_tt,
+                    "`auto_from` cannot be used with `context(false)` or `transparent`, which already implement `From`",
+                );
+            } else if !can_auto_from || is_whatever.is_some() {
+                errors.add(
+                    a_tt,
+                    "`auto_from` is only valid on errors with a source field and no context fields",
+                );
+            }
+            true
+        }
+        None => default_auto_from && can_auto_from,
+    };
+
+    // Only `context` errors have a selector struct to place the
+    // attributes on. The enum-wide attributes skip any other variants.
+    let has_selector_struct = !matches!(is_context, Some(((false, _), _))) && is_whatever.is_none();
+    if !has_selector_struct {
+        for (_, tokens) in &context_selector_attrs {
+            errors.add(
+                tokens,
+                "`context_selector_attr` is only valid on errors that generate a context selector",
+            );
+        }
+        if let Some((_, d_tt)) = &context_derive {
+            errors.add(
+                d_tt,
+                "`context(derive(...))` is only valid on errors that generate a context selector",
+            );
+        }
+        if let Some((_, b_tt)) = &context_builder {
+            errors.add(
+                b_tt,
+                "`context(builder)` is only valid on errors that generate a context selector",
+            );
+        }
+    } else if let (Some((_, b_tt)), true) = (&context_builder, user_fields.is_empty()) {
+        errors.add(
+            b_tt,
+            "`context(builder)` requires the context selector to have fields",
+        );
+    }
+    let derive_deserialize = context_derive.is_some() || default_derive_deserialize;
+    // Selectors without fields are already complete, so the
+    // enum-wide setting skips them.
+    let builder = (context_builder.is_some() || default_builder) && !user_fields.is_empty();
+    let context_selector_attrs: Vec<_> = default_context_selector_attrs
+        .iter()
+        .cloned()
+        .chain(context_selector_attrs.into_iter().map(|(m, _)| m))
+        .collect();
+
     let selector_kind = match (is_context, is_whatever) {
         (Some(((true, _), c_tt)), Some(((), o_tt))) => {
             let txt = "Cannot be both a `context` and `whatever` error";
@@ -1088,30 +1967,41 @@ fn field_container(
             suffix,
             source_field,
             user_fields,
+            auto_from,
+            attrs: context_selector_attrs,
+            derive_deserialize,
+            builder,
         },
 
         (None, None) => ContextSelectorKind::Context {
             suffix: SuffixKind::Default,
             source_field,
             user_fields,
+            auto_from,
+            attrs: context_selector_attrs,
+            derive_deserialize,
+            builder,
         },
 
         (Some(((false, _), _)), Some(_)) | (None, Some(_)) => {
             let mut messages = AtMostOne::new("message", outer_error_location);
 
+            // `whatever!` only supplies the message and source, so any
+            // other fields are filled in like ignored fields.
             for f in user_fields {
                 if is_implicit_message(&f.name) {
                     let l = f.original.clone();
                     messages.add(f, l);
                 } else {
-                    errors.add(
-                        f.original,
-                        "Whatever selectors must not have context fields",
-                    );
-                    // todo: phrasing?
+                    ignored_fields.push(IgnoredField {
+                        field: f,
+                        fill: FieldFill::Default,
+                    });
                 }
             }
 
+            ignored_fields.extend(defaulted_fields.drain(..).map(|(f, _)| f));
+
             let (message_field, errs) = messages.finish();
             errors.extend(errs);
 
@@ -1122,9 +2012,36 @@ fn field_container(
                 )]
             })?;
 
+            if let (Some((_, l_tt)), None) = (&error_chain_limit, &source_field) {
+                errors.add(
+                    l_tt,
+                    "`error_chain_limit` requires the error to have a source field",
+                );
+            }
+
+            // The conversion would otherwise silently drop parts of
+            // the `Whatever`.
+            if let Some((_, a_tt)) = &absorbs_whatever {
+                if source_field.is_none() {
+                    errors.add(
+                        a_tt,
+                        "`absorb_whatever` requires the error to have a source field to keep the source of the `Whatever`",
+                    );
+                }
+
+                if backtrace.is_none() {
+                    errors.add(
+                        a_tt,
+                        "`absorb_whatever` requires the error to have a backtrace field to keep the backtrace of the `Whatever`",
+                    );
+                }
+            }
+
             ContextSelectorKind::Whatever {
                 source_field,
                 message_field,
+                absorbs_whatever: absorbs_whatever.is_some(),
+                error_chain_limit: error_chain_limit.map(|(l, _)| l),
             }
         }
 
@@ -1153,30 +2070,377 @@ fn field_container(
         }
     };
 
+    for (field, location) in defaulted_fields {
+        errors.add(
+            location,
+            "`default` is only valid on fields of `whatever` errors",
+        );
+        ignored_fields.push(field);
+    }
+
+    // Only the fields passed to the context selector can be checked
+    // before the error is constructed.
+    let validations = validations
+        .into_iter()
+        .map(|(validation, location)| {
+            let is_user_field = match &selector_kind {
+                ContextSelectorKind::Context { user_fields, .. } => {
+                    user_fields.iter().any(|f| f.name == validation.field)
+                }
+                _ => false,
+            };
+
+            if !is_user_field {
+                errors.add(
+                    location,
+                    "`validate` must name a field that is set by the context selector",
+                );
+            }
+
+            validation
+        })
+        .collect();
+
+    let source_display_error = match &selector_kind {
+        ContextSelectorKind::Context {
+            source_field: None, ..
+        } => Some("`from_source_display` fields require the error to have a source field"),
+        ContextSelectorKind::Whatever { .. } => {
+            Some("`from_source_display` fields are not supported on `whatever` errors")
+        }
+        _ => None,
+    };
+
+    let source_display_fields = source_display_fields
+        .into_iter()
+        .map(|(field, location)| {
+            if let Some(txt) = source_display_error {
+                errors.add(location, txt);
+            }
+            field
+        })
+        .collect();
+
+    let doc_comment = doc_comment.finish();
+
+    if display_no_source {
+        if let Some(source_field) = selector_kind.source_field() {
+            let source_name = source_field.name();
+            let format = match (&display_format, &doc_comment) {
+                (Some((d, d_tt)), _) => Some((&d.shorthand_names, d_tt.clone())),
+                (None, Some(d)) => Some((&d.shorthand_names, name.to_token_stream())),
+                (None, None) => None,
+            };
+
+            if let Some((_, location)) = format.filter(|(n, _)| n.contains(source_name)) {
+                errors.add(
+                    location,
+                    "Errors using `display(no_source)` cannot refer to their source field in the display format",
+                );
+            }
+        }
+    }
+
     Ok(FieldContainer {
         name,
+        field_names,
         backtrace_field: backtrace.map(|(val, _tts)| val),
         implicit_fields,
+        source_display_fields,
+        ignored_fields,
+        field_display_withs,
         selector_kind,
         display_format: display_format.map(|(d, _)| d),
-        doc_comment: doc_comment.finish(),
+        display_no_source,
+        doc_comment,
         visibility,
         module,
         provides,
         is_transparent: is_transparent.is_some(),
+        cause_kind,
+        error_kind,
+        error_description,
+        display_context,
+        display_location,
+        display_inherit,
+        display_prefix,
+        http_status,
+        ffi_code,
+        parse_with,
+        code,
+        from_str_template: None,
+        validations,
     })
 }
 
-const IMPLICIT_SOURCE_FIELD_NAME: &str = "source";
-const IMPLICIT_BACKTRACE_FIELD_NAME: &str = "backtrace";
+/// Attributes that generate a trait implementation have no sensible
+/// fallback value, so once one variant uses them, all must.
+fn require_on_every_variant(
+    name: &syn::Ident,
+    variants: &[FieldContainer],
+    attribute: &str,
+    is_specified: impl Fn(&FieldContainer) -> bool,
+) -> MultiSynResult<()> {
+    if !variants.iter().any(&is_specified) {
+        return Ok(());
+    }
+
+    let missing: Vec<_> = variants
+        .iter()
+        .filter(|v| !is_specified(v))
+        .map(|v| format!("`{}`", v.name))
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    Err(vec![syn::Error::new_spanned(
+        name,
+        format!(
+            "`{}` must be specified on every variant when it is used on any; missing on {}",
+            attribute,
+            missing.join(", "),
+        ),
+    )])
+}
+
+/// Checks that `code` is only used with `kinds` and that no two
+/// variants share a code, as the kind could not be parsed back.
+fn validate_kinds(variants: &[FieldContainer], kinds: bool) -> MultiSynResult<()> {
+    let mut errors = vec![];
+    let mut seen = BTreeSet::new();
+
+    for variant in variants {
+        if !kinds {
+            if let Some(code) = &variant.code {
+                errors.push(syn::Error::new_spanned(
+                    code,
+                    "`code` requires `#[snafu(kinds)]`",
+                ));
+            }
+            continue;
+        }
+
+        if variant.error_kind.is_some() {
+            errors.push(syn::Error::new_spanned(
+                &variant.name,
+                "`kind` cannot be used with `#[snafu(kinds)]`, which generates its own `kind` method",
+            ));
+        }
+
+        let code = variant.kind_code();
+        if !seen.insert(code.clone()) {
+            let message = format!("The code `{}` is used by more than one variant", code);
+            match &variant.code {
+                Some(c) => errors.push(syn::Error::new_spanned(c, message)),
+                None => errors.push(syn::Error::new_spanned(&variant.name, message)),
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Builds the `FromStr` template of each variant that is not parsed
+/// by a `parse_with` function.
+fn prepare_from_str(variants: &mut [FieldContainer], impl_from_str: bool) -> MultiSynResult<()> {
+    let mut errors = vec![];
+
+    for variant in variants {
+        if let Some(parse_with) = &variant.parse_with {
+            if !impl_from_str {
+                errors.push(syn::Error::new_spanned(
+                    parse_with,
+                    "`parse_with` requires `#[snafu(impl_from_str)]`",
+                ));
+            }
+            continue;
+        }
+
+        if !impl_from_str {
+            continue;
+        }
+
+        match from_str_template(variant) {
+            Ok(template) => variant.from_str_template = Some(template),
+            Err(reason) => errors.push(syn::Error::new_spanned(
+                &variant.name,
+                format!(
+                    "`impl_from_str` cannot parse `{}` because {}; use `parse_with` instead",
+                    variant.name, reason,
+                ),
+            )),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn from_str_template(variant: &FieldContainer) -> Result<FromStrTemplate, String> {
+    let format = match (&variant.display_format, &variant.doc_comment) {
+        (Some(display), _) => {
+            if display.with.is_some() || display.template.is_some() {
+                return Err("its display does not use a format string".into());
+            }
+
+            match &display.exprs[..] {
+                [syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(format),
+                    ..
+                })] => format.value(),
+                _ => return Err("its display format has arguments".into()),
+            }
+        }
+        (None, Some(doc_comment)) => doc_comment.content.clone(),
+        (None, None) => variant.name.to_string(),
+    };
+
+    if variant.selector_kind.source_field().is_some() {
+        return Err("it has a source".into());
+    }
+
+    let (literals, placeholders) = split_format(&format)?;
+
+    let parsed_fields = variant
+        .user_fields()
+        .iter()
+        .chain(variant.selector_kind.message_field());
+
+    let mut fields = vec![];
+    for field in parsed_fields {
+        let name = field.name.unraw().to_string();
+        match placeholders.iter().position(|p| *p == name) {
+            Some(index) => fields.push((field.clone(), index)),
+            None => return Err(format!("field `{}` is not in its display format", name)),
+        }
+    }
+
+    if let Some(unknown) = placeholders
+        .iter()
+        .find(|p| !fields.iter().any(|(f, _)| f.name.unraw() == p))
+    {
+        return Err(format!(
+            "`{{{}}}` in its display format is not a field that can be parsed",
+            unknown,
+        ));
+    }
+
+    if let Some((field, _)) = variant
+        .field_display_withs
+        .iter()
+        .find(|(field, _)| fields.iter().any(|(f, _)| f.name == *field))
+    {
+        return Err(format!("field `{}` is displayed with a function", field));
+    }
+
+    Ok(FromStrTemplate { literals, fields })
+}
+
+/// Splits a format string into the literal text around each
+/// placeholder and the names of the placeholders.
+fn split_format(format: &str) -> Result<(Vec<String>, Vec<String>), String> {
+    let mut literals = vec![String::new()];
+    let mut placeholders = vec![];
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let literal = literals.last_mut().expect("There is always a literal");
+
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err("its display format is not valid".into()),
+                    }
+                }
+
+                if name.contains(':') {
+                    return Err(format!(
+                        "`{{{}}}` in its display format has a format specification",
+                        name,
+                    ));
+                }
+
+                if syn::parse_str::<syn::Ident>(&name).is_err() {
+                    return Err(format!(
+                        "`{{{}}}` in its display format does not name a field",
+                        name,
+                    ));
+                }
+
+                if literal.is_empty() && !placeholders.is_empty() {
+                    return Err("its display format has placeholders next to each other".into());
+                }
+
+                placeholders.push(name);
+                literals.push(String::new());
+            }
+            c => literal.push(c),
+        }
+    }
+
+    Ok((literals, placeholders))
+}
+
 const IMPLICIT_MESSAGE_FIELD_NAME: &str = "message";
 
-fn is_implicit_source(name: &proc_macro2::Ident) -> bool {
-    name == IMPLICIT_SOURCE_FIELD_NAME
+fn is_location(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(p) if p.qself.is_none() => p
+            .path
+            .segments
+            .last()
+            .map_or(false, |s| s.ident == "Location"),
+        _ => false,
+    }
 }
 
-fn is_implicit_backtrace(name: &proc_macro2::Ident) -> bool {
-    name == IMPLICIT_BACKTRACE_FIELD_NAME
+/// Given a field type of `Arc<T>`, returns the type that the context
+/// selector accepts: `T` itself, or `Box<T>` when `T` is a trait
+/// object.
+fn arc_source_type(ty: &syn::Type) -> Option<syn::Type> {
+    let path = match ty {
+        syn::Type::Path(p) if p.qself.is_none() => &p.path,
+        _ => return None,
+    };
+
+    let segment = path.segments.last()?;
+    if segment.ident != "Arc" {
+        return None;
+    }
+
+    let args = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(a) if a.args.len() == 1 => &a.args,
+        _ => return None,
+    };
+
+    match args.first()? {
+        syn::GenericArgument::Type(inner @ syn::Type::TraitObject(_)) => {
+            Some(syn::parse_quote! { Box<#inner> })
+        }
+        syn::GenericArgument::Type(inner) => Some(inner.clone()),
+        _ => None,
+    }
 }
 
 fn is_implicit_message(name: &proc_macro2::Ident) -> bool {
@@ -1221,6 +2485,13 @@ fn parse_snafu_named_struct(
     let attrs = attributes_from_syn(attrs)?;
 
     let mut crate_roots = AtMostOne::new("crate_root", ErrorLocation::OnNamedStruct);
+    let mut error_impl_modules = AtMostOne::new("error_impl", ErrorLocation::OnNamedStruct);
+    let mut debugs = AtMostOne::new("debug", ErrorLocation::OnNamedStruct);
+    let mut record_fields = AtMostOne::new("record_fields", ErrorLocation::OnNamedStruct);
+    let mut compat_std_errors = AtMostOne::new("compat_std_error", ErrorLocation::OnNamedStruct);
+    let mut assert_send_syncs = AtMostOne::new("assert_send_sync", ErrorLocation::OnNamedStruct);
+    let mut impl_from_strs = AtMostOne::new("impl_from_str", ErrorLocation::OnNamedStruct);
+    let mut bounds = Vec::new();
 
     let attrs = attrs
         .into_iter()
@@ -1229,15 +2500,51 @@ fn parse_snafu_named_struct(
                 crate_roots.add(root, tokens);
                 None
             }
+            SnafuAttribute::Bound(_tokens, b) => {
+                bounds.extend(b);
+                None
+            }
+            SnafuAttribute::ErrorImpl(tokens, module) => {
+                error_impl_modules.add(module, tokens);
+                None
+            }
+            SnafuAttribute::Debug(tokens) => {
+                debugs.add((), tokens);
+                None
+            }
+            SnafuAttribute::RecordFields(tokens) => {
+                record_fields.add((), tokens);
+                None
+            }
+            SnafuAttribute::CompatStdError(tokens) => {
+                compat_std_errors.add((), tokens);
+                None
+            }
+            SnafuAttribute::AssertSendSync(tokens) => {
+                assert_send_syncs.add((), tokens);
+                None
+            }
+            SnafuAttribute::ImplFromStr(tokens) => {
+                impl_from_strs.add((), tokens);
+                None
+            }
             other => Some(other),
         })
         .collect();
 
-    let field_container = field_container(
+    let mut field_container = field_container(
         name,
         span,
         attrs,
         fields,
+        false,
+        false,
+        false,
+        None,
+        false,
+        &[],
+        false,
+        false,
         &mut errors,
         ErrorLocation::OnNamedStruct,
         ErrorLocation::InNamedStruct,
@@ -1247,10 +2554,38 @@ fn parse_snafu_named_struct(
     let crate_root = maybe_crate_root.unwrap_or_else(default_crate_root);
     errors.extend(errs);
 
+    let (error_impl_module, errs) = error_impl_modules.finish();
+    errors.extend(errs);
+
+    let (compact_backtrace_debug, errs) = debugs.finish();
+    errors.extend(errs);
+
+    let (record_fields, errs) = record_fields.finish();
+    errors.extend(errs);
+
+    let (compat_std_error, errs) = compat_std_errors.finish();
+    errors.extend(errs);
+
+    let (assert_send_sync, errs) = assert_send_syncs.finish();
+    errors.extend(errs);
+
+    let (impl_from_str, errs) = impl_from_strs.finish();
+    let impl_from_str = impl_from_str.is_some();
+    errors.extend(errs);
+
     errors.finish()?;
 
+    prepare_from_str(std::slice::from_mut(&mut field_container), impl_from_str)?;
+
     Ok(NamedStructInfo {
         crate_root,
+        error_impl_module,
+        compact_backtrace_debug: compact_backtrace_debug.is_some(),
+        record_fields: record_fields.is_some(),
+        compat_std_error: compat_std_error.is_some(),
+        assert_send_sync: assert_send_sync.is_some(),
+        impl_from_str,
+        bounds,
         field_container,
         generics,
     })
@@ -1265,7 +2600,12 @@ fn parse_snafu_tuple_struct(
 ) -> MultiSynResult<TupleStructInfo> {
     let mut transformations = AtMostOne::new("source(from)", ErrorLocation::OnTupleStruct);
     let mut crate_roots = AtMostOne::new("crate_root", ErrorLocation::OnTupleStruct);
+    let mut error_impl_modules = AtMostOne::new("error_impl", ErrorLocation::OnTupleStruct);
+    let mut opaques = AtMostOne::new("opaque", ErrorLocation::OnTupleStruct);
+    let mut compat_std_errors = AtMostOne::new("compat_std_error", ErrorLocation::OnTupleStruct);
+    let mut assert_send_syncs = AtMostOne::new("assert_send_sync", ErrorLocation::OnTupleStruct);
     let mut provides = Vec::new();
+    let mut bounds = Vec::new();
 
     let mut errors = SyntaxErrors::default();
     let mut struct_errors = errors.scoped(ErrorLocation::OnTupleStruct);
@@ -1281,22 +2621,67 @@ fn parse_snafu_tuple_struct(
             Att::Provide(_tokens, ProvideKind::Expression(provide)) => {
                 provides.push(provide);
             }
-            Att::Display(tokens, ..) => struct_errors.add(tokens, ATTR_DISPLAY),
+            Att::Display(tokens, ..) | Att::DisplayNoSource(tokens) => {
+                struct_errors.add(tokens, ATTR_DISPLAY)
+            }
             Att::Visibility(tokens, ..) => struct_errors.add(tokens, ATTR_VISIBILITY),
             Att::Source(tokens, ss) => {
                 for s in ss {
                     match s {
                         Source::Flag(..) => struct_errors.add(tokens.clone(), ATTR_SOURCE_BOOL),
                         Source::From(t, e) => transformations.add((t, e), tokens.clone()),
+                        Source::Arc => struct_errors.add(tokens.clone(), ATTR_SOURCE_ARC),
+                        Source::Accessor => struct_errors.add(tokens.clone(), ATTR_SOURCE_ACCESSOR),
                     }
                 }
             }
             Att::Backtrace(tokens, ..) => struct_errors.add(tokens, ATTR_BACKTRACE),
+            Att::CauseKind(tokens, ..) => struct_errors.add(tokens, ATTR_CAUSE_KIND),
+            Att::Kind(tokens, ..) => struct_errors.add(tokens, ATTR_KIND),
+            Att::ErrorChainLimit(tokens, ..) => struct_errors.add(tokens, ATTR_ERROR_CHAIN_LIMIT),
+            Att::ErrorDescription(tokens, ..) => struct_errors.add(tokens, ATTR_ERROR_DESCRIPTION),
+            Att::DisplayContext(tokens) => struct_errors.add(tokens, ATTR_DISPLAY_CONTEXT),
+            Att::DisplayInherit(tokens, ..) => struct_errors.add(tokens, ATTR_DISPLAY_INHERIT),
+            Att::DisplayPrefix(tokens, DisplayPrefix::Prefix(_)) => {
+                struct_errors.add(tokens, ATTR_DISPLAY_PREFIX)
+            }
+            Att::DisplayPrefix(tokens, DisplayPrefix::Flag(_)) => {
+                struct_errors.add(tokens, ATTR_DISPLAY_PREFIX_FLAG)
+            }
+            Att::HttpStatus(tokens, ..) => struct_errors.add(tokens, ATTR_HTTP_STATUS),
+            Att::FfiCode(tokens, ..) => struct_errors.add(tokens, ATTR_FFI_CODE),
             Att::Implicit(tokens, ..) => struct_errors.add(tokens, ATTR_IMPLICIT),
+            Att::FromSourceDisplay(tokens) => struct_errors.add(tokens, ATTR_FROM_SOURCE_DISPLAY),
+            Att::IgnoreField(tokens, ..) => struct_errors.add(tokens, ATTR_IGNORE_FIELD),
             Att::Context(tokens, ..) => struct_errors.add(tokens, ATTR_CONTEXT),
             Att::Whatever(tokens) => struct_errors.add(tokens, ATTR_WHATEVER),
+            Att::AbsorbWhatever(tokens) => struct_errors.add(tokens, ATTR_ABSORB_WHATEVER),
+            Att::AutoFrom(tokens) => struct_errors.add(tokens, ATTR_AUTO_FROM),
+            Att::ContextSelectorAttr(tokens, ..) => {
+                struct_errors.add(tokens, ATTR_CONTEXT_SELECTOR_ATTR)
+            }
+            Att::ContextDerive(tokens) => struct_errors.add(tokens, ATTR_CONTEXT_DERIVE),
+            Att::ContextBuilder(tokens) => struct_errors.add(tokens, ATTR_CONTEXT_BUILDER),
             Att::Transparent(tokens, ..) => struct_errors.add(tokens, ATTR_TRANSPARENT),
+            Att::Validate(tokens, ..) => struct_errors.add(tokens, ATTR_VALIDATE),
             Att::CrateRoot(tokens, root) => crate_roots.add(root, tokens),
+            Att::Bound(_tokens, b) => bounds.extend(b),
+            Att::ErrorImpl(tokens, m) => error_impl_modules.add(m, tokens),
+            Att::Debug(tokens) => struct_errors.add(tokens, ATTR_DEBUG),
+            Att::DefaultValue(tokens, ..) => struct_errors.add(tokens, ATTR_DEFAULT_VALUE),
+            Att::Opaque(tokens) => opaques.add((), tokens),
+            Att::PhantomData(tokens) => struct_errors.add(tokens, ATTR_PHANTOM_DATA),
+            Att::CompatStdError(tokens) => compat_std_errors.add((), tokens),
+            Att::AssertSendSync(tokens) => assert_send_syncs.add((), tokens),
+            Att::ImplFromStr(tokens) => struct_errors.add(tokens, ATTR_IMPL_FROM_STR),
+            Att::ParseWith(tokens, ..) => struct_errors.add(tokens, ATTR_PARSE_WITH),
+            Att::Kinds(tokens) => struct_errors.add(tokens, ATTR_KINDS),
+            Att::Code(tokens, ..) => struct_errors.add(tokens, ATTR_CODE),
+            Att::RecordFields(tokens) => struct_errors.add(tokens, ATTR_RECORD_FIELDS),
+            Att::ForwardSourceBacktrace(tokens, ..) => {
+                struct_errors.add(tokens, ATTR_FORWARD_SOURCE_BACKTRACE)
+            }
+            Att::DisplayLocation(tokens, ..) => struct_errors.add(tokens, ATTR_DISPLAY_LOCATION),
             Att::DocComment(..) => { /* Just a regular doc comment. */ }
         }
     }
@@ -1317,6 +2702,7 @@ fn parse_snafu_tuple_struct(
     }
 
     let inner = inner.into_value();
+    let inner_visibility = inner.vis;
 
     let mut field_errors = errors.scoped(ErrorLocation::OnTupleStructField);
 
@@ -1325,16 +2711,59 @@ fn parse_snafu_tuple_struct(
 
         match attr {
             Att::Backtrace(tokens, ..) => field_errors.add(tokens, ATTR_BACKTRACE),
+            Att::CauseKind(tokens, ..) => field_errors.add(tokens, ATTR_CAUSE_KIND),
+            Att::Kind(tokens, ..) => field_errors.add(tokens, ATTR_KIND),
+            Att::ErrorChainLimit(tokens, ..) => field_errors.add(tokens, ATTR_ERROR_CHAIN_LIMIT),
+            Att::ErrorDescription(tokens, ..) => field_errors.add(tokens, ATTR_ERROR_DESCRIPTION),
+            Att::DisplayContext(tokens) => field_errors.add(tokens, ATTR_DISPLAY_CONTEXT),
+            Att::DisplayInherit(tokens, ..) => field_errors.add(tokens, ATTR_DISPLAY_INHERIT),
+            Att::DisplayPrefix(tokens, DisplayPrefix::Prefix(_)) => {
+                field_errors.add(tokens, ATTR_DISPLAY_PREFIX)
+            }
+            Att::DisplayPrefix(tokens, DisplayPrefix::Flag(_)) => {
+                field_errors.add(tokens, ATTR_DISPLAY_PREFIX_FLAG)
+            }
+            Att::HttpStatus(tokens, ..) => field_errors.add(tokens, ATTR_HTTP_STATUS),
+            Att::FfiCode(tokens, ..) => field_errors.add(tokens, ATTR_FFI_CODE),
             Att::Context(tokens, ..) => field_errors.add(tokens, ATTR_CONTEXT),
             Att::CrateRoot(tokens, ..) => field_errors.add(tokens, ATTR_CRATE_ROOT),
-            Att::Display(tokens, ..) => field_errors.add(tokens, ATTR_DISPLAY),
-            Att::Implicit(tokens, ..) => field_errors.add(tokens, ATTR_IMPLICIT),
+            Att::Bound(tokens, ..) => field_errors.add(tokens, ATTR_BOUND),
+            Att::Display(tokens, ..) | Att::DisplayNoSource(tokens) => {
+                field_errors.add(tokens, ATTR_DISPLAY)
+            }
+            Att::ErrorImpl(tokens, ..) => field_errors.add(tokens, ATTR_ERROR_IMPL),
+            Att::Debug(tokens) => field_errors.add(tokens, ATTR_DEBUG),
+            Att::DefaultValue(tokens, ..) => field_errors.add(tokens, ATTR_DEFAULT_VALUE),
+            Att::Opaque(tokens) => field_errors.add(tokens, ATTR_OPAQUE),
+            Att::PhantomData(tokens) => field_errors.add(tokens, ATTR_PHANTOM_DATA),
+            Att::CompatStdError(tokens) => field_errors.add(tokens, ATTR_COMPAT_STD_ERROR),
+            Att::AssertSendSync(tokens) => field_errors.add(tokens, ATTR_ASSERT_SEND_SYNC),
+            Att::ImplFromStr(tokens) => field_errors.add(tokens, ATTR_IMPL_FROM_STR),
+            Att::ParseWith(tokens, ..) => field_errors.add(tokens, ATTR_PARSE_WITH),
+            Att::Kinds(tokens) => field_errors.add(tokens, ATTR_KINDS),
+            Att::Code(tokens, ..) => field_errors.add(tokens, ATTR_CODE),
+            Att::RecordFields(tokens) => field_errors.add(tokens, ATTR_RECORD_FIELDS),
+            Att::ForwardSourceBacktrace(tokens, ..) => {
+                field_errors.add(tokens, ATTR_FORWARD_SOURCE_BACKTRACE)
+            }
+            Att::DisplayLocation(tokens, ..) => field_errors.add(tokens, ATTR_DISPLAY_LOCATION),
+            Att::FromSourceDisplay(tokens) => field_errors.add(tokens, ATTR_FROM_SOURCE_DISPLAY),
+            Att::IgnoreField(tokens, ..) => field_errors.add(tokens, ATTR_IGNORE_FIELD),
+            Att::Implicit(tokens, ..) => field_errors.add(tokens, ATTR_IMPLICIT),
             Att::Module(tokens, ..) => field_errors.add(tokens, ATTR_MODULE),
             Att::Provide(tokens, ..) => field_errors.add(tokens, ATTR_PROVIDE_FLAG),
             Att::Source(tokens, ..) => field_errors.add(tokens.clone(), ATTR_SOURCE),
             Att::Transparent(tokens, ..) => field_errors.add(tokens, ATTR_TRANSPARENT),
+            Att::Validate(tokens, ..) => field_errors.add(tokens, ATTR_VALIDATE),
             Att::Visibility(tokens, ..) => field_errors.add(tokens, ATTR_VISIBILITY),
             Att::Whatever(tokens) => field_errors.add(tokens, ATTR_WHATEVER),
+            Att::AbsorbWhatever(tokens) => field_errors.add(tokens, ATTR_ABSORB_WHATEVER),
+            Att::AutoFrom(tokens) => field_errors.add(tokens, ATTR_AUTO_FROM),
+            Att::ContextSelectorAttr(tokens, ..) => {
+                field_errors.add(tokens, ATTR_CONTEXT_SELECTOR_ATTR)
+            }
+            Att::ContextDerive(tokens) => field_errors.add(tokens, ATTR_CONTEXT_DERIVE),
+            Att::ContextBuilder(tokens) => field_errors.add(tokens, ATTR_CONTEXT_BUILDER),
             Att::DocComment(..) => { /* Just a regular doc comment. */ }
         }
     }
@@ -1354,42 +2783,38 @@ fn parse_snafu_tuple_struct(
     let crate_root = maybe_crate_root.unwrap_or_else(default_crate_root);
     errors.extend(errs);
 
+    let (error_impl_module, errs) = error_impl_modules.finish();
+    errors.extend(errs);
+
+    let (opaque, errs) = opaques.finish();
+    let transparent_source = opaque.is_some();
+    errors.extend(errs);
+
+    let (compat_std_error, errs) = compat_std_errors.finish();
+    let compat_std_error = compat_std_error.is_some();
+    errors.extend(errs);
+
+    let (assert_send_sync, errs) = assert_send_syncs.finish();
+    let assert_send_sync = assert_send_sync.is_some();
+    errors.extend(errs);
+
     errors.finish()?;
 
     Ok(TupleStructInfo {
         crate_root,
+        error_impl_module,
         name,
         generics,
         transformation,
         provides,
+        inner_visibility,
+        transparent_source,
+        compat_std_error,
+        assert_send_sync,
+        bounds,
     })
 }
 
-enum Context {
-    Flag(bool),
-    Suffix(SuffixKind),
-}
-
-impl Context {
-    fn into_enabled(self) -> (bool, SuffixKind) {
-        match self {
-            Context::Flag(b) => (b, SuffixKind::None),
-            Context::Suffix(suffix) => (true, suffix),
-        }
-    }
-}
-
-enum Source {
-    Flag(bool),
-    From(syn::Type, syn::Expr),
-}
-
-struct Display {
-    exprs: Vec<syn::Expr>,
-    shorthand_names: BTreeSet<syn::Ident>,
-    assigned_names: BTreeSet<syn::Ident>,
-}
-
 #[derive(Default)]
 struct DocComment {
     content: String,
@@ -1418,40 +2843,54 @@ impl DocComment {
     }
 }
 
-/// A SnafuAttribute represents one SNAFU-specific attribute inside of `#[snafu(...)]`.  For
-/// example, in `#[snafu(visibility(pub), display("hi"))]`, `visibility(pub)` and `display("hi")`
-/// are each a SnafuAttribute.
-///
-/// We store the location in the source where we found the attribute (as a `TokenStream`) along
-/// with the data.  The location can be used to give accurate error messages in case there was a
-/// problem with the use of the attribute.
-enum SnafuAttribute {
-    Backtrace(proc_macro2::TokenStream, bool),
-    Context(proc_macro2::TokenStream, Context),
-    CrateRoot(proc_macro2::TokenStream, UserInput),
-    Display(proc_macro2::TokenStream, Display),
-    DocComment(proc_macro2::TokenStream, String),
-    Implicit(proc_macro2::TokenStream, bool),
-    Module(proc_macro2::TokenStream, ModuleName),
-    Provide(proc_macro2::TokenStream, ProvideKind),
-    Source(proc_macro2::TokenStream, Vec<Source>),
-    Transparent(proc_macro2::TokenStream, bool),
-    Visibility(proc_macro2::TokenStream, UserInput),
-    Whatever(proc_macro2::TokenStream),
-}
-
 fn default_crate_root() -> UserInput {
     Box::new(quote! { ::snafu })
 }
 
-fn private_visibility() -> UserInput {
-    Box::new(quote! {})
-}
-
 // Private context selectors wouldn't be accessible outside the
 // module, so we use `pub(super)`.
-fn default_context_selector_visibility_in_module() -> proc_macro2::TokenStream {
-    quote! { pub(super) }
+fn default_context_selector_visibility_in_module() -> syn::Visibility {
+    syn::parse_quote! { pub(super) }
+}
+
+// Context selectors placed in a module are one level deeper than the
+// error, so visibilities relative to the current module need another
+// `super` to mean the same thing.
+fn context_selector_visibility_in_module(visibility: &syn::Visibility) -> syn::Visibility {
+    match visibility {
+        syn::Visibility::Inherited => default_context_selector_visibility_in_module(),
+        syn::Visibility::Restricted(restricted) => {
+            let path = &restricted.path;
+            let first = path.segments.first().map(|s| s.ident.to_string());
+
+            match first.as_deref() {
+                Some("self") if path.segments.len() == 1 => {
+                    default_context_selector_visibility_in_module()
+                }
+                Some("self") => {
+                    let rest = path.segments.iter().skip(1);
+                    syn::parse_quote! { pub(in super #(::#rest)*) }
+                }
+                Some("super") => syn::parse_quote! { pub(in super::#path) },
+                _ => visibility.clone(),
+            }
+        }
+        syn::Visibility::Public(_) => visibility.clone(),
+    }
+}
+
+// Consumers of the `rust_1_30_compat` feature expect only the name of
+// the variant, without the enum's name or a raw identifier prefix.
+fn error_description(
+    full_name: &dyn quote::ToTokens,
+    name: &syn::Ident,
+) -> proc_macro2::TokenStream {
+    if cfg!(feature = "rust_1_30_compat") {
+        let description = name.unraw().to_string();
+        quote! { #description }
+    } else {
+        quote! { stringify!(#full_name) }
+    }
 }
 
 impl From<SnafuInfo> for proc_macro::TokenStream {
@@ -1487,6 +2926,8 @@ trait GenericAwareNames {
 
     fn generics(&self) -> &syn::Generics;
 
+    fn bounds(&self) -> &[syn::WherePredicate];
+
     fn parameterized_name(&self) -> UserInput {
         let enum_name = self.name();
         let original_generics = self.provided_generic_names();
@@ -1579,6 +3020,19 @@ trait GenericAwareNames {
             .collect()
     }
 
+    fn provided_generic_type_names(&self) -> Vec<proc_macro2::TokenStream> {
+        self.generics()
+            .type_params()
+            .map(|t| {
+                // Respanned so that lints treat bounds added to the
+                // name as coming from the macro.
+                let mut ident = t.ident.clone();
+                ident.set_span(proc_macro2::Span::call_site());
+                quote! { #ident }
+            })
+            .collect()
+    }
+
     fn provided_where_clauses(&self) -> Vec<proc_macro2::TokenStream> {
         self.generics()
             .where_clause
@@ -1586,14 +3040,67 @@ trait GenericAwareNames {
             .flat_map(|c| c.predicates.iter().map(|p| quote! { #p }))
             .collect()
     }
+
+    /// The provided where clauses plus those added with
+    /// `#[snafu(bound(...))]`, used for the `IntoError` and
+    /// `ErrorCompat` implementations.
+    fn bounded_where_clauses(&self) -> Vec<proc_macro2::TokenStream> {
+        let mut where_clauses = self.provided_where_clauses();
+        where_clauses.extend(self.bounds().iter().map(|b| quote! { #b }));
+        where_clauses
+    }
+
+    /// The provided where clauses plus a `Debug` bound on every type
+    /// parameter, matching what `#[derive(Debug)]` would require.
+    fn debug_where_clauses(&self) -> Vec<proc_macro2::TokenStream> {
+        let mut where_clauses = self.provided_where_clauses();
+
+        where_clauses.extend(self.generics().type_params().map(|t| {
+            let ident = &t.ident;
+            quote! { #ident: ::core::fmt::Debug }
+        }));
+
+        where_clauses
+    }
 }
 
 impl EnumInfo {
+    /// Items are always generated in the same order: `Display`,
+    /// `Error`, `core::error::Error` (for `compat_std_error`),
+    /// `ErrorCompat`, `Debug`, the discriminant accessor,
+    /// `HasCauseKind`, `HasErrorKind`, `ErrorDescription`, `HttpStatus`,
+    /// `FfiCode`, `FromStr` (for `impl_from_str`), `record_fields`, the
+    /// `Send` and `Sync` assertion (for `assert_send_sync`), the source
+    /// accessors, the kind enum (for `kinds`), and then each variant's
+    /// context selector in declaration order.
     fn generate_snafu(self) -> proc_macro2::TokenStream {
         let context_selectors = ContextSelectors(&self);
         let display_impl = DisplayImpl(&self);
         let error_impl = ErrorImpl(&self);
+        let compat_std_error_impl = CompatStdErrorImpl(&self);
         let error_compat_impl = ErrorCompatImpl(&self);
+        let discriminant_impl = DiscriminantImpl(&self);
+        let cause_kind_impl = CauseKindImpl(&self);
+        let error_kind_impl = ErrorKindImpl(&self);
+        let error_description_impl = ErrorDescriptionImpl(&self);
+        let http_status_impl = HttpStatusImpl(&self);
+        let ffi_code_impl = FfiCodeImpl(&self);
+        let from_str_impl = FromStrImpl(&self);
+        let record_fields_impl = RecordFieldsImpl(&self);
+        let assert_send_sync_impl = AssertSendSyncImpl(&self);
+        let source_accessors_impl = SourceAccessorsImpl(&self);
+        let kinds_impl = KindsImpl(&self);
+        let debug_impl = DebugImpl(&self);
+
+        // The trait implementations of the context selectors move
+        // into the `error_impl` module with the others.
+        let (context_selectors, context_selector_impls) = match &self.error_impl_module {
+            None => (quote! { #context_selectors }, None),
+            Some(_) => {
+                let (items, impls) = context_selectors.split();
+                (items, Some(impls))
+            }
+        };
 
         let context = match &self.module {
             None => quote! { #context_selectors },
@@ -1611,11 +3118,44 @@ impl EnumInfo {
             }
         };
 
-        quote! {
-            #context
+        let impls = quote! {
             #display_impl
             #error_impl
+            #compat_std_error_impl
             #error_compat_impl
+            #debug_impl
+            #discriminant_impl
+            #cause_kind_impl
+            #error_kind_impl
+            #error_description_impl
+            #http_status_impl
+            #ffi_code_impl
+            #from_str_impl
+            #record_fields_impl
+            #assert_send_sync_impl
+            #context_selector_impls
+        };
+        use crate::shared::{module_ident, ErrorImplModule};
+
+        let context_module = self
+            .module
+            .as_ref()
+            .map(|module_name| module_ident(self.name(), module_name));
+        let impls = ErrorImplModule {
+            module_path: self.error_impl_module.as_ref(),
+            container_name: self.name(),
+            context_module: context_module.as_ref(),
+            body: &impls,
+        };
+
+        // The accessors and the kind enum stay next to the enum so
+        // that their visibility means the same thing as the context
+        // selectors'.
+        quote! {
+            #impls
+            #source_accessors_impl
+            #kinds_impl
+            #context
         }
     }
 }
@@ -1628,6 +3168,10 @@ impl GenericAwareNames for EnumInfo {
     fn generics(&self) -> &syn::Generics {
         &self.generics
     }
+
+    fn bounds(&self) -> &[syn::WherePredicate] {
+        &self.bounds
+    }
 }
 
 struct ContextSelectors<'a>(&'a EnumInfo);
@@ -1648,10 +3192,31 @@ impl<'a> quote::ToTokens for ContextSelectors<'a> {
     }
 }
 
+impl ContextSelectors<'_> {
+    /// Separates the trait implementations of every context selector
+    /// from the rest, as [`ContextSelector::split`] does.
+    fn split(&self) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+        self.0
+            .variants
+            .iter()
+            .map(|variant| ContextSelector(self.0, variant).split())
+            .unzip()
+    }
+}
+
 struct ContextSelector<'a>(&'a EnumInfo, &'a FieldContainer);
 
 impl<'a> quote::ToTokens for ContextSelector<'a> {
     fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        let (items, impls) = self.split();
+
+        stream.extend(items);
+        stream.extend(impls);
+    }
+}
+
+impl ContextSelector<'_> {
+    fn split(&self) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
         use crate::shared::ContextSelector;
 
         let enum_name = &self.0.name;
@@ -1663,18 +3228,24 @@ impl<'a> quote::ToTokens for ContextSelector<'a> {
             ..
         } = self.1;
 
-        let default_visibility;
+        let visibility_in_module;
         let selector_visibility = match (
-            &self.1.visibility,
-            &self.0.default_visibility,
+            self.1
+                .visibility
+                .as_ref()
+                .or(self.0.default_visibility.as_ref()),
             &self.0.module,
         ) {
-            (Some(v), _, _) | (_, Some(v), _) => Some(&**v),
-            (None, None, Some(_)) => {
-                default_visibility = default_context_selector_visibility_in_module();
-                Some(&default_visibility as _)
+            (Some(v), Some(_)) => {
+                visibility_in_module = context_selector_visibility_in_module(v);
+                Some(&visibility_in_module as _)
             }
-            (None, None, None) => None,
+            (None, Some(_)) => {
+                visibility_in_module = default_context_selector_visibility_in_module();
+                Some(&visibility_in_module as _)
+            }
+            (Some(v), None) => Some(v as _),
+            (None, None) => None,
         };
 
         let selector_doc_string = format!(
@@ -1685,67 +3256,565 @@ impl<'a> quote::ToTokens for ContextSelector<'a> {
         let context_selector = ContextSelector {
             backtrace_field: self.1.backtrace_field.as_ref(),
             implicit_fields: &self.1.implicit_fields,
+            source_display_fields: &self.1.source_display_fields,
+            ignored_fields: &self.1.ignored_fields,
+            validations: &self.1.validations,
             crate_root: &self.0.crate_root,
             error_constructor_name: &quote! { #enum_name::#variant_name },
             original_generics_without_defaults: &self.0.provided_generics_without_defaults(),
             parameterized_error_name: &self.0.parameterized_name(),
-            selector_doc_string: &selector_doc_string,
-            selector_kind,
-            selector_name: variant_name,
-            user_fields: selector_kind.user_fields(),
-            visibility: selector_visibility,
+            selector_doc_string: &selector_doc_string,
+            selector_kind,
+            selector_name: variant_name,
+            user_fields: selector_kind.user_fields(),
+            visibility: selector_visibility,
+            where_clauses: &self.0.bounded_where_clauses(),
+            default_suffix,
+        };
+
+        context_selector.split()
+    }
+}
+
+struct DisplayImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for DisplayImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        use self::shared::{Display, DisplayMatchArm};
+
+        let enum_name = &self.0.name;
+
+        let arms: Vec<_> = self
+            .0
+            .variants
+            .iter()
+            .map(|variant| {
+                let FieldContainer {
+                    display_format,
+                    doc_comment,
+                    name: variant_name,
+                    selector_kind,
+                    ..
+                } = variant;
+
+                let arm = DisplayMatchArm {
+                    crate_root: &self.0.crate_root,
+                    field_container: variant,
+                    default_name: &variant_name,
+                    display_format: display_format.as_ref(),
+                    doc_comment: doc_comment.as_ref(),
+                    pattern_ident: &quote! { #enum_name::#variant_name },
+                    selector_kind,
+                };
+
+                quote! { #arm }
+            })
+            .collect();
+
+        let display = Display {
+            arms: &arms,
+            original_generics: &self.0.provided_generics_without_defaults(),
+            parameterized_error_name: &self.0.parameterized_name(),
+            where_clauses: &self.0.provided_where_clauses(),
+        };
+
+        let display_impl = quote! { #display };
+
+        stream.extend(display_impl)
+    }
+}
+
+struct DebugImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for DebugImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        use self::shared::{Debug, DebugMatchArm};
+
+        if !self.0.compact_backtrace_debug {
+            return;
+        }
+
+        let enum_name = &self.0.name;
+
+        let arms: Vec<_> = self
+            .0
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_name = &variant.name;
+
+                let arm = DebugMatchArm {
+                    crate_root: &self.0.crate_root,
+                    field_container: variant,
+                    pattern_ident: &quote! { #enum_name::#variant_name },
+                };
+
+                quote! { #arm }
+            })
+            .collect();
+
+        let debug = Debug {
+            arms: &arms,
+            original_generics: &self.0.provided_generics_without_defaults(),
+            parameterized_error_name: &self.0.parameterized_name(),
+            where_clauses: &self.0.debug_where_clauses(),
+        };
+
+        stream.extend(quote! { #debug })
+    }
+}
+
+struct DiscriminantImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for DiscriminantImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        let discriminant_type = match &self.0.discriminant_type {
+            Some(t) => t,
+            None => return,
+        };
+
+        let enum_name = &self.0.name;
+        let original_generics = self.0.provided_generics_without_defaults();
+        let parameterized_enum_name = self.0.parameterized_name();
+        let where_clauses = self.0.provided_where_clauses();
+
+        let arms = self.0.variants.iter().map(|variant| {
+            let variant_name = &variant.name;
+            quote! {
+                #enum_name::#variant_name => #enum_name::#variant_name as #discriminant_type,
+            }
+        });
+
+        stream.extend(quote! {
+            impl<#(#original_generics),*> #parameterized_enum_name
+            where
+                #(#where_clauses),*
+            {
+                /// The explicit discriminant of this error variant.
+                pub fn code(&self) -> #discriminant_type {
+                    match *self {
+                        #(#arms)*
+                    }
+                }
+            }
+        })
+    }
+}
+
+struct KindsImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for KindsImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        let visibility = match &self.0.kinds {
+            Some(v) => v,
+            None => return,
+        };
+
+        let crate_root = &self.0.crate_root;
+        let enum_name = &self.0.name;
+        let kind_name = quote::format_ident!("{}Kind", enum_name);
+        let original_generics = self.0.provided_generics_without_defaults();
+        let parameterized_enum_name = self.0.parameterized_name();
+        let where_clauses = self.0.provided_where_clauses();
+
+        let kind_doc = format!(
+            "The kind of each variant of `{}`, generated by `#[snafu(kinds)]`",
+            enum_name,
+        );
+        let kind_type_name = kind_name.to_string();
+
+        let variant_names: Vec<_> = self.0.variants.iter().map(|v| &v.name).collect();
+        let variant_docs = variant_names
+            .iter()
+            .map(|v| format!("The kind of `{}::{}`", enum_name, v));
+        let codes: Vec<_> = self.0.variants.iter().map(|v| v.kind_code()).collect();
+
+        stream.extend(quote! {
+            #[doc = #kind_doc]
+            #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+            #visibility enum #kind_name {
+                #(
+                    #[doc = #variant_docs]
+                    #variant_names,
+                )*
+            }
+
+            impl #kind_name {
+                /// The code of this kind.
+                pub fn code(&self) -> &'static str {
+                    match *self {
+                        #(#kind_name::#variant_names => #codes,)*
+                    }
+                }
+            }
+
+            impl ::core::fmt::Display for #kind_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str(self.code())
+                }
+            }
+
+            impl ::core::str::FromStr for #kind_name {
+                type Err = #crate_root::UnknownCodeError;
+
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                    match s {
+                        #(#codes => ::core::result::Result::Ok(#kind_name::#variant_names),)*
+                        _ => ::core::result::Result::Err(#crate_root::UnknownCodeError::new(#kind_type_name)),
+                    }
+                }
+            }
+
+            impl<'__snafu> ::core::convert::TryFrom<&'__snafu str> for #kind_name {
+                type Error = #crate_root::UnknownCodeError;
+
+                fn try_from(s: &'__snafu str) -> ::core::result::Result<Self, Self::Error> {
+                    ::core::str::FromStr::from_str(s)
+                }
+            }
+
+            impl<#(#original_generics),*> #parameterized_enum_name
+            where
+                #(#where_clauses),*
+            {
+                /// The kind of this error.
+                pub fn kind(&self) -> #kind_name {
+                    match *self {
+                        #(#enum_name::#variant_names { .. } => #kind_name::#variant_names,)*
+                    }
+                }
+            }
+        })
+    }
+}
+
+struct CauseKindImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for CauseKindImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        use crate::shared::{CauseKind, CauseKindMatchArm};
+
+        if self.0.variants.iter().all(|v| v.cause_kind.is_none()) {
+            return;
+        }
+
+        let crate_root = &self.0.crate_root;
+        let enum_name = &self.0.name;
+
+        let arms: Vec<_> = self
+            .0
+            .variants
+            .iter()
+            .map(|field_container| {
+                let variant_name = &field_container.name;
+                let arm = CauseKindMatchArm {
+                    crate_root,
+                    field_container,
+                    pattern_ident: &quote! { #enum_name::#variant_name },
+                };
+                quote! { #arm }
+            })
+            .collect();
+
+        let cause_kind_impl = CauseKind {
+            crate_root,
+            parameterized_error_name: &self.0.parameterized_name(),
+            arms: &arms,
+            original_generics: &self.0.provided_generics_without_defaults(),
+            where_clauses: &self.0.provided_where_clauses(),
+        };
+
+        stream.extend(quote! { #cause_kind_impl });
+    }
+}
+
+struct ErrorKindImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for ErrorKindImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        use crate::shared::{ErrorKind, ErrorKindMatchArm};
+
+        if self.0.variants.iter().all(|v| v.error_kind.is_none()) {
+            return;
+        }
+
+        let crate_root = &self.0.crate_root;
+        let enum_name = &self.0.name;
+
+        let arms: Vec<_> = self
+            .0
+            .variants
+            .iter()
+            .map(|field_container| {
+                let variant_name = &field_container.name;
+                let arm = ErrorKindMatchArm {
+                    crate_root,
+                    field_container,
+                    pattern_ident: &quote! { #enum_name::#variant_name },
+                };
+                quote! { #arm }
+            })
+            .collect();
+
+        let error_kind_impl = ErrorKind {
+            crate_root,
+            parameterized_error_name: &self.0.parameterized_name(),
+            arms: &arms,
+            original_generics: &self.0.provided_generics_without_defaults(),
+            where_clauses: &self.0.provided_where_clauses(),
+        };
+
+        stream.extend(quote! { #error_kind_impl });
+    }
+}
+
+struct ErrorDescriptionImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for ErrorDescriptionImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        use crate::shared::{ErrorDescription, ErrorDescriptionMatchArm};
+
+        if self
+            .0
+            .variants
+            .iter()
+            .all(|v| v.error_description.is_none())
+        {
+            return;
+        }
+
+        let enum_name = &self.0.name;
+
+        let arms: Vec<_> = self
+            .0
+            .variants
+            .iter()
+            .map(|field_container| {
+                let variant_name = &field_container.name;
+                let arm = ErrorDescriptionMatchArm {
+                    field_container,
+                    pattern_ident: &quote! { #enum_name::#variant_name },
+                };
+                quote! { #arm }
+            })
+            .collect();
+
+        let error_description_impl = ErrorDescription {
+            crate_root: &self.0.crate_root,
+            parameterized_error_name: &self.0.parameterized_name(),
+            arms: &arms,
+            original_generics: &self.0.provided_generics_without_defaults(),
+            where_clauses: &self.0.provided_where_clauses(),
+        };
+
+        stream.extend(quote! { #error_description_impl });
+    }
+}
+
+struct RecordFieldsImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for RecordFieldsImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        use crate::shared::{RecordFields, RecordFieldsMatchArm};
+
+        if !self.0.record_fields {
+            return;
+        }
+
+        let enum_name = &self.0.name;
+
+        let arms: Vec<_> = self
+            .0
+            .variants
+            .iter()
+            .map(|field_container| {
+                let variant_name = &field_container.name;
+                let arm = RecordFieldsMatchArm {
+                    field_container,
+                    pattern_ident: &quote! { #enum_name::#variant_name },
+                };
+                quote! { #arm }
+            })
+            .collect();
+
+        let record_fields_impl = RecordFields {
+            parameterized_error_name: &self.0.parameterized_name(),
+            arms: &arms,
+            original_generics: &self.0.provided_generics_without_defaults(),
+            where_clauses: &self.0.provided_where_clauses(),
+        };
+
+        stream.extend(quote! { #record_fields_impl });
+    }
+}
+
+struct SourceAccessorsImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for SourceAccessorsImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        use crate::shared::{SourceAccessor, SourceAccessors};
+
+        let crate_root = &self.0.crate_root;
+        let enum_name = &self.0.name;
+        let private_visibility = private_visibility();
+
+        let accessors: Vec<_> = self
+            .0
+            .variants
+            .iter()
+            .map(|field_container| {
+                let variant_name = &field_container.name;
+                let visibility = field_container
+                    .visibility
+                    .as_ref()
+                    .or(self.0.default_visibility.as_ref())
+                    .unwrap_or(&private_visibility);
+
+                let accessor = SourceAccessor {
+                    crate_root,
+                    field_container,
+                    pattern_ident: &quote! { #enum_name::#variant_name },
+                    visibility,
+                };
+                quote! { #accessor }
+            })
+            .collect();
+
+        let source_accessors = SourceAccessors {
+            parameterized_error_name: &self.0.parameterized_name(),
+            accessors: &accessors,
+            original_generics: &self.0.provided_generics_without_defaults(),
+            where_clauses: &self.0.provided_where_clauses(),
+        };
+
+        stream.extend(quote! { #source_accessors });
+    }
+}
+
+struct HttpStatusImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for HttpStatusImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        use crate::shared::{HttpStatus, HttpStatusMatchArm};
+
+        // Parsing guarantees that either every variant has a status
+        // or none do.
+        if self.0.variants.iter().all(|v| v.http_status.is_none()) {
+            return;
+        }
+
+        let crate_root = &self.0.crate_root;
+        let enum_name = &self.0.name;
+
+        let arms: Vec<_> = self
+            .0
+            .variants
+            .iter()
+            .map(|field_container| {
+                let variant_name = &field_container.name;
+                let arm = HttpStatusMatchArm {
+                    crate_root,
+                    field_container,
+                    pattern_ident: &quote! { #enum_name::#variant_name },
+                };
+                quote! { #arm }
+            })
+            .collect();
+
+        let http_status_impl = HttpStatus {
+            crate_root,
+            parameterized_error_name: &self.0.parameterized_name(),
+            arms: &arms,
+            original_generics: &self.0.provided_generics_without_defaults(),
+            where_clauses: &self.0.provided_where_clauses(),
+        };
+
+        stream.extend(quote! { #http_status_impl });
+    }
+}
+
+struct FfiCodeImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for FfiCodeImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        use crate::shared::{FfiCode, FfiCodeMatchArm};
+
+        // Parsing guarantees that either every variant has a code or
+        // none do.
+        if self.0.variants.iter().all(|v| v.ffi_code.is_none()) {
+            return;
+        }
+
+        let crate_root = &self.0.crate_root;
+        let enum_name = &self.0.name;
+
+        let arms: Vec<_> = self
+            .0
+            .variants
+            .iter()
+            .map(|field_container| {
+                let variant_name = &field_container.name;
+                let arm = FfiCodeMatchArm {
+                    field_container,
+                    pattern_ident: &quote! { #enum_name::#variant_name },
+                };
+                quote! { #arm }
+            })
+            .collect();
+
+        let ffi_code_impl = FfiCode {
+            crate_root,
+            parameterized_error_name: &self.0.parameterized_name(),
+            arms: &arms,
+            original_generics: &self.0.provided_generics_without_defaults(),
             where_clauses: &self.0.provided_where_clauses(),
-            default_suffix,
         };
 
-        stream.extend(quote! { #context_selector });
+        stream.extend(quote! { #ffi_code_impl });
     }
 }
 
-struct DisplayImpl<'a>(&'a EnumInfo);
+struct FromStrImpl<'a>(&'a EnumInfo);
 
-impl<'a> quote::ToTokens for DisplayImpl<'a> {
+impl<'a> quote::ToTokens for FromStrImpl<'a> {
     fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
-        use self::shared::{Display, DisplayMatchArm};
+        use crate::shared::{FromStr, FromStrArm};
+
+        if !self.0.impl_from_str {
+            return;
+        }
 
+        let crate_root = &self.0.crate_root;
         let enum_name = &self.0.name;
 
         let arms: Vec<_> = self
             .0
             .variants
             .iter()
-            .map(|variant| {
-                let FieldContainer {
-                    display_format,
-                    doc_comment,
-                    name: variant_name,
-                    selector_kind,
-                    ..
-                } = variant;
-
-                let arm = DisplayMatchArm {
-                    field_container: variant,
-                    default_name: &variant_name,
-                    display_format: display_format.as_ref(),
-                    doc_comment: doc_comment.as_ref(),
-                    pattern_ident: &quote! { #enum_name::#variant_name },
-                    selector_kind,
+            .map(|field_container| {
+                let variant_name = &field_container.name;
+                let arm = FromStrArm {
+                    crate_root,
+                    field_container,
+                    constructor: &quote! { #enum_name::#variant_name },
                 };
-
                 quote! { #arm }
             })
             .collect();
 
-        let display = Display {
+        let field_containers: Vec<_> = self.0.variants.iter().collect();
+
+        let from_str_impl = FromStr {
+            crate_root,
+            parameterized_error_name: &self.0.parameterized_name(),
+            type_name: enum_name,
+            field_containers: &field_containers,
             arms: &arms,
             original_generics: &self.0.provided_generics_without_defaults(),
-            parameterized_error_name: &self.0.parameterized_name(),
             where_clauses: &self.0.provided_where_clauses(),
         };
 
-        let display_impl = quote! { #display };
-
-        stream.extend(display_impl)
+        stream.extend(quote! { #from_str_impl });
     }
 }
 
@@ -1766,8 +3835,9 @@ impl<'a> quote::ToTokens for ErrorImpl<'a> {
             let variant_name = &field_container.name;
             let pattern_ident = &quote! { #enum_name::#variant_name };
 
+            let description = error_description(pattern_ident, variant_name);
             let error_description_match_arm = quote! {
-                #pattern_ident { .. } => stringify!(#pattern_ident),
+                #pattern_ident { .. } => #description,
             };
 
             let error_source_match_arm = ErrorSourceMatchArm {
@@ -1803,6 +3873,48 @@ impl<'a> quote::ToTokens for ErrorImpl<'a> {
     }
 }
 
+struct CompatStdErrorImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for CompatStdErrorImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        use self::shared::CompatStdError;
+
+        if !self.0.compat_std_error {
+            return;
+        }
+
+        let compat_std_error_impl = CompatStdError {
+            crate_root: &self.0.crate_root,
+            parameterized_error_name: &self.0.parameterized_name(),
+            original_generics: &self.0.provided_generics_without_defaults(),
+            where_clauses: &self.0.provided_where_clauses(),
+        };
+
+        stream.extend(quote! { #compat_std_error_impl });
+    }
+}
+
+struct AssertSendSyncImpl<'a>(&'a EnumInfo);
+
+impl<'a> quote::ToTokens for AssertSendSyncImpl<'a> {
+    fn to_tokens(&self, stream: &mut proc_macro2::TokenStream) {
+        use self::shared::AssertSendSync;
+
+        if !self.0.assert_send_sync {
+            return;
+        }
+
+        let assert_send_sync_impl = AssertSendSync {
+            parameterized_error_name: &self.0.parameterized_name(),
+            original_generics: &self.0.provided_generics_without_defaults(),
+            generic_type_names: &self.0.provided_generic_type_names(),
+            where_clauses: &self.0.provided_where_clauses(),
+        };
+
+        stream.extend(quote! { #assert_send_sync_impl });
+    }
+}
+
 struct ErrorCompatImpl<'a>(&'a EnumInfo);
 
 impl<'a> quote::ToTokens for ErrorCompatImpl<'a> {
@@ -1833,7 +3945,7 @@ impl<'a> quote::ToTokens for ErrorCompatImpl<'a> {
             parameterized_error_name: &self.0.parameterized_name(),
             backtrace_arms: &variants_to_backtrace,
             original_generics: &self.0.provided_generics_without_defaults(),
-            where_clauses: &self.0.provided_where_clauses(),
+            where_clauses: &self.0.bounded_where_clauses(),
         };
 
         let error_compat_impl = quote! { #error_compat_impl };
@@ -1843,19 +3955,34 @@ impl<'a> quote::ToTokens for ErrorCompatImpl<'a> {
 }
 
 impl NamedStructInfo {
+    /// Items are generated in the same order as for enums: `Display`,
+    /// `Error`, `core::error::Error`, `ErrorCompat`, `Debug`, `HasCauseKind`,
+    /// `HasErrorKind`, `ErrorDescription`, `HttpStatus`, `FfiCode`,
+    /// `FromStr`, `record_fields`, the `Send` and `Sync` assertion, the
+    /// source accessor, and then the context selector.
     fn generate_snafu(self) -> proc_macro2::TokenStream {
         let parameterized_struct_name = self.parameterized_name();
         let original_generics = self.provided_generics_without_defaults();
         let where_clauses = self.provided_where_clauses();
+        let bounded_where_clauses = self.bounded_where_clauses();
 
         let Self {
             crate_root,
+            error_impl_module,
+            compact_backtrace_debug,
+            record_fields,
+            compat_std_error,
+            assert_send_sync,
+            impl_from_str,
             field_container:
                 FieldContainer {
                     name,
                     selector_kind,
                     backtrace_field,
                     implicit_fields,
+                    source_display_fields,
+                    ignored_fields,
+                    validations,
                     display_format,
                     doc_comment,
                     visibility,
@@ -1872,8 +3999,9 @@ impl NamedStructInfo {
 
         let pattern_ident = &quote! { Self };
 
+        let description = error_description(name, name);
         let error_description_match_arm = quote! {
-            #pattern_ident { .. } => stringify!(#name),
+            #pattern_ident { .. } => #description,
         };
 
         let error_source_match_arm = ErrorSourceMatchArm {
@@ -1900,6 +4028,19 @@ impl NamedStructInfo {
         };
         let error_impl = quote! { #error_impl };
 
+        let compat_std_error_impl = compat_std_error.then(|| {
+            use crate::shared::CompatStdError;
+
+            let compat_std_error_impl = CompatStdError {
+                crate_root: &crate_root,
+                parameterized_error_name: &parameterized_struct_name,
+                original_generics: &original_generics,
+                where_clauses: &where_clauses,
+            };
+
+            quote! { #compat_std_error_impl }
+        });
+
         use self::shared::{ErrorCompat, ErrorCompatBacktraceMatchArm};
 
         let match_arm = ErrorCompatBacktraceMatchArm {
@@ -1914,12 +4055,13 @@ impl NamedStructInfo {
             parameterized_error_name: &parameterized_struct_name,
             backtrace_arms: &[match_arm],
             original_generics: &original_generics,
-            where_clauses: &where_clauses,
+            where_clauses: &bounded_where_clauses,
         };
 
         use crate::shared::{Display, DisplayMatchArm};
 
         let arm = DisplayMatchArm {
+            crate_root: &crate_root,
             field_container,
             default_name: &name,
             display_format: display_format.as_ref(),
@@ -1936,23 +4078,234 @@ impl NamedStructInfo {
             where_clauses: &where_clauses,
         };
 
+        let debug_impl = if *compact_backtrace_debug {
+            use crate::shared::{Debug, DebugMatchArm};
+
+            let arm = DebugMatchArm {
+                crate_root: &crate_root,
+                field_container,
+                pattern_ident: &quote! { Self },
+            };
+            let arm = quote! { #arm };
+
+            let debug_impl = Debug {
+                arms: &[arm],
+                original_generics: &original_generics,
+                parameterized_error_name: &parameterized_struct_name,
+                where_clauses: &self.debug_where_clauses(),
+            };
+
+            Some(quote! { #debug_impl })
+        } else {
+            None
+        };
+
+        let cause_kind_impl = field_container.cause_kind.map(|_| {
+            use crate::shared::{CauseKind, CauseKindMatchArm};
+
+            let arm = CauseKindMatchArm {
+                crate_root: &crate_root,
+                field_container,
+                pattern_ident: &quote! { Self },
+            };
+            let arm = quote! { #arm };
+
+            let cause_kind_impl = CauseKind {
+                crate_root: &crate_root,
+                parameterized_error_name: &parameterized_struct_name,
+                arms: &[arm],
+                original_generics: &original_generics,
+                where_clauses: &where_clauses,
+            };
+
+            quote! { #cause_kind_impl }
+        });
+
+        let error_kind_impl = field_container.error_kind.map(|_| {
+            use crate::shared::{ErrorKind, ErrorKindMatchArm};
+
+            let arm = ErrorKindMatchArm {
+                crate_root: &crate_root,
+                field_container,
+                pattern_ident: &quote! { Self },
+            };
+            let arm = quote! { #arm };
+
+            let error_kind_impl = ErrorKind {
+                crate_root: &crate_root,
+                parameterized_error_name: &parameterized_struct_name,
+                arms: &[arm],
+                original_generics: &original_generics,
+                where_clauses: &where_clauses,
+            };
+
+            quote! { #error_kind_impl }
+        });
+
+        let error_description_impl = field_container.error_description.as_ref().map(|_| {
+            use crate::shared::{ErrorDescription, ErrorDescriptionMatchArm};
+
+            let arm = ErrorDescriptionMatchArm {
+                field_container,
+                pattern_ident: &quote! { Self },
+            };
+            let arm = quote! { #arm };
+
+            let error_description_impl = ErrorDescription {
+                crate_root: &crate_root,
+                parameterized_error_name: &parameterized_struct_name,
+                arms: &[arm],
+                original_generics: &original_generics,
+                where_clauses: &where_clauses,
+            };
+
+            quote! { #error_description_impl }
+        });
+
+        let http_status_impl = field_container.http_status.as_ref().map(|_| {
+            use crate::shared::{HttpStatus, HttpStatusMatchArm};
+
+            let arm = HttpStatusMatchArm {
+                crate_root: &crate_root,
+                field_container,
+                pattern_ident: &quote! { Self },
+            };
+            let arm = quote! { #arm };
+
+            let http_status_impl = HttpStatus {
+                crate_root: &crate_root,
+                parameterized_error_name: &parameterized_struct_name,
+                arms: &[arm],
+                original_generics: &original_generics,
+                where_clauses: &where_clauses,
+            };
+
+            quote! { #http_status_impl }
+        });
+
+        let ffi_code_impl = field_container.ffi_code.as_ref().map(|_| {
+            use crate::shared::{FfiCode, FfiCodeMatchArm};
+
+            let arm = FfiCodeMatchArm {
+                field_container,
+                pattern_ident: &quote! { Self },
+            };
+            let arm = quote! { #arm };
+
+            let ffi_code_impl = FfiCode {
+                crate_root: &crate_root,
+                parameterized_error_name: &parameterized_struct_name,
+                arms: &[arm],
+                original_generics: &original_generics,
+                where_clauses: &where_clauses,
+            };
+
+            quote! { #ffi_code_impl }
+        });
+
+        let from_str_impl = impl_from_str.then(|| {
+            use crate::shared::{FromStr, FromStrArm};
+
+            let arm = FromStrArm {
+                crate_root: &crate_root,
+                field_container,
+                constructor: &quote! { Self },
+            };
+            let arm = quote! { #arm };
+
+            let from_str_impl = FromStr {
+                crate_root: &crate_root,
+                parameterized_error_name: &parameterized_struct_name,
+                type_name: name,
+                field_containers: &[field_container],
+                arms: &[arm],
+                original_generics: &original_generics,
+                where_clauses: &where_clauses,
+            };
+
+            quote! { #from_str_impl }
+        });
+
+        let record_fields_impl = if *record_fields {
+            use crate::shared::{RecordFields, RecordFieldsMatchArm};
+
+            let arm = RecordFieldsMatchArm {
+                field_container,
+                pattern_ident: &quote! { Self },
+            };
+            let arm = quote! { #arm };
+
+            let record_fields_impl = RecordFields {
+                parameterized_error_name: &parameterized_struct_name,
+                arms: &[arm],
+                original_generics: &original_generics,
+                where_clauses: &where_clauses,
+            };
+
+            Some(quote! { #record_fields_impl })
+        } else {
+            None
+        };
+
+        let assert_send_sync_impl = assert_send_sync.then(|| {
+            use crate::shared::AssertSendSync;
+
+            let assert_send_sync_impl = AssertSendSync {
+                parameterized_error_name: &parameterized_struct_name,
+                original_generics: &original_generics,
+                generic_type_names: &self.provided_generic_type_names(),
+                where_clauses: &where_clauses,
+            };
+
+            quote! { #assert_send_sync_impl }
+        });
+
+        let source_accessors_impl = {
+            use crate::shared::{SourceAccessor, SourceAccessors};
+
+            let private_visibility = private_visibility();
+            let accessor = SourceAccessor {
+                crate_root: &crate_root,
+                field_container,
+                pattern_ident: &quote! { Self },
+                visibility: visibility.as_ref().unwrap_or(&private_visibility),
+            };
+            let accessor = quote! { #accessor };
+
+            let source_accessors = SourceAccessors {
+                parameterized_error_name: &parameterized_struct_name,
+                accessors: &[accessor],
+                original_generics: &original_generics,
+                where_clauses: &where_clauses,
+            };
+
+            quote! { #source_accessors }
+        };
+
         use crate::shared::ContextSelector;
 
         let selector_doc_string = format!("SNAFU context selector for the `{}` error", name);
 
-        let default_visibility;
+        let visibility_in_module;
         let selector_visibility = match (visibility, module) {
-            (Some(v), _) => Some(&**v),
+            (Some(v), Some(_)) => {
+                visibility_in_module = context_selector_visibility_in_module(v);
+                Some(&visibility_in_module as _)
+            }
             (None, Some(_)) => {
-                default_visibility = default_context_selector_visibility_in_module();
-                Some(&default_visibility as _)
+                visibility_in_module = default_context_selector_visibility_in_module();
+                Some(&visibility_in_module as _)
             }
+            (Some(v), None) => Some(v as _),
             (None, None) => None,
         };
 
         let context_selector = ContextSelector {
             backtrace_field: backtrace_field.as_ref(),
             implicit_fields,
+            source_display_fields,
+            ignored_fields,
+            validations,
             crate_root: &crate_root,
             error_constructor_name: &name,
             original_generics_without_defaults: &original_generics,
@@ -1962,10 +4315,18 @@ impl NamedStructInfo {
             selector_name: &field_container.name,
             user_fields,
             visibility: selector_visibility,
-            where_clauses: &where_clauses,
+            where_clauses: &bounded_where_clauses,
             default_suffix: &SuffixKind::Default,
         };
 
+        let (context_selector, context_selector_impls) = match error_impl_module {
+            None => (quote! { #context_selector }, None),
+            Some(_) => {
+                let (items, impls) = context_selector.split();
+                (items, Some(impls))
+            }
+        };
+
         let context = match module {
             None => quote! { #context_selector },
             Some(module_name) => {
@@ -1974,7 +4335,7 @@ impl NamedStructInfo {
                 let context_module = ContextModule {
                     container_name: self.name(),
                     body: &context_selector,
-                    visibility: visibility.as_ref().map(|x| &**x),
+                    visibility: visibility.as_ref().map(|x| x as _),
                     module_name,
                 };
 
@@ -1982,10 +4343,37 @@ impl NamedStructInfo {
             }
         };
 
-        quote! {
+        let impls = quote! {
+            #display_impl
             #error_impl
+            #compat_std_error_impl
             #error_compat_impl
-            #display_impl
+            #debug_impl
+            #cause_kind_impl
+            #error_kind_impl
+            #error_description_impl
+            #http_status_impl
+            #ffi_code_impl
+            #from_str_impl
+            #record_fields_impl
+            #assert_send_sync_impl
+            #context_selector_impls
+        };
+        use crate::shared::{module_ident, ErrorImplModule};
+
+        let context_module = module
+            .as_ref()
+            .map(|module_name| module_ident(self.name(), module_name));
+        let impls = ErrorImplModule {
+            module_path: error_impl_module.as_ref(),
+            container_name: self.name(),
+            context_module: context_module.as_ref(),
+            body: &impls,
+        };
+
+        quote! {
+            #impls
+            #source_accessors_impl
             #context
         }
     }
@@ -1999,21 +4387,40 @@ impl GenericAwareNames for NamedStructInfo {
     fn generics(&self) -> &syn::Generics {
         &self.generics
     }
+
+    fn bounds(&self) -> &[syn::WherePredicate] {
+        &self.bounds
+    }
 }
 
 impl TupleStructInfo {
+    /// Items are generated in the same order as for enums: `Display`,
+    /// `Error`, `ErrorCompat`, the `From` implementation, and then the
+    /// `Send` and `Sync` assertion. The
+    /// inherent `downcast_inner_ref` method follows, outside of any
+    /// `error_impl` module so that it keeps the field's visibility.
     fn generate_snafu(self) -> proc_macro2::TokenStream {
         let parameterized_struct_name = self.parameterized_name();
+        let original_generics = self.provided_generics_without_defaults();
+        let generic_type_names = self.provided_generic_type_names();
+        let bounded_where_clauses = self.bounded_where_clauses();
 
         let TupleStructInfo {
             crate_root,
+            error_impl_module,
             generics,
             name,
             transformation,
             provides,
+            inner_visibility,
+            transparent_source,
+            compat_std_error,
+            assert_send_sync,
+            bounds: _,
         } = self;
 
         let inner_type = transformation.source_ty();
+        let stored_type = transformation.target_ty();
         let transformation = transformation.transformation();
 
         let where_clauses: Vec<_> = generics
@@ -2028,16 +4435,34 @@ impl TupleStructInfo {
             }
         };
 
-        let cause_fn = quote! {
-            fn cause(&self) -> ::core::option::Option<&dyn #crate_root::Error> {
-                #crate_root::Error::cause(&self.0)
-            }
-        };
+        let (cause_fn, source_fn) = if transparent_source {
+            let cause_fn = quote! {
+                fn cause(&self) -> ::core::option::Option<&dyn #crate_root::Error> {
+                    ::core::option::Option::Some(&self.0)
+                }
+            };
 
-        let source_fn = quote! {
-            fn source(&self) -> ::core::option::Option<&(dyn #crate_root::Error + 'static)> {
-                #crate_root::Error::source(&self.0)
-            }
+            let source_fn = quote! {
+                fn source(&self) -> ::core::option::Option<&(dyn #crate_root::Error + 'static)> {
+                    ::core::option::Option::Some(&self.0)
+                }
+            };
+
+            (cause_fn, source_fn)
+        } else {
+            let cause_fn = quote! {
+                fn cause(&self) -> ::core::option::Option<&dyn #crate_root::Error> {
+                    #crate_root::Error::cause(&self.0)
+                }
+            };
+
+            let source_fn = quote! {
+                fn source(&self) -> ::core::option::Option<&(dyn #crate_root::Error + 'static)> {
+                    #crate_root::Error::source(&self.0)
+                }
+            };
+
+            (cause_fn, source_fn)
         };
 
         let backtrace_fn = quote! {
@@ -2086,11 +4511,37 @@ impl TupleStructInfo {
             }
         };
 
+        let compat_std_error_impl = compat_std_error.then(|| {
+            use crate::shared::CompatStdError;
+
+            let compat_std_error_impl = CompatStdError {
+                crate_root: &crate_root,
+                parameterized_error_name: &parameterized_struct_name,
+                original_generics: &original_generics,
+                where_clauses: &where_clauses,
+            };
+
+            quote! { #compat_std_error_impl }
+        });
+
+        let assert_send_sync_impl = assert_send_sync.then(|| {
+            use crate::shared::AssertSendSync;
+
+            let assert_send_sync_impl = AssertSendSync {
+                parameterized_error_name: &parameterized_struct_name,
+                original_generics: &original_generics,
+                generic_type_names: &generic_type_names,
+                where_clauses: &where_clauses,
+            };
+
+            quote! { #assert_send_sync_impl }
+        });
+
         let error_compat_impl = quote! {
             #[allow(single_use_lifetimes)]
             impl#generics #crate_root::ErrorCompat for #parameterized_struct_name
             where
-                #(#where_clauses),*
+                #(#bounded_where_clauses),*
             {
                 #backtrace_fn
             }
@@ -2119,11 +4570,46 @@ impl TupleStructInfo {
             }
         };
 
-        quote! {
+        let impls = quote! {
+            #display_impl
             #error_impl
+            #compat_std_error_impl
             #error_compat_impl
-            #display_impl
             #from_impl
+            #assert_send_sync_impl
+        };
+        use crate::shared::ErrorImplModule;
+
+        let impls = ErrorImplModule {
+            module_path: error_impl_module.as_ref(),
+            container_name: &name,
+            context_module: None,
+            body: &impls,
+        };
+
+        let downcast_impl = quote! {
+            #[allow(single_use_lifetimes)]
+            impl#generics #parameterized_struct_name
+            where
+                #(#where_clauses),*
+            {
+                /// Attempts to recover the wrapped error from an error
+                /// trait object that holds this type.
+                #[allow(dead_code)]
+                #inner_visibility fn downcast_inner_ref<'__snafu>(
+                    err: &'__snafu (dyn #crate_root::Error + 'static),
+                ) -> ::core::option::Option<&'__snafu #stored_type>
+                where
+                    Self: 'static,
+                {
+                    err.downcast_ref::<Self>().map(|e| &e.0)
+                }
+            }
+        };
+
+        quote! {
+            #impls
+            #downcast_impl
         }
     }
 }
@@ -2136,6 +4622,10 @@ impl GenericAwareNames for TupleStructInfo {
     fn generics(&self) -> &syn::Generics {
         &self.generics
     }
+
+    fn bounds(&self) -> &[syn::WherePredicate] {
+        &self.bounds
+    }
 }
 
 mod sponge {
@@ -2207,3 +4697,162 @@ mod sponge {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quote::ToTokens;
+
+    // Each generated item is placed on its own line so that changes
+    // to the order or contents of the items produce readable diffs.
+    fn expand(input: &str) -> String {
+        let input = syn::parse_str(input).expect("Input must be valid Rust");
+        let info = match parse_snafu_information(input) {
+            Ok(info) => info,
+            Err(e) => panic!("Input must be a valid error type: {:?}", e),
+        };
+        let tokens = match info {
+            SnafuInfo::Enum(e) => e.generate_snafu(),
+            SnafuInfo::NamedStruct(s) => s.generate_snafu(),
+            SnafuInfo::TupleStruct(s) => s.generate_snafu(),
+        };
+        let file: syn::File = syn::parse2(tokens).expect("Generated code must be valid Rust");
+
+        file.items
+            .iter()
+            .map(|item| format!("{}\n", item.to_token_stream()))
+            .collect()
+    }
+
+    #[cfg(not(any(
+        feature = "http-status",
+        feature = "rust_1_30_compat",
+        feature = "serde",
+        feature = "unstable-provider-api",
+    )))]
+    mod snapshots {
+        use super::expand;
+
+        // The snapshots are taken with no features enabled apart from
+        // `rust_1_61`, which the workspace enables through `snafu`'s
+        // default features and which only affects `#[report]`. Other
+        // features such as `rust_1_30_compat` and
+        // `unstable-provider-api` add items to the expansion, so the
+        // snapshot tests only run with this feature set.
+        const SNAPSHOT_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/snapshots");
+
+        // Set `SNAFU_UPDATE_SNAPSHOTS=1` to accept changes to the
+        // generated code.
+        fn assert_snapshot(name: &str, actual: &str) {
+            let path = format!("{}/{}.txt", SNAPSHOT_DIR, name);
+
+            if std::env::var_os("SNAFU_UPDATE_SNAPSHOTS").is_some() {
+                std::fs::write(&path, actual).expect("Unable to write the snapshot");
+                return;
+            }
+
+            let expected = std::fs::read_to_string(&path).expect("Unable to read the snapshot");
+            assert!(
+                expected == actual,
+                "The generated code for `{}` changed.\n\
+                 Expected:\n{}\n\
+                 Actual:\n{}",
+                name,
+                expected,
+                actual,
+            );
+        }
+
+        #[test]
+        fn enum_expansion_is_stable() {
+            let actual = expand(
+                r#"
+                enum Error<T>
+                where
+                    T: core::fmt::Debug,
+                {
+                    #[snafu(display("Could not open {filename}"))]
+                    OpenConfig {
+                        filename: String,
+                        source: std::io::Error,
+                    },
+
+                    /// The user id {user_id} is invalid
+                    UserIdInvalid { user_id: T, backtrace: Backtrace },
+
+                    #[snafu(context(false))]
+                    Parse { source: std::num::ParseIntError },
+
+                    Missing,
+                }
+                "#,
+            );
+
+            assert_snapshot("enum", &actual);
+        }
+
+        #[test]
+        fn display_prefix_expansion_is_stable() {
+            let actual = expand(
+                r#"
+                #[snafu(display_prefix = "[storage] ")]
+                enum Error {
+                    #[snafu(display("Could not open {filename}"))]
+                    Open {
+                        filename: String,
+                        source: std::io::Error,
+                    },
+
+                    Closed,
+
+                    #[snafu(display_prefix(false))]
+                    Offline,
+                }
+                "#,
+            );
+
+            // Only the two variants that did not opt out write the prefix.
+            assert_eq!(actual.matches(r#""[storage] ""#).count(), 2);
+            assert_snapshot("display_prefix", &actual);
+        }
+
+        #[test]
+        fn display_location_expansion_is_stable() {
+            let actual = expand(
+                r#"
+                #[snafu(display_location)]
+                enum Error {
+                    #[snafu(display("Could not open {filename}"))]
+                    Open {
+                        filename: String,
+                        #[snafu(implicit)]
+                        location: Location,
+                    },
+
+                    #[snafu(display_location(false))]
+                    Closed {
+                        #[snafu(implicit)]
+                        location: Location,
+                    },
+                }
+                "#,
+            );
+
+            // Only the variant that did not opt out writes the location.
+            assert_eq!(actual.matches(r#"" (at {})""#).count(), 1);
+            assert_snapshot("display_location", &actual);
+        }
+    }
+
+    #[test]
+    fn expansion_is_deterministic() {
+        let input = r#"
+            enum Error {
+                Alpha { a: i32, b: String, source: std::io::Error },
+                Beta { z: u8, y: u8, x: u8 },
+            }
+        "#;
+
+        assert_eq!(expand(input), expand(input));
+    }
+}
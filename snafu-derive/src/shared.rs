@@ -1,10 +1,22 @@
 use std::collections::BTreeSet;
 
-pub(crate) use self::context_module::ContextModule;
+pub(crate) use self::assert_send_sync::AssertSendSync;
+pub(crate) use self::cause_kind::{CauseKind, CauseKindMatchArm};
+pub(crate) use self::compat_std_error::CompatStdError;
+pub(crate) use self::context_module::{module_ident, ContextModule};
 pub(crate) use self::context_selector::ContextSelector;
+pub(crate) use self::debug::{Debug, DebugMatchArm};
 pub(crate) use self::display::{Display, DisplayMatchArm};
 pub(crate) use self::error::{Error, ErrorProvideMatchArm, ErrorSourceMatchArm};
 pub(crate) use self::error_compat::{ErrorCompat, ErrorCompatBacktraceMatchArm};
+pub(crate) use self::error_description::{ErrorDescription, ErrorDescriptionMatchArm};
+pub(crate) use self::error_impl_module::ErrorImplModule;
+pub(crate) use self::error_kind::{ErrorKind, ErrorKindMatchArm};
+pub(crate) use self::ffi_code::{FfiCode, FfiCodeMatchArm};
+pub(crate) use self::from_str::{FromStr, FromStrArm};
+pub(crate) use self::http_status::{HttpStatus, HttpStatusMatchArm};
+pub(crate) use self::record_fields::{RecordFields, RecordFieldsMatchArm};
+pub(crate) use self::source_accessor::{SourceAccessor, SourceAccessors};
 
 pub(crate) struct StaticIdent(&'static str);
 
@@ -21,6 +33,8 @@ impl<'a> AllFieldNames<'a> {
         let user_fields = self.0.selector_kind.user_fields();
         let backtrace_field = self.0.backtrace_field.as_ref();
         let implicit_fields = &self.0.implicit_fields;
+        let source_display_fields = &self.0.source_display_fields;
+        let ignored_fields = self.0.ignored_fields.iter().map(|f| &f.field);
         let message_field = self.0.selector_kind.message_field();
         let source_field = self.0.selector_kind.source_field();
 
@@ -28,6 +42,8 @@ impl<'a> AllFieldNames<'a> {
             .iter()
             .chain(backtrace_field)
             .chain(implicit_fields)
+            .chain(source_display_fields)
+            .chain(ignored_fields)
             .chain(message_field)
             .map(crate::Field::name)
             .chain(source_field.map(crate::SourceField::name))
@@ -55,32 +71,927 @@ pub mod context_module {
         T: ToTokens,
     {
         fn to_tokens(&self, stream: &mut TokenStream) {
-            let module_name = match self.module_name {
-                ModuleName::Default => {
-                    let name_str = self.container_name.to_string().to_snake_case();
-                    syn::Ident::new(&name_str, self.container_name.span())
+            let module_name = module_ident(self.container_name, self.module_name);
+
+            let visibility = self.visibility;
+            let body = self.body;
+
+            let module_tokens = quote! {
+                #visibility mod #module_name {
+                    use super::*;
+
+                    #body
+                }
+            };
+
+            stream.extend(module_tokens);
+        }
+    }
+
+    pub(crate) fn module_ident(container_name: &Ident, module_name: &ModuleName) -> Ident {
+        match module_name {
+            ModuleName::Default => {
+                let name_str = container_name.to_string().to_snake_case();
+                syn::Ident::new(&name_str, container_name.span())
+            }
+            ModuleName::Custom(name) => name.clone(),
+        }
+    }
+}
+
+pub mod cause_kind {
+    use crate::FieldContainer;
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+
+    pub(crate) struct CauseKind<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) parameterized_error_name: &'a dyn ToTokens,
+        pub(crate) arms: &'a [TokenStream],
+        pub(crate) original_generics: &'a [TokenStream],
+        pub(crate) where_clauses: &'a [TokenStream],
+    }
+
+    impl ToTokens for CauseKind<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                parameterized_error_name,
+                arms,
+                original_generics,
+                where_clauses,
+            } = *self;
+
+            let cause_kind_impl = quote! {
+                #[allow(single_use_lifetimes)]
+                impl<#(#original_generics),*> #crate_root::HasCauseKind for #parameterized_error_name
+                where
+                    #(#where_clauses),*
+                {
+                    fn cause_kind(&self) -> #crate_root::CauseKind {
+                        match *self {
+                            #(#arms),*
+                        }
+                    }
+                }
+            };
+
+            stream.extend(cause_kind_impl);
+        }
+    }
+
+    pub(crate) struct CauseKindMatchArm<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) field_container: &'a FieldContainer,
+        pub(crate) pattern_ident: &'a dyn ToTokens,
+    }
+
+    impl ToTokens for CauseKindMatchArm<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                field_container,
+                pattern_ident,
+            } = *self;
+
+            let kind = match field_container.cause_kind {
+                Some(crate::CauseKind::Transient) => quote! { Transient },
+                Some(crate::CauseKind::Permanent) => quote! { Permanent },
+                None => quote! { Unknown },
+            };
+
+            stream.extend(quote! {
+                #pattern_ident { .. } => #crate_root::CauseKind::#kind
+            });
+        }
+    }
+}
+
+pub mod error_kind {
+    use crate::FieldContainer;
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+
+    pub(crate) struct ErrorKind<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) parameterized_error_name: &'a dyn ToTokens,
+        pub(crate) arms: &'a [TokenStream],
+        pub(crate) original_generics: &'a [TokenStream],
+        pub(crate) where_clauses: &'a [TokenStream],
+    }
+
+    impl ToTokens for ErrorKind<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                parameterized_error_name,
+                arms,
+                original_generics,
+                where_clauses,
+            } = *self;
+
+            let error_kind_impl = quote! {
+                #[allow(single_use_lifetimes)]
+                impl<#(#original_generics),*> #crate_root::HasErrorKind for #parameterized_error_name
+                where
+                    #(#where_clauses),*
+                {
+                    fn error_kind(&self) -> #crate_root::ErrorKind {
+                        match *self {
+                            #(#arms),*
+                        }
+                    }
+                }
+            };
+
+            stream.extend(error_kind_impl);
+        }
+    }
+
+    pub(crate) struct ErrorKindMatchArm<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) field_container: &'a FieldContainer,
+        pub(crate) pattern_ident: &'a dyn ToTokens,
+    }
+
+    impl ToTokens for ErrorKindMatchArm<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                field_container,
+                pattern_ident,
+            } = *self;
+
+            let kind = match field_container.error_kind {
+                Some(crate::ErrorKind::Network) => quote! { Network },
+                Some(crate::ErrorKind::Io) => quote! { Io },
+                Some(crate::ErrorKind::Logic) => quote! { Logic },
+                None => quote! { Unknown },
+            };
+
+            stream.extend(quote! {
+                #pattern_ident { .. } => #crate_root::ErrorKind::#kind
+            });
+        }
+    }
+}
+
+pub mod assert_send_sync {
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+
+    /// Fails to compile when the error is not `Send` and `Sync`. Each
+    /// generic type parameter is assumed to be `Send` and `Sync`, so
+    /// only the error's own fields are checked.
+    pub(crate) struct AssertSendSync<'a> {
+        pub(crate) parameterized_error_name: &'a dyn ToTokens,
+        pub(crate) original_generics: &'a [TokenStream],
+        pub(crate) generic_type_names: &'a [TokenStream],
+        pub(crate) where_clauses: &'a [TokenStream],
+    }
+
+    impl ToTokens for AssertSendSync<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                parameterized_error_name,
+                original_generics,
+                generic_type_names,
+                where_clauses,
+            } = *self;
+
+            let assert_send_sync = quote! {
+                const _: () = {
+                    fn assert_send_sync<T>()
+                    where
+                        T: ::core::marker::Send + ::core::marker::Sync + ?::core::marker::Sized,
+                    {}
+
+                    #[allow(dead_code, single_use_lifetimes)]
+                    fn assert_error_is_send_sync<#(#original_generics),*>()
+                    where
+                        #(#where_clauses,)*
+                        #(#generic_type_names: ::core::marker::Send + ::core::marker::Sync,)*
+                    {
+                        assert_send_sync::<#parameterized_error_name>();
+                    }
+                };
+            };
+
+            stream.extend(assert_send_sync);
+        }
+    }
+}
+
+pub mod compat_std_error {
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+
+    /// Implements `core::error::Error` when `snafu::Error` is SNAFU's
+    /// own trait. The decision is made by a macro in the `snafu`
+    /// crate because only it knows which error trait it exports.
+    pub(crate) struct CompatStdError<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) parameterized_error_name: &'a dyn ToTokens,
+        pub(crate) original_generics: &'a [TokenStream],
+        pub(crate) where_clauses: &'a [TokenStream],
+    }
+
+    impl ToTokens for CompatStdError<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                parameterized_error_name,
+                original_generics,
+                where_clauses,
+            } = *self;
+
+            let compat_std_error_impl = quote! {
+                #crate_root::__compat_std_error! {
+                    #[allow(single_use_lifetimes)]
+                    impl<#(#original_generics),*> ::core::error::Error for #parameterized_error_name
+                    where
+                        #(#where_clauses),*
+                    {}
+                }
+            };
+
+            stream.extend(compat_std_error_impl);
+        }
+    }
+}
+
+pub mod error_description {
+    use crate::FieldContainer;
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+    use syn::ext::IdentExt;
+
+    pub(crate) struct ErrorDescription<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) parameterized_error_name: &'a dyn ToTokens,
+        pub(crate) arms: &'a [TokenStream],
+        pub(crate) original_generics: &'a [TokenStream],
+        pub(crate) where_clauses: &'a [TokenStream],
+    }
+
+    impl ToTokens for ErrorDescription<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                parameterized_error_name,
+                arms,
+                original_generics,
+                where_clauses,
+            } = *self;
+
+            let error_description_impl = quote! {
+                #[allow(single_use_lifetimes)]
+                impl<#(#original_generics),*> #crate_root::ErrorDescription for #parameterized_error_name
+                where
+                    #(#where_clauses),*
+                {
+                    fn description(&self) -> &str {
+                        match *self {
+                            #(#arms),*
+                        }
+                    }
+                }
+            };
+
+            stream.extend(error_description_impl);
+        }
+    }
+
+    pub(crate) struct ErrorDescriptionMatchArm<'a> {
+        pub(crate) field_container: &'a FieldContainer,
+        pub(crate) pattern_ident: &'a dyn ToTokens,
+    }
+
+    impl ToTokens for ErrorDescriptionMatchArm<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                field_container,
+                pattern_ident,
+            } = *self;
+
+            let description = match &field_container.error_description {
+                Some(description) => quote! { #description },
+                None => {
+                    let name = field_container.name.unraw().to_string();
+                    quote! { #name }
+                }
+            };
+
+            stream.extend(quote! {
+                #pattern_ident { .. } => #description
+            });
+        }
+    }
+}
+
+pub mod record_fields {
+    use super::StaticIdent;
+    use crate::FieldContainer;
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+    use syn::ext::IdentExt;
+
+    const VISITOR_ARG: StaticIdent = StaticIdent("__snafu_visitor");
+
+    pub(crate) struct RecordFields<'a> {
+        pub(crate) parameterized_error_name: &'a dyn ToTokens,
+        pub(crate) arms: &'a [TokenStream],
+        pub(crate) original_generics: &'a [TokenStream],
+        pub(crate) where_clauses: &'a [TokenStream],
+    }
+
+    impl ToTokens for RecordFields<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                parameterized_error_name,
+                arms,
+                original_generics,
+                where_clauses,
+            } = *self;
+
+            let record_fields_impl = quote! {
+                #[allow(single_use_lifetimes)]
+                impl<#(#original_generics),*> #parameterized_error_name
+                where
+                    #(#where_clauses),*
+                {
+                    /// Calls the visitor with the name and value of each
+                    /// context field of this error, in declaration
+                    /// order. Sources, backtraces, and implicit fields
+                    /// are skipped.
+                    #[allow(unused_variables)]
+                    pub fn record_fields(
+                        &self,
+                        #VISITOR_ARG: &mut dyn ::core::ops::FnMut(&'static str, &dyn ::core::fmt::Display),
+                    ) {
+                        match *self {
+                            #(#arms),*
+                        }
+                    }
+                }
+            };
+
+            stream.extend(record_fields_impl);
+        }
+    }
+
+    pub(crate) struct RecordFieldsMatchArm<'a> {
+        pub(crate) field_container: &'a FieldContainer,
+        pub(crate) pattern_ident: &'a dyn ToTokens,
+    }
+
+    impl ToTokens for RecordFieldsMatchArm<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                field_container,
+                pattern_ident,
+            } = *self;
+
+            let field_names: Vec<_> = field_container
+                .user_fields()
+                .iter()
+                .map(crate::Field::name)
+                .collect();
+            let visits = field_names.iter().map(|field_name| {
+                let field_name_str = field_name.unraw().to_string();
+                quote! { #VISITOR_ARG(#field_name_str, #field_name); }
+            });
+
+            stream.extend(quote! {
+                #pattern_ident { #(ref #field_names,)* .. } => {
+                    #(#visits)*
+                }
+            });
+        }
+    }
+}
+
+pub mod http_status {
+    use crate::FieldContainer;
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+
+    pub(crate) struct HttpStatus<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) parameterized_error_name: &'a dyn ToTokens,
+        pub(crate) arms: &'a [TokenStream],
+        pub(crate) original_generics: &'a [TokenStream],
+        pub(crate) where_clauses: &'a [TokenStream],
+    }
+
+    impl ToTokens for HttpStatus<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                parameterized_error_name,
+                arms,
+                original_generics,
+                where_clauses,
+            } = *self;
+
+            let http_status_impl = quote! {
+                #[allow(single_use_lifetimes)]
+                impl<#(#original_generics),*> #crate_root::HttpStatus for #parameterized_error_name
+                where
+                    #(#where_clauses),*
+                {
+                    fn http_status(&self) -> u16 {
+                        match *self {
+                            #(#arms),*
+                        }
+                    }
+                }
+            };
+
+            stream.extend(http_status_impl);
+        }
+    }
+
+    pub(crate) struct HttpStatusMatchArm<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) field_container: &'a FieldContainer,
+        pub(crate) pattern_ident: &'a dyn ToTokens,
+    }
+
+    impl ToTokens for HttpStatusMatchArm<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                field_container,
+                pattern_ident,
+            } = *self;
+
+            let status = match &field_container.http_status {
+                Some(crate::HttpStatus::Named(name)) => quote! { #crate_root::http_status::#name },
+                Some(crate::HttpStatus::Code(code)) => quote! { #code },
+                None => return,
+            };
+
+            stream.extend(quote! {
+                #pattern_ident { .. } => #status
+            });
+        }
+    }
+}
+
+pub mod ffi_code {
+    use crate::FieldContainer;
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+
+    pub(crate) struct FfiCode<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) parameterized_error_name: &'a dyn ToTokens,
+        pub(crate) arms: &'a [TokenStream],
+        pub(crate) original_generics: &'a [TokenStream],
+        pub(crate) where_clauses: &'a [TokenStream],
+    }
+
+    impl ToTokens for FfiCode<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                parameterized_error_name,
+                arms,
+                original_generics,
+                where_clauses,
+            } = *self;
+
+            let ffi_code_impl = quote! {
+                #[allow(single_use_lifetimes)]
+                impl<#(#original_generics),*> #crate_root::FfiCode for #parameterized_error_name
+                where
+                    #(#where_clauses),*
+                {
+                    fn ffi_code(&self) -> i32 {
+                        match *self {
+                            #(#arms),*
+                        }
+                    }
+                }
+            };
+
+            stream.extend(ffi_code_impl);
+        }
+    }
+
+    pub(crate) struct FfiCodeMatchArm<'a> {
+        pub(crate) field_container: &'a FieldContainer,
+        pub(crate) pattern_ident: &'a dyn ToTokens,
+    }
+
+    impl ToTokens for FfiCodeMatchArm<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                field_container,
+                pattern_ident,
+            } = *self;
+
+            let code = match &field_container.ffi_code {
+                Some(code) => code,
+                None => return,
+            };
+
+            stream.extend(quote! {
+                #pattern_ident { .. } => #code
+            });
+        }
+    }
+}
+
+pub mod from_str {
+    use super::StaticIdent;
+    use crate::{FieldContainer, FieldFill, IgnoredField};
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+    use std::collections::BTreeMap;
+
+    const INPUT_ARG: StaticIdent = StaticIdent("__snafu_input");
+    const VALUES: StaticIdent = StaticIdent("__snafu_values");
+    const PARSED: StaticIdent = StaticIdent("__snafu_parsed");
+
+    pub(crate) struct FromStr<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) parameterized_error_name: &'a dyn ToTokens,
+        pub(crate) type_name: &'a syn::Ident,
+        pub(crate) field_containers: &'a [&'a FieldContainer],
+        pub(crate) arms: &'a [TokenStream],
+        pub(crate) original_generics: &'a [TokenStream],
+        pub(crate) where_clauses: &'a [TokenStream],
+    }
+
+    impl ToTokens for FromStr<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                parameterized_error_name,
+                type_name,
+                field_containers,
+                arms,
+                original_generics,
+                where_clauses,
+            } = *self;
+
+            // Every parsed field type must itself be parseable; each
+            // type is only listed once.
+            let parsed_types: BTreeMap<_, _> = field_containers
+                .iter()
+                .flat_map(|fc| &fc.from_str_template)
+                .flat_map(|template| &template.fields)
+                .map(|(field, _)| (field.ty.to_token_stream().to_string(), &field.ty))
+                .collect();
+            let parsed_types = parsed_types.values();
+
+            let from_str_impl = quote! {
+                #[allow(single_use_lifetimes)]
+                impl<#(#original_generics),*> ::core::str::FromStr for #parameterized_error_name
+                where
+                    #(#where_clauses,)*
+                    #(#parsed_types: ::core::str::FromStr,)*
+                {
+                    type Err = #crate_root::FromStrError;
+
+                    fn from_str(#INPUT_ARG: &str) -> ::core::result::Result<Self, Self::Err> {
+                        #(#arms)*
+
+                        ::core::result::Result::Err(
+                            #crate_root::FromStrError::new(::core::stringify!(#type_name))
+                        )
+                    }
+                }
+            };
+
+            stream.extend(from_str_impl);
+        }
+    }
+
+    pub(crate) struct FromStrArm<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) field_container: &'a FieldContainer,
+        pub(crate) constructor: &'a dyn ToTokens,
+    }
+
+    impl ToTokens for FromStrArm<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                field_container,
+                constructor,
+            } = *self;
+
+            if let Some(parse_with) = &field_container.parse_with {
+                stream.extend(quote! {
+                    if let ::core::option::Option::Some(#PARSED) = (#parse_with)(#INPUT_ARG) {
+                        return ::core::result::Result::Ok(#PARSED);
+                    }
+                });
+                return;
+            }
+
+            let template = match &field_container.from_str_template {
+                Some(template) => template,
+                None => return,
+            };
+
+            let prefix = field_container
+                .display_prefix
+                .as_ref()
+                .map(|prefix| quote! { #prefix });
+            let parent = field_container
+                .display_inherit
+                .as_ref()
+                .map(|parent| quote! { #parent });
+            let prefixes = prefix.into_iter().chain(parent);
+
+            let literals = &template.literals;
+
+            let implicit_fields = field_container
+                .implicit_fields
+                .iter()
+                .chain(&field_container.backtrace_field)
+                .map(|field| {
+                    let name = &field.name;
+                    quote! { #name: #crate_root::GenerateImplicitData::generate(), }
+                });
+
+            let ignored_fields =
+                field_container
+                    .ignored_fields
+                    .iter()
+                    .map(|IgnoredField { field, fill }| {
+                        let name = &field.name;
+                        match fill {
+                            FieldFill::Default => {
+                                quote! { #name: ::core::default::Default::default(), }
+                            }
+                            FieldFill::Expression(value) => quote! { #name: #value, },
+                            FieldFill::Function(with) => quote! { #name: (#with)(), },
+                            FieldFill::PhantomData => {
+                                quote! { #name: ::core::marker::PhantomData, }
+                            }
+                        }
+                    });
+
+            let names: Vec<_> = template
+                .fields
+                .iter()
+                .map(|(field, _)| &field.name)
+                .collect();
+
+            let construct = quote! {
+                return ::core::result::Result::Ok(#constructor {
+                    #(#names,)*
+                    #(#implicit_fields)*
+                    #(#ignored_fields)*
+                });
+            };
+
+            if names.is_empty() {
+                stream.extend(quote! {
+                    if #crate_root::__match_display(#INPUT_ARG, &[#(#prefixes),*], &[#(#literals),*], &mut []) {
+                        #construct
+                    }
+                });
+                return;
+            }
+
+            let placeholder_count = literals.len() - 1;
+            let parsed = template.fields.iter().map(|(field, index)| {
+                let ty = &field.ty;
+                quote! { <#ty as ::core::str::FromStr>::from_str(#VALUES[#index]) }
+            });
+
+            stream.extend(quote! {
+                {
+                    let mut #VALUES = [""; #placeholder_count];
+                    if #crate_root::__match_display(#INPUT_ARG, &[#(#prefixes),*], &[#(#literals),*], &mut #VALUES) {
+                        if let (#(::core::result::Result::Ok(#names),)*) = (#(#parsed,)*) {
+                            #construct
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+pub mod source_accessor {
+    use crate::FieldContainer;
+    use heck::ToSnakeCase;
+    use proc_macro2::TokenStream;
+    use quote::{format_ident, quote, ToTokens};
+    use syn::ext::IdentExt;
+
+    pub(crate) struct SourceAccessors<'a> {
+        pub(crate) parameterized_error_name: &'a dyn ToTokens,
+        pub(crate) accessors: &'a [TokenStream],
+        pub(crate) original_generics: &'a [TokenStream],
+        pub(crate) where_clauses: &'a [TokenStream],
+    }
+
+    impl ToTokens for SourceAccessors<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                parameterized_error_name,
+                accessors,
+                original_generics,
+                where_clauses,
+            } = *self;
+
+            if accessors.iter().all(TokenStream::is_empty) {
+                return;
+            }
+
+            stream.extend(quote! {
+                #[allow(single_use_lifetimes)]
+                impl<#(#original_generics),*> #parameterized_error_name
+                where
+                    #(#where_clauses),*
+                {
+                    #(#accessors)*
+                }
+            });
+        }
+    }
+
+    pub(crate) struct SourceAccessor<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) field_container: &'a FieldContainer,
+        pub(crate) pattern_ident: &'a dyn ToTokens,
+        pub(crate) visibility: &'a dyn ToTokens,
+    }
+
+    impl ToTokens for SourceAccessor<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                field_container,
+                pattern_ident,
+                visibility,
+            } = *self;
+
+            let source_field = match field_container.selector_kind.source_field() {
+                Some(source_field) if source_field.accessor => source_field,
+                _ => return,
+            };
+
+            let container_name = &field_container.name;
+            let method_name = format_ident!(
+                "{}_source",
+                container_name.unraw().to_string().to_snake_case()
+            );
+            let source_ty = source_field.transformation.source_ty();
+            let field_name = source_field.name();
+
+            let downcast = if field_container.selector_kind.is_whatever() {
+                quote! {
+                    #field_name
+                        .as_ref()
+                        .and_then(|e| e.as_error_source().downcast_ref::<#source_ty>())
                 }
-                ModuleName::Custom(name) => name.clone(),
+            } else {
+                quote! { #field_name.as_error_source().downcast_ref::<#source_ty>() }
             };
 
-            let visibility = self.visibility;
+            let doc = format!(
+                "Returns the source of `{}` as the type it was created from. \
+                 Returns `None` if the conversion produced a different type.",
+                container_name.unraw(),
+            );
+
+            stream.extend(quote! {
+                #[doc = #doc]
+                #[allow(dead_code)]
+                #visibility fn #method_name(&self) -> ::core::option::Option<&#source_ty> {
+                    use #crate_root::AsErrorSource;
+
+                    #[allow(unreachable_patterns)]
+                    match *self {
+                        #pattern_ident { ref #field_name, .. } => #downcast,
+                        _ => ::core::option::Option::None,
+                    }
+                }
+            });
+        }
+    }
+}
+
+pub mod error_impl_module {
+    use heck::{ToShoutySnakeCase, ToSnakeCase};
+    use proc_macro2::TokenStream;
+    use quote::{format_ident, quote, quote_spanned, ToTokens};
+    use syn::{spanned::Spanned, Ident, Path};
+
+    pub(crate) struct ErrorImplModule<'a, T> {
+        pub module_path: Option<&'a Path>,
+        pub container_name: &'a Ident,
+        /// The module holding the context selectors, when they are
+        /// not next to the error type.
+        pub context_module: Option<&'a Ident>,
+        pub body: &'a T,
+    }
+
+    impl<'a, T> ToTokens for ErrorImplModule<'a, T>
+    where
+        T: ToTokens,
+    {
+        fn to_tokens(&self, stream: &mut TokenStream) {
             let body = self.body;
 
-            let module_tokens = quote! {
-                #visibility mod #module_name {
-                    use super::*;
+            let module_path = match self.module_path {
+                None => {
+                    stream.extend(quote! { #body });
+                    return;
+                }
+                Some(module_path) => module_path,
+            };
 
-                    #body
+            let module_tokens = match module_path.get_ident() {
+                Some(module_name) => {
+                    let context_module = self.context_module.map(|m| quote! { use super::#m::*; });
+
+                    quote! {
+                        mod #module_name {
+                            use super::*;
+                            #context_module
+
+                            #body
+                        }
+                    }
                 }
+                None => self.generate_macro(module_path),
             };
 
             stream.extend(module_tokens);
         }
     }
+
+    impl<T> ErrorImplModule<'_, T>
+    where
+        T: ToTokens,
+    {
+        // Derived code can only be emitted next to the type, so a
+        // module elsewhere receives the implementations by invoking
+        // a macro named after the error type with the path of the
+        // module that contains the error type. The invocation defines
+        // a marker that is required next to the type, so forgetting
+        // it, or invoking it in another module, fails to compile.
+        fn generate_macro(&self, module_path: &Path) -> TokenStream {
+            let body = self.body;
+            let container_name = self.container_name;
+            let snake_name = container_name.to_string().to_snake_case();
+            let macro_name = format_ident!("{}_impls", snake_name, span = container_name.span());
+            let marker_name = format_ident!(
+                "{}_IMPLS",
+                snake_name.to_shouty_snake_case(),
+                span = container_name.span()
+            );
+            let context_module = self
+                .context_module
+                .map(|m| quote! { use $($error_module)::+::#m::*; });
+
+            let path_span = module_path.span();
+            let marker_reference = Ident::new(&marker_name.to_string(), path_span);
+            let marker_check = quote_spanned! { path_span=>
+                const _: () = #module_path::#marker_reference;
+            };
+
+            // Lints are not suppressed for the expansion of a local
+            // macro, as they are for derived code.
+            quote! {
+                #[doc(hidden)]
+                macro_rules! #macro_name {
+                    ($($error_module:ident)::+) => {
+                        #[doc(hidden)]
+                        pub(crate) const #marker_name: () = ();
+
+                        #[allow(deprecated, unused_imports, unused_variables)]
+                        const _: () = {
+                            use $($error_module)::+::*;
+                            use $($error_module)::+::#container_name;
+                            #context_module
+
+                            #body
+                        };
+                    };
+                }
+
+                #[doc(hidden)]
+                pub(crate) use #macro_name;
+
+                #marker_check
+            }
+        }
+    }
 }
 
 pub mod context_selector {
-    use crate::{ContextSelectorKind, Field, SuffixKind};
+    use crate::{ContextSelectorKind, Field, FieldFill, IgnoredField, SuffixKind, Validation};
     use proc_macro2::TokenStream;
     use quote::{format_ident, quote, IdentFragment, ToTokens};
 
@@ -90,6 +1001,9 @@ pub mod context_selector {
     pub(crate) struct ContextSelector<'a> {
         pub backtrace_field: Option<&'a Field>,
         pub implicit_fields: &'a [Field],
+        pub source_display_fields: &'a [Field],
+        pub ignored_fields: &'a [IgnoredField],
+        pub validations: &'a [Validation],
         pub crate_root: &'a dyn ToTokens,
         pub error_constructor_name: &'a dyn ToTokens,
         pub original_generics_without_defaults: &'a [TokenStream],
@@ -103,12 +1017,34 @@ pub mod context_selector {
         pub default_suffix: &'a SuffixKind,
     }
 
+    fn validated_ident(name: &proc_macro2::Ident) -> proc_macro2::Ident {
+        format_ident!("__snafu_validated_{}", name)
+    }
+
     impl ToTokens for ContextSelector<'_> {
         fn to_tokens(&self, stream: &mut TokenStream) {
+            let (items, impls) = self.split();
+
+            stream.extend(items);
+            stream.extend(impls);
+        }
+    }
+
+    impl ContextSelector<'_> {
+        /// Separates the context selector's types and their inherent
+        /// methods from its trait implementations, so
+        /// that the latter can be placed into an `error_impl` module
+        /// while the methods keep the selector's visibility.
+        pub(crate) fn split(&self) -> (TokenStream, TokenStream) {
             use self::ContextSelectorKind::*;
 
-            let context_selector = match self.selector_kind {
-                Context { source_field, .. } => {
+            match self.selector_kind {
+                Context {
+                    source_field,
+                    auto_from,
+                    builder,
+                    ..
+                } => {
                     let context_selector_type = self.generate_type();
                     let context_selector_impl = match source_field {
                         Some(_) => None,
@@ -116,25 +1052,62 @@ pub mod context_selector {
                     };
                     let context_selector_into_error_impl =
                         self.generate_into_error(source_field.as_ref());
+                    let (context_selector_builder, context_selector_builder_impl) = if *builder {
+                        let (builder, builder_impl) = self.generate_builder(source_field.as_ref());
+                        (Some(builder), Some(builder_impl))
+                    } else {
+                        (None, None)
+                    };
+                    let auto_from_impl = match source_field {
+                        Some(source_field) if *auto_from => {
+                            Some(self.generate_from_source(source_field))
+                        }
+                        _ => None,
+                    };
 
-                    quote! {
+                    let items = quote! {
                         #context_selector_type
                         #context_selector_impl
+                        #context_selector_builder
+                    };
+                    let impls = quote! {
                         #context_selector_into_error_impl
-                    }
+                        #context_selector_builder_impl
+                        #auto_from_impl
+                    };
+
+                    (items, impls)
                 }
                 Whatever {
                     source_field,
                     message_field,
-                } => self.generate_whatever(source_field.as_ref(), message_field),
-                NoContext { source_field } => self.generate_from_source(source_field),
-            };
+                    absorbs_whatever,
+                    error_chain_limit,
+                } => {
+                    let whatever_impl = self.generate_whatever(
+                        source_field.as_ref(),
+                        message_field,
+                        error_chain_limit.as_ref(),
+                    );
+                    let absorb_whatever_impl = if *absorbs_whatever {
+                        Some(self.generate_absorb_whatever(source_field.as_ref(), message_field))
+                    } else {
+                        None
+                    };
+
+                    let impls = quote! {
+                        #whatever_impl
+                        #absorb_whatever_impl
+                    };
 
-            stream.extend(context_selector)
+                    (TokenStream::new(), impls)
+                }
+                NoContext { source_field } => {
+                    (TokenStream::new(), self.generate_from_source(source_field))
+                }
+            }
         }
-    }
 
-    impl ContextSelector<'_> {
         fn user_field_generics(&self) -> Vec<proc_macro2::Ident> {
             (0..self.user_fields.len())
                 .map(|i| format_ident!("__T{}", i))
@@ -148,7 +1121,7 @@ pub mod context_selector {
                 .collect()
         }
 
-        fn parameterized_selector_name(&self) -> TokenStream {
+        fn selector_ident(&self) -> proc_macro2::Ident {
             let selector_name = self.selector_name.to_string();
             let selector_name = selector_name.trim_end_matches("Error");
             let suffix: &dyn IdentFragment = match self.selector_kind {
@@ -161,12 +1134,16 @@ pub mod context_selector {
                 }
                 _ => &DEFAULT_SUFFIX,
             };
-            let selector_name = format_ident!(
+            format_ident!(
                 "{}{}",
                 selector_name,
                 suffix,
                 span = self.selector_name.span()
-            );
+            )
+        }
+
+        fn parameterized_selector_name(&self) -> TokenStream {
+            let selector_name = self.selector_ident();
             let user_generics = self.user_field_generics();
 
             quote! { #selector_name<#(#user_generics,)*> }
@@ -193,7 +1170,51 @@ pub mod context_selector {
             self.user_field_names()
                 .into_iter()
                 .map(|name| {
-                    quote! { #name: ::core::convert::Into::into(self.#name) }
+                    if self.is_validated(name) {
+                        // Already converted by `validate_user_fields`
+                        let validated = validated_ident(name);
+                        quote! { #name: #validated }
+                    } else {
+                        quote! { #name: ::core::convert::Into::into(self.#name) }
+                    }
+                })
+                .collect()
+        }
+
+        fn is_validated(&self, name: &proc_macro2::Ident) -> bool {
+            self.validations.iter().any(|v| v.field == *name)
+        }
+
+        // Converts each validated field into its final type so that
+        // the check sees the same value that is stored in the error.
+        fn validate_user_fields(&self) -> TokenStream {
+            self.user_fields
+                .iter()
+                .filter(|field| self.is_validated(&field.name))
+                .map(|Field { name, ty, .. }| {
+                    let validated = validated_ident(name);
+                    let checks = self.validations.iter().filter(|v| v.field == *name).map(
+                        |Validation { check, msg, .. }| {
+                            let msg = match msg {
+                                Some(msg) => quote! { #msg },
+                                None => {
+                                    let msg = format!("validation of `{}` failed", name);
+                                    quote! { #msg }
+                                }
+                            };
+
+                            quote! {
+                                if !(#check)(&#validated) {
+                                    ::core::panic!("{}", #msg);
+                                }
+                            }
+                        },
+                    );
+
+                    quote! {
+                        let #validated: #ty = ::core::convert::Into::into(self.#name);
+                        #(#checks)*
+                    }
                 })
                 .collect()
         }
@@ -207,6 +1228,16 @@ pub mod context_selector {
             self.construct_implicit_fields_with_expression(expression)
         }
 
+        // Assumes that the type name is in a variable called "type_name"
+        fn construct_implicit_fields_for_missing(&self) -> TokenStream {
+            let crate_root = self.crate_root;
+            let expression = quote! {
+                #crate_root::GenerateImplicitData::generate_for_missing(type_name)
+            };
+
+            self.construct_implicit_fields_with_expression(expression)
+        }
+
         fn construct_implicit_fields_with_source(&self) -> TokenStream {
             let crate_root = self.crate_root;
             let expression = quote! { {
@@ -218,57 +1249,258 @@ pub mod context_selector {
             self.construct_implicit_fields_with_expression(expression)
         }
 
-        fn construct_implicit_fields_with_expression(
-            &self,
-            expression: TokenStream,
-        ) -> TokenStream {
-            self.implicit_fields
-                .iter()
-                .chain(self.backtrace_field)
-                .map(|field| {
-                    let name = &field.name;
-                    quote! { #name: #expression, }
-                })
-                .collect()
-        }
+        fn construct_implicit_fields_with_expression(
+            &self,
+            expression: TokenStream,
+        ) -> TokenStream {
+            self.implicit_fields
+                .iter()
+                .chain(self.backtrace_field)
+                .map(|field| {
+                    let name = &field.name;
+                    quote! { #name: #expression, }
+                })
+                .collect()
+        }
+
+        fn construct_ignored_fields(&self) -> TokenStream {
+            self.ignored_fields
+                .iter()
+                .map(|IgnoredField { field, fill }| {
+                    let name = &field.name;
+                    match fill {
+                        FieldFill::Default => {
+                            quote! { #name: ::core::default::Default::default(), }
+                        }
+                        FieldFill::Expression(value) => quote! { #name: #value, },
+                        FieldFill::Function(with) => quote! { #name: (#with)(), },
+                        FieldFill::PhantomData => quote! { #name: ::core::marker::PhantomData, },
+                    }
+                })
+                .collect()
+        }
+
+        // Assumes that the source is in a variable called "error"
+        fn construct_source_display_fields(&self) -> TokenStream {
+            let crate_root = self.crate_root;
+
+            self.source_display_fields
+                .iter()
+                .map(|field| {
+                    let Field { name, ty, .. } = field;
+                    quote! {
+                        #name: {
+                            use ::core::fmt::Write as _;
+                            use #crate_root::AsErrorSource;
+                            let mut display = <#ty as ::core::default::Default>::default();
+                            let _ = ::core::write!(display, "{}", error.as_error_source());
+                            display
+                        },
+                    }
+                })
+                .collect()
+        }
+
+        fn generate_type(self) -> TokenStream {
+            let visibility = self.visibility;
+            let parameterized_selector_name = self.parameterized_selector_name();
+            let user_field_generics = self.user_field_generics();
+            let user_field_names = self.user_field_names();
+            let selector_doc_string = self.selector_doc_string;
+            let (attrs, derive_deserialize) = match self.selector_kind {
+                ContextSelectorKind::Context {
+                    attrs,
+                    derive_deserialize,
+                    ..
+                } => (&attrs[..], *derive_deserialize),
+                _ => (&[][..], false),
+            };
+
+            let deserialize = if derive_deserialize {
+                let crate_root = self.crate_root;
+                let serde_root = quote! { #crate_root::__serde }.to_string();
+                Some(quote! {
+                    #[derive(#crate_root::__serde::Deserialize)]
+                    #[serde(crate = #serde_root)]
+                })
+            } else {
+                None
+            };
+
+            let user_must_use = attrs.iter().any(|attr| attr.path().is_ident("must_use"));
+            let must_use = if user_must_use {
+                None
+            } else {
+                Some(quote! {
+                    #[must_use = "context selectors do nothing unless they are used to create an error"]
+                })
+            };
+
+            let body = if user_field_names.is_empty() {
+                quote! { ; }
+            } else {
+                quote! {
+                    {
+                        #(
+                            #[allow(missing_docs)]
+                            #visibility #user_field_names: #user_field_generics
+                        ),*
+                    }
+                }
+            };
+
+            quote! {
+                #[derive(Debug, Copy, Clone)]
+                #[doc = #selector_doc_string]
+                #must_use
+                #(#[#attrs])*
+                #deserialize
+                #visibility struct #parameterized_selector_name #body
+            }
+        }
+
+        fn generate_builder(
+            self,
+            source_field: Option<&crate::SourceField>,
+        ) -> (TokenStream, TokenStream) {
+            let crate_root = self.crate_root;
+            let visibility = self.visibility;
+            let selector_name = self.selector_ident();
+            let parameterized_selector_name = self.parameterized_selector_name();
+            let parameterized_error_name = self.parameterized_error_name;
+            let original_generics_without_defaults = self.original_generics_without_defaults;
+            let where_clauses = self.where_clauses;
+            let extended_where_clauses = self.extended_where_clauses();
+            let user_field_generics = self.user_field_generics();
+            let user_field_names = self.user_field_names();
+
+            let builder_name =
+                format_ident!("{}Builder", selector_name, span = self.selector_name.span());
+            let builder_doc_string = format!(
+                "Incrementally constructs a [`{}`] context selector",
+                selector_name
+            );
+
+            let setter_doc_strings = user_field_names
+                .iter()
+                .map(|name| format!("Sets the `{}` field of the context selector", name));
+            let missing_field_messages = user_field_names.iter().map(|name| {
+                format!(
+                    "the `{}` field was not set when building the `{}` context selector",
+                    name, selector_name,
+                )
+            });
+
+            // Mirror the ways of finishing the selector itself: leaf
+            // selectors can fail directly, others wrap a source.
+            let finisher = match source_field {
+                None => quote! {
+                    #[doc = "Consume the builder and return a `Result` with the associated error"]
+                    #[track_caller]
+                    #visibility fn fail<#(#original_generics_without_defaults,)* __T>(self) -> ::core::result::Result<__T, #parameterized_error_name>
+                    where
+                        #(#extended_where_clauses),*
+                    {
+                        self.build_selector().fail()
+                    }
+                },
+                Some(_) => quote! {
+                    #[doc = "Consume the builder and add the context selector to the error of a `Result`"]
+                    #[track_caller]
+                    #visibility fn context<#(#original_generics_without_defaults,)* __T, __E>(
+                        self,
+                        result: ::core::result::Result<__T, __E>,
+                    ) -> ::core::result::Result<__T, #parameterized_error_name>
+                    where
+                        #parameterized_selector_name: #crate_root::IntoError<#parameterized_error_name, Source = __E>,
+                        #parameterized_error_name: #crate_root::Error + #crate_root::ErrorCompat,
+                        #(#where_clauses),*
+                    {
+                        let selector = self.build_selector();
+                        match result {
+                            ::core::result::Result::Ok(v) => ::core::result::Result::Ok(v),
+                            ::core::result::Result::Err(e) => {
+                                ::core::result::Result::Err(#crate_root::IntoError::into_error(selector, e))
+                            }
+                        }
+                    }
+                },
+            };
+
+            let builder = quote! {
+                #[derive(Debug, Clone)]
+                #[doc = #builder_doc_string]
+                #visibility struct #builder_name<#(#user_field_generics,)*> {
+                    #(
+                        #user_field_names: ::core::option::Option<#user_field_generics>,
+                    )*
+                }
+
+                impl<#(#user_field_generics,)*> #builder_name<#(#user_field_generics,)*> {
+                    #(
+                        #[doc = #setter_doc_strings]
+                        #[must_use]
+                        #visibility fn #user_field_names(mut self, #user_field_names: #user_field_generics) -> Self {
+                            self.#user_field_names = ::core::option::Option::Some(#user_field_names);
+                            self
+                        }
+                    )*
+
+                    #[doc = "Consume the builder and return the context selector"]
+                    #[doc = ""]
+                    #[doc = "# Panics"]
+                    #[doc = ""]
+                    #[doc = "Panics if any field of the context selector has not been set."]
+                    #[must_use]
+                    #[track_caller]
+                    #visibility fn build_selector(self) -> #parameterized_selector_name {
+                        <Self as #crate_root::ContextBuilder<#parameterized_selector_name>>::build(self)
+                    }
+
+                    #finisher
+                }
+
+                impl<#(#user_field_generics,)*> #parameterized_selector_name {
+                    #[doc = "Start incrementally constructing this context selector"]
+                    #visibility fn builder() -> #builder_name<#(#user_field_generics,)*> {
+                        <#builder_name<#(#user_field_generics,)*> as #crate_root::ContextBuilder<Self>>::new()
+                    }
+                }
+            };
 
-        fn generate_type(self) -> TokenStream {
-            let visibility = self.visibility;
-            let parameterized_selector_name = self.parameterized_selector_name();
-            let user_field_generics = self.user_field_generics();
-            let user_field_names = self.user_field_names();
-            let selector_doc_string = self.selector_doc_string;
+            let builder_impl = quote! {
+                impl<#(#user_field_generics,)*> #crate_root::ContextBuilder<#parameterized_selector_name> for #builder_name<#(#user_field_generics,)*> {
+                    fn new() -> Self {
+                        Self {
+                            #(#user_field_names: ::core::option::Option::None,)*
+                        }
+                    }
 
-            let body = if user_field_names.is_empty() {
-                quote! { ; }
-            } else {
-                quote! {
-                    {
-                        #(
-                            #[allow(missing_docs)]
-                            #visibility #user_field_names: #user_field_generics
-                        ),*
+                    #[track_caller]
+                    fn build(self) -> #parameterized_selector_name {
+                        #selector_name {
+                            #(
+                                #user_field_names: match self.#user_field_names {
+                                    ::core::option::Option::Some(v) => v,
+                                    ::core::option::Option::None => ::core::panic!(#missing_field_messages),
+                                },
+                            )*
+                        }
                     }
                 }
             };
 
-            quote! {
-                #[derive(Debug, Copy, Clone)]
-                #[doc = #selector_doc_string]
-                #visibility struct #parameterized_selector_name #body
-            }
+            (builder, builder_impl)
         }
 
         fn generate_leaf(self) -> TokenStream {
-            let error_constructor_name = self.error_constructor_name;
             let original_generics_without_defaults = self.original_generics_without_defaults;
             let parameterized_error_name = self.parameterized_error_name;
             let parameterized_selector_name = self.parameterized_selector_name();
             let user_field_generics = self.user_field_generics();
             let visibility = self.visibility;
             let extended_where_clauses = self.extended_where_clauses();
-            let transfer_user_fields = self.transfer_user_fields();
-            let construct_implicit_fields = self.construct_implicit_fields();
+            let construct_error = self.construct_error(None, self.construct_implicit_fields());
 
             quote! {
                 impl<#(#user_field_generics,)*> #parameterized_selector_name {
@@ -279,10 +1511,7 @@ pub mod context_selector {
                     where
                         #(#extended_where_clauses),*
                     {
-                        #error_constructor_name {
-                            #construct_implicit_fields
-                            #(#transfer_user_fields,)*
-                        }
+                        #construct_error
                     }
 
                     #[doc = "Consume the selector and return a `Result` with the associated error"]
@@ -297,35 +1526,80 @@ pub mod context_selector {
             }
         }
 
+        // Builds the error from the selector's fields. Assumes that
+        // the source, if any, is in a variable called "error".
+        fn construct_error(
+            &self,
+            source_field: Option<&crate::SourceField>,
+            construct_implicit_fields: TokenStream,
+        ) -> TokenStream {
+            let error_constructor_name = self.error_constructor_name;
+            let transfer_user_fields = self.transfer_user_fields();
+            let validate_user_fields = self.validate_user_fields();
+            let construct_ignored_fields = self.construct_ignored_fields();
+
+            let (transform_source, transfer_source_field, construct_source_display_fields) =
+                match source_field {
+                    Some(source_field) => {
+                        let SourceInfo {
+                            transform_source,
+                            transfer_source_field,
+                            ..
+                        } = build_source_info(source_field);
+                        (
+                            Some(quote! { #transform_source; }),
+                            Some(transfer_source_field),
+                            Some(self.construct_source_display_fields()),
+                        )
+                    }
+                    None => (None, None, None),
+                };
+
+            quote! {
+                #transform_source
+                #validate_user_fields
+                #error_constructor_name {
+                    #construct_implicit_fields
+                    #construct_source_display_fields
+                    #construct_ignored_fields
+                    #transfer_source_field
+                    #(#transfer_user_fields,)*
+                }
+            }
+        }
+
         fn generate_into_error(self, source_field: Option<&crate::SourceField>) -> TokenStream {
             let crate_root = self.crate_root;
-            let error_constructor_name = self.error_constructor_name;
             let original_generics_without_defaults = self.original_generics_without_defaults;
             let parameterized_error_name = self.parameterized_error_name;
             let parameterized_selector_name = self.parameterized_selector_name();
             let user_field_generics = self.user_field_generics();
             let extended_where_clauses = self.extended_where_clauses();
-            let transfer_user_fields = self.transfer_user_fields();
-            let construct_implicit_fields = if source_field.is_some() {
-                self.construct_implicit_fields_with_source()
-            } else {
-                self.construct_implicit_fields()
-            };
 
-            let (source_ty, transform_source, transfer_source_field) = match source_field {
+            let (source_ty, construct_error, into_error_for_missing) = match source_field {
                 Some(source_field) => {
-                    let SourceInfo {
-                        source_field_type,
-                        transform_source,
-                        transfer_source_field,
-                    } = build_source_info(source_field);
+                    let source_field_type = source_field.transformation.source_ty();
+                    let construct_error = self.construct_error(
+                        Some(source_field),
+                        self.construct_implicit_fields_with_source(),
+                    );
+                    (quote! { #source_field_type }, construct_error, None)
+                }
+                None => {
+                    let construct_error_for_missing =
+                        self.construct_error(None, self.construct_implicit_fields_for_missing());
+                    let into_error_for_missing = quote! {
+                        #[track_caller]
+                        fn into_error_for_missing(self, error: Self::Source, type_name: &'static str) -> #parameterized_error_name {
+                            #construct_error_for_missing
+                        }
+                    };
                     (
-                        quote! { #source_field_type },
-                        Some(transform_source),
-                        Some(transfer_source_field),
+                        quote! { #crate_root::NoneError },
+                        self.construct_error(None, self.construct_implicit_fields()),
+                        Some(into_error_for_missing),
                     )
                 }
-                None => (quote! { #crate_root::NoneError }, None, None),
             };
 
             quote! {
@@ -338,14 +1612,17 @@ pub mod context_selector {
 
                     #[track_caller]
                     fn into_error(self, error: Self::Source) -> #parameterized_error_name {
-                        #transform_source;
-                        #error_constructor_name {
-                            #construct_implicit_fields
-                            #transfer_source_field
-                            #(#transfer_user_fields),*
-                        }
+                        #construct_error
                     }
+
+                    #into_error_for_missing
                 }
+
+                impl<#(#original_generics_without_defaults,)* #(#user_field_generics,)*> #crate_root::ContextSelector<#parameterized_error_name> for #parameterized_selector_name
+                where
+                    #parameterized_error_name: #crate_root::Error + #crate_root::ErrorCompat,
+                    #(#extended_where_clauses),*
+                {}
             }
         }
 
@@ -353,6 +1630,7 @@ pub mod context_selector {
             self,
             source_field: Option<&crate::SourceField>,
             message_field: &crate::Field,
+            error_chain_limit: Option<&syn::LitInt>,
         ) -> TokenStream {
             let crate_root = self.crate_root;
             let parameterized_error_name = self.parameterized_error_name;
@@ -361,6 +1639,7 @@ pub mod context_selector {
             let original_generics_without_defaults = self.original_generics_without_defaults;
             let construct_implicit_fields_with_source =
                 self.construct_implicit_fields_with_source();
+            let construct_ignored_fields = self.construct_ignored_fields();
             let extended_where_clauses = self.extended_where_clauses();
 
             // testme: transform
@@ -380,6 +1659,12 @@ pub mod context_selector {
                 None => (quote! { #crate_root::NoneError }, None, None),
             };
 
+            let limit_error_chain = error_chain_limit.map(|limit| {
+                quote! {
+                    let error = #crate_root::__LimitErrorChain::limit_error_chain(error, #limit);
+                }
+            });
+
             let message_field_name = &message_field.name;
 
             quote! {
@@ -393,6 +1678,7 @@ pub mod context_selector {
                     fn without_source(message: String) -> Self {
                         #error_constructor_name {
                             #construct_implicit_fields
+                            #construct_ignored_fields
                             #empty_source_field
                             #message_field_name: message,
                         }
@@ -400,8 +1686,74 @@ pub mod context_selector {
 
                     #[track_caller]
                     fn with_source(error: Self::Source, message: String) -> Self {
+                        #limit_error_chain
                         #error_constructor_name {
                             #construct_implicit_fields_with_source
+                            #construct_ignored_fields
+                            #transfer_source_field
+                            #message_field_name: message,
+                        }
+                    }
+                }
+            }
+        }
+
+        fn generate_absorb_whatever(
+            self,
+            source_field: Option<&crate::SourceField>,
+            message_field: &crate::Field,
+        ) -> TokenStream {
+            let crate_root = self.crate_root;
+            let parameterized_error_name = self.parameterized_error_name;
+            let error_constructor_name = self.error_constructor_name;
+            let original_generics_without_defaults = self.original_generics_without_defaults;
+            let where_clauses = self.where_clauses;
+
+            // The location is where the `Whatever` was created, not
+            // where it is converted.
+            let construct_implicit_fields = self.implicit_fields.iter().map(|field| {
+                let name = &field.name;
+                if crate::is_location(&field.ty) {
+                    quote! { #name: ::core::convert::Into::into(location), }
+                } else {
+                    quote! { #name: #crate_root::GenerateImplicitData::generate(), }
+                }
+            });
+
+            let construct_ignored_fields = self.construct_ignored_fields();
+
+            let transfer_backtrace_field = self.backtrace_field.map(|field| {
+                let name = &field.name;
+                quote! { #name: ::core::convert::Into::into(backtrace), }
+            });
+
+            let transfer_source_field = source_field.map(|f| {
+                let source_field_name = &f.name;
+                let source_transformation = f.transformation.transformation();
+
+                quote! {
+                    #source_field_name: match source {
+                        ::core::option::Option::Some(error) => (#source_transformation)(error),
+                        ::core::option::Option::None => ::core::option::Option::None,
+                    },
+                }
+            });
+
+            let message_field_name = &message_field.name;
+
+            quote! {
+                impl<#(#original_generics_without_defaults,)*> ::core::convert::From<#crate_root::Whatever> for #parameterized_error_name
+                where
+                    #(#where_clauses),*
+                {
+                    #[track_caller]
+                    #[allow(unused_variables)]
+                    fn from(error: #crate_root::Whatever) -> Self {
+                        let (message, source, backtrace, location) = error.into_parts();
+                        #error_constructor_name {
+                            #(#construct_implicit_fields)*
+                            #construct_ignored_fields
+                            #transfer_backtrace_field
                             #transfer_source_field
                             #message_field_name: message,
                         }
@@ -415,6 +1767,8 @@ pub mod context_selector {
             let error_constructor_name = self.error_constructor_name;
             let construct_implicit_fields_with_source =
                 self.construct_implicit_fields_with_source();
+            let construct_source_display_fields = self.construct_source_display_fields();
+            let construct_ignored_fields = self.construct_ignored_fields();
             let original_generics_without_defaults = self.original_generics_without_defaults;
             let user_field_generics = self.user_field_generics();
             let where_clauses = self.where_clauses;
@@ -435,6 +1789,8 @@ pub mod context_selector {
                         #transform_source;
                         #error_constructor_name {
                             #construct_implicit_fields_with_source
+                            #construct_source_display_fields
+                            #construct_ignored_fields
                             #transfer_source_field
                         }
                     }
@@ -471,8 +1827,9 @@ pub mod context_selector {
 pub mod display {
     use super::StaticIdent;
     use proc_macro2::TokenStream;
-    use quote::{quote, ToTokens};
+    use quote::{quote, quote_spanned, ToTokens};
     use std::collections::BTreeSet;
+    use syn::{ext::IdentExt, spanned::Spanned};
 
     const FORMATTER_ARG: StaticIdent = StaticIdent("__snafu_display_formatter");
 
@@ -512,6 +1869,7 @@ pub mod display {
     }
 
     pub(crate) struct DisplayMatchArm<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
         pub(crate) field_container: &'a crate::FieldContainer,
         pub(crate) default_name: &'a dyn ToTokens,
         pub(crate) display_format: Option<&'a crate::Display>,
@@ -523,6 +1881,7 @@ pub mod display {
     impl ToTokens for DisplayMatchArm<'_> {
         fn to_tokens(&self, stream: &mut TokenStream) {
             let Self {
+                crate_root,
                 field_container,
                 default_name,
                 display_format,
@@ -533,6 +1892,64 @@ pub mod display {
 
             let source_field = selector_kind.source_field();
 
+            // The enum-wide prefix and then the parent's text are
+            // written before this error's own display, whichever way
+            // that is produced.
+            let prefix = field_container.display_prefix.as_ref().map(|prefix| {
+                quote! {
+                    ::core::fmt::Formatter::write_str(#FORMATTER_ARG, #prefix)?;
+                }
+            });
+            let parent = field_container.display_inherit.as_ref().map(|parent| {
+                quote! {
+                    ::core::fmt::Formatter::write_str(#FORMATTER_ARG, #parent)?;
+                }
+            });
+            let inherited = quote! { #prefix #parent };
+
+            // `display_context` follows the message with the value of
+            // each field other than the source, backtrace, and message,
+            // and then `display_location` adds the location.
+            let backtrace_field = field_container
+                .backtrace_field
+                .as_ref()
+                .map(crate::Field::name);
+            let context_fields: Vec<_> = field_container
+                .field_names
+                .iter()
+                .filter(|&f| Some(f) != source_field.map(crate::SourceField::name))
+                .filter(|&f| Some(f) != backtrace_field)
+                .filter(|&f| Some(f) != selector_kind.message_field().map(crate::Field::name))
+                .filter(|&f| Some(f) != field_container.display_location.as_ref())
+                .collect();
+            let context = if field_container.display_context && !context_fields.is_empty() {
+                let format = context_fields
+                    .iter()
+                    .map(|f| format!("{}: {{:?}}", f.unraw()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let format = format!(" {{{{ {} }}}}", format);
+
+                Some(quote! {
+                    write!(#FORMATTER_ARG, #format, #(#context_fields),*)
+                })
+            } else {
+                None
+            };
+            // `Report` recognizes this form of the location and keeps
+            // it out of the text compared with the source's.
+            let location = field_container.display_location.as_ref().map(|location| {
+                quote! {
+                    write!(#FORMATTER_ARG, " (at {})", #location)
+                }
+            });
+            let with_suffixes = |message: TokenStream| {
+                context
+                    .iter()
+                    .chain(&location)
+                    .fold(message, |message, suffix| quote! { #message?; #suffix })
+            };
+
             if field_container.is_transparent {
                 // transparent errors always have a source field
                 let source_field_name = source_field.unwrap().name();
@@ -547,6 +1964,66 @@ pub mod display {
                 return;
             }
 
+            if let Some(with) = display_format.and_then(|d| d.with.as_ref()) {
+                let mut field_names = field_container.field_names.clone();
+
+                // The source is not made available to the display function.
+                if field_container.display_no_source {
+                    if let Some(source_field) = source_field {
+                        field_names.retain(|f| f != source_field.name());
+                    }
+                }
+
+                // Re-spanning the arguments makes type errors from a
+                // mismatched function signature point at the attribute.
+                let args = field_names.iter().map(|f| {
+                    let mut f = f.clone();
+                    f.set_span(with.span());
+                    f
+                });
+
+                let call = quote_spanned! { with.span()=>
+                    (#with)(#FORMATTER_ARG, #(#args),*)
+                };
+                let call = with_suffixes(call);
+
+                let match_arm = quote! {
+                    #pattern_ident { #(ref #field_names,)* .. } => {
+                        #inherited
+                        #call
+                    }
+                };
+
+                stream.extend(match_arm);
+                return;
+            }
+
+            if let Some(template) = display_format.and_then(|d| d.template.as_ref()) {
+                let crate::DisplayTemplate { template, args } = template;
+                let field_names = &field_container.field_names;
+
+                let args = args.iter().map(|(name, value)| {
+                    let name = name.unraw().to_string();
+                    quote_spanned! { value.span()=>
+                        (#name, &(#value) as &dyn ::core::fmt::Display)
+                    }
+                });
+
+                let call = with_suffixes(quote! {
+                    #crate_root::__format_template(#FORMATTER_ARG, #template, &[#(#args),*])
+                });
+
+                let match_arm = quote! {
+                    #pattern_ident { #(ref #field_names,)* .. } => {
+                        #inherited
+                        #call
+                    }
+                };
+
+                stream.extend(match_arm);
+                return;
+            }
+
             let mut shorthand_names = &BTreeSet::new();
             let mut assigned_names = &BTreeSet::new();
 
@@ -565,7 +2042,14 @@ pub mod display {
                 _ => quote! { stringify!(#default_name) },
             };
 
-            let field_names = super::AllFieldNames(field_container).field_names();
+            let mut field_names = super::AllFieldNames(field_container).field_names();
+
+            // The source is not made available to the display format.
+            if field_container.display_no_source {
+                if let Some(source_field) = source_field {
+                    field_names.remove(source_field.name());
+                }
+            }
 
             let shorthand_names = shorthand_names.iter().collect::<BTreeSet<_>>();
             let assigned_names = assigned_names.iter().collect::<BTreeSet<_>>();
@@ -573,11 +2057,122 @@ pub mod display {
             let shorthand_fields = &shorthand_names & &field_names;
             let shorthand_fields = &shorthand_fields - &assigned_names;
 
-            let shorthand_assignments = quote! { #( #shorthand_fields = #shorthand_fields ),* };
+            // A field's own `display(with)` function transforms it
+            // when it is captured by name.
+            let shorthand_assignments = shorthand_fields.iter().map(|&name| {
+                let with = field_container
+                    .field_display_withs
+                    .iter()
+                    .find(|(field, _)| field == name)
+                    .map(|(_, with)| with);
+
+                match with {
+                    Some(with) => {
+                        // Re-spanning the argument makes type errors from a
+                        // mismatched function signature point at the attribute.
+                        let mut arg = name.clone();
+                        arg.set_span(with.span());
+                        quote_spanned! { with.span()=> #name = (#with)(#arg) }
+                    }
+                    None => quote! { #name = #name },
+                }
+            });
+            let shorthand_assignments = quote! { #(#shorthand_assignments),* };
+
+            let write = with_suffixes(quote! {
+                write!(#FORMATTER_ARG, #format, #shorthand_assignments)
+            });
+
+            let match_arm = quote! {
+                #pattern_ident { #(ref #field_names,)* .. } => {
+                    #inherited
+                    #write
+                }
+            };
+
+            stream.extend(match_arm);
+        }
+    }
+}
+
+pub mod debug {
+    use super::StaticIdent;
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+    use syn::ext::IdentExt;
+
+    const FORMATTER_ARG: StaticIdent = StaticIdent("__snafu_debug_formatter");
+
+    pub(crate) struct Debug<'a> {
+        pub(crate) arms: &'a [TokenStream],
+        pub(crate) original_generics: &'a [TokenStream],
+        pub(crate) parameterized_error_name: &'a dyn ToTokens,
+        pub(crate) where_clauses: &'a [TokenStream],
+    }
+
+    impl ToTokens for Debug<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                arms,
+                original_generics,
+                parameterized_error_name,
+                where_clauses,
+            } = *self;
+
+            let debug_impl = quote! {
+                #[allow(single_use_lifetimes)]
+                impl<#(#original_generics),*> ::core::fmt::Debug for #parameterized_error_name
+                where
+                    #(#where_clauses),*
+                {
+                    fn fmt(&self, #FORMATTER_ARG: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        match *self {
+                            #(#arms),*
+                        }
+                    }
+                }
+            };
+
+            stream.extend(debug_impl);
+        }
+    }
+
+    pub(crate) struct DebugMatchArm<'a> {
+        pub(crate) crate_root: &'a dyn ToTokens,
+        pub(crate) field_container: &'a crate::FieldContainer,
+        pub(crate) pattern_ident: &'a dyn ToTokens,
+    }
+
+    impl ToTokens for DebugMatchArm<'_> {
+        fn to_tokens(&self, stream: &mut TokenStream) {
+            let Self {
+                crate_root,
+                field_container,
+                pattern_ident,
+            } = *self;
+
+            let name = field_container.name.unraw().to_string();
+            let field_names = &field_container.field_names;
+            let backtrace_field_name = field_container
+                .backtrace_field
+                .as_ref()
+                .map(crate::Field::name);
+
+            let fields = field_names.iter().map(|field_name| {
+                let field_name_str = field_name.unraw().to_string();
+
+                if Some(field_name) == backtrace_field_name {
+                    quote! { .field(#field_name_str, &#crate_root::CompactBacktrace(#field_name)) }
+                } else {
+                    quote! { .field(#field_name_str, #field_name) }
+                }
+            });
 
             let match_arm = quote! {
                 #pattern_ident { #(ref #field_names),* } => {
-                    write!(#FORMATTER_ARG, #format, #shorthand_assignments)
+                    #FORMATTER_ARG.debug_struct(#name)
+                        #(#fields)*
+                        .finish()
                 }
             };
 
@@ -695,20 +2290,34 @@ pub mod error {
             let arm = match source_field {
                 Some(source_field) => {
                     let SourceField {
-                        name: field_name, ..
+                        name: field_name,
+                        is_arc,
+                        is_reference,
+                        ..
                     } = source_field;
 
+                    // Look through the `Arc` or reference so that the
+                    // original error is the source, allowing it to be
+                    // downcast. A reference such as `&'static dyn Error`
+                    // is itself an `Error` and would otherwise become
+                    // the source.
+                    let source = if *is_arc || *is_reference {
+                        quote! { (**#field_name) }
+                    } else {
+                        quote! { #field_name }
+                    };
+
                     let convert_to_error_source = if selector_kind.is_whatever() {
                         quote! {
-                            #field_name.as_ref().map(|e| e.as_error_source())
+                            #source.as_ref().map(|e| e.as_error_source())
                         }
                     } else if *is_transparent {
                         quote! {
-                            #field_name.as_error_source().source()
+                            #source.as_error_source().source()
                         }
                     } else {
                         quote! {
-                            ::core::option::Option::Some(#field_name.as_error_source())
+                            ::core::option::Option::Some(#source.as_error_source())
                         }
                     };
 
@@ -759,6 +2368,8 @@ pub mod error {
             let provide_refs = user_fields
                 .iter()
                 .chain(&field_container.implicit_fields)
+                .chain(&field_container.source_display_fields)
+                .chain(field_container.ignored_fields.iter().map(|f| &f.field))
                 .chain(field_container.selector_kind.message_field())
                 .flat_map(|f| {
                     if f.provide {
@@ -0,0 +1,274 @@
+//! Backtrace types offering more control over when work is done and
+//! which frames are shown.
+
+use ::backtrace::{Backtrace, BacktraceFmt, BacktraceFrame, BytesOrWideString, PrintFmt};
+use std::{
+    fmt,
+    sync::{Mutex, PoisonError},
+};
+
+/// A backtrace that captures raw frame addresses and only resolves
+/// them to symbols when it is first displayed.
+///
+/// Capturing a backtrace is relatively cheap, but resolving the
+/// symbol names, file names, and line numbers of every frame is
+/// not. Errors that are often created but rarely reported can use
+/// this type to defer that cost until the backtrace is actually
+/// shown.
+///
+/// This type does not replace the SNAFU [`Backtrace`][crate::Backtrace]
+/// type, so it is not used for fields named `backtrace`. Mark the
+/// field as [implicit](crate::Snafu#controlling-implicitly-generated-data)
+/// instead:
+///
+/// ```rust
+/// use snafu::{backtrace::SymbolizedBacktrace, prelude::*};
+///
+/// #[derive(Debug, Snafu)]
+/// struct Error {
+///     #[snafu(implicit)]
+///     trace: SymbolizedBacktrace,
+/// }
+///
+/// let error = Snafu.build();
+/// assert!(!error.trace.is_resolved());
+///
+/// println!("{}", error.trace);
+/// assert!(error.trace.is_resolved());
+/// ```
+pub struct SymbolizedBacktrace {
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    backtrace: Backtrace,
+    resolved: bool,
+}
+
+impl SymbolizedBacktrace {
+    /// Captures the frames of the current thread's stack without
+    /// resolving any symbols.
+    pub fn capture() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                backtrace: Backtrace::new_unresolved(),
+                resolved: false,
+            }),
+        }
+    }
+
+    /// Resolves the symbols of every frame, if that has not already
+    /// been done.
+    pub fn resolve(&self) {
+        self.with_resolved(|_| ());
+    }
+
+    /// Returns `true` once the symbols of the frames have been
+    /// resolved.
+    pub fn is_resolved(&self) -> bool {
+        self.lock().resolved
+    }
+
+    fn with_resolved<R>(&self, f: impl FnOnce(&Backtrace) -> R) -> R {
+        let mut inner = self.lock();
+
+        if !inner.resolved {
+            inner.backtrace.resolve();
+            inner.resolved = true;
+        }
+
+        f(&inner.backtrace)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        // Resolving is the only operation that mutates the
+        // backtrace; a panic during it leaves the frames usable.
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl crate::GenerateImplicitData for SymbolizedBacktrace {
+    fn generate() -> Self {
+        Self::capture()
+    }
+}
+
+impl fmt::Display for SymbolizedBacktrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_resolved(|backtrace| fmt::Debug::fmt(backtrace, f))
+    }
+}
+
+impl fmt::Debug for SymbolizedBacktrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SymbolizedBacktrace")
+            .field("resolved", &self.is_resolved())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the frames of a SNAFU [`Backtrace`][crate::Backtrace] as
+/// frames of the `backtrace` crate.
+///
+/// Only the `backtrace` crate's implementation, selected by the
+/// `backtraces-impl-backtrace-crate` feature, exposes its frames. With
+/// any other implementation this returns `None`, so reporting code can
+/// fall back to [`DisplayBacktrace`][crate::DisplayBacktrace]:
+///
+/// ```rust
+/// use snafu::{backtrace, prelude::*, Backtrace, DisplayBacktrace, ErrorCompat};
+///
+/// #[derive(Debug, Snafu)]
+/// struct Error {
+///     backtrace: Backtrace,
+/// }
+///
+/// let error = Snafu.build();
+/// let trace = ErrorCompat::backtrace(&error).unwrap();
+///
+/// match backtrace::frames(trace) {
+///     Some(frames) => eprintln!("{} frames", frames.len()),
+///     None => eprintln!("{}", DisplayBacktrace(trace)),
+/// }
+/// ```
+pub fn frames(backtrace: &crate::Backtrace) -> Option<&[BacktraceFrame]> {
+    #[cfg(feature = "backtraces-impl-backtrace-crate")]
+    {
+        Some(backtrace.frames())
+    }
+
+    #[cfg(not(feature = "backtraces-impl-backtrace-crate"))]
+    {
+        let _ = backtrace;
+        None
+    }
+}
+
+/// Converts a SNAFU [`Backtrace`][crate::Backtrace] into a
+/// `backtrace::Backtrace` with the same frames.
+///
+/// This is `None` whenever [`frames`][] is.
+pub fn to_backtrace_crate(backtrace: &crate::Backtrace) -> Option<Backtrace> {
+    frames(backtrace).map(|frames| Backtrace::from(frames.to_vec()))
+}
+
+/// Wraps a backtrace so that frames matching `exclude` are skipped
+/// when it is displayed.
+///
+/// Frames are matched as they are displayed, so the backtrace itself
+/// is not changed. A backtrace that has not been resolved has no
+/// symbol names for the predicate to inspect.
+///
+/// [`in_crate`][] builds a predicate that matches the frames of a
+/// crate, such as SNAFU's own frames for creating the error:
+///
+/// ```rust
+/// use snafu::backtrace::{filter_frames, in_crate};
+///
+/// let backtrace = backtrace::Backtrace::new();
+/// let without_snafu = filter_frames(&backtrace, in_crate("snafu"));
+///
+/// println!("{}", without_snafu);
+/// ```
+///
+/// When the `backtraces-impl-backtrace-crate` feature is enabled,
+/// the SNAFU [`Backtrace`][crate::Backtrace] type is the same type
+/// and can be filtered directly.
+pub fn filter_frames<'a>(
+    backtrace: &'a Backtrace,
+    exclude: impl Fn(&BacktraceFrame) -> bool + 'a,
+) -> FilteredBacktrace<'a> {
+    FilteredBacktrace {
+        backtrace,
+        exclude: Box::new(exclude),
+    }
+}
+
+/// Returns a predicate for [`filter_frames`][] that matches frames
+/// from the crate named `crate_name`.
+///
+/// A frame matches when any of its symbols is a path in the crate,
+/// including a method of one of the crate's traits. The predicate for
+/// `"snafu"` therefore also matches `IntoError::into_error` for
+/// context selectors defined in other crates.
+pub fn in_crate(crate_name: &str) -> impl Fn(&BacktraceFrame) -> bool + '_ {
+    move |frame| {
+        frame.symbols().iter().any(|symbol| {
+            symbol.name().map_or(false, |name| {
+                let name = name.to_string();
+                let path = name.trim_start_matches('<');
+
+                is_path_in_crate(path, crate_name)
+                    || name
+                        .split(" as ")
+                        .skip(1)
+                        .any(|trait_path| is_path_in_crate(trait_path, crate_name))
+            })
+        })
+    }
+}
+
+fn is_path_in_crate(path: &str, crate_name: &str) -> bool {
+    path.strip_prefix(crate_name)
+        .map_or(false, |rest| rest.starts_with("::"))
+}
+
+/// A backtrace that skips some of its frames when displayed.
+///
+/// Created by [`filter_frames`][]. The output has the same format as
+/// the `Debug` output of the backtrace; use the alternate form
+/// (`{:#}`) to show the full paths of files.
+pub struct FilteredBacktrace<'a> {
+    backtrace: &'a Backtrace,
+    exclude: Box<dyn Fn(&BacktraceFrame) -> bool + 'a>,
+}
+
+impl FilteredBacktrace<'_> {
+    /// Returns the frames that are not excluded.
+    pub fn frames(&self) -> impl Iterator<Item = &BacktraceFrame> {
+        self.backtrace
+            .frames()
+            .iter()
+            .filter(move |frame| !(self.exclude)(frame))
+    }
+}
+
+impl fmt::Display for FilteredBacktrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let style = if f.alternate() {
+            PrintFmt::Full
+        } else {
+            PrintFmt::Short
+        };
+
+        // Paths are shown relative to the current directory, as the
+        // backtrace itself does.
+        let cwd = std::env::current_dir();
+        let mut print_path = move |f: &mut fmt::Formatter<'_>, path: BytesOrWideString<'_>| {
+            let path = path.into_path_buf();
+            if style != PrintFmt::Full {
+                if let Ok(cwd) = &cwd {
+                    if let Ok(suffix) = path.strip_prefix(cwd) {
+                        return fmt::Display::fmt(&suffix.display(), f);
+                    }
+                }
+            }
+            fmt::Display::fmt(&path.display(), f)
+        };
+
+        let mut f = BacktraceFmt::new(f, style, &mut print_path);
+        f.add_context()?;
+        for frame in self.frames() {
+            f.frame().backtrace_frame(frame)?;
+        }
+        f.finish()
+    }
+}
+
+impl fmt::Debug for FilteredBacktrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilteredBacktrace")
+            .field("frames", &self.frames().count())
+            .finish_non_exhaustive()
+    }
+}
@@ -1,7 +1,18 @@
+use core::fmt;
+
 pub use backtrace::Backtrace;
 
 impl crate::GenerateImplicitData for Backtrace {
     fn generate() -> Self {
+        // Faking backtraces is only possible with `std`, which also
+        // provides `Vec` in the prelude.
+        #[cfg(any(feature = "std", test))]
+        {
+            if crate::fake_backtraces() {
+                return Backtrace::from(Vec::new());
+            }
+        }
+
         Backtrace::new()
     }
 }
@@ -11,3 +22,12 @@ impl crate::AsBacktrace for Backtrace {
         Some(self)
     }
 }
+
+pub(crate) fn is_captured(_backtrace: &Backtrace) -> bool {
+    true
+}
+
+// This type only implements `Debug`, which lists the resolved frames.
+pub(crate) fn fmt_display(backtrace: &Backtrace, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Debug::fmt(backtrace, f)
+}
@@ -19,6 +19,14 @@ impl crate::AsBacktrace for Backtrace {
     }
 }
 
+pub(crate) fn is_captured(_backtrace: &Backtrace) -> bool {
+    false
+}
+
+pub(crate) fn fmt_display(backtrace: &Backtrace, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(backtrace, f)
+}
+
 impl fmt::Display for Backtrace {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "disabled backtrace")
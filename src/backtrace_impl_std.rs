@@ -1,8 +1,14 @@
+use core::fmt;
+
 pub use std::backtrace::Backtrace;
 
 impl crate::GenerateImplicitData for Backtrace {
     fn generate() -> Self {
-        Backtrace::force_capture()
+        if crate::fake_backtraces() {
+            Backtrace::disabled()
+        } else {
+            Backtrace::force_capture()
+        }
     }
 }
 
@@ -11,3 +17,11 @@ impl crate::AsBacktrace for Backtrace {
         Some(self)
     }
 }
+
+pub(crate) fn is_captured(backtrace: &Backtrace) -> bool {
+    backtrace.status() == std::backtrace::BacktraceStatus::Captured
+}
+
+pub(crate) fn fmt_display(backtrace: &Backtrace, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(backtrace, f)
+}
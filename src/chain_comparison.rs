@@ -0,0 +1,165 @@
+use crate::{ChainCompat, Error};
+use std::fmt;
+
+/// Compares two errors and each of their sources, level by level,
+/// using their `Display` output.
+///
+/// Returns the first level where the chains differ. Level `0` is the
+/// errors themselves, level `1` is their sources, and so on. Chains of
+/// different lengths differ at the first level that only one of them
+/// has.
+///
+/// This works for any error type, not only those created by SNAFU.
+/// [`assert_same_chain!`][crate::assert_same_chain] panics with the
+/// difference instead.
+///
+/// ```rust
+/// use snafu::{prelude::*, IntoError};
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     #[snafu(display("could not load {name}"))]
+///     Load { name: String, source: std::io::Error },
+/// }
+///
+/// let load = |kind, message| {
+///     let source = std::io::Error::new(kind, message);
+///     LoadSnafu { name: "settings" }.into_error(source)
+/// };
+///
+/// let left = load(std::io::ErrorKind::NotFound, "no such file");
+/// let right = load(std::io::ErrorKind::Other, "disk on fire");
+///
+/// let difference = snafu::compare_chains(&left, &right).unwrap_err();
+///
+/// assert_eq!(difference.level(), 1);
+/// assert_eq!(difference.left(), Some("no such file"));
+/// assert_eq!(difference.right(), Some("disk on fire"));
+/// ```
+pub fn compare_chains<'a, 'b>(
+    left: &'a (dyn Error + 'b),
+    right: &'a (dyn Error + 'b),
+) -> Result<(), ChainDifference> {
+    let mut left_chain = ChainCompat::new(left);
+    let mut right_chain = ChainCompat::new(right);
+    let mut level = 0;
+
+    loop {
+        let (left, right) = match (left_chain.next(), right_chain.next()) {
+            (None, None) => return Ok(()),
+            (left, right) => (left.map(|e| e.to_string()), right.map(|e| e.to_string())),
+        };
+
+        if left != right {
+            return Err(ChainDifference { level, left, right });
+        }
+
+        level += 1;
+    }
+}
+
+/// The first level where two chains of errors differ, as found by
+/// [`compare_chains`][].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainDifference {
+    level: usize,
+    left: Option<String>,
+    right: Option<String>,
+}
+
+impl ChainDifference {
+    /// The position in the chains where they differ, where `0` is the
+    /// outermost error.
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// The `Display` output of the left error at this level, or `None`
+    /// if the left chain ended before this level.
+    pub fn left(&self) -> Option<&str> {
+        self.left.as_deref()
+    }
+
+    /// The `Display` output of the right error at this level, or
+    /// `None` if the right chain ended before this level.
+    pub fn right(&self) -> Option<&str> {
+        self.right.as_deref()
+    }
+}
+
+impl fmt::Display for ChainDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = |f: &mut fmt::Formatter<'_>, message: &Option<String>| match message {
+            Some(message) => write!(f, "{:?}", message),
+            None => f.write_str("<end of chain>"),
+        };
+
+        writeln!(f, "error chains differ at level {}", self.level)?;
+        f.write_str("  left: ")?;
+        side(f, &self.left)?;
+        f.write_str("\n right: ")?;
+        side(f, &self.right)
+    }
+}
+
+impl Error for ChainDifference {}
+
+/// Asserts that two errors and each of their sources have the same
+/// `Display` output, level by level.
+///
+/// On failure, this panics with the first level where the chains
+/// differ and what each error displays there. Any error type can be
+/// compared, including boxed trait objects. An optional message can
+/// be given, as with [`assert_eq!`][].
+///
+/// ```rust
+/// use snafu::{prelude::*, IntoError};
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     #[snafu(display("could not load {name}"))]
+///     Load { name: String, source: std::io::Error },
+/// }
+///
+/// fn load(name: &str) -> Error {
+///     let source = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+///     LoadSnafu { name }.into_error(source)
+/// }
+///
+/// let expected: Box<dyn std::error::Error> = Box::new(load("settings"));
+///
+/// snafu::assert_same_chain!(load("settings"), expected);
+/// snafu::assert_same_chain!(load("settings"), expected, "while loading {}", "settings");
+/// ```
+///
+/// See [`compare_chains`][crate::compare_chains] to inspect the
+/// difference instead of panicking.
+#[macro_export]
+macro_rules! assert_same_chain {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                use $crate::AsErrorSource as _;
+
+                if let ::core::result::Result::Err(difference) =
+                    $crate::compare_chains(left.as_error_source(), right.as_error_source())
+                {
+                    ::core::panic!("{}", difference);
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                use $crate::AsErrorSource as _;
+
+                if let ::core::result::Result::Err(difference) =
+                    $crate::compare_chains(left.as_error_source(), right.as_error_source())
+                {
+                    ::core::panic!("{}: {}", ::core::format_args!($($arg)+), difference);
+                }
+            }
+        }
+    };
+}
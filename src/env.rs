@@ -0,0 +1,88 @@
+//! Runtime control over settings that are otherwise read from
+//! environment variables.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Controls whether optional backtraces are captured.
+///
+/// This affects fields of type `Option<Backtrace>` (and
+/// `Option<Arc<Backtrace>>`); fields of type `Backtrace` are always
+/// captured.
+///
+/// See [`set_backtrace_policy`][].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum BacktracePolicy {
+    /// Always capture a backtrace, regardless of the environment
+    /// variables.
+    AlwaysCapture,
+    /// Never capture a backtrace, regardless of the environment
+    /// variables.
+    NeverCapture,
+    /// Capture a backtrace if `RUST_LIB_BACKTRACE` or
+    /// `RUST_BACKTRACE` is set to `1`. The environment variables are
+    /// read only once per process. This is the default.
+    UseEnvVar,
+    /// Always provide a backtrace, but do not capture any frames.
+    ///
+    /// Every backtrace, including fields of type `Backtrace`, is
+    /// displayed as [`FAKE_BACKTRACE`][]. This makes the output of
+    /// [`Report`][crate::Report] and [`DisplayBacktrace`][crate::DisplayBacktrace]
+    /// the same on every machine, such as for snapshot tests, while
+    /// still treating the backtrace as present.
+    Fake,
+}
+
+impl BacktracePolicy {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            v if v == Self::AlwaysCapture as u8 => Self::AlwaysCapture,
+            v if v == Self::NeverCapture as u8 => Self::NeverCapture,
+            v if v == Self::Fake as u8 => Self::Fake,
+            _ => Self::UseEnvVar,
+        }
+    }
+}
+
+/// How every backtrace is displayed under [`BacktracePolicy::Fake`][].
+pub const FAKE_BACKTRACE: &str = "<backtrace captured: 0 frames>";
+
+static BACKTRACE_POLICY: AtomicU8 = AtomicU8::new(BacktracePolicy::UseEnvVar as u8);
+
+/// Overrides whether optional backtraces are captured for the entire
+/// process.
+///
+/// This may be called at any time and from any thread; errors created
+/// afterwards use the new policy. This is useful for tests or for
+/// long-running processes that change their behavior at runtime.
+///
+/// ```rust
+/// use snafu::{
+///     env::{self, BacktracePolicy},
+///     prelude::*,
+///     Backtrace, ErrorCompat,
+/// };
+///
+/// #[derive(Debug, Snafu)]
+/// struct Error {
+///     backtrace: Option<Backtrace>,
+/// }
+///
+/// env::set_backtrace_policy(BacktracePolicy::AlwaysCapture);
+/// assert!(Snafu.build().backtrace().is_some());
+///
+/// env::set_backtrace_policy(BacktracePolicy::NeverCapture);
+/// assert!(Snafu.build().backtrace().is_none());
+/// ```
+pub fn set_backtrace_policy(policy: BacktracePolicy) {
+    BACKTRACE_POLICY.store(policy as u8, Ordering::SeqCst);
+}
+
+/// Returns the current policy for capturing optional backtraces.
+pub fn backtrace_policy() -> BacktracePolicy {
+    BacktracePolicy::from_u8(BACKTRACE_POLICY.load(Ordering::SeqCst))
+}
+
+pub(crate) fn fake_backtraces() -> bool {
+    backtrace_policy() == BacktracePolicy::Fake
+}
@@ -0,0 +1,310 @@
+use crate::{Backtrace, ErrorCompat, IntoError, NoneError};
+use alloc::vec::{self, Vec};
+use core::{fmt, slice};
+
+/// Collects multiple errors so that all of them can be reported at
+/// once.
+///
+/// This is useful when validating a value where every problem should
+/// be reported instead of stopping at the first one, as [`ensure!`][]
+/// would.
+///
+/// Nothing is allocated until the first error is recorded.
+///
+/// ```rust
+/// use snafu::{prelude::*, ErrorAccumulator, MultipleErrors};
+///
+/// #[derive(Debug, Snafu)]
+/// enum ConfigError {
+///     #[snafu(display("The name must not be empty"))]
+///     EmptyName,
+///
+///     #[snafu(display("The port {port} is reserved"))]
+///     ReservedPort { port: u16 },
+/// }
+///
+/// struct Config {
+///     name: String,
+///     port: u16,
+/// }
+///
+/// fn validate(config: &Config) -> Result<(), MultipleErrors<ConfigError>> {
+///     let mut errors = ErrorAccumulator::new();
+///
+///     errors.ensure(!config.name.is_empty(), EmptyNameSnafu);
+///     errors.ensure(config.port >= 1024, ReservedPortSnafu { port: config.port });
+///
+///     errors.finish()
+/// }
+///
+/// let error = validate(&Config {
+///     name: String::new(),
+///     port: 80,
+/// })
+/// .unwrap_err();
+///
+/// assert_eq!(error.errors().len(), 2);
+/// assert_eq!(
+///     error.to_string(),
+///     "2 errors occurred:\n1. The name must not be empty\n2. The port 80 is reserved",
+/// );
+/// ```
+#[derive(Debug)]
+pub struct ErrorAccumulator<E> {
+    errors: Vec<E>,
+}
+
+impl<E> ErrorAccumulator<E> {
+    /// Creates an accumulator without any errors.
+    pub const fn new() -> Self {
+        Self { errors: Vec::new() }
+    }
+
+    /// Records an error built from the context selector if the
+    /// predicate is false.
+    ///
+    /// Returns the value of the predicate.
+    #[track_caller]
+    pub fn ensure<C>(&mut self, predicate: bool, context_selector: C) -> bool
+    where
+        C: IntoError<E, Source = NoneError>,
+        E: crate::Error + ErrorCompat,
+    {
+        if !predicate {
+            self.push(context_selector.into_error(NoneError));
+        }
+        predicate
+    }
+
+    /// Records an error.
+    pub fn push(&mut self, error: E) {
+        self.errors.push(error);
+    }
+
+    /// Returns `true` if no errors have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the number of errors that have been recorded.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Returns `Ok` if no errors have been recorded, otherwise
+    /// returns all of the recorded errors.
+    pub fn finish(self) -> Result<(), MultipleErrors<E>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(MultipleErrors {
+                errors: self.errors,
+            })
+        }
+    }
+}
+
+impl<E> Default for ErrorAccumulator<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> Extend<E> for ErrorAccumulator<E> {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        self.errors.extend(iter)
+    }
+}
+
+/// One or more errors collected by an [`ErrorAccumulator`].
+///
+/// The `Display` implementation shows a numbered list of each
+/// error's message. The backtrace reported by [`ErrorCompat`] is the
+/// first backtrace found among the errors.
+#[derive(Debug)]
+pub struct MultipleErrors<E> {
+    errors: Vec<E>,
+}
+
+impl<E> MultipleErrors<E> {
+    /// The collected errors, in the order they were recorded.
+    pub fn errors(&self) -> &[E] {
+        &self.errors
+    }
+
+    /// Returns an iterator over the collected errors.
+    pub fn iter(&self) -> slice::Iter<'_, E> {
+        self.errors.iter()
+    }
+
+    /// Consumes this value, returning the collected errors.
+    pub fn into_errors(self) -> Vec<E> {
+        self.errors
+    }
+}
+
+impl<E> IntoIterator for MultipleErrors<E> {
+    type Item = E;
+    type IntoIter = vec::IntoIter<E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl<'a, E> IntoIterator for &'a MultipleErrors<E> {
+    type Item = &'a E;
+    type IntoIter = slice::Iter<'a, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<E> fmt::Display for MultipleErrors<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.errors.len() {
+            1 => write!(f, "1 error occurred:")?,
+            n => write!(f, "{} errors occurred:", n)?,
+        }
+
+        for (i, error) in self.errors.iter().enumerate() {
+            write!(f, "\n{}. {}", i + 1, error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E> crate::Error for MultipleErrors<E>
+where
+    E: crate::Error,
+{
+    #[allow(deprecated)]
+    fn description(&self) -> &str {
+        "multiple errors occurred"
+    }
+}
+
+impl<E> ErrorCompat for MultipleErrors<E>
+where
+    E: ErrorCompat,
+{
+    fn backtrace(&self) -> Option<&Backtrace> {
+        self.errors.iter().find_map(ErrorCompat::backtrace)
+    }
+}
+
+/// Additions to iterators of [`Result`][].
+pub trait ResultIteratorExt<T, E>: Iterator<Item = Result<T, E>> + Sized {
+    /// Collects every success and every error, instead of stopping
+    /// at the first error as collecting into a `Result` does.
+    ///
+    /// Returns `Ok` with all of the values if there were no errors,
+    /// otherwise returns `Err` with all of the errors in the order
+    /// they were produced.
+    ///
+    /// ```rust
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// #[snafu(display("{text:?} is not a number"))]
+    /// struct ParseError {
+    ///     text: String,
+    ///     source: std::num::ParseIntError,
+    /// }
+    ///
+    /// fn parse_all(texts: &[&str]) -> Result<Vec<i32>, Vec<ParseError>> {
+    ///     texts
+    ///         .iter()
+    ///         .map(|&text| text.parse().context(ParseSnafu { text }))
+    ///         .collect_all_errors()
+    /// }
+    ///
+    /// assert_eq!(parse_all(&["1", "2"]).unwrap(), [1, 2]);
+    ///
+    /// let errors = parse_all(&["1", "a", "b"]).unwrap_err();
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors[0].to_string(), r#""a" is not a number"#);
+    /// ```
+    ///
+    /// The errors can be reported together by collecting them into
+    /// an [`ErrorAccumulator`][].
+    fn collect_all_errors(self) -> Result<Vec<T>, Vec<E>>;
+}
+
+impl<I, T, E> ResultIteratorExt<T, E> for I
+where
+    I: Iterator<Item = Result<T, E>>,
+{
+    fn collect_all_errors(self) -> Result<Vec<T>, Vec<E>> {
+        let mut values = Vec::new();
+        let mut errors = Vec::new();
+
+        for item in self {
+            match item {
+                Ok(value) => {
+                    // Once there is an error the values will be
+                    // discarded, so don't keep collecting them.
+                    if errors.is_empty() {
+                        values.push(value);
+                    }
+                }
+                Err(error) => errors.push(error),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(values)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Separates the successes from the failures, keeping both.
+///
+/// Unlike [`ResultIteratorExt::collect_all_errors`][], the values are
+/// returned even when there are errors. This is useful when each item
+/// is processed independently and the successful ones should still
+/// be used.
+///
+/// ```rust
+/// use snafu::prelude::*;
+///
+/// #[derive(Debug, Snafu)]
+/// #[snafu(display("{text:?} is not a number"))]
+/// struct ParseError {
+///     text: String,
+///     source: std::num::ParseIntError,
+/// }
+///
+/// let (values, errors) = snafu::collect_errors(
+///     ["1", "a", "3"]
+///         .iter()
+///         .map(|&text| text.parse::<i32>().context(ParseSnafu { text })),
+/// );
+///
+/// assert_eq!(values, [1, 3]);
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].to_string(), r#""a" is not a number"#);
+/// ```
+pub fn collect_errors<T, E, I>(iter: I) -> (Vec<T>, Vec<E>)
+where
+    I: IntoIterator<Item = Result<T, E>>,
+{
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+
+    for item in iter {
+        match item {
+            Ok(value) => values.push(value),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    (values, errors)
+}
@@ -1,17 +1,130 @@
+use core::fmt;
+
+/// The default maximum number of errors visited when walking a chain
+/// of sources.
+///
+/// Used by [`ChainCompat::new`][], [`CleanedErrorText::new`][crate::CleanedErrorText::new],
+/// and [`Report`][crate::Report].
+pub const DEFAULT_CHAIN_LIMIT: usize = 128;
+
+/// The number of consecutive errors with identical `Display` output
+/// that are printed before the rest of the chain is skipped.
+///
+/// An error that formats itself, or wraps copies of itself, would
+/// otherwise fill a log with the same message. Used by
+/// [`display_chain`][] and [`Report`][crate::Report].
+pub const MAX_REPEATED_MESSAGES: usize = 5;
+
+/// Written in place of the rest of a chain whose messages repeat more
+/// than [`MAX_REPEATED_MESSAGES`][] times.
+pub(crate) const REPEATED_MESSAGES_TRUNCATED: &str = "<repeated messages truncated>";
+
+/// Stands in for the rest of a chain of sources that was not walked.
+///
+/// A buggy error may return itself from `source`, or a chain may be
+/// unreasonably long. Rather than looping forever, the utilities that
+/// walk a chain stop and yield one of these as the final error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChainTruncated {
+    /// An error returned itself as its own source.
+    Cycle,
+    /// The chain contained more errors than the limit.
+    Limit,
+}
+
+static CYCLE: ChainTruncated = ChainTruncated::Cycle;
+static LIMIT: ChainTruncated = ChainTruncated::Limit;
+
+impl fmt::Display for ChainTruncated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainTruncated::Cycle => f.write_str("<cycle detected>"),
+            ChainTruncated::Limit => f.write_str("<chain truncated>"),
+        }
+    }
+}
+
+impl crate::Error for ChainTruncated {}
+
+/// Returns the source of the error, substituting a [`ChainTruncated`]
+/// if the error is its own source or `visited` has reached `limit`.
+pub(crate) fn next_source<'a, 'b>(
+    error: &'a (dyn crate::Error + 'b),
+    visited: usize,
+    limit: usize,
+) -> Option<&'a (dyn crate::Error + 'b)> {
+    let source = error.source()?;
+
+    if is_self_cycle(error, source) {
+        Some(&CYCLE)
+    } else if visited >= limit {
+        Some(&LIMIT)
+    } else {
+        Some(source)
+    }
+}
+
+/// Whether `error` returned itself as its own `source`.
+///
+/// Both the data address and the vtable are compared. A source stored
+/// at the start of the error, or a zero-sized source, can share the
+/// error's address, but it is a different type with a different
+/// vtable. The same type may have several vtables, so a cycle can be
+/// missed here; those are stopped by the chain limit.
+fn is_self_cycle(error: &dyn crate::Error, source: &dyn crate::Error) -> bool {
+    core::ptr::eq(error, source)
+}
+
 /// An iterator over an Error and its sources.
 ///
 /// If you want to omit the initial error and only process its sources, use `skip(1)`.
 ///
+/// At most [`DEFAULT_CHAIN_LIMIT`][] errors are visited, or the limit
+/// given to [`ChainCompat::with_limit`][]. If the chain is longer, or
+/// an error returns itself as its source, the final item is a
+/// [`ChainTruncated`][].
+///
 /// Can be created via [`ErrorCompat::iter_chain`][crate::ErrorCompat::iter_chain].
 #[derive(Debug, Clone)]
 pub struct ChainCompat<'a, 'b> {
     inner: Option<&'a (dyn crate::Error + 'b)>,
+    visited: usize,
+    limit: usize,
 }
 
 impl<'a, 'b> ChainCompat<'a, 'b> {
     /// Creates a new error chain iterator.
     pub fn new(error: &'a (dyn crate::Error + 'b)) -> Self {
-        ChainCompat { inner: Some(error) }
+        Self::with_limit(error, DEFAULT_CHAIN_LIMIT)
+    }
+
+    /// Creates a new error chain iterator that visits at most `limit`
+    /// errors before yielding [`ChainTruncated::Limit`][].
+    ///
+    /// ```rust
+    /// use snafu::{prelude::*, ChainCompat, ChainTruncated, IntoError};
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     Outer { source: Box<Error> },
+    ///     Inner,
+    /// }
+    ///
+    /// let inner = InnerSnafu.build();
+    /// let middle = OuterSnafu.into_error(Box::new(inner));
+    /// let error = OuterSnafu.into_error(Box::new(middle));
+    ///
+    /// let chain: Vec<_> = ChainCompat::with_limit(&error, 2).collect();
+    ///
+    /// assert_eq!(chain.len(), 3);
+    /// assert!(chain[2].is::<ChainTruncated>());
+    /// ```
+    pub fn with_limit(error: &'a (dyn crate::Error + 'b), limit: usize) -> Self {
+        ChainCompat {
+            inner: Some(error),
+            visited: 0,
+            limit,
+        }
     }
 }
 
@@ -22,9 +135,182 @@ impl<'a, 'b> Iterator for ChainCompat<'a, 'b> {
         match self.inner {
             None => None,
             Some(e) => {
-                self.inner = e.source();
+                self.visited += 1;
+                self.inner = next_source(e, self.visited, self.limit);
                 Some(e)
             }
         }
     }
 }
+
+/// Tracks runs of errors with identical `Display` output while a
+/// chain is printed.
+///
+/// Messages are compared by a hash of their text, so nothing needs to
+/// be allocated.
+pub(crate) struct RepeatGuard {
+    last: Option<(u64, usize)>,
+    repeats: usize,
+}
+
+impl RepeatGuard {
+    pub(crate) fn new() -> Self {
+        RepeatGuard {
+            last: None,
+            repeats: 0,
+        }
+    }
+
+    /// Returns `true` when `error` has the same message as more than
+    /// [`MAX_REPEATED_MESSAGES`][] errors directly before it.
+    pub(crate) fn is_exceeded_by(&mut self, error: &dyn crate::Error) -> bool {
+        let fingerprint = MessageHasher::fingerprint(error);
+
+        if fingerprint.is_some() && fingerprint == self.last {
+            self.repeats += 1;
+        } else {
+            self.last = fingerprint;
+            self.repeats = 1;
+        }
+
+        self.repeats > MAX_REPEATED_MESSAGES
+    }
+}
+
+/// An FNV-1a hash of everything written, along with its length.
+struct MessageHasher {
+    hash: u64,
+    len: usize,
+}
+
+impl MessageHasher {
+    fn fingerprint(error: &dyn crate::Error) -> Option<(u64, usize)> {
+        let mut hasher = MessageHasher {
+            hash: 0xcbf2_9ce4_8422_2325,
+            len: 0,
+        };
+        fmt::write(&mut hasher, format_args!("{}", error)).ok()?;
+        Some((hasher.hash, hasher.len))
+    }
+}
+
+impl fmt::Write for MessageHasher {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for b in s.bytes() {
+            self.hash ^= u64::from(b);
+            self.hash = self.hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+/// Displays an error and each of its sources on a single line,
+/// separated by `: `.
+///
+/// Nothing is allocated; the chain is walked each time the value is
+/// formatted. The chain is truncated the same way as
+/// [`ChainCompat`][], and after more than [`MAX_REPEATED_MESSAGES`][]
+/// consecutive identical messages.
+///
+/// Created by [`display_chain`][].
+#[derive(Debug, Copy, Clone)]
+pub struct ChainDisplay<'a> {
+    error: &'a (dyn crate::Error + 'a),
+}
+
+impl<'a> ChainDisplay<'a> {
+    /// Displays the chain starting at `error`.
+    ///
+    /// Unlike [`display_chain`][], this also accepts unsized errors
+    /// such as boxed trait objects.
+    pub fn new(error: &'a (dyn crate::Error + 'a)) -> Self {
+        ChainDisplay { error }
+    }
+}
+
+impl<'a> fmt::Display for ChainDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut guard = RepeatGuard::new();
+
+        for (i, error) in ChainCompat::new(self.error).enumerate() {
+            if i != 0 {
+                f.write_str(": ")?;
+            }
+            if guard.is_exceeded_by(error) {
+                return f.write_str(REPEATED_MESSAGES_TRUNCATED);
+            }
+            fmt::Display::fmt(error, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lazily displays an error and each of its sources on a single line.
+///
+/// This works for any error type, not only those created by SNAFU.
+///
+/// ```rust
+/// use snafu::{prelude::*, IntoError};
+///
+/// #[derive(Debug, Snafu)]
+/// #[snafu(display("could not load the settings"))]
+/// struct SettingsError {
+///     source: std::io::Error,
+/// }
+///
+/// let source = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+/// let error = SettingsSnafu.into_error(source);
+///
+/// assert_eq!(
+///     snafu::display_chain(&error).to_string(),
+///     "could not load the settings: no such file",
+/// );
+/// ```
+pub fn display_chain<E>(error: &E) -> ChainDisplay<'_>
+where
+    E: crate::Error,
+{
+    ChainDisplay::new(error)
+}
+
+/// An owned copy of an error's `Display` and `Debug` output.
+///
+/// The original error's sources are not preserved; each error in a
+/// chain is captured separately.
+///
+/// Created by [`ResultExt::context_chain`][crate::ResultExt::context_chain].
+#[cfg(any(feature = "std", test))]
+#[derive(Clone, PartialEq, Eq)]
+pub struct CapturedError {
+    display: String,
+    debug: String,
+}
+
+#[cfg(any(feature = "std", test))]
+impl CapturedError {
+    /// Captures the `Display` and `Debug` output of the error.
+    pub fn new(error: &dyn crate::Error) -> Self {
+        CapturedError {
+            display: error.to_string(),
+            debug: format!("{:?}", error),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl core::fmt::Debug for CapturedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.debug)
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl core::fmt::Display for CapturedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.display)
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl crate::Error for CapturedError {}
@@ -0,0 +1,90 @@
+use crate::{ChainCompat, Error};
+use std::fmt;
+
+/// Bounds the number of errors stored in a chain of sources.
+///
+/// Used by the code generated for `#[snafu(error_chain_limit = N)]`.
+/// A chain that is already within the limit is returned unchanged.
+/// Otherwise, the first `limit` errors are replaced by copies of their
+/// `Display` output and the rest of the chain is dropped. The deepest
+/// copy is suffixed with the number of errors that were dropped.
+pub trait LimitErrorChain {
+    /// Limits the chain starting at `self` to `limit` errors.
+    fn limit_error_chain(self, limit: usize) -> Self;
+}
+
+impl LimitErrorChain for Box<dyn Error> {
+    fn limit_error_chain(self, limit: usize) -> Self {
+        match TruncatedError::new(&*self, limit) {
+            Some(truncated) => Box::new(truncated),
+            None => self,
+        }
+    }
+}
+
+impl LimitErrorChain for Box<dyn Error + Send> {
+    fn limit_error_chain(self, limit: usize) -> Self {
+        match TruncatedError::new(&*self, limit) {
+            Some(truncated) => Box::new(truncated),
+            None => self,
+        }
+    }
+}
+
+impl LimitErrorChain for Box<dyn Error + Send + Sync> {
+    fn limit_error_chain(self, limit: usize) -> Self {
+        match TruncatedError::new(&*self, limit) {
+            Some(truncated) => Box::new(truncated),
+            None => self,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TruncatedError {
+    message: String,
+    source: Option<Box<TruncatedError>>,
+}
+
+impl TruncatedError {
+    fn new(error: &dyn Error, limit: usize) -> Option<Self> {
+        let depth = ChainCompat::new(error).count();
+        if depth <= limit {
+            return None;
+        }
+
+        let mut messages: Vec<_> = ChainCompat::new(error)
+            .take(limit)
+            .map(|e| e.to_string())
+            .collect();
+
+        let deepest = messages.pop()?;
+        let omitted = depth - limit;
+        let summary = Self {
+            message: format!("{} (and {} more)", deepest, omitted),
+            source: None,
+        };
+
+        let truncated = messages
+            .into_iter()
+            .rev()
+            .fold(summary, |source, message| Self {
+                message,
+                source: Some(Box::new(source)),
+            });
+
+        Some(truncated)
+    }
+}
+
+impl fmt::Display for TruncatedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for TruncatedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as _)
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{ChainCompat, Error};
+use log_crate::{
+    kv::{self, Key, Source, Value, VisitSource},
+    Level, Log, Metadata, Record,
+};
+use std::fmt;
+
+/// Logs an error with the [`log`](https://docs.rs/log) crate, along
+/// with each of its sources and any additional fields as structured
+/// key-value pairs.
+///
+/// The first argument is a reference to the [`Log`][log-trait]
+/// implementation, such as `log::logger()`, and the second is the
+/// error. The message of the record is the error's `Display` output
+/// and its level is `Error`. Each source in the error's chain is added
+/// as a field named `cause_0`, `cause_1`, and so on, holding that
+/// source's `Display` output. Additional fields can be given after a
+/// semicolon as `name = value`, where each value implements
+/// `Display`; these come before the causes.
+///
+/// The record is only logged when the logger reports that it is
+/// enabled for it.
+///
+/// Requires the `log` feature.
+///
+/// ```rust
+/// # use log_crate as log;
+/// use snafu::{prelude::*, IntoError};
+///
+/// #[derive(Debug, Snafu)]
+/// #[snafu(display("could not load {name}"))]
+/// struct LoadError {
+///     name: String,
+///     source: std::io::Error,
+/// }
+///
+/// let source = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+/// let error = LoadSnafu { name: "settings" }.into_error(source);
+///
+/// // Logs "could not load settings" with the fields
+/// // `attempt=3` and `cause_0="no such file"`
+/// snafu::error_info!(log::logger(), error; attempt = 3);
+/// ```
+///
+/// [log-trait]: https://docs.rs/log/0.4/log/trait.Log.html
+#[macro_export]
+macro_rules! error_info {
+    ($logger:expr, $error:expr $(,)?) => {
+        $crate::error_info!($logger, $error;)
+    };
+    ($logger:expr, $error:expr; $($name:ident = $value:expr),* $(,)?) => {
+        match &$error {
+            error => {
+                use $crate::AsErrorSource as _;
+
+                $crate::__error_info(
+                    $logger,
+                    error.as_error_source(),
+                    &[$((::core::stringify!($name), &$value as &dyn ::core::fmt::Display)),*],
+                    ::core::module_path!(),
+                    ::core::file!(),
+                    ::core::line!(),
+                )
+            }
+        }
+    };
+}
+
+/// Used by [`error_info!`][crate::error_info].
+#[doc(hidden)]
+pub fn __error_info(
+    logger: &dyn Log,
+    error: &dyn Error,
+    fields: &[(&str, &dyn fmt::Display)],
+    module_path: &'static str,
+    file: &'static str,
+    line: u32,
+) {
+    let metadata = Metadata::builder()
+        .level(Level::Error)
+        .target(module_path)
+        .build();
+
+    if !logger.enabled(&metadata) {
+        return;
+    }
+
+    let causes = ChainCompat::new(error)
+        .skip(1)
+        .enumerate()
+        .map(|(i, cause)| (format!("cause_{}", i), cause.to_string()))
+        .collect();
+
+    let key_values = KeyValues { fields, causes };
+
+    logger.log(
+        &Record::builder()
+            .metadata(metadata)
+            .args(format_args!("{}", error))
+            .module_path_static(Some(module_path))
+            .file_static(Some(file))
+            .line(Some(line))
+            .key_values(&key_values)
+            .build(),
+    );
+}
+
+struct KeyValues<'a> {
+    fields: &'a [(&'a str, &'a dyn fmt::Display)],
+    causes: Vec<(String, String)>,
+}
+
+impl Source for KeyValues<'_> {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), kv::Error> {
+        for &(name, value) in self.fields {
+            visitor.visit_pair(Key::from_str(name), Value::from_dyn_display(value))?;
+        }
+
+        for (name, cause) in &self.causes {
+            visitor.visit_pair(Key::from_str(name), Value::from(&**cause))?;
+        }
+
+        Ok(())
+    }
+}
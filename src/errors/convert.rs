@@ -0,0 +1,91 @@
+//! Converting errors between types.
+//!
+//! These functions name the conversions that are otherwise spelled
+//! out with `From`, `Into`, and `Any` at each use.
+//!
+//! ```rust
+//! use snafu::{errors::convert, prelude::*};
+//!
+//! #[derive(Debug, Snafu)]
+//! #[snafu(display("The disk is full"))]
+//! struct DiskFullError;
+//!
+//! #[derive(Debug, Snafu)]
+//! #[snafu(context(false))]
+//! struct SaveError {
+//!     source: DiskFullError,
+//! }
+//!
+//! let e: SaveError = convert::wrap(DiskFullError);
+//! assert_eq!(e.to_string(), "SaveError");
+//! ```
+
+use crate::Error;
+use core::any::Any;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Converts an error into an error that wraps it.
+///
+/// This is the same as calling [`From::from`][], but names both types
+/// at the call site, which is useful when type inference can't
+/// determine the outer type.
+pub fn wrap<Inner, Outer>(e: Inner) -> Outer
+where
+    Inner: Error,
+    Outer: From<Inner>,
+{
+    Outer::from(e)
+}
+
+/// Recovers an error of type `Inner` from a value of type `Outer`.
+///
+/// Returns `Ok` when `Outer` and `Inner` are the same type and gives
+/// the error back unchanged otherwise. This is useful in generic code
+/// that needs to treat one specific error type differently. The check
+/// uses [`Any`][], so no unsafe code is involved.
+///
+/// ```rust
+/// use snafu::{errors::convert, prelude::*};
+///
+/// #[derive(Debug, Snafu)]
+/// struct TimeoutError;
+///
+/// fn is_timeout<E>(e: E) -> bool
+/// where
+///     E: std::error::Error + 'static,
+/// {
+///     convert::try_unwrap::<TimeoutError, _>(e).is_ok()
+/// }
+///
+/// assert!(is_timeout(TimeoutError));
+/// assert!(!is_timeout(std::fmt::Error));
+/// ```
+pub fn try_unwrap<Inner, Outer>(e: Outer) -> Result<Inner, Outer>
+where
+    Inner: Error + 'static,
+    Outer: Error + 'static,
+{
+    // Moving the value out through `Any` requires a place that can be
+    // left empty afterwards.
+    let mut slot = Some(e);
+
+    match (&mut slot as &mut dyn Any).downcast_mut::<Option<Inner>>() {
+        Some(inner) => Ok(inner.take().expect("The slot was just filled")),
+        None => Err(slot.expect("The slot is only emptied when the types match")),
+    }
+}
+
+/// Converts every error in a collection into an error that wraps it.
+///
+/// This is useful after collecting errors with
+/// [`ErrorAccumulator`](crate::ErrorAccumulator) or similar, when the
+/// errors need to be reported at a higher level.
+#[cfg(feature = "alloc")]
+pub fn convert_chain<E1, E2>(chain: Vec<E1>) -> Vec<E2>
+where
+    E2: From<E1>,
+{
+    chain.into_iter().map(E2::from).collect()
+}
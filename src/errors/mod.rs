@@ -0,0 +1,3 @@
+//! Utilities for working with error values.
+
+pub mod convert;
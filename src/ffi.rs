@@ -0,0 +1,115 @@
+use crate::{ChainDisplay, Error};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+};
+
+/// Reports an error to foreign code as a numeric code and a message.
+///
+/// This is implemented by `#[derive(Snafu)]` when a struct or every
+/// variant of an enum uses `#[snafu(ffi_code(...))]`. The value may be
+/// any constant expression of type `i32`.
+///
+/// ```rust
+/// use snafu::{prelude::*, FfiCode, IntoError};
+///
+/// const BUSY: i32 = -3;
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     #[snafu(ffi_code(-2), display("could not find {name}"))]
+///     NotFound { name: String },
+///
+///     #[snafu(ffi_code(BUSY), display("the device is busy"))]
+///     Busy { source: std::io::Error },
+/// }
+///
+/// let io_error = std::io::Error::new(std::io::ErrorKind::Other, "locked");
+/// let ffi = BusySnafu.into_error(io_error).to_ffi();
+///
+/// assert_eq!(ffi.code(), -3);
+/// assert_eq!(ffi.message().to_str(), Ok("the device is busy: locked"));
+/// ```
+///
+/// Once any variant of an enum has a code, every variant must have
+/// one.
+pub trait FfiCode {
+    /// Returns the code that identifies this error to foreign code.
+    fn ffi_code(&self) -> i32;
+
+    /// Converts this error to its code and the `Display` text of the
+    /// error and each of its sources, joined by `": "`.
+    fn to_ffi(&self) -> FfiError
+    where
+        Self: Error + Sized,
+    {
+        FfiError::new(self.ffi_code(), self)
+    }
+}
+
+impl<E> FfiCode for &E
+where
+    E: FfiCode + ?Sized,
+{
+    fn ffi_code(&self) -> i32 {
+        (**self).ffi_code()
+    }
+}
+
+impl<E> FfiCode for Box<E>
+where
+    E: FfiCode + ?Sized,
+{
+    fn ffi_code(&self) -> i32 {
+        (**self).ffi_code()
+    }
+}
+
+/// An error converted for foreign code by [`FfiCode::to_ffi`][].
+///
+/// The message is a nul-terminated string that can be passed across
+/// an FFI boundary with [`message_ptr`](FfiError::message_ptr) while
+/// this value is alive, or handed over entirely with
+/// [`into_message`](FfiError::into_message) and
+/// [`CString::into_raw`][]. Any nul bytes in the error's text are
+/// removed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FfiError {
+    code: i32,
+    message: CString,
+}
+
+impl FfiError {
+    /// Converts `error` and its chain of sources, using `code`.
+    ///
+    /// Unlike [`FfiCode::to_ffi`][], this also accepts unsized errors
+    /// such as boxed trait objects.
+    pub fn new(code: i32, error: &dyn Error) -> Self {
+        let mut message = ChainDisplay::new(error).to_string();
+        message.retain(|c| c != '\0');
+        let message = CString::new(message).expect("nul bytes were removed from the message");
+
+        FfiError { code, message }
+    }
+
+    /// The code of the error.
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// The message, flattened from the error's chain of sources.
+    pub fn message(&self) -> &CStr {
+        &self.message
+    }
+
+    /// A pointer to the message that is valid while this value is
+    /// alive.
+    pub fn message_ptr(&self) -> *const c_char {
+        self.message.as_ptr()
+    }
+
+    /// Returns the owned message.
+    pub fn into_message(self) -> CString {
+        self.message
+    }
+}
@@ -0,0 +1,186 @@
+use core::fmt;
+
+/// The error returned by the [`FromStr`][core::str::FromStr]
+/// implementation generated by `#[snafu(impl_from_str)]`.
+///
+/// This is returned when the text does not match the display format
+/// of any variant, or when a field's text could not be parsed.
+///
+/// ```rust
+/// use snafu::{prelude::*, FromStrError};
+///
+/// #[derive(Debug, PartialEq, Snafu)]
+/// #[snafu(impl_from_str)]
+/// enum Error {
+///     #[snafu(display("user {id} was not found"))]
+///     UserNotFound { id: u32 },
+/// }
+///
+/// let error = "user 42 was not found".parse::<Error>();
+/// assert_eq!(error, Ok(Error::UserNotFound { id: 42 }));
+///
+/// let error = "user ? was not found".parse::<Error>();
+/// assert_eq!(error, Err(FromStrError::new("Error")));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FromStrError {
+    type_name: &'static str,
+}
+
+impl FromStrError {
+    /// Creates an error reporting that text could not be parsed as
+    /// the type named `type_name`.
+    pub fn new(type_name: &'static str) -> Self {
+        FromStrError { type_name }
+    }
+
+    /// The name of the type that could not be parsed.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+impl fmt::Display for FromStrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the text does not match the display format of `{}`",
+            self.type_name,
+        )
+    }
+}
+
+impl crate::Error for FromStrError {}
+
+/// The error returned when parsing a kind generated by
+/// `#[snafu(kinds)]` from text that is not the code of any kind.
+///
+/// ```rust
+/// use snafu::{prelude::*, UnknownCodeError};
+///
+/// #[derive(Debug, Snafu)]
+/// #[snafu(kinds)]
+/// enum Error {
+///     #[snafu(code = "not_found")]
+///     NotFound,
+/// }
+///
+/// assert_eq!("not_found".parse(), Ok(ErrorKind::NotFound));
+/// assert_eq!(
+///     "gone".parse::<ErrorKind>(),
+///     Err(UnknownCodeError::new("ErrorKind")),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnknownCodeError {
+    type_name: &'static str,
+}
+
+impl UnknownCodeError {
+    /// Creates an error reporting that text is not the code of any
+    /// kind in the type named `type_name`.
+    pub fn new(type_name: &'static str) -> Self {
+        UnknownCodeError { type_name }
+    }
+
+    /// The name of the kind type that could not be parsed.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+impl fmt::Display for UnknownCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the text is not the code of any `{}`", self.type_name)
+    }
+}
+
+impl crate::Error for UnknownCodeError {}
+
+/// Used by `#[snafu(impl_from_str)]`. Matches `input` against each of
+/// `prefixes` and then a display format split into `literals`, storing
+/// the text of each placeholder between two literals in `values`.
+///
+/// The text of a placeholder ends at the first occurrence of the
+/// literal that follows it.
+#[doc(hidden)]
+pub fn __match_display<'a>(
+    input: &'a str,
+    prefixes: &[&str],
+    literals: &[&str],
+    values: &mut [&'a str],
+) -> bool {
+    let mut rest = input;
+
+    for prefix in prefixes {
+        match rest.strip_prefix(prefix) {
+            Some(r) => rest = r,
+            None => return false,
+        }
+    }
+
+    let (first, literals) = match literals.split_first() {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    rest = match rest.strip_prefix(first) {
+        Some(r) => r,
+        None => return false,
+    };
+
+    for (i, (literal, value)) in literals.iter().zip(values.iter_mut()).enumerate() {
+        let is_last = i + 1 == literals.len();
+
+        let end = if is_last {
+            match rest.strip_suffix(literal) {
+                Some(r) => r.len(),
+                None => return false,
+            }
+        } else {
+            match rest.find(literal) {
+                Some(end) => end,
+                None => return false,
+            }
+        };
+
+        *value = &rest[..end];
+        rest = &rest[end + literal.len()..];
+    }
+
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::__match_display;
+
+    #[test]
+    fn placeholders_end_at_the_next_literal() {
+        let mut values = [""; 2];
+        assert!(__match_display(
+            "a-b-c is 1",
+            &[],
+            &["", " is ", ""],
+            &mut values
+        ));
+        assert_eq!(values, ["a-b-c", "1"]);
+
+        assert!(__match_display(
+            "x: 1, 2",
+            &["x: "],
+            &["", ", ", ""],
+            &mut values
+        ));
+        assert_eq!(values, ["1", "2"]);
+    }
+
+    #[test]
+    fn literals_must_match_exactly() {
+        let mut values = [""; 1];
+        assert!(!__match_display("id 1.", &[], &["id ", "!"], &mut values));
+        assert!(!__match_display("ID 1!", &[], &["id ", "!"], &mut values));
+        assert!(!__match_display("done!", &[], &["done"], &mut []));
+        assert!(__match_display("done", &[], &["done"], &mut []));
+    }
+}
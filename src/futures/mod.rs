@@ -7,9 +7,12 @@
 //! [`TryStream`]: futures_core_crate::TryStream
 //! [feature flag]: crate::guide::feature_flags
 
+pub mod stream;
 pub mod try_future;
 pub mod try_stream;
 
+#[doc(inline)]
+pub use self::stream::{ContextStream, StreamErrorContext};
 #[doc(inline)]
 pub use self::try_future::TryFutureExt;
 #[doc(inline)]
@@ -0,0 +1,104 @@
+//! Adding context to plain [`Stream`]s of `Result`s.
+//!
+//! [`Stream`]: futures_core_crate::Stream
+
+use crate::{Error, ErrorCompat, IntoError};
+use core::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context as TaskContext, Poll},
+};
+use futures_core_crate::stream::Stream;
+use pin_project_lite::pin_project;
+
+/// Adds context to the errors produced by any [`Stream`] of
+/// `Result`s.
+///
+/// This is useful when the stream's type is only known to be a
+/// `Stream<Item = Result<T, E>>`, such as in a generic function bound
+/// on [`Stream`], where the [`TryStreamExt`](super::TryStreamExt)
+/// methods are not in scope.
+///
+/// ```rust
+/// use futures_core_crate::Stream;
+/// use snafu::{futures::StreamErrorContext, prelude::*};
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     Authenticating { user_id: i32, source: ApiError },
+/// }
+///
+/// fn example<S>(stock_prices: S) -> impl Stream<Item = Result<i32, Error>>
+/// where
+///     S: Stream<Item = Result<i32, ApiError>>,
+/// {
+///     StreamErrorContext::context(stock_prices, AuthenticatingSnafu { user_id: 42 })
+/// }
+///
+/// # type ApiError = Box<dyn std::error::Error>;
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct StreamErrorContext;
+
+impl StreamErrorContext {
+    /// Extend each error produced by `stream` with additional
+    /// context-sensitive information.
+    ///
+    /// The context selector is cloned for every error item.
+    pub fn context<S, T, C, E, E2>(stream: S, context: C) -> ContextStream<S, C, E2>
+    where
+        S: Stream<Item = Result<T, E>>,
+        C: IntoError<E2, Source = E> + Clone,
+        E2: Error + ErrorCompat,
+    {
+        ContextStream {
+            inner: stream,
+            context,
+            _e: PhantomData,
+        }
+    }
+}
+
+pin_project! {
+    /// Stream for the [`StreamErrorContext::context`] adapter.
+    ///
+    /// See the [`StreamErrorContext::context`] function for more details.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ContextStream<S, C, E> {
+        #[pin]
+        inner: S,
+        context: C,
+        _e: PhantomData<E>,
+    }
+}
+
+impl<S, T, C, E, E2> Stream for ContextStream<S, C, E2>
+where
+    S: Stream<Item = Result<T, E>>,
+    C: IntoError<E2, Source = E> + Clone,
+    E2: Error + ErrorCompat,
+{
+    type Item = Result<T, E2>;
+
+    #[track_caller]
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut TaskContext) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let inner = this.inner;
+        let context = this.context;
+
+        match inner.poll_next(ctx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Ready(Some(Ok(v))) => Poll::Ready(Some(Ok(v))),
+            Poll::Ready(Some(Err(error))) => {
+                let error = context.clone().into_error(error);
+                Poll::Ready(Some(Err(error)))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
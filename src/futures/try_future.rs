@@ -1,5 +1,23 @@
 //! Additions to the [`TryFuture`] trait.
 //!
+//! ## Choosing a context combinator
+//!
+//! None of the combinators do any work of their own until the future
+//! resolves to an error; the selector or closure is stored untouched.
+//!
+//! - [`context`][TryFutureExt::context] is the cheapest choice when
+//!   the selector's fields are borrowed or cheap to create.
+//! - [`lazy_context`][TryFutureExt::lazy_context] and
+//!   [`with_context`][TryFutureExt::with_context] should be used when
+//!   creating the fields allocates or is otherwise expensive. The
+//!   work is skipped entirely when the future succeeds or is dropped
+//!   without being polled.
+//! - [`context_async`][TryFutureExt::context_async] should be used
+//!   when the fields can only be created by awaiting another future.
+//!
+//! The `futures_context` benchmark in the repository compares these
+//! forms.
+//!
 //! [`TryFuture`]: futures_core_crate::future::TryFuture
 
 use crate::{Error, ErrorCompat, FromString, IntoError};
@@ -10,7 +28,7 @@ use core::{
     task::{Context as TaskContext, Poll},
 };
 use futures_core_crate::future::TryFuture;
-use pin_project::pin_project;
+use pin_project_lite::pin_project;
 
 /// Additions to [`TryFuture`].
 pub trait TryFutureExt: TryFuture + Sized {
@@ -47,6 +65,12 @@ pub trait TryFutureExt: TryFuture + Sized {
     ///
     /// Note that the context selector will call [`Into::into`] on
     /// each field, so the types are not required to exactly match.
+    ///
+    /// The selector is stored as-is and its fields are only
+    /// converted if the future resolves to an error. Building the
+    /// selector itself still happens immediately, so use
+    /// [`lazy_context`][Self::lazy_context] when computing the
+    /// selector's fields is expensive.
     fn context<C, E>(self, context: C) -> Context<Self, C, E>
     where
         C: IntoError<E, Source = Self::Error>,
@@ -91,6 +115,121 @@ pub trait TryFutureExt: TryFuture + Sized {
         C: IntoError<E, Source = Self::Error>,
         E: Error + ErrorCompat;
 
+    /// The same as [`context`](TryFutureExt::context), under a name that
+    /// does not collide with the `context` methods of other extension
+    /// traits, such as `anyhow::Context`.
+    ///
+    /// When both traits are imported, the SNAFU method can be called
+    /// by this name instead of with fully-qualified syntax.
+    #[doc(alias = "context")]
+    fn snafu_context<C, E>(self, context: C) -> Context<Self, C, E>
+    where
+        C: IntoError<E, Source = Self::Error>,
+        E: Error + ErrorCompat;
+
+    /// The same as [`with_context`](TryFutureExt::with_context), under a
+    /// name that does not collide with other extension traits. See
+    /// [`snafu_context`](TryFutureExt::snafu_context).
+    #[doc(alias = "with_context")]
+    fn with_snafu_context<F, C, E>(self, context: F) -> WithContext<Self, F, E>
+    where
+        F: FnOnce(&mut Self::Error) -> C,
+        C: IntoError<E, Source = Self::Error>,
+        E: Error + ErrorCompat;
+
+    /// Extend a [`TryFuture`]'s error with context that is only
+    /// created if the future resolves to an error.
+    ///
+    /// This is the same as [`with_context`][Self::with_context] for a
+    /// closure that does not need the underlying error. Nothing is
+    /// computed, allocated, or converted when the combinator is
+    /// constructed, so it costs nothing for futures that are never
+    /// polled (such as an untaken branch of `select!`) or that
+    /// succeed.
+    ///
+    /// ```rust
+    /// # use futures_crate as futures;
+    /// use futures::future::TryFuture;
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     Loading {
+    ///         snapshot: String,
+    ///         source: ApiError,
+    ///     },
+    /// }
+    ///
+    /// fn example() -> impl TryFuture<Ok = i32, Error = Error> {
+    ///     another_function().lazy_context(|| LoadingSnafu {
+    ///         snapshot: expensive_snapshot(),
+    ///     })
+    /// }
+    ///
+    /// # type ApiError = Box<dyn std::error::Error>;
+    /// fn another_function() -> impl TryFuture<Ok = i32, Error = ApiError> {
+    ///     /* ... */
+    /// # futures::future::ok(42)
+    /// }
+    ///
+    /// fn expensive_snapshot() -> String {
+    ///     /* ... */
+    /// # String::new()
+    /// }
+    /// ```
+    fn lazy_context<F, C, E>(self, context: F) -> LazyContext<Self, F, E>
+    where
+        F: FnOnce() -> C,
+        C: IntoError<E, Source = Self::Error>,
+        E: Error + ErrorCompat;
+
+    /// Extend a [`TryFuture`]'s error with context that is created by
+    /// awaiting another future.
+    ///
+    /// This is the same as [`lazy_context`][Self::lazy_context] for a
+    /// closure that returns a future of the context selector, such as
+    /// one that looks up a request ID. The closure is only called, and
+    /// its future only polled, once this future resolves to an error.
+    ///
+    /// ```rust
+    /// # use futures_crate as futures;
+    /// use futures::future::TryFuture;
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     Loading {
+    ///         request_id: u64,
+    ///         source: ApiError,
+    ///     },
+    /// }
+    ///
+    /// fn example() -> impl TryFuture<Ok = i32, Error = Error> {
+    ///     another_function().context_async(|| async {
+    ///         LoadingSnafu {
+    ///             request_id: current_request_id().await,
+    ///         }
+    ///     })
+    /// }
+    ///
+    /// # type ApiError = Box<dyn std::error::Error>;
+    /// fn another_function() -> impl TryFuture<Ok = i32, Error = ApiError> {
+    ///     /* ... */
+    /// # futures::future::ok(42)
+    /// }
+    ///
+    /// async fn current_request_id() -> u64 {
+    ///     /* ... */
+    /// # 42
+    /// }
+    /// ```
+    fn context_async<F, CFut, E>(self, context: F) -> ContextFutureAsync<Self, F, CFut, E>
+    where
+        F: FnOnce() -> CFut,
+        CFut: Future,
+        CFut::Output: IntoError<E, Source = Self::Error>,
+        E: Error + ErrorCompat;
+
     /// Extend a [`TryFuture`]'s error with information from a string.
     ///
     /// The target error type must implement [`FromString`] by using
@@ -152,6 +291,123 @@ pub trait TryFutureExt: TryFuture + Sized {
         F: FnOnce(&mut Self::Error) -> S,
         S: Into<String>,
         E: FromString;
+
+    /// Convert a [`TryFuture`]'s error into another type before
+    /// context is added to it.
+    ///
+    /// This is the asynchronous form of
+    /// [`ResultExt::map_source`](crate::ResultExt::map_source).
+    ///
+    /// ```rust
+    /// # use futures_crate as futures;
+    /// use futures::future::TryFuture;
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// #[snafu(display("database failure: {message}"))]
+    /// struct DbError {
+    ///     message: String,
+    /// }
+    ///
+    /// impl From<std::io::Error> for DbError {
+    ///     fn from(e: std::io::Error) -> Self {
+    ///         DbError { message: e.to_string() }
+    ///     }
+    /// }
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     Query { table: String, source: DbError },
+    /// }
+    ///
+    /// fn example() -> impl TryFuture<Ok = u32, Error = Error> {
+    ///     run_query()
+    ///         .map_source(DbError::from)
+    ///         .context(QuerySnafu { table: "users" })
+    /// }
+    ///
+    /// fn run_query() -> impl TryFuture<Ok = u32, Error = std::io::Error> {
+    ///     /* ... */
+    /// # futures::future::ok(42)
+    /// }
+    /// ```
+    fn map_source<F, S2>(self, f: F) -> MapSource<Self, F>
+    where
+        F: FnOnce(Self::Error) -> S2;
+
+    /// Convert a [`TryFuture`]'s error into another type using
+    /// [`Into`].
+    ///
+    /// This allows a chain of combinators to end in the error type
+    /// required by the caller without importing another extension
+    /// trait.
+    ///
+    /// ```rust
+    /// # use futures_crate as futures;
+    /// use futures::future::TryFuture;
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     Loading { source: ApiError },
+    /// }
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// #[snafu(context(false))]
+    /// struct ServiceError {
+    ///     source: Error,
+    /// }
+    ///
+    /// fn example() -> impl TryFuture<Ok = i32, Error = ServiceError> {
+    ///     another_function()
+    ///         .context(LoadingSnafu)
+    ///         .err_into::<ServiceError>()
+    /// }
+    ///
+    /// # type ApiError = Box<dyn std::error::Error>;
+    /// fn another_function() -> impl TryFuture<Ok = i32, Error = ApiError> {
+    ///     /* ... */
+    /// # futures::future::ok(42)
+    /// }
+    /// ```
+    fn err_into<E>(self) -> ErrInto<Self, E>
+    where
+        Self::Error: Into<E>;
+
+    /// Convert a [`TryFuture`]'s error into a boxed trait object.
+    ///
+    /// This is the same as [`err_into`][Self::err_into] with
+    /// `Box<dyn Error + Send + Sync>`, the error type commonly required
+    /// by service frameworks such as `tower` and `hyper`.
+    ///
+    /// ```rust
+    /// # use futures_crate as futures;
+    /// use futures::future::TryFuture;
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     Loading { user_id: u64, source: ApiError },
+    /// }
+    ///
+    /// type BoxError = Box<dyn std::error::Error + Send + Sync>;
+    ///
+    /// fn handler(user_id: u64) -> impl TryFuture<Ok = i32, Error = BoxError> {
+    ///     another_function()
+    ///         .context(LoadingSnafu { user_id })
+    ///         .boxed_error()
+    /// }
+    ///
+    /// # type ApiError = std::io::Error;
+    /// fn another_function() -> impl TryFuture<Ok = i32, Error = ApiError> {
+    ///     /* ... */
+    /// # futures::future::ok(42)
+    /// }
+    /// ```
+    #[cfg(any(feature = "std", test))]
+    fn boxed_error(self) -> ErrInto<Self, Box<dyn std::error::Error + Send + Sync>>
+    where
+        Self::Error: std::error::Error + Send + Sync + 'static;
 }
 
 impl<Fut> TryFutureExt for Fut
@@ -183,6 +439,52 @@ where
         }
     }
 
+    fn snafu_context<C, E>(self, context: C) -> Context<Self, C, E>
+    where
+        C: IntoError<E, Source = Self::Error>,
+        E: Error + ErrorCompat,
+    {
+        self.context(context)
+    }
+
+    fn with_snafu_context<F, C, E>(self, context: F) -> WithContext<Self, F, E>
+    where
+        F: FnOnce(&mut Self::Error) -> C,
+        C: IntoError<E, Source = Self::Error>,
+        E: Error + ErrorCompat,
+    {
+        self.with_context(context)
+    }
+
+    fn lazy_context<F, C, E>(self, context: F) -> LazyContext<Self, F, E>
+    where
+        F: FnOnce() -> C,
+        C: IntoError<E, Source = Self::Error>,
+        E: Error + ErrorCompat,
+    {
+        LazyContext {
+            inner: self,
+            context: Some(context),
+            _e: PhantomData,
+        }
+    }
+
+    fn context_async<F, CFut, E>(self, context: F) -> ContextFutureAsync<Self, F, CFut, E>
+    where
+        F: FnOnce() -> CFut,
+        CFut: Future,
+        CFut::Output: IntoError<E, Source = Self::Error>,
+        E: Error + ErrorCompat,
+    {
+        ContextFutureAsync {
+            inner: self,
+            context: Some(context),
+            pending: None,
+            error: None,
+            _e: PhantomData,
+        }
+    }
+
     fn whatever_context<S, E>(self, context: S) -> WhateverContext<Self, S, E>
     where
         S: Into<String>,
@@ -207,19 +509,48 @@ where
             _e: PhantomData,
         }
     }
+
+    fn map_source<F, S2>(self, f: F) -> MapSource<Self, F>
+    where
+        F: FnOnce(Self::Error) -> S2,
+    {
+        MapSource {
+            inner: self,
+            f: Some(f),
+        }
+    }
+
+    fn err_into<E>(self) -> ErrInto<Self, E>
+    where
+        Self::Error: Into<E>,
+    {
+        ErrInto {
+            inner: self,
+            _e: PhantomData,
+        }
+    }
+
+    #[cfg(any(feature = "std", test))]
+    fn boxed_error(self) -> ErrInto<Self, Box<dyn std::error::Error + Send + Sync>>
+    where
+        Self::Error: std::error::Error + Send + Sync + 'static,
+    {
+        self.err_into()
+    }
 }
 
-/// Future for the [`context`](TryFutureExt::context) combinator.
-///
-/// See the [`TryFutureExt::context`] method for more details.
-#[pin_project]
-#[derive(Debug)]
-#[must_use = "futures do nothing unless polled"]
-pub struct Context<Fut, C, E> {
-    #[pin]
-    inner: Fut,
-    context: Option<C>,
-    _e: PhantomData<E>,
+pin_project! {
+    /// Future for the [`context`](TryFutureExt::context) combinator.
+    ///
+    /// See the [`TryFutureExt::context`] method for more details.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless polled"]
+    pub struct Context<Fut, C, E> {
+        #[pin]
+        inner: Fut,
+        context: Option<C>,
+        _e: PhantomData<E>,
+    }
 }
 
 impl<Fut, C, E> Future for Context<Fut, C, E>
@@ -251,17 +582,18 @@ where
     }
 }
 
-/// Future for the [`with_context`](TryFutureExt::with_context) combinator.
-///
-/// See the [`TryFutureExt::with_context`] method for more details.
-#[pin_project]
-#[derive(Debug)]
-#[must_use = "futures do nothing unless polled"]
-pub struct WithContext<Fut, F, E> {
-    #[pin]
-    inner: Fut,
-    context: Option<F>,
-    _e: PhantomData<E>,
+pin_project! {
+    /// Future for the [`with_context`](TryFutureExt::with_context) combinator.
+    ///
+    /// See the [`TryFutureExt::with_context`] method for more details.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless polled"]
+    pub struct WithContext<Fut, F, E> {
+        #[pin]
+        inner: Fut,
+        context: Option<F>,
+        _e: PhantomData<E>,
+    }
 }
 
 impl<Fut, F, C, E> Future for WithContext<Fut, F, E>
@@ -296,19 +628,136 @@ where
     }
 }
 
-/// Future for the
-/// [`whatever_context`](TryFutureExt::whatever_context) combinator.
-///
-/// See the [`TryFutureExt::whatever_context`] method for more
-/// details.
-#[pin_project]
-#[derive(Debug)]
-#[must_use = "futures do nothing unless polled"]
-pub struct WhateverContext<Fut, S, E> {
-    #[pin]
-    inner: Fut,
-    context: Option<S>,
-    _e: PhantomData<E>,
+pin_project! {
+    /// Future for the [`lazy_context`](TryFutureExt::lazy_context) combinator.
+    ///
+    /// See the [`TryFutureExt::lazy_context`] method for more details.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless polled"]
+    pub struct LazyContext<Fut, F, E> {
+        #[pin]
+        inner: Fut,
+        context: Option<F>,
+        _e: PhantomData<E>,
+    }
+}
+
+impl<Fut, F, C, E> Future for LazyContext<Fut, F, E>
+where
+    Fut: TryFuture,
+    F: FnOnce() -> C,
+    C: IntoError<E, Source = Fut::Error>,
+    E: Error + ErrorCompat,
+{
+    type Output = Result<Fut::Ok, E>;
+
+    #[track_caller]
+    fn poll(self: Pin<&mut Self>, ctx: &mut TaskContext) -> Poll<Self::Output> {
+        let this = self.project();
+        let inner = this.inner;
+        let context = this.context;
+
+        // https://github.com/rust-lang/rust/issues/74042
+        match inner.try_poll(ctx) {
+            Poll::Ready(Ok(v)) => Poll::Ready(Ok(v)),
+            Poll::Ready(Err(error)) => {
+                let context = context
+                    .take()
+                    .expect("Cannot poll LazyContext after it resolves");
+
+                let error = context().into_error(error);
+
+                Poll::Ready(Err(error))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+pin_project! {
+    /// Future for the [`context_async`](TryFutureExt::context_async) combinator.
+    ///
+    /// See the [`TryFutureExt::context_async`] method for more details.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless polled"]
+    pub struct ContextFutureAsync<Fut, F, CFut, E>
+    where
+        Fut: TryFuture,
+    {
+        #[pin]
+        inner: Fut,
+        context: Option<F>,
+        #[pin]
+        pending: Option<CFut>,
+        error: Option<Fut::Error>,
+        _e: PhantomData<E>,
+    }
+}
+
+impl<Fut, F, CFut, E> Future for ContextFutureAsync<Fut, F, CFut, E>
+where
+    Fut: TryFuture,
+    F: FnOnce() -> CFut,
+    CFut: Future,
+    CFut::Output: IntoError<E, Source = Fut::Error>,
+    E: Error + ErrorCompat,
+{
+    type Output = Result<Fut::Ok, E>;
+
+    #[track_caller]
+    fn poll(self: Pin<&mut Self>, ctx: &mut TaskContext) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        loop {
+            // The underlying error is held until the context future
+            // completes.
+            if let Some(pending) = this.pending.as_mut().as_pin_mut() {
+                let context = match pending.poll(ctx) {
+                    Poll::Ready(context) => context,
+                    Poll::Pending => return Poll::Pending,
+                };
+                this.pending.set(None);
+
+                let error = this
+                    .error
+                    .take()
+                    .expect("Cannot poll ContextFutureAsync after it resolves");
+
+                return Poll::Ready(Err(context.into_error(error)));
+            }
+
+            // https://github.com/rust-lang/rust/issues/74042
+            match this.inner.as_mut().try_poll(ctx) {
+                Poll::Ready(Ok(v)) => return Poll::Ready(Ok(v)),
+                Poll::Ready(Err(error)) => {
+                    let context = this
+                        .context
+                        .take()
+                        .expect("Cannot poll ContextFutureAsync after it resolves");
+
+                    *this.error = Some(error);
+                    this.pending.set(Some(context()));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Future for the
+    /// [`whatever_context`](TryFutureExt::whatever_context) combinator.
+    ///
+    /// See the [`TryFutureExt::whatever_context`] method for more
+    /// details.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless polled"]
+    pub struct WhateverContext<Fut, S, E> {
+        #[pin]
+        inner: Fut,
+        context: Option<S>,
+        _e: PhantomData<E>,
+    }
 }
 
 impl<Fut, S, E> Future for WhateverContext<Fut, S, E>
@@ -342,20 +791,21 @@ where
     }
 }
 
-/// Future for the
-/// [`with_whatever_context`](TryFutureExt::with_whatever_context)
-/// combinator.
-///
-/// See the [`TryFutureExt::with_whatever_context`] method for more
-/// details.
-#[pin_project]
-#[derive(Debug)]
-#[must_use = "futures do nothing unless polled"]
-pub struct WithWhateverContext<Fut, F, E> {
-    #[pin]
-    inner: Fut,
-    context: Option<F>,
-    _e: PhantomData<E>,
+pin_project! {
+    /// Future for the
+    /// [`with_whatever_context`](TryFutureExt::with_whatever_context)
+    /// combinator.
+    ///
+    /// See the [`TryFutureExt::with_whatever_context`] method for more
+    /// details.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless polled"]
+    pub struct WithWhateverContext<Fut, F, E> {
+        #[pin]
+        inner: Fut,
+        context: Option<F>,
+        _e: PhantomData<E>,
+    }
 }
 
 impl<Fut, F, S, E> Future for WithWhateverContext<Fut, F, E>
@@ -390,3 +840,71 @@ where
         }
     }
 }
+
+pin_project! {
+    /// Future for the [`map_source`](TryFutureExt::map_source) combinator.
+    ///
+    /// See the [`TryFutureExt::map_source`] method for more details.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless polled"]
+    pub struct MapSource<Fut, F> {
+        #[pin]
+        inner: Fut,
+        f: Option<F>,
+    }
+}
+
+impl<Fut, F, S2> Future for MapSource<Fut, F>
+where
+    Fut: TryFuture,
+    F: FnOnce(Fut::Error) -> S2,
+{
+    type Output = Result<Fut::Ok, S2>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut TaskContext) -> Poll<Self::Output> {
+        let this = self.project();
+        let inner = this.inner;
+        let f = this.f;
+
+        match inner.try_poll(ctx) {
+            Poll::Ready(Ok(v)) => Poll::Ready(Ok(v)),
+            Poll::Ready(Err(error)) => {
+                let f = f.take().expect("Cannot poll MapSource after it resolves");
+                Poll::Ready(Err(f(error)))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+pin_project! {
+    /// Future for the [`err_into`](TryFutureExt::err_into) and
+    /// [`boxed_error`](TryFutureExt::boxed_error) combinators.
+    ///
+    /// See the [`TryFutureExt::err_into`] method for more details.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless polled"]
+    pub struct ErrInto<Fut, E> {
+        #[pin]
+        inner: Fut,
+        _e: PhantomData<E>,
+    }
+}
+
+impl<Fut, E> Future for ErrInto<Fut, E>
+where
+    Fut: TryFuture,
+    Fut::Error: Into<E>,
+{
+    type Output = Result<Fut::Ok, E>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut TaskContext) -> Poll<Self::Output> {
+        let this = self.project();
+
+        match this.inner.try_poll(ctx) {
+            Poll::Ready(Ok(v)) => Poll::Ready(Ok(v)),
+            Poll::Ready(Err(error)) => Poll::Ready(Err(error.into())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -9,7 +9,7 @@ use core::{
     task::{Context as TaskContext, Poll},
 };
 use futures_core_crate::stream::{Stream, TryStream};
-use pin_project::pin_project;
+use pin_project_lite::pin_project;
 
 /// Additions to [`TryStream`].
 pub trait TryStreamExt: TryStream + Sized {
@@ -92,6 +92,28 @@ pub trait TryStreamExt: TryStream + Sized {
         C: IntoError<E, Source = Self::Error>,
         E: Error + ErrorCompat;
 
+    /// The same as [`context`](TryStreamExt::context), under a name that
+    /// does not collide with the `context` methods of other extension
+    /// traits, such as `anyhow::Context`.
+    ///
+    /// When both traits are imported, the SNAFU method can be called
+    /// by this name instead of with fully-qualified syntax.
+    #[doc(alias = "context")]
+    fn snafu_context<C, E>(self, context: C) -> Context<Self, C, E>
+    where
+        C: IntoError<E, Source = Self::Error> + Clone,
+        E: Error + ErrorCompat;
+
+    /// The same as [`with_context`](TryStreamExt::with_context), under a
+    /// name that does not collide with other extension traits. See
+    /// [`snafu_context`](TryStreamExt::snafu_context).
+    #[doc(alias = "with_context")]
+    fn with_snafu_context<F, C, E>(self, context: F) -> WithContext<Self, F, E>
+    where
+        F: FnMut(&mut Self::Error) -> C,
+        C: IntoError<E, Source = Self::Error>,
+        E: Error + ErrorCompat;
+
     /// Extend a [`TryStream`]'s error with information from a string.
     ///
     /// The target error type must implement [`FromString`] by using
@@ -155,6 +177,50 @@ pub trait TryStreamExt: TryStream + Sized {
         F: FnMut(&mut Self::Error) -> S,
         S: Into<String>,
         E: FromString;
+
+    /// Convert each of a [`TryStream`]'s errors into another type
+    /// before context is added to it.
+    ///
+    /// This is the stream form of
+    /// [`ResultExt::map_source`](crate::ResultExt::map_source).
+    ///
+    /// ```rust
+    /// # use futures_crate as futures;
+    /// use futures::TryStream;
+    /// # use futures::stream;
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// #[snafu(display("database failure: {message}"))]
+    /// struct DbError {
+    ///     message: String,
+    /// }
+    ///
+    /// impl From<std::io::Error> for DbError {
+    ///     fn from(e: std::io::Error) -> Self {
+    ///         DbError { message: e.to_string() }
+    ///     }
+    /// }
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     Query { table: String, source: DbError },
+    /// }
+    ///
+    /// fn example() -> impl TryStream<Ok = u32, Error = Error> {
+    ///     rows()
+    ///         .map_source(DbError::from)
+    ///         .context(QuerySnafu { table: "users" })
+    /// }
+    ///
+    /// fn rows() -> impl TryStream<Ok = u32, Error = std::io::Error> {
+    ///     /* ... */
+    /// # stream::empty()
+    /// }
+    /// ```
+    fn map_source<F, S2>(self, f: F) -> MapSource<Self, F>
+    where
+        F: FnMut(Self::Error) -> S2;
 }
 
 impl<St> TryStreamExt for St
@@ -186,6 +252,23 @@ where
         }
     }
 
+    fn snafu_context<C, E>(self, context: C) -> Context<Self, C, E>
+    where
+        C: IntoError<E, Source = Self::Error> + Clone,
+        E: Error + ErrorCompat,
+    {
+        self.context(context)
+    }
+
+    fn with_snafu_context<F, C, E>(self, context: F) -> WithContext<Self, F, E>
+    where
+        F: FnMut(&mut Self::Error) -> C,
+        C: IntoError<E, Source = Self::Error>,
+        E: Error + ErrorCompat,
+    {
+        self.with_context(context)
+    }
+
     fn whatever_context<S, E>(self, context: S) -> WhateverContext<Self, S, E>
     where
         S: Into<String>,
@@ -210,19 +293,27 @@ where
             _e: PhantomData,
         }
     }
+
+    fn map_source<F, S2>(self, f: F) -> MapSource<Self, F>
+    where
+        F: FnMut(Self::Error) -> S2,
+    {
+        MapSource { inner: self, f }
+    }
 }
 
-/// Stream for the [`context`](TryStreamExt::context) combinator.
-///
-/// See the [`TryStreamExt::context`] method for more details.
-#[pin_project]
-#[derive(Debug)]
-#[must_use = "streams do nothing unless polled"]
-pub struct Context<St, C, E> {
-    #[pin]
-    inner: St,
-    context: C,
-    _e: PhantomData<E>,
+pin_project! {
+    /// Stream for the [`context`](TryStreamExt::context) combinator.
+    ///
+    /// See the [`TryStreamExt::context`] method for more details.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Context<St, C, E> {
+        #[pin]
+        inner: St,
+        context: C,
+        _e: PhantomData<E>,
+    }
 }
 
 impl<St, C, E> Stream for Context<St, C, E>
@@ -251,17 +342,18 @@ where
     }
 }
 
-/// Stream for the [`with_context`](TryStreamExt::with_context) combinator.
-///
-/// See the [`TryStreamExt::with_context`] method for more details.
-#[pin_project]
-#[derive(Debug)]
-#[must_use = "streams do nothing unless polled"]
-pub struct WithContext<St, F, E> {
-    #[pin]
-    inner: St,
-    context: F,
-    _e: PhantomData<E>,
+pin_project! {
+    /// Stream for the [`with_context`](TryStreamExt::with_context) combinator.
+    ///
+    /// See the [`TryStreamExt::with_context`] method for more details.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct WithContext<St, F, E> {
+        #[pin]
+        inner: St,
+        context: F,
+        _e: PhantomData<E>,
+    }
 }
 
 impl<St, F, C, E> Stream for WithContext<St, F, E>
@@ -291,19 +383,20 @@ where
     }
 }
 
-/// Stream for the
-/// [`whatever_context`](TryStreamExt::whatever_context) combinator.
-///
-/// See the [`TryStreamExt::whatever_context`] method for more
-/// details.
-#[pin_project]
-#[derive(Debug)]
-#[must_use = "streams do nothing unless polled"]
-pub struct WhateverContext<St, S, E> {
-    #[pin]
-    inner: St,
-    context: S,
-    _e: PhantomData<E>,
+pin_project! {
+    /// Stream for the
+    /// [`whatever_context`](TryStreamExt::whatever_context) combinator.
+    ///
+    /// See the [`TryStreamExt::whatever_context`] method for more
+    /// details.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct WhateverContext<St, S, E> {
+        #[pin]
+        inner: St,
+        context: S,
+        _e: PhantomData<E>,
+    }
 }
 
 impl<St, S, E> Stream for WhateverContext<St, S, E>
@@ -333,20 +426,21 @@ where
     }
 }
 
-/// Stream for the
-/// [`with_whatever_context`](TryStreamExt::with_whatever_context)
-/// combinator.
-///
-/// See the [`TryStreamExt::with_whatever_context`] method for more
-/// details.
-#[pin_project]
-#[derive(Debug)]
-#[must_use = "streams do nothing unless polled"]
-pub struct WithWhateverContext<St, F, E> {
-    #[pin]
-    inner: St,
-    context: F,
-    _e: PhantomData<E>,
+pin_project! {
+    /// Stream for the
+    /// [`with_whatever_context`](TryStreamExt::with_whatever_context)
+    /// combinator.
+    ///
+    /// See the [`TryStreamExt::with_whatever_context`] method for more
+    /// details.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct WithWhateverContext<St, F, E> {
+        #[pin]
+        inner: St,
+        context: F,
+        _e: PhantomData<E>,
+    }
 }
 
 impl<St, F, S, E> Stream for WithWhateverContext<St, F, E>
@@ -377,3 +471,37 @@ where
         }
     }
 }
+
+pin_project! {
+    /// Stream for the [`map_source`](TryStreamExt::map_source) combinator.
+    ///
+    /// See the [`TryStreamExt::map_source`] method for more details.
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct MapSource<St, F> {
+        #[pin]
+        inner: St,
+        f: F,
+    }
+}
+
+impl<St, F, S2> Stream for MapSource<St, F>
+where
+    St: TryStream,
+    F: FnMut(St::Error) -> S2,
+{
+    type Item = Result<St::Ok, S2>;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut TaskContext) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let inner = this.inner;
+        let f = this.f;
+
+        match inner.try_poll_next(ctx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Ready(Some(Ok(v))) => Poll::Ready(Some(Ok(v))),
+            Poll::Ready(Some(Err(error))) => Poll::Ready(Some(Err(f(error)))),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use core::cell::UnsafeCell;
+use std::sync::Once;
+
+/// Storage for a value created by [`lazy_static_error!`][crate::lazy_static_error].
+///
+/// This is not part of the public API.
+#[doc(hidden)]
+pub struct LazyStaticError<T> {
+    start: Once,
+    value: UnsafeCell<Option<T>>,
+}
+
+// The value is only written once, inside of `Once::call_once`, and
+// only read after that call has completed. Since a shared reference
+// to the value can be obtained from any thread, the value must be
+// `Sync`; since it may be created on any thread, it must be `Send`.
+unsafe impl<T: Send + Sync> Sync for LazyStaticError<T> {}
+
+impl<T> LazyStaticError<T> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            start: Once::new(),
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    pub fn get<F: FnOnce() -> T>(&'static self, f: F) -> &'static T {
+        self.start.call_once(|| {
+            // SAFETY: No other access to the value can happen while
+            // `call_once` is running.
+            unsafe { *self.value.get() = Some(f()) };
+        });
+
+        // SAFETY: `call_once` has completed, so the value has been
+        // written and will never be written again.
+        match unsafe { &*self.value.get() } {
+            Some(value) => value,
+            None => unreachable!("The value was initialized by `call_once`"),
+        }
+    }
+}
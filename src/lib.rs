@@ -62,14 +62,13 @@
 //! [`Backtrace`][] for every error:
 //!
 //! ```rust
-//! use snafu::{prelude::*, ErrorCompat, Whatever};
+//! use snafu::{prelude::*, DisplayBacktrace, ErrorCompat, Whatever};
 //!
 //! # fn returns_an_error() -> Result<(), Whatever> { Ok(()) }
 //! if let Err(e) = returns_an_error() {
 //!     eprintln!("An error occurred: {e}");
 //!     if let Some(bt) = ErrorCompat::backtrace(&e) {
-//! #       #[cfg(not(feature = "backtraces-impl-backtrace-crate"))]
-//!         eprintln!("{bt}");
+//!         eprintln!("{}", DisplayBacktrace(bt));
 //!     }
 //! }
 //! ```
@@ -205,26 +204,72 @@
 //! capabilities, then read the [user's guide](guide) for deeper
 //! understanding.
 
-use core::fmt;
+use core::{fmt, ops::ControlFlow};
 
 pub mod prelude {
     //! Traits and macros used by most projects. Add `use
     //! snafu::prelude::*` to your code to quickly get started with
     //! SNAFU.
+    //!
+    //! This module re-exports the newest versioned prelude, so new
+    //! items may appear here as SNAFU gains features. No new items
+    //! are added to a versioned prelude ([`v1`], [`v2`]) once it is
+    //! published, apart from those tied to crate features.
+    //!
+    //! This does not freeze the items themselves. The traits are
+    //! shared between all versions, so a trait such as
+    //! [`ResultExt`](crate::ResultExt) may gain methods that conflict
+    //! with methods from your own extension traits.
+    //!
+    //! Both versions may be imported into the same scope.
+
+    pub use self::v2::*;
+
+    pub mod v1 {
+        //! The original prelude.
+        //!
+        //! Contains the [`Snafu`][] derive macro, the [`ensure!`][]
+        //! macro, and the [`ResultExt`](crate::ResultExt) and
+        //! [`OptionExt`](crate::OptionExt) extension traits.
+
+        pub use crate::{ensure, OptionExt as _, ResultExt as _};
+
+        pub use snafu_derive::Snafu;
+
+        #[cfg(any(feature = "std", test))]
+        pub use crate::{ensure_whatever, whatever};
+
+        #[cfg(feature = "futures")]
+        pub use crate::futures::{TryFutureExt as _, TryStreamExt as _};
+    }
 
-    pub use crate::{ensure, OptionExt as _, ResultExt as _};
+    pub mod v2 {
+        //! Everything in [`v1`](super::v1), plus the [`check!`][],
+        //! [`ensure_or!`][], and [`with_context!`][] macros and the
+        //! [`ContextBuilder`](crate::ContextBuilder),
+        //! [`ControlFlowExt`](crate::ControlFlowExt), and
+        //! `ResultIteratorExt` extension traits.
 
-    // https://github.com/rust-lang/rust/issues/89020
-    #[doc = include_str!("Snafu.md")]
-    // Links are reported as broken, but don't appear to be
-    #[allow(rustdoc::broken_intra_doc_links)]
-    pub use snafu_derive::Snafu;
+        pub use crate::{
+            check, ensure, ensure_or, with_context, ContextBuilder as _, ControlFlowExt as _,
+            OptionExt as _, ResultExt as _,
+        };
 
-    #[cfg(any(feature = "std", test))]
-    pub use crate::{ensure_whatever, whatever};
+        // https://github.com/rust-lang/rust/issues/89020
+        #[doc = include_str!("Snafu.md")]
+        // Links are reported as broken, but don't appear to be
+        #[allow(rustdoc::broken_intra_doc_links)]
+        pub use snafu_derive::Snafu;
+
+        #[cfg(any(feature = "std", test))]
+        pub use crate::{ensure_whatever, whatever};
+
+        #[cfg(feature = "alloc")]
+        pub use crate::ResultIteratorExt as _;
 
-    #[cfg(feature = "futures")]
-    pub use crate::futures::{TryFutureExt as _, TryStreamExt as _};
+        #[cfg(feature = "futures")]
+        pub use crate::futures::{TryFutureExt as _, TryStreamExt as _};
+    }
 }
 
 #[cfg(not(any(
@@ -248,39 +293,220 @@ mod backtrace_impl;
 
 pub use backtrace_impl::*;
 
+#[cfg(all(
+    feature = "unstable-provider-api",
+    not(any(feature = "std", feature = "unstable-core-error"))
+))]
+compile_error!(
+    "The `unstable-provider-api` feature requires either the `std` or the `unstable-core-error` feature"
+);
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "backtraces-symbolized")]
+pub mod backtrace;
+
 #[cfg(any(feature = "std", test))]
 mod once_bool;
 
+#[cfg(any(feature = "std", test))]
+pub mod env;
+
 #[cfg(feature = "futures")]
 pub mod futures;
 
 mod error_chain;
 pub use crate::error_chain::*;
 
+pub mod errors;
+
+#[cfg(feature = "alloc")]
+mod error_accumulator;
+#[cfg(feature = "alloc")]
+pub use crate::error_accumulator::{
+    collect_errors, ErrorAccumulator, MultipleErrors, ResultIteratorExt,
+};
+
+#[cfg(feature = "alloc")]
+mod string_source;
+#[cfg(feature = "alloc")]
+pub use crate::string_source::StringSource;
+
+#[cfg(feature = "http-status")]
+pub mod http_status;
+#[cfg(feature = "http-status")]
+pub use crate::http_status::HttpStatus;
+
+#[cfg(any(feature = "std", test))]
+mod ffi;
+#[cfg(any(feature = "std", test))]
+pub use crate::ffi::{FfiCode, FfiError};
+
+mod from_str;
+#[doc(hidden)]
+pub use crate::from_str::__match_display;
+pub use crate::from_str::{FromStrError, UnknownCodeError};
+
+mod missing_type_name;
+pub use crate::missing_type_name::MissingTypeName;
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde_crate as __serde;
+
+#[cfg(feature = "log")]
+mod error_info;
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub use crate::error_info::__error_info;
+
+#[cfg(feature = "testing")]
+mod mock_error;
+#[cfg(feature = "testing")]
+pub use crate::mock_error::MockError;
+
+#[cfg(any(feature = "std", test))]
+mod chain_comparison;
+#[cfg(any(feature = "std", test))]
+pub use crate::chain_comparison::{compare_chains, ChainDifference};
+
+#[cfg(any(feature = "std", test))]
+mod error_chain_limit;
+#[cfg(any(feature = "std", test))]
+#[doc(hidden)]
+pub use crate::error_chain_limit::LimitErrorChain as __LimitErrorChain;
+
+#[cfg(any(feature = "std", test))]
+mod lazy_static_error;
+#[cfg(any(feature = "std", test))]
+#[doc(hidden)]
+pub use crate::lazy_static_error::LazyStaticError as __LazyStaticError;
+
+#[cfg(any(feature = "std", test))]
+pub mod panic;
+
+#[cfg(any(feature = "std", test))]
+mod thread_info;
+#[cfg(any(feature = "std", test))]
+pub use crate::thread_info::ThreadInfo;
+
+#[cfg(any(feature = "std", test))]
+mod typed_whatever;
+#[cfg(any(feature = "std", test))]
+pub use crate::typed_whatever::TypedWhatever;
+
+#[cfg(any(feature = "std", test))]
+mod whatever_shared;
+#[cfg(any(feature = "std", test))]
+pub use crate::whatever_shared::WhateverShared;
+
 mod report;
+#[cfg(any(feature = "std", test))]
+#[doc(hidden)]
+pub use report::__debug_error;
+#[cfg(feature = "alloc")]
+pub use report::to_report_string;
 #[cfg(feature = "std")]
 pub use report::CleanedErrorText;
-pub use report::{Report, __InternalExtractErrorType};
+pub use report::{__InternalExtractErrorType, into_report, IntoReport, Report};
+
+mod variant_matcher;
+pub use crate::variant_matcher::VariantMatcher;
 
 #[doc = include_str!("Snafu.md")]
 #[doc(alias(
+    "absorb_whatever",
+    "assert_send_sync",
+    "auto_from",
     "backtrace",
+    "bound",
+    "cause_kind",
+    "code",
+    "compat_std_error",
     "context",
+    "context_selector_attr",
     "crate_root",
+    "debug",
+    "default",
     "display",
+    "display_context",
+    "display_inherit",
+    "display_location",
+    "display_prefix",
+    "error_chain_limit",
+    "error_description",
+    "error_impl",
+    "ffi_code",
+    "forward_source_backtrace",
+    "from_source_display",
+    "http_status",
+    "ignore_field",
+    "impl_from_str",
     "implicit",
+    "kind",
+    "kinds",
     "module",
+    "opaque",
+    "parse_with",
+    "phantom_data",
     "provide",
+    "record_fields",
     "source",
     "transparent",
+    "validate",
     "visibility",
     "whatever",
 ))]
 pub use snafu_derive::Snafu;
 
+/// Implements [`ErrorCompat`][] for an error type that implements
+/// `Display` and `Error` itself.
+///
+/// Only `ErrorCompat` is generated; `Display`, `Error`, and context
+/// selectors are left to you. Each variant (or the struct) returns
+/// the backtrace from its field named `backtrace` or marked with
+/// `#[snafu(backtrace)]`, and `None` if there is no such field. A
+/// field named `backtrace` can be ignored with
+/// `#[snafu(backtrace(false))]`. The type may also use
+/// `#[snafu(crate_root(...))]`.
+///
+/// Since the type implements `Error`, it can already be used as a
+/// source with [`ResultExt::context`][] and [`AsErrorSource`][].
+///
+/// ```rust
+/// use snafu::{Backtrace, ErrorCompat, GenerateImplicitData, SnafuCompat};
+/// use std::fmt;
+///
+/// #[derive(Debug, SnafuCompat)]
+/// enum LegacyError {
+///     Timeout { backtrace: Backtrace },
+///     Closed(#[snafu(backtrace)] Backtrace),
+///     Unknown,
+/// }
+///
+/// impl fmt::Display for LegacyError {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         f.write_str("legacy failure")
+///     }
+/// }
+///
+/// impl std::error::Error for LegacyError {}
+///
+/// let error = LegacyError::Timeout {
+///     backtrace: Backtrace::generate(),
+/// };
+/// assert!(ErrorCompat::backtrace(&error).is_some());
+/// assert!(ErrorCompat::backtrace(&LegacyError::Unknown).is_none());
+/// ```
+pub use snafu_derive::SnafuCompat;
+
 #[doc = include_str!("report.md")]
 pub use snafu_derive::report;
 
+#[doc = include_str!("defaults.md")]
+pub use snafu_derive::defaults;
+
 macro_rules! generate_guide {
     (pub mod $name:ident { $($children:tt)* } $($rest:tt)*) => {
         generate_guide!(@gen ".", pub mod $name { $($children)* } $($rest)*);
@@ -359,9 +585,80 @@ mod no_std_error;
 #[doc(hidden)]
 pub use no_std_error::Error;
 
+/// Used by `#[snafu(compat_std_error)]`. Expands to its input only
+/// when [`Error`][] is SNAFU's own trait; otherwise the derived
+/// implementation of [`Error`][] already covers `core::error::Error`.
+#[cfg(not(any(feature = "unstable-core-error", feature = "std", test)))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __compat_std_error {
+    ($($item:tt)*) => { $($item)* };
+}
+
+/// Used by `#[snafu(compat_std_error)]`. Expands to its input only
+/// when [`Error`][] is SNAFU's own trait; otherwise the derived
+/// implementation of [`Error`][] already covers `core::error::Error`.
+#[cfg(any(feature = "unstable-core-error", feature = "std", test))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __compat_std_error {
+    ($($item:tt)*) => {};
+}
+
+/// Used by `#[snafu(display(template = ...))]`. Writes `template`,
+/// replacing each `{name}` with the matching argument. `{{` and `}}`
+/// are written as `{` and `}`. Placeholders without a matching
+/// argument are written unchanged.
+#[doc(hidden)]
+pub fn __format_template(
+    f: &mut fmt::Formatter<'_>,
+    mut template: &str,
+    args: &[(&str, &dyn fmt::Display)],
+) -> fmt::Result {
+    use fmt::Write as _;
+
+    while let Some(idx) = template.find(|c| c == '{' || c == '}') {
+        f.write_str(&template[..idx])?;
+        let rest = &template[idx..];
+
+        if let Some(after) = rest.strip_prefix("{{") {
+            f.write_char('{')?;
+            template = after;
+            continue;
+        }
+
+        if let Some(after) = rest.strip_prefix("}}").or_else(|| rest.strip_prefix('}')) {
+            f.write_char('}')?;
+            template = after;
+            continue;
+        }
+
+        let end = match rest.find('}') {
+            Some(end) => end,
+            None => return f.write_str(rest),
+        };
+        let placeholder = &rest[..=end];
+        let name = placeholder[1..end].trim();
+
+        match args.iter().find(|(n, _)| *n == name) {
+            Some((_, value)) => write!(f, "{}", value)?,
+            None => f.write_str(placeholder)?,
+        }
+
+        template = &rest[end + 1..];
+    }
+
+    f.write_str(template)
+}
+
 /// Ensure a condition is true. If it is not, return from the function
 /// with an error.
 ///
+/// The condition is evaluated exactly once. Any borrows and
+/// temporaries it creates end before the context selector is
+/// evaluated, so the context selector may take ownership of values
+/// that the condition borrowed.
+///
 /// ## Examples
 ///
 /// ```rust
@@ -382,12 +679,141 @@ pub use no_std_error::Error;
 #[macro_export]
 macro_rules! ensure {
     ($predicate:expr, $context_selector:expr $(,)?) => {
+        // The condition of an `if` drops its temporaries before the
+        // body runs; a `match` on the predicate would keep them alive
+        // while the context selector is built.
+        if !$predicate {
+            return $crate::__ensure_failed($context_selector);
+        }
+    };
+}
+
+/// The failure path of [`ensure!`][]. Kept out of line so that each
+/// use of the macro only adds a single branch to the calling
+/// function; the error construction is shared between all uses with
+/// the same context selector and error types.
+#[doc(hidden)]
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub fn __ensure_failed<C, E, E2, T>(context_selector: C) -> Result<T, E2>
+where
+    C: IntoError<E, Source = NoneError>,
+    E: Error + ErrorCompat + Into<E2>,
+{
+    Err(context_selector.into_error(NoneError).into())
+}
+
+/// Check that a condition is true, evaluating to a [`Result`][]
+/// instead of returning from the function.
+///
+/// This is the expression form of [`ensure!`][]. It is useful inside
+/// closures and iterator adapters where returning from the enclosing
+/// function is not possible or not desired. The error is created with
+/// the context selector only when the condition is false, capturing
+/// any backtrace or other implicit data at that point.
+///
+/// [`Result`]: std::result::Result
+///
+/// ## Examples
+///
+/// ```rust
+/// use snafu::prelude::*;
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     InvalidUser { user_id: i32 },
+/// }
+///
+/// fn example(user_ids: &[i32]) -> Vec<Result<u32, Error>> {
+///     user_ids
+///         .iter()
+///         .map(|&user_id| {
+///             check!(user_id > 0, InvalidUserSnafu { user_id })?;
+///             Ok(user_id as u32)
+///         })
+///         .collect()
+/// }
+///
+/// let results = example(&[1, -1]);
+/// assert!(results[0].is_ok());
+/// assert!(results[1].is_err());
+/// ```
+#[macro_export]
+macro_rules! check {
+    ($predicate:expr, $context_selector:expr $(,)?) => {
+        if $predicate {
+            ::core::result::Result::Ok(())
+        } else {
+            ::core::result::Result::Err($crate::__check_failed($context_selector))
+        }
+    };
+}
+
+/// The failure path of [`check!`][] and [`ensure_or!`][]. Unlike
+/// [`__ensure_failed`][], the error is not converted so that its type
+/// can be inferred from the context selector.
+#[doc(hidden)]
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub fn __check_failed<C, E>(context_selector: C) -> E
+where
+    C: IntoError<E, Source = NoneError>,
+    E: Error + ErrorCompat,
+{
+    context_selector.into_error(NoneError)
+}
+
+/// Ensure a condition is true. If it is not, evaluate a fallback
+/// instead of returning from the function.
+///
+/// When the condition is false, the error is created with the context
+/// selector and the fallback is evaluated. The fallback may bind the
+/// error using closure syntax; it is evaluated in place, so `continue`
+/// and `break` may be used. The macro evaluates to `()`, so the
+/// fallback must either evaluate to `()` or diverge.
+///
+/// ## Examples
+///
+/// ```rust
+/// use snafu::prelude::*;
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     InvalidUser { user_id: i32 },
+/// }
+///
+/// fn example(user_ids: &[i32]) -> (Vec<u32>, Vec<Error>) {
+///     let mut valid = Vec::new();
+///     let mut errors = Vec::new();
+///
+///     for &user_id in user_ids {
+///         ensure_or!(user_id > 0, InvalidUserSnafu { user_id }, |e| {
+///             errors.push(e);
+///             continue;
+///         });
+///         valid.push(user_id as u32);
+///     }
+///
+///     (valid, errors)
+/// }
+///
+/// let (valid, errors) = example(&[1, -1, 2]);
+/// assert_eq!(valid, [1, 2]);
+/// assert_eq!(errors.len(), 1);
+/// ```
+#[macro_export]
+macro_rules! ensure_or {
+    ($predicate:expr, $context_selector:expr, |$error:ident| $fallback:expr $(,)?) => {
         if !$predicate {
-            return $context_selector
-                .fail()
-                .map_err(::core::convert::Into::into);
+            let $error = $crate::__check_failed($context_selector);
+            $fallback
         }
     };
+    ($predicate:expr, $context_selector:expr, $fallback:expr $(,)?) => {
+        $crate::ensure_or!($predicate, $context_selector, |_error| $fallback)
+    };
 }
 
 /// Instantiate and return a stringly-typed error message.
@@ -463,9 +889,64 @@ macro_rules! ensure {
 ///     whatever!("The programmer forgot to implement this...");
 /// }
 /// ```
+///
+/// # Converting to another error type
+///
+/// Start the arguments with `as T,` to create the error as the
+/// stringly-typed error `T` and then convert it into the function's
+/// error type using [`Into`][]. This is useful when the function's
+/// error type can be created from a [`Whatever`][] but is not a
+/// stringly-typed error itself. Both of the forms above are
+/// supported.
+///
+/// ## Examples
+///
+/// ```rust
+/// use snafu::{prelude::*, Whatever};
+///
+/// #[derive(Debug, Snafu)]
+/// enum LayerError {
+///     #[snafu(display("The layer {name} is missing"))]
+///     Missing { name: String },
+///
+///     #[snafu(transparent)]
+///     Other { source: Whatever },
+/// }
+///
+/// fn parse_depth(depth: &str) -> Result<u8, LayerError> {
+///     let depth = whatever!(as Whatever, depth.parse(), "Invalid depth {depth}");
+///     if depth > 10 {
+///         whatever!(as Whatever, "The depth {depth} is too large");
+///     }
+///     Ok(depth)
+/// }
+///
+/// assert_eq!(parse_depth("5").unwrap(), 5);
+/// assert_eq!(parse_depth("50").unwrap_err().to_string(), "The depth 50 is too large");
+/// ```
 #[macro_export]
 #[cfg(any(feature = "std", test))]
 macro_rules! whatever {
+    (as $ty:ty, $fmt:literal$(, $($arg:expr),* $(,)?)?) => {
+        return core::result::Result::Err({
+            core::convert::Into::into(<$ty as $crate::FromString>::without_source(
+                format!($fmt$(, $($arg),*)*),
+            ))
+        });
+    };
+    (as $ty:ty, $source:expr, $fmt:literal$(, $($arg:expr),* $(,)?)*) => {
+        match $source {
+            core::result::Result::Ok(v) => v,
+            core::result::Result::Err(e) => {
+                return core::result::Result::Err({
+                    core::convert::Into::into(<$ty as $crate::FromString>::with_source(
+                        core::convert::Into::into(e),
+                        format!($fmt$(, $($arg),*)*),
+                    ))
+                });
+            }
+        }
+    };
     ($fmt:literal$(, $($arg:expr),* $(,)?)?) => {
         return core::result::Result::Err({
             $crate::FromString::without_source(
@@ -526,6 +1007,195 @@ macro_rules! ensure_whatever {
     };
 }
 
+/// Extend a [`Result`][]'s error with lazily-generated
+/// context-sensitive information.
+///
+/// This is the expression form of
+/// [`ResultExt::with_context`][]. It does not require the
+/// [`ResultExt`][] trait to be in scope or implemented for the
+/// receiver, which is useful in generic code where adding that bound
+/// is not possible. The closure is only called when the `Result` is
+/// an error.
+///
+/// ## Examples
+///
+/// ```rust
+/// use snafu::prelude::*;
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     Authenticating { user_id: i32, source: ApiError },
+/// }
+///
+/// fn example<T>(result: Result<T, ApiError>) -> Result<T, Error> {
+///     let user_id = 42;
+///     snafu::with_context!(result, || AuthenticatingSnafu { user_id })
+/// }
+///
+/// # type ApiError = std::io::Error;
+/// ```
+#[macro_export]
+macro_rules! with_context {
+    ($result:expr, $context:expr $(,)?) => {
+        match $result {
+            ::core::result::Result::Ok(v) => ::core::result::Result::Ok(v),
+            ::core::result::Result::Err(error) => {
+                ::core::result::Result::Err($crate::IntoError::into_error(($context)(), error))
+            }
+        }
+    };
+}
+
+/// Convert between two error enums variant by variant.
+///
+/// Each arm names a variant of the source enum, optionally with a
+/// pattern for its fields, and the variant of the target enum to
+/// construct, optionally with expressions for its fields. The arms
+/// become a `match`, so leaving out a variant of the source enum is a
+/// compile error, just as with a handwritten `match`.
+///
+/// The error must be a single token tree, such as an identifier or a
+/// parenthesized expression.
+///
+/// ## Examples
+///
+/// ```rust
+/// use snafu::prelude::*;
+///
+/// #[derive(Debug, Snafu)]
+/// enum LowLevelError {
+///     Timeout { seconds: u64 },
+///     Refused { source: std::io::Error },
+///     Closed,
+/// }
+///
+/// #[derive(Debug, Snafu)]
+/// enum HighLevelError {
+///     TimedOut { seconds: u64 },
+///     Unreachable { source: std::io::Error },
+///     Disconnected,
+/// }
+///
+/// fn convert(e: LowLevelError) -> HighLevelError {
+///     snafu::error_map!(e: LowLevelError => HighLevelError {
+///         Timeout { seconds } => TimedOut { seconds },
+///         Refused { source } => Unreachable { source },
+///         Closed => Disconnected,
+///     })
+/// }
+/// ```
+///
+/// Leaving out a variant fails to compile:
+///
+/// ```rust,compile_fail,E0004
+/// # use snafu::prelude::*;
+/// # #[derive(Debug, Snafu)]
+/// # enum LowLevelError {
+/// #     Timeout { seconds: u64 },
+/// #     Closed,
+/// # }
+/// # #[derive(Debug, Snafu)]
+/// # enum HighLevelError {
+/// #     TimedOut { seconds: u64 },
+/// # }
+/// fn convert(e: LowLevelError) -> HighLevelError {
+///     snafu::error_map!(e: LowLevelError => HighLevelError {
+///         Timeout { seconds } => TimedOut { seconds },
+///         // `Closed` is not handled
+///     })
+/// }
+/// ```
+#[macro_export]
+macro_rules! error_map {
+    (@arm $error:tt, $from:tt, $to:tt, [$($arms:tt)*] $(,)?) => {{
+        #[allow(unused_parens)]
+        let error = $error;
+        match error {
+            $($arms)*
+        }
+    }};
+
+    (@arm
+        $error:tt,
+        [$($from:ident)::+],
+        [$($to:ident)::+],
+        [$($arms:tt)*],
+        $from_variant:ident $({ $($from_fields:tt)* })?
+        => $to_variant:ident $({ $($to_fields:tt)* })?
+        $(, $($rest:tt)*)?
+    ) => {
+        $crate::error_map!(
+            @arm
+            $error,
+            [$($from)::+],
+            [$($to)::+],
+            [
+                $($arms)*
+                $($from)::+::$from_variant $({ $($from_fields)* })?
+                    => $($to)::+::$to_variant $({ $($to_fields)* })?,
+            ],
+            $($($rest)*)?
+        )
+    };
+
+    ($error:tt : $($from:ident)::+ => $($to:ident)::+ { $($arms:tt)* }) => {
+        $crate::error_map!(@arm $error, [$($from)::+], [$($to)::+], [], $($arms)*)
+    };
+}
+
+/// Defines an error type that wraps an error from another crate.
+///
+/// The SNAFU traits cannot be implemented for types defined in other
+/// crates. Instead, a new error type is declared with the foreign
+/// error as its `source`, along with any other fields. This macro
+/// declares that type, implementing `Debug`, `Display`, and
+/// [`Error`][] and creating a context selector, the same as if
+/// [`Snafu`][] had been derived.
+///
+/// The display format follows `display =` and may refer to any field
+/// by name.
+///
+/// ```rust
+/// use snafu::prelude::*;
+/// use std::io;
+///
+/// snafu::derive_impl! {
+///     /// Reading the configuration failed
+///     pub ConfigError {
+///         source: io::Error,
+///         path: String,
+///     }
+///     display = "could not read {path}: {source}"
+/// }
+///
+/// fn read_config(path: &str) -> Result<String, ConfigError> {
+///     std::fs::read_to_string(path).context(ConfigSnafu { path })
+/// }
+///
+/// let error = read_config("/no/such/file").unwrap_err();
+/// assert!(error.to_string().starts_with("could not read /no/such/file: "));
+/// ```
+///
+/// Attributes, including `#[snafu(...)]` attributes, are applied to
+/// the generated type.
+#[macro_export]
+macro_rules! derive_impl {
+    (
+        $(#[$attr:meta])*
+        $vis:vis $name:ident {
+            $($(#[$field_attr:meta])* $field_vis:vis $field:ident : $field_ty:ty),* $(,)?
+        }
+        display = $display:literal $(,)?
+    ) => {
+        #[derive(::core::fmt::Debug, $crate::Snafu)]
+        #[snafu(crate_root($crate), display($display))]
+        $(#[$attr])*
+        $vis struct $name {
+            $($(#[$field_attr])* $field_vis $field: $field_ty,)*
+        }
+    };
+}
+
 /// Additions to [`Result`][].
 pub trait ResultExt<T, E>: Sized {
     /// Extend a [`Result`]'s error with additional context-sensitive information.
@@ -605,16 +1275,38 @@ pub trait ResultExt<T, E>: Sized {
         C: IntoError<E2, Source = E>,
         E2: Error + ErrorCompat;
 
-    /// Extend a [`Result`]'s error with information from a string.
+    /// The same as [`context`](ResultExt::context), under a name that
+    /// does not collide with the `context` methods of other extension
+    /// traits, such as `anyhow::Context`.
     ///
-    /// The target error type must implement [`FromString`] by using
-    /// the
-    /// [`#[snafu(whatever)]`][Snafu#controlling-stringly-typed-errors]
-    /// attribute. The premade [`Whatever`] type is also available.
-    ///
-    /// In many cases, you will want to use
-    /// [`with_whatever_context`][Self::with_whatever_context] instead
-    /// as it gives you access to the error and is only called in case
+    /// When both traits are imported, the SNAFU method can be called
+    /// by this name instead of with fully-qualified syntax.
+    #[doc(alias = "context")]
+    fn snafu_context<C, E2>(self, context: C) -> Result<T, E2>
+    where
+        C: IntoError<E2, Source = E>,
+        E2: Error + ErrorCompat;
+
+    /// The same as [`with_context`](ResultExt::with_context), under a
+    /// name that does not collide with other extension traits. See
+    /// [`snafu_context`](ResultExt::snafu_context).
+    #[doc(alias = "with_context")]
+    fn with_snafu_context<F, C, E2>(self, context: F) -> Result<T, E2>
+    where
+        F: FnOnce(&mut E) -> C,
+        C: IntoError<E2, Source = E>,
+        E2: Error + ErrorCompat;
+
+    /// Extend a [`Result`]'s error with information from a string.
+    ///
+    /// The target error type must implement [`FromString`] by using
+    /// the
+    /// [`#[snafu(whatever)]`][Snafu#controlling-stringly-typed-errors]
+    /// attribute. The premade [`Whatever`] type is also available.
+    ///
+    /// In many cases, you will want to use
+    /// [`with_whatever_context`][Self::with_whatever_context] instead
+    /// as it gives you access to the error and is only called in case
     /// of error. This method is best suited for when you have a
     /// string literal.
     ///
@@ -796,6 +1488,289 @@ pub trait ResultExt<T, E>: Sized {
     fn boxed_local<'a>(self) -> Result<T, Box<dyn Error + 'a>>
     where
         E: Error + 'a;
+
+    /// Replace specific errors with a successful value.
+    ///
+    /// The closure inspects the error. Returning `Some` recovers from
+    /// the error, while returning `None` leaves the original error
+    /// untouched.
+    ///
+    /// ```rust
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     NotFound { key: String },
+    ///     PermissionDenied,
+    /// }
+    ///
+    /// fn lookup(key: &str) -> Result<u32, Error> {
+    ///     NotFoundSnafu { key }.fail()
+    /// }
+    ///
+    /// fn lookup_or_zero(key: &str) -> Result<u32, Error> {
+    ///     lookup(key).recover(|e| match e {
+    ///         Error::NotFound { .. } => Some(0),
+    ///         _ => None,
+    ///     })
+    /// }
+    /// ```
+    fn recover<F>(self, recover: F) -> Result<T, E>
+    where
+        F: FnOnce(&E) -> Option<T>;
+
+    /// Replace specific error variants with a successful value,
+    /// taking ownership of the error.
+    ///
+    /// The variants are selected with [`variant!`][crate::variant],
+    /// which lists patterns and the value to produce for each. Unlike
+    /// [`recover`](ResultExt::recover), the matched error is moved
+    /// into its arm, so data can be moved out of the variant. Errors
+    /// that match no pattern are returned unchanged.
+    ///
+    /// ```rust
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     Stale { cached: String },
+    ///     Unavailable,
+    /// }
+    ///
+    /// fn fetch() -> Result<String, Error> {
+    ///     StaleSnafu { cached: "yesterday" }.fail()
+    /// }
+    ///
+    /// fn fetch_allowing_stale() -> Result<String, Error> {
+    ///     fetch().recover_variant(snafu::variant!(Error::Stale { cached } => cached))
+    /// }
+    /// ```
+    fn recover_variant<F>(self, matcher: VariantMatcher<F>) -> Result<T, E>
+    where
+        F: FnOnce(E) -> Result<T, E>;
+
+    /// Convert the error into another type before context is added
+    /// to it.
+    ///
+    /// This is [`Result::map_err`][] under a name that reads as a
+    /// step in building the error. It is useful when the underlying
+    /// error needs to be normalized into the type that a context
+    /// selector expects as its source.
+    ///
+    /// ```rust
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// #[snafu(display("database failure: {message}"))]
+    /// struct DbError {
+    ///     message: String,
+    /// }
+    ///
+    /// impl From<std::io::Error> for DbError {
+    ///     fn from(e: std::io::Error) -> Self {
+    ///         DbError { message: e.to_string() }
+    ///     }
+    /// }
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     Query { table: String, source: DbError },
+    /// }
+    ///
+    /// fn example() -> Result<u32, Error> {
+    ///     run_query()
+    ///         .map_source(DbError::from)
+    ///         .context(QuerySnafu { table: "users" })
+    /// }
+    ///
+    /// fn run_query() -> Result<u32, std::io::Error> {
+    ///     /* ... */
+    /// #   Ok(42)
+    /// }
+    /// ```
+    fn map_source<F, S2>(self, f: F) -> Result<T, S2>
+    where
+        F: FnOnce(E) -> S2;
+
+    /// Convert the error with a conversion that can itself fail.
+    ///
+    /// When the conversion succeeds, its value is the new error.
+    /// When it fails, the error it returns is used instead. Either
+    /// way, the result is still an `Err`; unlike
+    /// [`Result::and_then`][] or [`recover_variant`](ResultExt::recover_variant),
+    /// this never turns an error into a success.
+    ///
+    /// ```rust
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     #[snafu(display("the device reported {description}"))]
+    ///     Device { description: &'static str },
+    ///
+    ///     #[snafu(display("the device reported unknown code {code}"))]
+    ///     UnknownCode { code: i32 },
+    /// }
+    ///
+    /// fn describe(code: i32) -> Result<Error, Error> {
+    ///     match code {
+    ///         5 => Ok(DeviceSnafu { description: "a paper jam" }.build()),
+    ///         _ => UnknownCodeSnafu { code }.fail(),
+    ///     }
+    /// }
+    ///
+    /// fn print(code: i32) -> Result<(), i32> {
+    ///     Err(code)
+    /// }
+    ///
+    /// let jammed = print(5).flat_map_err(describe).unwrap_err();
+    /// assert_eq!(jammed.to_string(), "the device reported a paper jam");
+    ///
+    /// let unknown = print(99).flat_map_err(describe).unwrap_err();
+    /// assert_eq!(unknown.to_string(), "the device reported unknown code 99");
+    /// ```
+    fn flat_map_err<F, E2, E3>(self, f: F) -> Result<T, E3>
+    where
+        F: FnOnce(E) -> Result<E2, E3>,
+        E2: Into<E3>;
+
+    /// Convert a [`Result`][] into a [`ControlFlow`][], adding
+    /// context to the error and breaking with it.
+    ///
+    /// This is the reverse of
+    /// [`ControlFlowExt::break_context`][], useful inside closures
+    /// passed to [`Iterator::try_fold`][] and similar methods.
+    ///
+    /// ```rust
+    /// use core::ops::ControlFlow;
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     #[snafu(display("Could not parse {text}"))]
+    ///     Parse {
+    ///         text: String,
+    ///         source: std::num::ParseIntError,
+    ///     },
+    /// }
+    ///
+    /// fn sum(texts: &[&str]) -> ControlFlow<Error, i32> {
+    ///     texts.iter().try_fold(0, |acc, &text| {
+    ///         let value = text.parse::<i32>().context_break(ParseSnafu { text })?;
+    ///         ControlFlow::Continue(acc + value)
+    ///     })
+    /// }
+    /// ```
+    fn context_break<C, E2>(self, context: C) -> ControlFlow<E2, T>
+    where
+        C: IntoError<E2, Source = E>,
+        E2: Error + ErrorCompat;
+
+    /// Extend a [`Result`][]'s error with context that includes a copy
+    /// of the error's chain of sources.
+    ///
+    /// Up to `depth` errors are captured, starting with the error
+    /// itself and continuing through its sources. Each error is
+    /// captured as a [`CapturedError`][] that preserves its `Display`
+    /// and `Debug` output. The captured chain is passed to the
+    /// closure, which creates the context selector.
+    ///
+    /// [`Result`]: std::result::Result
+    ///
+    /// ```rust
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     #[snafu(display("Could not synchronize {} items", error_chain.len()))]
+    ///     Synchronizing {
+    ///         source: ApiError,
+    ///         error_chain: Vec<Box<dyn snafu::Error + Send + Sync>>,
+    ///     },
+    /// }
+    ///
+    /// fn example() -> Result<(), Error> {
+    ///     another_function().context_chain(|error_chain| SynchronizingSnafu { error_chain }, 3)?;
+    ///     Ok(())
+    /// }
+    ///
+    /// # type ApiError = std::io::Error;
+    /// fn another_function() -> Result<i32, ApiError> {
+    ///     /* ... */
+    /// # Ok(42)
+    /// }
+    /// ```
+    #[cfg(any(feature = "std", test))]
+    fn context_chain<F, C, E2>(self, context: F, depth: usize) -> Result<T, E2>
+    where
+        F: FnOnce(Vec<Box<dyn Error + Send + Sync>>) -> C,
+        C: IntoError<E2, Source = E>,
+        E: Error,
+        E2: Error + ErrorCompat;
+
+    /// Flatten a nested [`Result`][], extending the outer error with
+    /// additional context-sensitive information and converting the
+    /// inner error with [`Into::into`].
+    ///
+    /// This shape is commonly produced when waiting on work performed
+    /// by another thread or task, where the outer error reports that
+    /// the work could not be completed and the inner error is
+    /// produced by the work itself.
+    ///
+    /// [`Result`]: std::result::Result
+    ///
+    /// ```rust
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     #[snafu(display("The worker for job {job} did not complete"))]
+    ///     Worker { job: i32, source: WorkerPanicked },
+    ///
+    ///     #[snafu(transparent)]
+    ///     Job { source: JobError },
+    /// }
+    ///
+    /// fn example() -> Result<i32, Error> {
+    ///     run_in_background().flatten_context(WorkerSnafu { job: 42 })
+    /// }
+    ///
+    /// # #[derive(Debug, Snafu)]
+    /// # struct WorkerPanicked;
+    /// # #[derive(Debug, Snafu)]
+    /// # struct JobError;
+    /// fn run_in_background() -> Result<Result<i32, JobError>, WorkerPanicked> {
+    ///     /* ... */
+    /// # Ok(Ok(42))
+    /// }
+    /// ```
+    fn flatten_context<C, E2>(self, context: C) -> Result<T::Ok, E2>
+    where
+        T: nested::NestedResult,
+        T::Error: Into<E2>,
+        C: IntoError<E2, Source = E>,
+        E2: Error + ErrorCompat;
+}
+
+mod nested {
+    /// Implemented by the [`Result`][] contained inside of another
+    /// `Result`. Used to name the inner types in
+    /// [`ResultExt::flatten_context`](super::ResultExt::flatten_context).
+    pub trait NestedResult {
+        type Ok;
+        type Error;
+
+        fn into_result(self) -> Result<Self::Ok, Self::Error>;
+    }
+
+    impl<T, E> NestedResult for Result<T, E> {
+        type Ok = T;
+        type Error = E;
+
+        fn into_result(self) -> Result<T, E> {
+            self
+        }
+    }
 }
 
 impl<T, E> ResultExt<T, E> for Result<T, E> {
@@ -829,6 +1804,25 @@ impl<T, E> ResultExt<T, E> for Result<T, E> {
         }
     }
 
+    #[track_caller]
+    fn snafu_context<C, E2>(self, context: C) -> Result<T, E2>
+    where
+        C: IntoError<E2, Source = E>,
+        E2: Error + ErrorCompat,
+    {
+        self.context(context)
+    }
+
+    #[track_caller]
+    fn with_snafu_context<F, C, E2>(self, context: F) -> Result<T, E2>
+    where
+        F: FnOnce(&mut E) -> C,
+        C: IntoError<E2, Source = E>,
+        E2: Error + ErrorCompat,
+    {
+        self.with_context(context)
+    }
+
     #[cfg(any(feature = "std", test))]
     #[track_caller]
     fn whatever_context<S, E2>(self, context: S) -> Result<T, E2>
@@ -878,6 +1872,100 @@ impl<T, E> ResultExt<T, E> for Result<T, E> {
     {
         self.map_err(|e| Box::new(e) as _)
     }
+
+    fn recover<F>(self, recover: F) -> Result<T, E>
+    where
+        F: FnOnce(&E) -> Option<T>,
+    {
+        match self {
+            Ok(v) => Ok(v),
+            Err(error) => match recover(&error) {
+                Some(v) => Ok(v),
+                None => Err(error),
+            },
+        }
+    }
+
+    fn recover_variant<F>(self, matcher: VariantMatcher<F>) -> Result<T, E>
+    where
+        F: FnOnce(E) -> Result<T, E>,
+    {
+        match self {
+            Ok(v) => Ok(v),
+            Err(error) => matcher.recover(error),
+        }
+    }
+
+    fn map_source<F, S2>(self, f: F) -> Result<T, S2>
+    where
+        F: FnOnce(E) -> S2,
+    {
+        self.map_err(f)
+    }
+
+    fn flat_map_err<F, E2, E3>(self, f: F) -> Result<T, E3>
+    where
+        F: FnOnce(E) -> Result<E2, E3>,
+        E2: Into<E3>,
+    {
+        match self {
+            Ok(v) => Ok(v),
+            Err(error) => match f(error) {
+                Ok(mapped) => Err(mapped.into()),
+                Err(fallback) => Err(fallback),
+            },
+        }
+    }
+
+    #[track_caller]
+    fn context_break<C, E2>(self, context: C) -> ControlFlow<E2, T>
+    where
+        C: IntoError<E2, Source = E>,
+        E2: Error + ErrorCompat,
+    {
+        match self {
+            Ok(v) => ControlFlow::Continue(v),
+            Err(error) => ControlFlow::Break(context.into_error(error)),
+        }
+    }
+
+    #[cfg(any(feature = "std", test))]
+    #[track_caller]
+    fn context_chain<F, C, E2>(self, context: F, depth: usize) -> Result<T, E2>
+    where
+        F: FnOnce(Vec<Box<dyn Error + Send + Sync>>) -> C,
+        C: IntoError<E2, Source = E>,
+        E: Error,
+        E2: Error + ErrorCompat,
+    {
+        // https://github.com/rust-lang/rust/issues/74042
+        match self {
+            Ok(v) => Ok(v),
+            Err(error) => {
+                let chain = ChainCompat::new(&error)
+                    .take(depth)
+                    .map(|e| Box::new(CapturedError::new(e)) as _)
+                    .collect();
+                let context = context(chain);
+                Err(context.into_error(error))
+            }
+        }
+    }
+
+    #[track_caller]
+    fn flatten_context<C, E2>(self, context: C) -> Result<T::Ok, E2>
+    where
+        T: nested::NestedResult,
+        T::Error: Into<E2>,
+        C: IntoError<E2, Source = E>,
+        E2: Error + ErrorCompat,
+    {
+        // https://github.com/rust-lang/rust/issues/74042
+        match self {
+            Ok(inner) => inner.into_result().map_err(Into::into),
+            Err(error) => Err(context.into_error(error)),
+        }
+    }
 }
 
 /// A temporary error type used when converting an [`Option`][] into a
@@ -885,8 +1973,15 @@ impl<T, E> ResultExt<T, E> for Result<T, E> {
 ///
 /// [`Option`]: std::option::Option
 /// [`Result`]: std::result::Result
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct NoneError;
 
+impl fmt::Display for NoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a value was missing")
+    }
+}
+
 /// Additions to [`Option`][].
 pub trait OptionExt<T>: Sized {
     /// Convert an [`Option`][] into a [`Result`][] with additional
@@ -962,6 +2057,28 @@ pub trait OptionExt<T>: Sized {
         C: IntoError<E, Source = NoneError>,
         E: Error + ErrorCompat;
 
+    /// The same as [`context`](OptionExt::context), under a name that
+    /// does not collide with the `context` methods of other extension
+    /// traits, such as `anyhow::Context`.
+    ///
+    /// When both traits are imported, the SNAFU method can be called
+    /// by this name instead of with fully-qualified syntax.
+    #[doc(alias = "context")]
+    fn snafu_context<C, E>(self, context: C) -> Result<T, E>
+    where
+        C: IntoError<E, Source = NoneError>,
+        E: Error + ErrorCompat;
+
+    /// The same as [`with_context`](OptionExt::with_context), under a
+    /// name that does not collide with other extension traits. See
+    /// [`snafu_context`](OptionExt::snafu_context).
+    #[doc(alias = "with_context")]
+    fn with_snafu_context<F, C, E>(self, context: F) -> Result<T, E>
+    where
+        F: FnOnce() -> C,
+        C: IntoError<E, Source = NoneError>,
+        E: Error + ErrorCompat;
+
     /// Convert an [`Option`] into a [`Result`] with information
     /// from a string.
     ///
@@ -1035,19 +2152,48 @@ pub trait OptionExt<T>: Sized {
         F: FnOnce() -> S,
         S: Into<String>,
         E: FromString;
-}
 
-impl<T> OptionExt<T> for Option<T> {
-    #[track_caller]
-    fn context<C, E>(self, context: C) -> Result<T, E>
-    where
-        C: IntoError<E, Source = NoneError>,
-        E: Error + ErrorCompat,
+    /// Convert an [`Option`] into a [`Result`][] with a [`Whatever`][]
+    /// error holding a lazily-generated message.
+    ///
+    /// This is the same as
+    /// [`with_whatever_context`][Self::with_whatever_context], but the
+    /// error type is always [`Whatever`][]. This avoids needing to name
+    /// the error type when the result is not immediately returned
+    /// with `?`.
+    ///
+    /// ```rust
+    /// use snafu::prelude::*;
+    ///
+    /// let ports = [("http", 80), ("https", 443)];
+    /// let lookup = |name: &str| {
+    ///     ports
+    ///         .iter()
+    ///         .find(|(n, _)| *n == name)
+    ///         .map(|(_, port)| *port)
+    ///         .ok_or_whatever_with(|| format!("unknown service {name}"))
+    /// };
+    ///
+    /// assert_eq!(lookup("https").unwrap(), 443);
+    /// assert_eq!(lookup("gopher").unwrap_err().to_string(), "unknown service gopher");
+    /// ```
+    #[cfg(any(feature = "std", test))]
+    fn ok_or_whatever_with<F>(self, f: F) -> Result<T, Whatever>
+    where
+        F: FnOnce() -> String;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    #[track_caller]
+    fn context<C, E>(self, context: C) -> Result<T, E>
+    where
+        C: IntoError<E, Source = NoneError>,
+        E: Error + ErrorCompat,
     {
         // https://github.com/rust-lang/rust/issues/74042
         match self {
             Some(v) => Ok(v),
-            None => Err(context.into_error(NoneError)),
+            None => Err(context.into_error_for_missing(NoneError, core::any::type_name::<T>())),
         }
     }
 
@@ -1061,10 +2207,29 @@ impl<T> OptionExt<T> for Option<T> {
         // https://github.com/rust-lang/rust/issues/74042
         match self {
             Some(v) => Ok(v),
-            None => Err(context().into_error(NoneError)),
+            None => Err(context().into_error_for_missing(NoneError, core::any::type_name::<T>())),
         }
     }
 
+    #[track_caller]
+    fn snafu_context<C, E>(self, context: C) -> Result<T, E>
+    where
+        C: IntoError<E, Source = NoneError>,
+        E: Error + ErrorCompat,
+    {
+        self.context(context)
+    }
+
+    #[track_caller]
+    fn with_snafu_context<F, C, E>(self, context: F) -> Result<T, E>
+    where
+        F: FnOnce() -> C,
+        C: IntoError<E, Source = NoneError>,
+        E: Error + ErrorCompat,
+    {
+        self.with_context(context)
+    }
+
     #[cfg(any(feature = "std", test))]
     #[track_caller]
     fn whatever_context<S, E>(self, context: S) -> Result<T, E>
@@ -1094,6 +2259,209 @@ impl<T> OptionExt<T> for Option<T> {
             }
         }
     }
+
+    #[cfg(any(feature = "std", test))]
+    #[track_caller]
+    fn ok_or_whatever_with<F>(self, f: F) -> Result<T, Whatever>
+    where
+        F: FnOnce() -> String,
+    {
+        self.with_whatever_context(f)
+    }
+}
+
+/// Additions to [`ControlFlow`][].
+///
+/// This allows the break value of iterator adapters such as
+/// [`Iterator::try_fold`][] and [`Iterator::try_for_each`][] to be
+/// used as the source of an error.
+pub trait ControlFlowExt<B, C>: Sized {
+    /// Convert a [`ControlFlow`][] into a [`Result`][], adding
+    /// context to the break value.
+    ///
+    /// ```rust
+    /// use core::ops::ControlFlow;
+    /// use snafu::prelude::*;
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     #[snafu(display("The total exceeded {limit}"))]
+    ///     TooLarge { limit: u32, source: OverflowError },
+    /// }
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// #[snafu(display("Adding {value} overflowed"))]
+    /// struct OverflowError {
+    ///     value: u32,
+    /// }
+    ///
+    /// fn total(values: &[u32], limit: u32) -> Result<u32, Error> {
+    ///     values
+    ///         .iter()
+    ///         .try_fold(0u32, |acc, &value| match acc + value {
+    ///             sum if sum > limit => ControlFlow::Break(OverflowSnafu { value }.build()),
+    ///             sum => ControlFlow::Continue(sum),
+    ///         })
+    ///         .break_context(TooLargeSnafu { limit })
+    /// }
+    /// ```
+    fn break_context<S, E>(self, context: S) -> Result<C, E>
+    where
+        S: IntoError<E, Source = B>,
+        E: Error + ErrorCompat;
+
+    /// Convert a [`ControlFlow`][] into a [`Result`][], adding
+    /// lazily-generated context to the break value.
+    ///
+    /// The closure is only called when the value is
+    /// [`ControlFlow::Break`][].
+    fn with_break_context<F, S, E>(self, context: F) -> Result<C, E>
+    where
+        F: FnOnce(&mut B) -> S,
+        S: IntoError<E, Source = B>,
+        E: Error + ErrorCompat;
+}
+
+impl<B, C> ControlFlowExt<B, C> for ControlFlow<B, C> {
+    #[track_caller]
+    fn break_context<S, E>(self, context: S) -> Result<C, E>
+    where
+        S: IntoError<E, Source = B>,
+        E: Error + ErrorCompat,
+    {
+        match self {
+            ControlFlow::Continue(v) => Ok(v),
+            ControlFlow::Break(error) => Err(context.into_error(error)),
+        }
+    }
+
+    #[track_caller]
+    fn with_break_context<F, S, E>(self, context: F) -> Result<C, E>
+    where
+        F: FnOnce(&mut B) -> S,
+        S: IntoError<E, Source = B>,
+        E: Error + ErrorCompat,
+    {
+        match self {
+            ControlFlow::Continue(v) => Ok(v),
+            ControlFlow::Break(mut error) => {
+                let context = context(&mut error);
+                Err(context.into_error(error))
+            }
+        }
+    }
+}
+
+/// Additions to the [`LockResult`][] returned when acquiring a
+/// [`Mutex`][] or [`RwLock`][].
+///
+/// A [`PoisonError`][] holds on to the lock guard, which borrows the
+/// lock and usually cannot be sent between threads, so it is a poor
+/// fit for the source of an error that is propagated further. These
+/// methods release the guard and use a `PoisonError<()>` as the
+/// source instead.
+///
+/// The methods are named differently from those of [`ResultExt`][],
+/// which also applies to a [`LockResult`][], so both traits can be in
+/// scope at once.
+///
+/// [`LockResult`]: std::sync::LockResult
+/// [`Mutex`]: std::sync::Mutex
+/// [`RwLock`]: std::sync::RwLock
+/// [`PoisonError`]: std::sync::PoisonError
+#[cfg(any(feature = "std", test))]
+pub trait PoisonExt<G>: Sized {
+    /// Convert a poisoned lock into an error with additional
+    /// context-sensitive information.
+    ///
+    /// ```rust
+    /// use snafu::{prelude::*, PoisonExt};
+    /// use std::sync::{Mutex, PoisonError};
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     CounterPoisoned {
+    ///         name: String,
+    ///         source: PoisonError<()>,
+    ///     },
+    /// }
+    ///
+    /// fn example(counter: &Mutex<u32>) -> Result<u32, Error> {
+    ///     let mut count = counter
+    ///         .lock()
+    ///         .poison_context(CounterPoisonedSnafu { name: "requests" })?;
+    ///     *count += 1;
+    ///     Ok(*count)
+    /// }
+    /// ```
+    ///
+    /// Note that the context selector will call [`Into::into`][] on each field,
+    /// so the types are not required to exactly match.
+    fn poison_context<C, E>(self, context: C) -> Result<G, E>
+    where
+        C: IntoError<E, Source = std::sync::PoisonError<()>>,
+        E: Error + ErrorCompat;
+
+    /// Convert a poisoned lock into an error with lazily-generated
+    /// context-sensitive information.
+    ///
+    /// ```rust
+    /// use snafu::{prelude::*, PoisonExt};
+    /// use std::sync::{PoisonError, RwLock};
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// enum Error {
+    ///     ConfigPoisoned {
+    ///         name: String,
+    ///         source: PoisonError<()>,
+    ///     },
+    /// }
+    ///
+    /// fn example(config: &RwLock<String>, name: &str) -> Result<usize, Error> {
+    ///     let config = config
+    ///         .read()
+    ///         .with_poison_context(|| ConfigPoisonedSnafu { name })?;
+    ///     Ok(config.len())
+    /// }
+    /// ```
+    ///
+    /// Note that this *may not* be needed in many cases because the context
+    /// selector will call [`Into::into`][] on each field.
+    fn with_poison_context<F, C, E>(self, context: F) -> Result<G, E>
+    where
+        F: FnOnce() -> C,
+        C: IntoError<E, Source = std::sync::PoisonError<()>>,
+        E: Error + ErrorCompat;
+}
+
+#[cfg(any(feature = "std", test))]
+impl<G> PoisonExt<G> for std::sync::LockResult<G> {
+    #[track_caller]
+    fn poison_context<C, E>(self, context: C) -> Result<G, E>
+    where
+        C: IntoError<E, Source = std::sync::PoisonError<()>>,
+        E: Error + ErrorCompat,
+    {
+        // https://github.com/rust-lang/rust/issues/74042
+        match self {
+            Ok(guard) => Ok(guard),
+            Err(_) => Err(context.into_error(std::sync::PoisonError::new(()))),
+        }
+    }
+
+    #[track_caller]
+    fn with_poison_context<F, C, E>(self, context: F) -> Result<G, E>
+    where
+        F: FnOnce() -> C,
+        C: IntoError<E, Source = std::sync::PoisonError<()>>,
+        E: Error + ErrorCompat,
+    {
+        // https://github.com/rust-lang/rust/issues/74042
+        match self {
+            Ok(guard) => Ok(guard),
+            Err(_) => Err(context().into_error(std::sync::PoisonError::new(()))),
+        }
+    }
 }
 
 /// Backports changes to the [`Error`][] trait to versions of Rust
@@ -1123,15 +2491,47 @@ pub trait ErrorCompat {
     ///
     /// To omit the current error and only traverse its sources,
     /// use `skip(1)`.
-    fn iter_chain(&self) -> ChainCompat
+    fn iter_chain(&self) -> ChainCompat<'_, '_>
     where
         Self: AsErrorSource,
     {
         ChainCompat::new(self.as_error_source())
     }
+
+    /// Formats this error and its sources the same way as
+    /// [`Report`][].
+    ///
+    /// This is useful when the complete error needs to be stored or
+    /// sent somewhere as text, such as in logging middleware. Use
+    /// [`to_report_string`][crate::to_report_string] for unsized
+    /// errors such as boxed trait objects.
+    ///
+    /// ```rust
+    /// use snafu::{prelude::*, ErrorCompat};
+    ///
+    /// #[derive(Debug, Snafu)]
+    /// #[snafu(display("could not load the settings"))]
+    /// struct SettingsError {
+    ///     source: std::io::Error,
+    /// }
+    ///
+    /// let error = std::fs::read("/no/such/file")
+    ///     .context(SettingsSnafu)
+    ///     .unwrap_err();
+    ///
+    /// let report = ErrorCompat::to_report_string(&error);
+    /// assert!(report.starts_with("could not load the settings\n\nCaused by this error:\n"));
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn to_report_string(&self) -> alloc::string::String
+    where
+        Self: Error + Sized,
+    {
+        report::to_report_string(self)
+    }
 }
 
-impl<'a, E> ErrorCompat for &'a E
+impl<E> ErrorCompat for &E
 where
     E: ErrorCompat,
 {
@@ -1150,6 +2550,220 @@ where
     }
 }
 
+#[cfg(any(feature = "std", test))]
+impl<E> ErrorCompat for std::sync::Arc<E>
+where
+    E: ErrorCompat + ?Sized,
+{
+    fn backtrace(&self) -> Option<&Backtrace> {
+        (**self).backtrace()
+    }
+}
+
+/// Whether retrying the operation that caused an error may succeed.
+///
+/// See [`HasCauseKind`][].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CauseKind {
+    /// The error is temporary; retrying may succeed.
+    Transient,
+    /// The error will happen again; retrying will not help.
+    Permanent,
+    /// The error has not been categorized.
+    Unknown,
+}
+
+/// Categorizes an error for retry logic.
+///
+/// This is implemented by `#[derive(Snafu)]` when any variant or
+/// struct uses `#[snafu(cause_kind = "...")]`. Variants without the
+/// attribute report [`CauseKind::Unknown`][].
+///
+/// ```rust
+/// use snafu::{prelude::*, CauseKind, HasCauseKind};
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     #[snafu(cause_kind = "transient")]
+///     Timeout,
+///
+///     #[snafu(cause_kind = "permanent")]
+///     NotFound,
+///
+///     Unexpected,
+/// }
+///
+/// assert_eq!(TimeoutSnafu.build().cause_kind(), CauseKind::Transient);
+/// assert_eq!(NotFoundSnafu.build().cause_kind(), CauseKind::Permanent);
+/// assert_eq!(UnexpectedSnafu.build().cause_kind(), CauseKind::Unknown);
+/// ```
+pub trait HasCauseKind {
+    /// Returns the category of this error.
+    fn cause_kind(&self) -> CauseKind;
+}
+
+impl<E> HasCauseKind for &E
+where
+    E: HasCauseKind + ?Sized,
+{
+    fn cause_kind(&self) -> CauseKind {
+        (**self).cause_kind()
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl<E> HasCauseKind for Box<E>
+where
+    E: HasCauseKind + ?Sized,
+{
+    fn cause_kind(&self) -> CauseKind {
+        (**self).cause_kind()
+    }
+}
+
+/// The broad category of an error.
+///
+/// See [`HasErrorKind`][].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The error came from communicating over a network.
+    Network,
+    /// The error came from reading or writing local resources, such
+    /// as files.
+    Io,
+    /// The error came from a bug or a violated assumption in the
+    /// program.
+    Logic,
+    /// The error has not been categorized.
+    Unknown,
+}
+
+impl ErrorKind {
+    /// Returns the name of the category, as written in
+    /// `#[snafu(kind = "...")]`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Network => "network",
+            ErrorKind::Io => "io",
+            ErrorKind::Logic => "logic",
+            ErrorKind::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Categorizes an error so that it can be handled by category at
+/// runtime.
+///
+/// This is implemented by `#[derive(Snafu)]` when any variant or
+/// struct uses `#[snafu(kind = "...")]`. Variants without the
+/// attribute report [`ErrorKind::Unknown`][].
+///
+/// ```rust
+/// use snafu::{prelude::*, ErrorKind, HasErrorKind};
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     #[snafu(kind = "network")]
+///     Connect,
+///
+///     #[snafu(kind = "io")]
+///     ReadConfig,
+///
+///     Unexpected,
+/// }
+///
+/// assert_eq!(ConnectSnafu.build().kind(), "network");
+/// assert_eq!(ReadConfigSnafu.build().error_kind(), ErrorKind::Io);
+/// assert_eq!(UnexpectedSnafu.build().kind(), "unknown");
+/// ```
+pub trait HasErrorKind {
+    /// Returns the category of this error.
+    fn error_kind(&self) -> ErrorKind;
+
+    /// Returns the name of the category of this error.
+    fn kind(&self) -> &'static str {
+        self.error_kind().as_str()
+    }
+}
+
+impl<E> HasErrorKind for &E
+where
+    E: HasErrorKind + ?Sized,
+{
+    fn error_kind(&self) -> ErrorKind {
+        (**self).error_kind()
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl<E> HasErrorKind for Box<E>
+where
+    E: HasErrorKind + ?Sized,
+{
+    fn error_kind(&self) -> ErrorKind {
+        (**self).error_kind()
+    }
+}
+
+/// A short, single-line description of an error.
+///
+/// This is implemented by `#[derive(Snafu)]` when any variant or
+/// struct uses `#[snafu(error_description = "...")]`. Unlike
+/// [`Display`](core::fmt::Display), the description does not include
+/// any of the error's data. Variants without the attribute use the
+/// variant name.
+///
+/// ```rust
+/// use snafu::{prelude::*, ErrorDescription};
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     #[snafu(
+///         display("Could not open {path}"),
+///         error_description = "file not found"
+///     )]
+///     Opening { path: String },
+///
+///     Unexpected,
+/// }
+///
+/// let e = OpeningSnafu { path: "/etc/config" }.build();
+/// assert_eq!(ErrorDescription::description(&e), "file not found");
+/// assert_eq!(e.to_string(), "Could not open /etc/config");
+///
+/// let e = UnexpectedSnafu.build();
+/// assert_eq!(ErrorDescription::description(&e), "Unexpected");
+/// ```
+pub trait ErrorDescription {
+    /// Returns the description of this error.
+    fn description(&self) -> &str;
+}
+
+impl<E> ErrorDescription for &E
+where
+    E: ErrorDescription + ?Sized,
+{
+    fn description(&self) -> &str {
+        (**self).description()
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl<E> ErrorDescription for Box<E>
+where
+    E: ErrorDescription + ?Sized,
+{
+    fn description(&self) -> &str {
+        (**self).description()
+    }
+}
+
 /// Converts the receiver into an [`Error`][] trait object, suitable
 /// for use in [`Error::source`][].
 ///
@@ -1205,47 +2819,224 @@ impl AsErrorSource for dyn Error + 'static {
     }
 }
 
-impl AsErrorSource for dyn Error + Send + 'static {
-    fn as_error_source(&self) -> &(dyn Error + 'static) {
-        self
-    }
+impl AsErrorSource for dyn Error + Send + 'static {
+    fn as_error_source(&self) -> &(dyn Error + 'static) {
+        self
+    }
+}
+
+impl AsErrorSource for dyn Error + Sync + 'static {
+    fn as_error_source(&self) -> &(dyn Error + 'static) {
+        self
+    }
+}
+
+impl AsErrorSource for dyn Error + Send + Sync + 'static {
+    fn as_error_source(&self) -> &(dyn Error + 'static) {
+        self
+    }
+}
+
+impl<T> AsErrorSource for T
+where
+    T: Error + 'static,
+{
+    fn as_error_source(&self) -> &(dyn Error + 'static) {
+        self
+    }
+}
+
+/// Combines an underlying error with additional information
+/// about the error.
+///
+/// It is expected that most users of SNAFU will not directly interact
+/// with this trait.
+pub trait IntoError<E>
+where
+    E: Error + ErrorCompat,
+{
+    /// The underlying error
+    type Source;
+
+    /// Combine the information to produce the error
+    fn into_error(self, source: Self::Source) -> E;
+
+    /// Combine the information to produce the error for a missing
+    /// value of the type named `type_name`. This is used by
+    /// [`OptionExt`][].
+    ///
+    /// Implicit fields are built with
+    /// [`GenerateImplicitData::generate_for_missing`][]. By default,
+    /// this is the same as [`into_error`][Self::into_error].
+    #[track_caller]
+    fn into_error_for_missing(self, source: Self::Source, type_name: &'static str) -> E
+    where
+        Self: Sized,
+    {
+        let _type_name = type_name;
+        self.into_error(source)
+    }
+}
+
+/// Implemented by every context selector generated by the
+/// [`Snafu`][] macro, as well as by [`from_fn`][].
+///
+/// This allows writing functions that accept any context selector
+/// for a specific error type without naming the selector types.
+///
+/// ```rust
+/// use snafu::{prelude::*, ContextSelector};
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     Opening { source: std::io::Error },
+///     Reading { source: std::io::Error },
+/// }
+///
+/// fn apply_context<C>(context: C) -> impl FnOnce(C::Source) -> Error
+/// where
+///     C: ContextSelector<Error>,
+/// {
+///     move |source| context.into_error(source)
+/// }
+///
+/// let to_error = apply_context(OpeningSnafu);
+/// let error = to_error(std::io::ErrorKind::NotFound.into());
+/// assert!(matches!(error, Error::Opening { .. }));
+/// ```
+pub trait ContextSelector<E>: IntoError<E>
+where
+    E: Error + ErrorCompat,
+{
+}
+
+/// Creates a context selector from a closure that converts the
+/// underlying error into the final error.
+///
+/// This is useful for a one-off transformation where declaring a
+/// dedicated context selector would be excessive. The result can be
+/// used anywhere a context selector is accepted, such as
+/// [`ResultExt::context`][] or the [`futures`][crate::futures]
+/// adapters.
+///
+/// ```rust
+/// use snafu::prelude::*;
+/// use std::io;
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     Weird { source: io::Error, attempt: u32 },
+/// }
+///
+/// fn example(attempt: u32) -> Result<(), Error> {
+///     read_settings().context(snafu::from_fn(|source| Error::Weird { source, attempt }))
+/// }
+///
+/// fn read_settings() -> io::Result<()> {
+///     /* ... */
+/// # Ok(())
+/// }
+/// ```
+pub fn from_fn<F, S, E>(f: F) -> FromFn<F, S>
+where
+    F: FnOnce(S) -> E,
+{
+    FromFn {
+        f,
+        _source: core::marker::PhantomData,
+    }
+}
+
+/// A context selector created from a closure by [`from_fn`][].
+pub struct FromFn<F, S> {
+    f: F,
+    _source: core::marker::PhantomData<fn(S)>,
 }
 
-impl AsErrorSource for dyn Error + Sync + 'static {
-    fn as_error_source(&self) -> &(dyn Error + 'static) {
-        self
+impl<F, S> Clone for FromFn<F, S>
+where
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            f: self.f.clone(),
+            _source: core::marker::PhantomData,
+        }
     }
 }
 
-impl AsErrorSource for dyn Error + Send + Sync + 'static {
-    fn as_error_source(&self) -> &(dyn Error + 'static) {
-        self
+impl<F, S> Copy for FromFn<F, S> where F: Copy {}
+
+impl<F, S> fmt::Debug for FromFn<F, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromFn").finish_non_exhaustive()
     }
 }
 
-impl<T> AsErrorSource for T
+impl<F, S, E> IntoError<E> for FromFn<F, S>
 where
-    T: Error + 'static,
+    F: FnOnce(S) -> E,
+    E: Error + ErrorCompat,
 {
-    fn as_error_source(&self) -> &(dyn Error + 'static) {
-        self
+    type Source = S;
+
+    #[inline]
+    fn into_error(self, source: S) -> E {
+        (self.f)(source)
     }
 }
 
-/// Combines an underlying error with additional information
-/// about the error.
-///
-/// It is expected that most users of SNAFU will not directly interact
-/// with this trait.
-pub trait IntoError<E>
+impl<F, S, E> ContextSelector<E> for FromFn<F, S>
 where
+    F: FnOnce(S) -> E,
     E: Error + ErrorCompat,
 {
-    /// The underlying error
-    type Source;
+}
 
-    /// Combine the information to produce the error
-    fn into_error(self, source: Self::Source) -> E;
+/// Incrementally constructs a context selector of type `C`.
+///
+/// When `#[snafu(context(builder))]` is used on an error whose context
+/// selector has user-specified fields, SNAFU generates a builder for
+/// it, named after the selector with a `Builder` suffix. This is
+/// useful when the selector has many fields or when the values of the
+/// fields are determined in different places.
+///
+/// ```rust
+/// use snafu::prelude::*;
+///
+/// #[derive(Debug, Snafu)]
+/// #[snafu(context(builder))]
+/// enum Error {
+///     #[snafu(display("Could not open {filename} in mode {mode}"))]
+///     OpenConfig {
+///         filename: String,
+///         mode: u32,
+///         source: std::io::Error,
+///     },
+/// }
+///
+/// fn open(filename: &str, read_only: bool) -> Result<(), Error> {
+///     let selector = OpenConfigSnafu::builder().filename(filename);
+///     let selector = if read_only {
+///         selector.mode(0o444_u32)
+///     } else {
+///         selector.mode(0o644_u32)
+///     };
+///
+///     selector.context(std::fs::File::open(filename))?;
+///     Ok(())
+/// }
+/// ```
+pub trait ContextBuilder<C> {
+    /// Create a builder where no fields have been set.
+    fn new() -> Self;
+
+    /// Consume the builder and return the context selector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any field of the context selector has not been set.
+    fn build(self) -> C;
 }
 
 /// Takes a string message and builds the corresponding error.
@@ -1258,9 +3049,11 @@ pub trait FromString {
     type Source;
 
     /// Create a brand new error from the given string
+    #[track_caller]
     fn without_source(message: String) -> Self;
 
     /// Wrap an existing error with the given string
+    #[track_caller]
     fn with_source(source: Self::Source, message: String) -> Self;
 }
 
@@ -1279,6 +3072,18 @@ pub trait GenerateImplicitData {
         let _source = source;
         Self::generate()
     }
+
+    /// Build the data for an error about a missing value of the type
+    /// named `type_name`, such as when an [`Option`][] is converted
+    /// by [`OptionExt`][]. See [`MissingTypeName`][].
+    #[track_caller]
+    fn generate_for_missing(type_name: &'static str) -> Self
+    where
+        Self: Sized,
+    {
+        let _type_name = type_name;
+        Self::generate()
+    }
 }
 
 /// View a backtrace-like value as an optional backtrace.
@@ -1295,7 +3100,8 @@ pub trait AsBacktrace {
 ///
 /// This value will be tested only once per program execution;
 /// changing the environment variable after it has been checked will
-/// have no effect.
+/// have no effect. Use [`env::set_backtrace_policy`][] to change the
+/// behavior at runtime.
 ///
 /// ## Interaction with the Provider API
 ///
@@ -1343,11 +3149,164 @@ impl AsBacktrace for Option<Backtrace> {
     }
 }
 
+/// Shares the backtrace so that the error containing it can be
+/// cloned.
+#[cfg(any(feature = "std", test))]
+impl GenerateImplicitData for std::sync::Arc<Backtrace> {
+    fn generate() -> Self {
+        std::sync::Arc::new(Backtrace::generate())
+    }
+
+    fn generate_with_source(source: &dyn crate::Error) -> Self {
+        std::sync::Arc::new(Backtrace::generate_with_source(source))
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl AsBacktrace for std::sync::Arc<Backtrace> {
+    fn as_backtrace(&self) -> Option<&Backtrace> {
+        Some(self)
+    }
+}
+
+/// Shares the backtrace so that the error containing it can be
+/// cloned. The backtrace is captured under the same conditions as
+/// `Option<Backtrace>`.
+#[cfg(any(feature = "std", test))]
+impl GenerateImplicitData for Option<std::sync::Arc<Backtrace>> {
+    fn generate() -> Self {
+        Option::<Backtrace>::generate().map(std::sync::Arc::new)
+    }
+
+    fn generate_with_source(source: &dyn crate::Error) -> Self {
+        Option::<Backtrace>::generate_with_source(source).map(std::sync::Arc::new)
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl AsBacktrace for Option<std::sync::Arc<Backtrace>> {
+    fn as_backtrace(&self) -> Option<&Backtrace> {
+        self.as_deref()
+    }
+}
+
+/// Displays a [`Backtrace`][] the same way regardless of which
+/// implementation has been selected by the [feature flags].
+///
+/// Depending on the feature flags enabled anywhere in the dependency
+/// graph, [`Backtrace`][] may be the standard library's type, the
+/// `backtrace` crate's type, or an inert placeholder. Not all of these
+/// implement [`Display`][fmt::Display], so reporting code that should
+/// work with any of them can use this wrapper instead.
+///
+/// ```rust
+/// use snafu::{prelude::*, Backtrace, DisplayBacktrace, ErrorCompat};
+///
+/// #[derive(Debug, Snafu)]
+/// struct Error {
+///     backtrace: Backtrace,
+/// }
+///
+/// let error = Snafu.build();
+/// if let Some(backtrace) = ErrorCompat::backtrace(&error) {
+///     eprintln!("{}", DisplayBacktrace(backtrace));
+/// }
+/// ```
+///
+/// [feature flags]: crate::guide::feature_flags
+pub struct DisplayBacktrace<'a>(pub &'a Backtrace);
+
+impl fmt::Display for DisplayBacktrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(any(feature = "std", test))]
+        {
+            if fake_backtraces() {
+                return f.write_str(env::FAKE_BACKTRACE);
+            }
+        }
+
+        backtrace_impl::fmt_display(self.0, f)
+    }
+}
+
+/// Formats a backtrace-like value as a single line in `Debug` output.
+///
+/// Instead of every frame, only whether a backtrace was captured is
+/// shown, as `Backtrace(<captured>)` or `Backtrace(<disabled>)`. The
+/// alternate form (`{:#?}`), which `dbg!` uses, formats the complete
+/// backtrace.
+///
+/// This is used by [`Whatever`][] and by errors using
+/// [`#[snafu(debug(compact_backtrace))]`](Snafu#compact-backtraces-in-debug-output),
+/// and may be used when implementing `Debug` by hand.
+///
+/// ```rust
+/// use snafu::{Backtrace, CompactBacktrace};
+/// use std::fmt;
+///
+/// struct Error {
+///     id: u32,
+///     backtrace: Backtrace,
+/// }
+///
+/// impl fmt::Debug for Error {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         f.debug_struct("Error")
+///             .field("id", &self.id)
+///             .field("backtrace", &CompactBacktrace(&self.backtrace))
+///             .finish()
+///     }
+/// }
+/// ```
+pub struct CompactBacktrace<'a, B>(pub &'a B);
+
+impl<B> fmt::Debug for CompactBacktrace<'_, B>
+where
+    B: AsBacktrace + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Debug::fmt(self.0, f);
+        }
+
+        let captured = self.0.as_backtrace().map_or(false, |bt| {
+            fake_backtraces() || backtrace_impl::is_captured(bt)
+        });
+
+        if captured {
+            f.write_str("Backtrace(<captured>)")
+        } else {
+            f.write_str("Backtrace(<disabled>)")
+        }
+    }
+}
+
+/// Whether backtraces are replaced by a placeholder, as requested by
+/// [`env::BacktracePolicy::Fake`][].
+#[cfg(any(feature = "std", test))]
+fn fake_backtraces() -> bool {
+    env::fake_backtraces()
+}
+
+#[cfg(not(any(feature = "std", test)))]
+fn fake_backtraces() -> bool {
+    false
+}
+
 #[cfg(any(feature = "std", test))]
 fn backtrace_collection_enabled() -> bool {
-    use crate::once_bool::OnceBool;
+    use crate::{
+        env::{backtrace_policy, BacktracePolicy},
+        once_bool::OnceBool,
+    };
     use std::env;
 
+    match backtrace_policy() {
+        BacktracePolicy::AlwaysCapture | BacktracePolicy::Fake => return true,
+        BacktracePolicy::NeverCapture => return false,
+        BacktracePolicy::UseEnvVar => {}
+    }
+
     static ENABLED: OnceBool = OnceBool::new();
 
     ENABLED.get(|| {
@@ -1511,6 +3470,147 @@ macro_rules! location {
     };
 }
 
+/// Prints an error and its chain of sources to stderr, then returns
+/// the error.
+///
+/// This is like [`dbg!`][], but uses the same format as [`Report`][]
+/// instead of the error's `Debug` implementation so that every error
+/// in the chain is shown. The output is prefixed with the location of
+/// the macro invocation and the expression that was evaluated.
+///
+/// The error is returned unchanged, so the macro can wrap an
+/// expression without altering the surrounding code. Pass a reference
+/// to print an error without taking ownership of it.
+///
+/// ```rust
+/// use snafu::prelude::*;
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     #[snafu(display("Could not load the configuration"))]
+///     Loading { source: std::io::Error },
+/// }
+///
+/// fn load() -> Result<String, Error> {
+///     std::fs::read_to_string("/this/does/not/exist")
+///         .context(LoadingSnafu)
+///         .map_err(|e| snafu::debug_error!(e))
+/// }
+///
+/// # assert!(load().is_err());
+/// ```
+///
+/// This prints something like:
+///
+/// ```text
+/// [src/main.rs:11:22] e = Could not load the configuration
+///
+/// Caused by this error:
+///   1: No such file or directory (os error 2)
+/// ```
+///
+/// This macro is only available when the `std` [feature flag][] is
+/// enabled.
+///
+/// [feature flag]: guide::feature_flags
+#[cfg(any(feature = "std", test))]
+#[macro_export]
+macro_rules! debug_error {
+    ($error:expr $(,)?) => {
+        match $error {
+            error => {
+                $crate::__debug_error(
+                    $crate::Location::new(file!(), line!(), column!()),
+                    ::core::stringify!($error),
+                    {
+                        use $crate::AsErrorSource as _;
+                        error.as_error_source()
+                    },
+                );
+                error
+            }
+        }
+    };
+}
+
+/// Defines a static error value that is created the first time it is
+/// used.
+///
+/// Some APIs require a reference to an error that lives for the
+/// entire program, but SNAFU errors frequently contain data that
+/// cannot be created in a `const` context, such as a `String` or a
+/// [`Backtrace`][]. Each static defined by this macro is a unique type
+/// that dereferences to the error, evaluating the initializer on the
+/// first access. The error type must be `Send` and `Sync`.
+///
+/// ```rust
+/// use snafu::prelude::*;
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     #[snafu(display("The service is unavailable (code {code})"))]
+///     Unavailable { code: u16, reason: String },
+/// }
+///
+/// snafu::lazy_static_error! {
+///     /// Returned whenever the service is shutting down
+///     static SHUTTING_DOWN: Error = Error::Unavailable {
+///         code: 503,
+///         reason: String::from("shutting down"),
+///     };
+/// }
+///
+/// fn check() -> Result<(), &'static Error> {
+///     Err(&SHUTTING_DOWN)
+/// }
+///
+/// let error = check().unwrap_err();
+/// assert_eq!(error.to_string(), "The service is unavailable (code 503)");
+/// ```
+///
+/// This macro is only available when the `std` [feature flag][] is
+/// enabled.
+///
+/// [feature flag]: guide::feature_flags
+#[cfg(any(feature = "std", test))]
+#[macro_export]
+macro_rules! lazy_static_error {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident : $ty:ty = $init:expr;)+) => {
+        $(
+            $(#[$attr])*
+            #[allow(non_camel_case_types)]
+            #[derive(Copy, Clone)]
+            $vis struct $name {
+                __private: (),
+            }
+
+            $(#[$attr])*
+            $vis static $name: $name = $name { __private: () };
+
+            impl ::core::ops::Deref for $name {
+                type Target = $ty;
+
+                fn deref(&self) -> &$ty {
+                    static VALUE: $crate::__LazyStaticError<$ty> = $crate::__LazyStaticError::new();
+                    VALUE.get(|| $init)
+                }
+            }
+
+            impl ::core::fmt::Debug for $name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::Debug::fmt(&**self, f)
+                }
+            }
+
+            impl ::core::fmt::Display for $name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::Display::fmt(&**self, f)
+                }
+            }
+        )+
+    };
+}
+
 /// A basic error type that you can use as a first step to better
 /// error handling.
 ///
@@ -1541,7 +3641,10 @@ macro_rules! location {
 /// }
 /// ```
 ///
-/// See [`whatever!`][] for detailed usage instructions.
+/// See [`whatever!`][] for detailed usage instructions. If the error
+/// needs to be cloned or shared between threads, use
+/// [`WhateverShared`][] instead. If the source is always of the same
+/// type, [`TypedWhatever`][] keeps it without boxing.
 ///
 /// ## Limitations
 ///
@@ -1554,7 +3657,33 @@ macro_rules! location {
 ///
 /// When the standard library stabilizes backtrace support, this
 /// behavior may change.
-#[derive(Debug, Snafu)]
+///
+/// ## Location
+///
+/// The [`Location`][] where the error was created is always recorded,
+/// even when backtraces are disabled. When the error is created by
+/// [`whatever!`][] or the `whatever_context` methods, this is the
+/// location of that call.
+///
+/// ```rust
+/// use snafu::{prelude::*, Whatever};
+///
+/// let expected_line = line!() + 2;
+/// fn example() -> Result<(), Whatever> {
+///     whatever!("Something went wrong")
+/// }
+///
+/// let error = example().unwrap_err();
+/// assert_eq!(error.location().line, expected_line);
+/// ```
+///
+/// ## Debug output
+///
+/// The backtrace is shown as a single line in `{:?}` output. The
+/// alternate form (`{:#?}`), which `dbg!` uses, prints the complete
+/// backtrace. See [`CompactBacktrace`][] for details. The location
+/// follows the fields, such as `at src/main.rs:42:13`.
+#[derive(Snafu)]
 #[snafu(crate_root(crate))]
 #[snafu(whatever)]
 #[snafu(display("{message}"))]
@@ -1565,27 +3694,116 @@ pub struct Whatever {
     #[snafu(provide(false))]
     source: Option<Box<dyn std::error::Error>>,
     message: String,
+    #[snafu(implicit)]
+    location: Location,
     backtrace: Backtrace,
 }
 
+#[cfg(any(feature = "std", test))]
+impl fmt::Debug for Whatever {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Whatever")
+            .field("source", &self.source)
+            .field("message", &self.message)
+            .field("backtrace", &CompactBacktrace(&self.backtrace))
+            .field("location", &AtLocation(&self.location))
+            .finish()
+    }
+}
+
+/// Formats a [`Location`][] as `at {file}:{line}:{column}` in `Debug`
+/// output.
+#[cfg(any(feature = "std", test))]
+struct AtLocation<'a>(&'a Location);
+
+#[cfg(any(feature = "std", test))]
+impl fmt::Debug for AtLocation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}", self.0)
+    }
+}
+
 #[cfg(any(feature = "std", test))]
 impl Whatever {
+    /// Wraps an already-boxed error with the given message.
+    ///
+    /// This is the same as what [`whatever!`][] and the
+    /// `whatever_context` methods do, but is useful when the error
+    /// has already been boxed, such as when converting code that
+    /// returns `Box<dyn Error>`.
+    ///
+    /// ```rust
+    /// use snafu::Whatever;
+    /// use std::error::Error as _;
+    ///
+    /// fn legacy() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    ///     Err("the disk is full".into())
+    /// }
+    ///
+    /// let error = Whatever::from_boxed(legacy().unwrap_err(), "Could not save".into());
+    ///
+    /// assert_eq!(error.to_string(), "Could not save");
+    /// assert_eq!(error.source().unwrap().to_string(), "the disk is full");
+    /// ```
+    #[track_caller]
+    pub fn from_boxed(source: Box<dyn std::error::Error>, message: String) -> Self {
+        FromString::with_source(source, message)
+    }
+
     /// Gets the backtrace from the deepest `Whatever` error. If none
     /// of the underlying errors are `Whatever`, returns the backtrace
     /// from when this instance was created.
     pub fn backtrace(&self) -> Option<&Backtrace> {
         let mut best_backtrace = &self.backtrace;
 
-        let mut source = self.source();
-        while let Some(s) = source {
+        for s in ChainCompat::new(self).skip(1) {
             if let Some(this) = s.downcast_ref::<Self>() {
                 best_backtrace = &this.backtrace;
             }
-            source = s.source();
         }
 
         Some(best_backtrace)
     }
+
+    /// The location where this error was created.
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Consumes the error, returning the message, the underlying
+    /// error (if any), and the backtrace and location captured when
+    /// this instance was created.
+    ///
+    /// This allows moving the contents of a `Whatever` into a more
+    /// structured error type without losing information. See also the
+    /// [`absorb_whatever`][Snafu#absorbing-whatever-errors] attribute.
+    ///
+    /// ```rust
+    /// use snafu::{prelude::*, Whatever};
+    ///
+    /// let error: Result<(), Whatever> = (|| whatever!("Something went wrong"))();
+    /// let (message, source, _backtrace, _location) = error.unwrap_err().into_parts();
+    ///
+    /// assert_eq!(message, "Something went wrong");
+    /// assert!(source.is_none());
+    /// ```
+    pub fn into_parts(
+        self,
+    ) -> (
+        String,
+        Option<Box<dyn std::error::Error>>,
+        Backtrace,
+        Location,
+    ) {
+        let Self {
+            source,
+            message,
+            location,
+            backtrace,
+        } = self;
+
+        (message, source, backtrace, location)
+    }
 }
 
 mod tests {
@@ -0,0 +1,60 @@
+use crate::GenerateImplicitData;
+use core::fmt;
+
+/// The name of the type of a value that was missing when an error was
+/// created.
+///
+/// Mark a field of this type with `#[snafu(implicit)]` to record what
+/// was `None` when an [`Option`][] is converted into an error by
+/// [`OptionExt`][crate::OptionExt]. The name comes from
+/// [`core::any::type_name`][], so it is meant for diagnostics and its
+/// exact text may change between compiler versions. When the error is
+/// created any other way, such as with [`ensure!`][crate::ensure] or
+/// by calling `build`, there is no missing value and no name.
+///
+/// ```rust
+/// use snafu::{prelude::*, MissingTypeName};
+///
+/// #[derive(Debug, Snafu)]
+/// #[snafu(display("expected value of type {missing} was missing"))]
+/// struct MissingError {
+///     #[snafu(implicit)]
+///     missing: MissingTypeName,
+/// }
+///
+/// let error = None::<String>.context(MissingSnafu).unwrap_err();
+///
+/// assert_eq!(
+///     error.to_string(),
+///     "expected value of type alloc::string::String was missing",
+/// );
+/// assert_eq!(MissingSnafu.build().missing.name(), None);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MissingTypeName(Option<&'static str>);
+
+impl MissingTypeName {
+    /// The name of the missing value's type, if the error was created
+    /// for a missing value.
+    pub fn name(&self) -> Option<&'static str> {
+        self.0
+    }
+}
+
+impl GenerateImplicitData for MissingTypeName {
+    #[inline]
+    fn generate() -> Self {
+        MissingTypeName(None)
+    }
+
+    #[inline]
+    fn generate_for_missing(type_name: &'static str) -> Self {
+        MissingTypeName(Some(type_name))
+    }
+}
+
+impl fmt::Display for MissingTypeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.unwrap_or("<unknown>"))
+    }
+}
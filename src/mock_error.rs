@@ -0,0 +1,133 @@
+use crate::{ErrorCompat, FromString};
+use std::{error::Error, fmt};
+
+/// An error for testing code that handles errors, without having to
+/// cause a real failure.
+///
+/// The message and each error in the source chain are given when the
+/// error is created, so a test can check exactly what the code under
+/// test adds to them. The error can be cloned and compared, which
+/// makes it convenient to return from mocked dependencies. See also
+/// [`mock_result!`][crate::mock_result].
+///
+/// Requires the `testing` feature.
+///
+/// ```rust
+/// use snafu::{prelude::*, ErrorCompat, MockError};
+///
+/// #[derive(Debug, Snafu)]
+/// #[snafu(display("could not save {name}"))]
+/// struct SaveError {
+///     name: String,
+///     source: MockError,
+/// }
+///
+/// let disk = MockError::new("disk full").caused_by(MockError::new("device unplugged"));
+/// let error = Err::<(), _>(disk).context(SaveSnafu { name: "config" }).unwrap_err();
+///
+/// let chain: Vec<_> = error.iter_chain().map(ToString::to_string).collect();
+/// assert_eq!(chain, ["could not save config", "disk full", "device unplugged"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MockError {
+    message: String,
+    source: Option<Box<MockError>>,
+}
+
+impl MockError {
+    /// Creates an error that displays `message` and has no source.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Sets the source of this error, replacing any existing source.
+    ///
+    /// Longer chains are built by giving the source its own source.
+    pub fn caused_by(mut self, source: MockError) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// Copies the message of `error` and of each of its sources.
+    pub fn from_error(error: &dyn Error) -> Self {
+        Self {
+            message: error.to_string(),
+            source: error.source().map(|s| Box::new(Self::from_error(s))),
+        }
+    }
+
+    /// The message describing this error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for MockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for MockError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as _)
+    }
+}
+
+impl ErrorCompat for MockError {}
+
+impl FromString for MockError {
+    type Source = Box<dyn Error>;
+
+    fn without_source(message: String) -> Self {
+        Self::new(message)
+    }
+
+    fn with_source(source: Self::Source, message: String) -> Self {
+        Self::new(message).caused_by(Self::from_error(&*source))
+    }
+}
+
+impl From<&str> for MockError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<String> for MockError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+/// Creates a `Result` whose error type is [`MockError`][crate::MockError].
+///
+/// `mock_result!(Ok(value))` creates a successful result and
+/// `mock_result!(Err(error))` creates a failed one, where `error` is
+/// anything that can be converted into a `MockError`, such as a
+/// string. This is useful for the return value of mocked
+/// dependencies, as the error type does not need to be written out.
+///
+/// Requires the `testing` feature.
+///
+/// ```rust
+/// use snafu::{mock_result, MockError};
+///
+/// let success = mock_result!(Ok(42));
+/// assert_eq!(success, Ok(42));
+///
+/// let failure: Result<i32, _> = mock_result!(Err("test error"));
+/// assert_eq!(failure, Err(MockError::new("test error")));
+/// ```
+#[macro_export]
+macro_rules! mock_result {
+    (Ok($value:expr $(,)?) $(,)?) => {
+        ::core::result::Result::Ok::<_, $crate::MockError>($value)
+    };
+    (Err($error:expr $(,)?) $(,)?) => {
+        ::core::result::Result::Err::<_, $crate::MockError>(::core::convert::Into::into($error))
+    };
+}
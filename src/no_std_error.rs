@@ -1,6 +1,9 @@
 #![allow(missing_docs)]
 
-use core::fmt::{Debug, Display};
+use core::{
+    any::TypeId,
+    fmt::{Debug, Display},
+};
 
 pub trait Error: Debug + Display {
     #[deprecated(since = "1.42.0", note = "use the Display impl or to_string()")]
@@ -19,6 +22,36 @@ pub trait Error: Debug + Display {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         None
     }
+
+    // Sealed by the private argument, mirroring the standard library,
+    // so that implementors can't lie about their type.
+    #[doc(hidden)]
+    fn type_id(&self, _: private::Internal) -> TypeId
+    where
+        Self: 'static,
+    {
+        TypeId::of::<Self>()
+    }
+}
+
+mod private {
+    #[derive(Debug)]
+    pub struct Internal;
+}
+
+impl dyn Error + 'static {
+    pub fn is<T: Error + 'static>(&self) -> bool {
+        self.type_id(private::Internal) == TypeId::of::<T>()
+    }
+
+    pub fn downcast_ref<T: Error + 'static>(&self) -> Option<&T> {
+        if self.is::<T>() {
+            // SAFETY: `is` has confirmed that the concrete type is `T`.
+            unsafe { Some(&*(self as *const dyn Error as *const T)) }
+        } else {
+            None
+        }
+    }
 }
 
 macro_rules! impl_error {
@@ -45,3 +78,14 @@ impl_error![
     core::char::CharTryFromError,   // 1.34
     core::num::TryFromIntError      // 1.34
 ];
+
+// Mirrors the standard library so that references to errors, such as
+// a `&'static dyn Error` singleton, can be used as sources.
+impl<E> Error for &E
+where
+    E: Error + ?Sized,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Error::source(&**self)
+    }
+}
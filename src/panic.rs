@@ -0,0 +1,94 @@
+//! Capturing panics as errors.
+
+use crate::{Error, ErrorCompat, IntoError};
+use std::{any::Any, fmt, panic};
+
+/// The source error used when the closure passed to
+/// [`context_on_panic`][] panics.
+///
+/// Only the panic message is retained. Panics with a payload other
+/// than a `&str` or `String` are reported with the message
+/// `Box<dyn Any>`, matching the standard library's panic hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panicked {
+    message: String,
+}
+
+impl Panicked {
+    fn from_payload(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => (*message).into(),
+                Err(_) => "Box<dyn Any>".into(),
+            },
+        };
+
+        Self { message }
+    }
+
+    /// The message that the closure panicked with.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Panicked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "panicked: {}", self.message)
+    }
+}
+
+impl Error for Panicked {}
+
+/// Runs the closure, converting a panic into an error by way of the
+/// context selector.
+///
+/// If the closure returns normally, its value is returned in `Ok`. If
+/// it panics, the panic is caught and the context selector is called
+/// with a [`Panicked`][] source holding the panic message.
+///
+/// ```rust
+/// use snafu::{panic, prelude::*};
+///
+/// #[derive(Debug, Snafu)]
+/// #[snafu(display("the plugin {name} crashed"))]
+/// struct PluginError {
+///     name: String,
+///     source: panic::Panicked,
+/// }
+///
+/// let error = panic::context_on_panic(
+///     || -> i32 { panic!("out of cheese") },
+///     PluginSnafu { name: "cheddar" },
+/// )
+/// .unwrap_err();
+///
+/// assert_eq!(error.source.message(), "out of cheese");
+/// ```
+///
+/// # Unwind safety
+///
+/// This function is built on [`std::panic::catch_unwind`][] and has
+/// the same limitations:
+///
+/// - Panics are only caught when the program is compiled with
+///   `panic = "unwind"`. With `panic = "abort"`, the process still
+///   aborts.
+/// - The panic hook still runs before the panic is caught, so the
+///   panic message is printed to standard error by default.
+/// - The closure must be [`UnwindSafe`](std::panic::UnwindSafe).
+///   Data observed through shared references may have been left in
+///   an inconsistent state when the panic occurred. Wrapping the
+///   closure in [`AssertUnwindSafe`](std::panic::AssertUnwindSafe)
+///   asserts that any such state is not used afterwards.
+/// - Dropping the panic payload can itself panic; that panic is not
+///   caught.
+pub fn context_on_panic<F, T, C, E>(f: F, context: C) -> Result<T, E>
+where
+    F: FnOnce() -> T + panic::UnwindSafe,
+    C: IntoError<E, Source = Panicked>,
+    E: Error + ErrorCompat,
+{
+    panic::catch_unwind(f).map_err(|payload| context.into_error(Panicked::from_payload(payload)))
+}
@@ -1,4 +1,5 @@
-use crate::ChainCompat;
+use crate::error_chain::{RepeatGuard, REPEATED_MESSAGES_TRUNCATED};
+use crate::{ChainCompat, ErrorCompat};
 use core::fmt;
 
 #[cfg(all(feature = "std", feature = "rust_1_61"))]
@@ -152,6 +153,57 @@ impl<E> Report<E> {
     }
 }
 
+/// Convert an error into a [`Report`][].
+///
+/// This is the same as [`Report::from_error`][], restricted to
+/// errors, which can be convenient to pass to methods like
+/// [`Result::map_err`][].
+///
+/// ```rust
+/// use snafu::prelude::*;
+///
+/// #[derive(Debug, Snafu)]
+/// struct PlaceholderError;
+///
+/// let report = PlaceholderSnafu.fail::<()>().map_err(snafu::into_report);
+/// assert_eq!(report.unwrap_err().to_string(), "PlaceholderError\n");
+/// ```
+pub fn into_report<E>(error: E) -> Report<E>
+where
+    E: crate::Error + ErrorCompat,
+{
+    Report::from_error(error)
+}
+
+/// Convert an error into a [`Report`][] using method syntax.
+///
+/// This is implemented for every type that implements both
+/// [`Error`][crate::Error] and [`ErrorCompat`][], which includes
+/// all errors created by SNAFU.
+///
+/// ```rust
+/// use snafu::{prelude::*, IntoReport};
+///
+/// #[derive(Debug, Snafu)]
+/// struct PlaceholderError;
+///
+/// let report = PlaceholderSnafu.build().into_report();
+/// assert_eq!(report.to_string(), "PlaceholderError\n");
+/// ```
+pub trait IntoReport: Sized {
+    /// Convert this error into a [`Report`][].
+    fn into_report(self) -> Report<Self>;
+}
+
+impl<E> IntoReport for E
+where
+    E: crate::Error + ErrorCompat,
+{
+    fn into_report(self) -> Report<Self> {
+        into_report(self)
+    }
+}
+
 impl<E> From<Result<(), E>> for Report<E> {
     fn from(other: Result<(), E>) -> Self {
         Self(other)
@@ -219,6 +271,43 @@ impl<T, E> core::ops::FromResidual<Result<T, E>> for Report<E> {
     }
 }
 
+/// Formats an error and its sources the same way as [`Report`][].
+///
+/// This is the same as [`ErrorCompat::to_report_string`][], but also
+/// works with unsized errors such as boxed trait objects.
+///
+/// ```rust
+/// use snafu::prelude::*;
+///
+/// #[derive(Debug, Snafu)]
+/// #[snafu(display("could not load the settings"))]
+/// struct SettingsError {
+///     source: std::io::Error,
+/// }
+///
+/// let error: Box<dyn std::error::Error> = Box::new(
+///     std::fs::read("/no/such/file")
+///         .context(SettingsSnafu)
+///         .unwrap_err(),
+/// );
+///
+/// let report = snafu::to_report_string(&*error);
+/// assert!(report.starts_with("could not load the settings\n\nCaused by this error:\n"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn to_report_string(error: &dyn crate::Error) -> alloc::string::String {
+    use alloc::string::ToString;
+
+    ReportFormatter(error).to_string()
+}
+
+/// The implementation of [`debug_error!`](crate::debug_error).
+#[cfg(any(feature = "std", test))]
+#[doc(hidden)]
+pub fn __debug_error(location: crate::Location, expression: &str, error: &dyn crate::Error) {
+    eprint!("[{}] {} = {}", location, expression, ReportFormatter(error));
+}
+
 struct ReportFormatter<'a>(&'a dyn crate::Error);
 
 impl<'a> fmt::Display for ReportFormatter<'a> {
@@ -242,7 +331,7 @@ impl<'a> fmt::Display for ReportFormatter<'a> {
             use crate::error;
 
             if let Some(bt) = error::request_ref::<crate::Backtrace>(self.0) {
-                writeln!(f, "\nBacktrace:\n{}", bt)?;
+                writeln!(f, "\nBacktrace:\n{}", crate::DisplayBacktrace(bt))?;
             }
         }
 
@@ -252,6 +341,9 @@ impl<'a> fmt::Display for ReportFormatter<'a> {
 
 impl<'a> ReportFormatter<'a> {
     fn error_trace(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut guard = RepeatGuard::new();
+        guard.is_exceeded_by(self.0);
+
         writeln!(f, "{}", self.0)?;
 
         let sources = ChainCompat::new(self.0).skip(1);
@@ -266,6 +358,10 @@ impl<'a> ReportFormatter<'a> {
         for (i, source) in sources.enumerate() {
             // Let's use 1-based indexing for presentation
             let i = i + 1;
+            if guard.is_exceeded_by(source) {
+                writeln!(f, "{:3}: {}", i, REPEATED_MESSAGES_TRUNCATED)?;
+                break;
+            }
             writeln!(f, "{:3}: {}", i, source)?;
         }
 
@@ -379,14 +475,36 @@ fn trace_cleaning_enabled() -> bool {
 /// 2. Middle error text
 /// 3. Inner error text
 /// ```
+///
+/// A location written at the end of the text by
+/// `#[snafu(display_location)]`, such as ` (at src/io.rs:88:17)`, is
+/// not part of the comparison and is kept after the cleaned text.
+///
+/// Like [`ChainCompat`][], the chain is cut short with a
+/// [`ChainTruncated`][crate::ChainTruncated] when it is too long or
+/// an error returns itself as its source.
 #[cfg(feature = "std")]
-pub struct CleanedErrorText<'a>(Option<CleanedErrorTextStep<'a>>);
+pub struct CleanedErrorText<'a> {
+    step: Option<CleanedErrorTextStep<'a>>,
+    visited: usize,
+    limit: usize,
+}
 
 #[cfg(feature = "std")]
 impl<'a> CleanedErrorText<'a> {
     /// Constructs the iterator.
     pub fn new(error: &'a dyn crate::Error) -> Self {
-        Self(Some(CleanedErrorTextStep::new(error)))
+        Self::with_limit(error, crate::DEFAULT_CHAIN_LIMIT)
+    }
+
+    /// Constructs the iterator, visiting at most `limit` errors
+    /// before yielding [`ChainTruncated::Limit`][crate::ChainTruncated::Limit].
+    pub fn with_limit(error: &'a dyn crate::Error, limit: usize) -> Self {
+        Self {
+            step: Some(CleanedErrorTextStep::new(error)),
+            visited: 0,
+            limit,
+        }
     }
 }
 
@@ -398,22 +516,28 @@ impl<'a> Iterator for CleanedErrorText<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         use std::mem;
 
-        let mut step = self.0.take()?;
+        let mut step = self.step.take()?;
         let mut error_text = mem::take(&mut step.error_text);
 
-        match step.error.source() {
+        self.visited += 1;
+
+        match crate::error_chain::next_source(step.error, self.visited, self.limit) {
             Some(next_error) => {
                 let next_error_text = next_error.to_string();
 
-                let cleaned_text = error_text
+                // The location is kept after the cleaned text.
+                let (message, _) = split_location(&error_text);
+                let message_len = message.len();
+
+                let cleaned_text = message
                     .trim_end_matches(&next_error_text)
                     .trim_end()
                     .trim_end_matches(':');
-                let cleaned = cleaned_text.len() != error_text.len();
+                let cleaned = cleaned_text.len() != message_len;
                 let cleaned_len = cleaned_text.len();
-                error_text.truncate(cleaned_len);
+                error_text.replace_range(cleaned_len..message_len, "");
 
-                self.0 = Some(CleanedErrorTextStep {
+                self.step = Some(CleanedErrorTextStep {
                     error: next_error,
                     error_text: next_error_text,
                 });
@@ -425,6 +549,30 @@ impl<'a> Iterator for CleanedErrorText<'a> {
     }
 }
 
+/// Splits the text into the message and the ` (at file:line:column)`
+/// written after it by `#[snafu(display_location)]`, if present.
+#[cfg(feature = "std")]
+fn split_location(text: &str) -> (&str, &str) {
+    const START: &str = " (at ";
+
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+    let start = match text.strip_suffix(')').and_then(|t| t.rfind(START)) {
+        Some(start) => start,
+        None => return (text, ""),
+    };
+
+    let mut parts = text[start + START.len()..text.len() - 1].rsplitn(3, ':');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(column), Some(line), Some(file))
+            if is_number(column) && is_number(line) && !file.is_empty() =>
+        {
+            text.split_at(start)
+        }
+        _ => (text, ""),
+    }
+}
+
 #[cfg(feature = "std")]
 struct CleanedErrorTextStep<'a> {
     error: &'a dyn crate::Error,
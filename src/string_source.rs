@@ -0,0 +1,85 @@
+use crate::Error;
+use alloc::{borrow::Cow, string::String};
+use core::fmt;
+
+/// An error that consists only of a message.
+///
+/// Some layers, such as C libraries called through FFI, report
+/// failures as plain strings. Wrapping the string in a `StringSource`
+/// allows it to be the source of another error, keeping it as its own
+/// link in the chain of errors instead of adding it to the message.
+///
+/// Both owned and borrowed strings can be used. Combine this with
+/// `source(from(...))` to accept the string directly in the context
+/// selector:
+///
+/// ```rust
+/// use snafu::{prelude::*, StringSource};
+///
+/// #[derive(Debug, Snafu)]
+/// #[snafu(display("could not initialize the device"))]
+/// struct InitError {
+///     #[snafu(source(from(String, StringSource::new)))]
+///     source: StringSource,
+/// }
+///
+/// fn ffi_init() -> Result<(), String> {
+///     Err("device busy".to_string())
+/// }
+///
+/// let error = ffi_init().context(InitSnafu).unwrap_err();
+///
+/// assert_eq!(error.to_string(), "could not initialize the device");
+/// assert_eq!(error.source.as_str(), "device busy");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StringSource(Cow<'static, str>);
+
+impl StringSource {
+    /// Creates an error with the given message.
+    pub fn new(message: impl Into<Cow<'static, str>>) -> Self {
+        Self(message.into())
+    }
+
+    /// The message of this error.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the message, without copying it.
+    pub fn into_inner(self) -> Cow<'static, str> {
+        self.0
+    }
+}
+
+impl fmt::Display for StringSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for StringSource {}
+
+impl AsRef<str> for StringSource {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for StringSource {
+    fn from(other: String) -> Self {
+        Self::new(other)
+    }
+}
+
+impl From<&'static str> for StringSource {
+    fn from(other: &'static str) -> Self {
+        Self::new(other)
+    }
+}
+
+impl From<Cow<'static, str>> for StringSource {
+    fn from(other: Cow<'static, str>) -> Self {
+        Self(other)
+    }
+}
@@ -0,0 +1,68 @@
+use crate::GenerateImplicitData;
+use std::{fmt, thread};
+
+/// The thread that an error was created on.
+///
+/// This is useful when debugging concurrent code, as it records which
+/// thread was running when the error occurred. Mark a field of this
+/// type with `#[snafu(implicit)]` to have it filled in automatically.
+///
+/// ```rust
+/// use snafu::{prelude::*, ThreadInfo};
+///
+/// #[derive(Debug, Snafu)]
+/// struct WorkerError {
+///     #[snafu(implicit)]
+///     thread: ThreadInfo,
+/// }
+///
+/// let error = std::thread::Builder::new()
+///     .name("worker-1".into())
+///     .spawn(|| WorkerSnafu.build())
+///     .unwrap()
+///     .join()
+///     .unwrap();
+///
+/// assert_eq!(error.thread.name(), Some("worker-1"));
+/// assert!(error.thread.to_string().starts_with("thread: worker-1 (ThreadId("));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadInfo {
+    name: Option<String>,
+    id: thread::ThreadId,
+}
+
+impl ThreadInfo {
+    /// Captures the name and ID of the current thread.
+    pub fn current() -> Self {
+        let thread = thread::current();
+        Self {
+            name: thread.name().map(Into::into),
+            id: thread.id(),
+        }
+    }
+
+    /// The name of the thread, if it had one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The ID of the thread.
+    pub fn id(&self) -> thread::ThreadId {
+        self.id
+    }
+}
+
+impl GenerateImplicitData for ThreadInfo {
+    #[inline]
+    fn generate() -> Self {
+        Self::current()
+    }
+}
+
+impl fmt::Display for ThreadInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.name().unwrap_or("<unnamed>");
+        write!(f, "thread: {} ({:?})", name, self.id)
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{Backtrace, CompactBacktrace, ErrorCompat, FromString, GenerateImplicitData, Location};
+use std::{error::Error, fmt};
+
+/// A basic error type whose optional source is always of type `E`.
+///
+/// This works like [`Whatever`][crate::Whatever], but the source is
+/// stored as `E` instead of a boxed trait object. The concrete source
+/// can be borrowed with [`typed_source`][Self::typed_source] or
+/// recovered with [`into_parts`][Self::into_parts], without boxing or
+/// downcasting. [`Error::source`][] returns the same value.
+///
+/// ```rust
+/// use snafu::{prelude::*, TypedWhatever};
+/// use std::io;
+///
+/// fn load() -> Result<String, TypedWhatever<io::Error>> {
+///     let text = std::fs::read_to_string("/no/such/file")
+///         .whatever_context("Could not load the configuration")?;
+///     Ok(text)
+/// }
+///
+/// let error = load().unwrap_err();
+/// let source: &io::Error = error.typed_source().unwrap();
+///
+/// assert_eq!(error.to_string(), "Could not load the configuration");
+/// assert_eq!(source.kind(), io::ErrorKind::NotFound);
+/// ```
+///
+/// Only errors that convert into `E` can be used as the source, so
+/// wrapping an unrelated error is a compile error rather than a
+/// source that needs to be downcast at runtime.
+///
+/// ## Debug output
+///
+/// The backtrace is shown as a single line in `Debug` output. Use the
+/// alternate form (`{:#?}`) to include the complete backtrace. See
+/// [`CompactBacktrace`][] for details. The location follows the
+/// fields, such as `at src/main.rs:42:13`.
+pub struct TypedWhatever<E> {
+    source: Option<E>,
+    message: String,
+    location: Location,
+    backtrace: Backtrace,
+}
+
+impl<E> TypedWhatever<E> {
+    /// The message describing this error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The underlying error, if any.
+    pub fn typed_source(&self) -> Option<&E> {
+        self.source.as_ref()
+    }
+
+    /// The location where this error was created.
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Consumes the error, returning the message, the underlying
+    /// error (if any), and the backtrace and location captured when
+    /// this instance was created.
+    pub fn into_parts(self) -> (String, Option<E>, Backtrace, Location) {
+        let Self {
+            source,
+            message,
+            location,
+            backtrace,
+        } = self;
+
+        (message, source, backtrace, location)
+    }
+}
+
+impl<E> fmt::Debug for TypedWhatever<E>
+where
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedWhatever")
+            .field("source", &self.source)
+            .field("message", &self.message)
+            .field("backtrace", &CompactBacktrace(&self.backtrace))
+            .finish()?;
+        write!(f, " at {}", self.location)
+    }
+}
+
+impl<E> fmt::Display for TypedWhatever<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl<E> Error for TypedWhatever<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|e| e as _)
+    }
+}
+
+impl<E> ErrorCompat for TypedWhatever<E> {
+    fn backtrace(&self) -> Option<&Backtrace> {
+        Some(&self.backtrace)
+    }
+}
+
+impl<E> FromString for TypedWhatever<E>
+where
+    E: Error + 'static,
+{
+    type Source = E;
+
+    #[track_caller]
+    fn without_source(message: String) -> Self {
+        Self {
+            source: None,
+            message,
+            location: GenerateImplicitData::generate(),
+            backtrace: GenerateImplicitData::generate(),
+        }
+    }
+
+    #[track_caller]
+    fn with_source(source: Self::Source, message: String) -> Self {
+        Self {
+            location: GenerateImplicitData::generate_with_source(&source),
+            backtrace: GenerateImplicitData::generate_with_source(&source),
+            source: Some(source),
+            message,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+/// Recovers from the error variants matched by a [`variant!`][crate::variant]
+/// invocation.
+///
+/// This is created by [`variant!`][crate::variant] and consumed by
+/// [`ResultExt::recover_variant`][crate::ResultExt::recover_variant].
+/// Because it can only be created from patterns, errors that do not
+/// match are always handed back unchanged.
+#[must_use = "a VariantMatcher does nothing unless passed to `recover_variant`"]
+pub struct VariantMatcher<F>(F);
+
+impl<F> VariantMatcher<F> {
+    #[doc(hidden)]
+    pub fn __new<T, E>(matcher: F) -> Self
+    where
+        F: FnOnce(E) -> Result<T, E>,
+    {
+        VariantMatcher(matcher)
+    }
+
+    pub(crate) fn recover<T, E>(self, error: E) -> Result<T, E>
+    where
+        F: FnOnce(E) -> Result<T, E>,
+    {
+        (self.0)(error)
+    }
+}
+
+/// Match specific variants of an error enum for
+/// [`ResultExt::recover_variant`][crate::ResultExt::recover_variant].
+///
+/// Each arm is a pattern, optionally followed by an `if` guard, and
+/// the successful value to produce when the error matches. The
+/// pattern takes the error by value, so data can be moved out of the
+/// matched variant. Errors that match no arm are returned unchanged;
+/// there is no need to write a catch-all arm.
+///
+/// ```rust
+/// use snafu::prelude::*;
+///
+/// #[derive(Debug, Snafu)]
+/// enum Error {
+///     Stale { cached: String, age: u64 },
+///     Unavailable,
+/// }
+///
+/// let stale = StaleSnafu { cached: "yesterday", age: 1u64 }.fail::<String>();
+/// let value = stale
+///     .recover_variant(snafu::variant!(Error::Stale { cached, age } if age < 7 => cached))
+///     .unwrap();
+/// assert_eq!(value, "yesterday");
+///
+/// let unavailable = UnavailableSnafu.fail::<String>();
+/// let error = unavailable
+///     .recover_variant(snafu::variant!(Error::Stale { cached, .. } => cached))
+///     .unwrap_err();
+/// assert!(matches!(error, Error::Unavailable));
+/// ```
+#[macro_export]
+macro_rules! variant {
+    ($($pattern:pat $(if $guard:expr)? => $value:expr),+ $(,)?) => {
+        $crate::VariantMatcher::__new(|error| match error {
+            $($pattern $(if $guard)? => ::core::result::Result::Ok($value),)+
+            error => ::core::result::Result::Err(error),
+        })
+    };
+}
@@ -0,0 +1,160 @@
+use crate::{Backtrace, CompactBacktrace, ErrorCompat, FromString, GenerateImplicitData, Whatever};
+use std::{error::Error, fmt, sync::Arc};
+
+/// A basic error type that can be cheaply cloned and shared between
+/// threads.
+///
+/// This works like [`Whatever`][], but the message, source, and
+/// backtrace are reference counted. This is useful when the same
+/// error needs to be given to multiple callers, such as when caching
+/// the result of a fallible operation.
+///
+/// The source must be `Send` and `Sync`. The backtrace is captured
+/// under the same conditions as `Option<Backtrace>`.
+///
+/// ```rust
+/// use snafu::{prelude::*, WhateverShared};
+///
+/// fn load(id: u32) -> Result<String, WhateverShared> {
+///     let text = std::fs::read_to_string(format!("/no/such/{id}"))
+///         .whatever_context(format!("Could not load item {id}"))?;
+///     Ok(text)
+/// }
+///
+/// let error = load(42).unwrap_err();
+/// let shared = error.clone();
+///
+/// assert_eq!(shared.to_string(), "Could not load item 42");
+/// ```
+///
+/// ## Debug output
+///
+/// The backtrace is shown as a single line in `Debug` output. Use the
+/// alternate form (`{:#?}`) to include the complete backtrace. See
+/// [`CompactBacktrace`][] for details.
+#[derive(Clone)]
+pub struct WhateverShared {
+    message: Arc<str>,
+    source: Option<Arc<dyn Error + Send + Sync>>,
+    backtrace: Option<Arc<Backtrace>>,
+}
+
+impl WhateverShared {
+    /// Converts a [`Whatever`][] into a shareable error.
+    ///
+    /// The source of a `Whatever` is not required to be `Send` or
+    /// `Sync`, so the source chain is converted into errors that only
+    /// preserve the `Display` output of each error. The message and
+    /// backtrace are kept.
+    ///
+    /// This is not a `From` implementation because that would make
+    /// the error type ambiguous when using `?` after
+    /// [`whatever_context`][crate::ResultExt::whatever_context].
+    ///
+    /// ```rust
+    /// use snafu::{prelude::*, Whatever, WhateverShared};
+    ///
+    /// fn original() -> Result<Vec<u8>, Whatever> {
+    ///     std::fs::read("/no/such/file").whatever_context("Could not read the file")
+    /// }
+    ///
+    /// let error = WhateverShared::from_whatever(original().unwrap_err());
+    ///
+    /// assert_eq!(error.to_string(), "Could not read the file");
+    /// assert!(std::error::Error::source(&error).is_some());
+    /// ```
+    pub fn from_whatever(error: Whatever) -> Self {
+        let (message, source, backtrace, _location) = error.into_parts();
+
+        Self {
+            message: message.into(),
+            source: source.map(|s| Arc::new(DisplayedError::new(&*s)) as _),
+            backtrace: Some(Arc::new(backtrace)),
+        }
+    }
+
+    /// The message describing this error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Debug for WhateverShared {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WhateverShared")
+            .field("message", &self.message)
+            .field("source", &self.source)
+            .field("backtrace", &CompactBacktrace(&self.backtrace))
+            .finish()
+    }
+}
+
+impl fmt::Display for WhateverShared {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for WhateverShared {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as _)
+    }
+}
+
+impl ErrorCompat for WhateverShared {
+    fn backtrace(&self) -> Option<&Backtrace> {
+        self.backtrace.as_deref()
+    }
+}
+
+impl FromString for WhateverShared {
+    type Source = Box<dyn Error + Send + Sync>;
+
+    #[track_caller]
+    fn without_source(message: String) -> Self {
+        Self {
+            message: message.into(),
+            source: None,
+            backtrace: GenerateImplicitData::generate(),
+        }
+    }
+
+    #[track_caller]
+    fn with_source(source: Self::Source, message: String) -> Self {
+        Self {
+            message: message.into(),
+            backtrace: GenerateImplicitData::generate_with_source(&*source),
+            source: Some(source.into()),
+        }
+    }
+}
+
+/// An error that was converted from an error that could not be
+/// shared between threads, keeping only its message and the
+/// messages of its sources.
+#[derive(Debug)]
+struct DisplayedError {
+    message: String,
+    source: Option<Box<DisplayedError>>,
+}
+
+impl DisplayedError {
+    fn new(error: &dyn Error) -> Self {
+        Self {
+            message: error.to_string(),
+            source: error.source().map(|s| Box::new(Self::new(s))),
+        }
+    }
+}
+
+impl fmt::Display for DisplayedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for DisplayedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as _)
+    }
+}
@@ -0,0 +1,129 @@
+use snafu::{prelude::*, Backtrace, ErrorCompat, Location, Whatever};
+
+#[derive(Debug, Snafu)]
+struct UnderlyingError;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("User {username} is not allowed"))]
+    NotAllowed { username: String },
+
+    #[snafu(whatever, absorb_whatever, display("{message}"))]
+    Prototype {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        backtrace: Backtrace,
+    },
+}
+
+fn prototype(fail_underlying: bool) -> Result<(), Whatever> {
+    if fail_underlying {
+        whatever!(UnderlyingSnafu.fail::<()>(), "Prototype failed");
+    }
+    whatever!("Prototype finished early")
+}
+
+fn structured(fail_underlying: bool) -> Result<(), Error> {
+    prototype(fail_underlying)?;
+    NotAllowedSnafu { username: "admin" }.fail()
+}
+
+fn chain_text(e: &dyn std::error::Error) -> Vec<String> {
+    snafu::ChainCompat::new(e)
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn question_mark_converts_whatever() {
+    let error = structured(false).unwrap_err();
+
+    assert!(matches!(error, Error::Prototype { .. }));
+    assert_eq!(error.to_string(), "Prototype finished early");
+}
+
+#[test]
+fn chain_is_preserved() {
+    let original = prototype(true).unwrap_err();
+    let original_chain = chain_text(&original);
+
+    let error = Error::from(original);
+
+    assert_eq!(chain_text(&error), original_chain);
+    assert_eq!(chain_text(&error), ["Prototype failed", "UnderlyingError"]);
+}
+
+#[test]
+fn backtrace_is_preserved() {
+    let original = prototype(true).unwrap_err();
+    let original_backtrace = ErrorCompat::backtrace(&original)
+        .map(ToString::to_string)
+        .unwrap();
+
+    let error = Error::from(original);
+    let backtrace = ErrorCompat::backtrace(&error)
+        .map(ToString::to_string)
+        .unwrap();
+
+    assert_eq!(backtrace, original_backtrace);
+}
+
+#[test]
+fn into_parts_returns_all_parts() {
+    let original = prototype(true).unwrap_err();
+    let expected_location = original.location();
+    let (message, source, _backtrace, location) = original.into_parts();
+
+    assert_eq!(message, "Prototype failed");
+    assert_eq!(source.unwrap().to_string(), "UnderlyingError");
+    assert_eq!(location.to_string(), expected_location.to_string());
+}
+
+#[test]
+fn location_is_preserved() {
+    #[derive(Debug, Snafu)]
+    #[snafu(whatever, absorb_whatever, display("{message}"))]
+    struct LocatedError {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        backtrace: Backtrace,
+        #[snafu(implicit)]
+        location: Location,
+    }
+
+    let original = prototype(false).unwrap_err();
+    let expected_location = original.location();
+
+    let error = LocatedError::from(original);
+
+    assert_eq!(error.location.to_string(), expected_location.to_string());
+}
+
+mod struct_style {
+    use snafu::{prelude::*, Backtrace, Whatever};
+
+    #[derive(Debug, Snafu)]
+    #[snafu(whatever, absorb_whatever, display("{message}"))]
+    struct Error {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        backtrace: Backtrace,
+    }
+
+    #[test]
+    fn absorbs_into_a_struct() {
+        fn prototype() -> Result<(), Whatever> {
+            whatever!("Prototype failed")
+        }
+
+        fn structured() -> Result<(), Error> {
+            prototype()?;
+            Ok(())
+        }
+
+        assert_eq!(structured().unwrap_err().to_string(), "Prototype failed");
+    }
+}
@@ -0,0 +1,86 @@
+use snafu::{assert_same_chain, compare_chains, prelude::*, IntoError};
+use std::io;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("could not load {name}"))]
+    Load { name: String, source: io::Error },
+
+    #[snafu(display("could not save {name}"))]
+    Save { name: String, source: Box<Error> },
+
+    #[snafu(display("the disk is full"))]
+    DiskFull,
+}
+
+fn load(name: &str, message: &str) -> Error {
+    LoadSnafu { name }.into_error(io::Error::new(io::ErrorKind::NotFound, message))
+}
+
+#[test]
+fn equal_chains_are_the_same() {
+    assert_eq!(
+        compare_chains(&load("a", "gone"), &load("a", "gone")),
+        Ok(())
+    );
+    assert_same_chain!(load("a", "gone"), load("a", "gone"));
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(load("a", "gone"));
+    assert_same_chain!(load("a", "gone"), boxed, "comparing with a {}", "box");
+}
+
+#[test]
+fn diverging_tails_report_the_first_differing_level() {
+    let left = SaveSnafu { name: "b" }.into_error(Box::new(load("a", "gone")));
+    let right = SaveSnafu { name: "b" }.into_error(Box::new(load("a", "moved")));
+
+    let difference = compare_chains(&left, &right).unwrap_err();
+
+    assert_eq!(difference.level(), 2);
+    assert_eq!(difference.left(), Some("gone"));
+    assert_eq!(difference.right(), Some("moved"));
+    assert_eq!(
+        difference.to_string(),
+        "error chains differ at level 2\n  left: \"gone\"\n right: \"moved\"",
+    );
+}
+
+#[test]
+fn chains_of_different_lengths_differ_where_the_shorter_one_ends() {
+    let shorter = SaveSnafu { name: "b" }.into_error(Box::new(DiskFullSnafu.build()));
+    let longer = SaveSnafu { name: "c" }.into_error(Box::new(DiskFullSnafu.build()));
+    let longer = SaveSnafu { name: "b" }.into_error(Box::new(longer));
+
+    let difference = compare_chains(&longer, &shorter).unwrap_err();
+
+    assert_eq!(difference.level(), 1);
+    assert_eq!(difference.left(), Some("could not save c"));
+    assert_eq!(difference.right(), Some("the disk is full"));
+
+    let longer = SaveSnafu { name: "b" }.into_error(Box::new(load("a", "gone")));
+    let shorter = SaveSnafu { name: "b" }.into_error(Box::new(load("a", "gone")));
+    let shorter = shorter.to_string();
+    let shorter = io::Error::new(io::ErrorKind::NotFound, shorter);
+
+    let difference = compare_chains(&longer, &shorter).unwrap_err();
+
+    assert_eq!(difference.level(), 1);
+    assert_eq!(difference.left(), Some("could not load a"));
+    assert_eq!(difference.right(), None);
+    assert_eq!(
+        difference.to_string(),
+        "error chains differ at level 1\n  left: \"could not load a\"\n right: <end of chain>",
+    );
+}
+
+#[test]
+#[should_panic(expected = "error chains differ at level 0\n  left: \"could not load a\"")]
+fn completely_different_errors_panic() {
+    assert_same_chain!(load("a", "gone"), DiskFullSnafu.build());
+}
+
+#[test]
+#[should_panic(expected = "while saving: error chains differ at level 1")]
+fn the_custom_message_is_included() {
+    assert_same_chain!(load("a", "gone"), load("a", "moved"), "while {}", "saving");
+}
@@ -0,0 +1,82 @@
+use snafu::{prelude::*, IntoError};
+use std::{borrow::Cow, marker::PhantomData, sync::Arc};
+
+#[derive(Debug, Snafu)]
+#[snafu(assert_send_sync)]
+enum EnumError {
+    #[snafu(display("Could not open {path}"))]
+    Open {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("The cache is shared"))]
+    Shared { cache: Arc<Vec<u8>> },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(assert_send_sync)]
+struct StructError {
+    code: i32,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(assert_send_sync)]
+struct OpaqueError(EnumError);
+
+// The type parameters are assumed to be `Send` and `Sync`, so the
+// error can still be used with parameters that are not.
+#[derive(Debug, Snafu)]
+#[snafu(assert_send_sync)]
+enum GenericError<'a, T, const N: usize>
+where
+    T: std::fmt::Debug,
+{
+    Wrapped { value: T, name: Cow<'a, str> },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(assert_send_sync)]
+struct PhantomError<T: 'static> {
+    marker: PhantomData<T>,
+}
+
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn errors_are_send_and_sync() {
+    let e = SharedSnafu {
+        cache: Arc::new(vec![1u8]),
+    }
+    .build();
+    assert_send_sync(&e);
+
+    let e = StructSnafu { code: 1 }.build();
+    assert_send_sync(&e);
+
+    let e = OpaqueError::from(e_enum());
+    assert_eq!(e.to_string(), "Could not open /etc/hosts");
+    assert_send_sync(&e);
+}
+
+#[test]
+fn generic_errors_are_not_restricted() {
+    let e: GenericError<'_, std::rc::Rc<i32>, 3> = WrappedSnafu {
+        value: std::rc::Rc::new(1),
+        name: "alpha",
+    }
+    .build();
+    assert_eq!(e.to_string(), "Wrapped");
+
+    let e: GenericError<'_, i32, 3> = WrappedSnafu {
+        value: 1,
+        name: "beta",
+    }
+    .build();
+    assert_send_sync(&e);
+}
+
+fn e_enum() -> EnumError {
+    let source = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+    OpenSnafu { path: "/etc/hosts" }.into_error(source)
+}
@@ -0,0 +1,125 @@
+mod enums {
+    use snafu::{prelude::*, Backtrace, ErrorCompat};
+
+    #[derive(Debug, Snafu)]
+    #[snafu(auto_from)]
+    enum Error {
+        Parse {
+            source: std::num::ParseIntError,
+        },
+
+        Io {
+            source: std::io::Error,
+            backtrace: Backtrace,
+        },
+
+        // Has a context field, so it is skipped by the enum-wide setting.
+        Format {
+            value: i32,
+            source: std::fmt::Error,
+        },
+    }
+
+    fn parse(text: &str) -> Result<i32, Error> {
+        Ok(text.parse::<i32>()?)
+    }
+
+    fn read() -> Result<(), Error> {
+        Err(std::io::Error::new(std::io::ErrorKind::Other, "gone"))?;
+        Ok(())
+    }
+
+    #[test]
+    fn bare_question_mark_converts_the_source() {
+        let error = parse("a").unwrap_err();
+
+        assert!(matches!(error, Error::Parse { .. }));
+    }
+
+    #[test]
+    fn the_context_selector_is_still_available() {
+        let error = "a".parse::<i32>().context(ParseSnafu).unwrap_err();
+
+        assert!(matches!(error, Error::Parse { .. }));
+    }
+
+    #[test]
+    fn implicit_fields_are_generated() {
+        let error = read().unwrap_err();
+
+        assert!(matches!(error, Error::Io { .. }));
+        assert!(ErrorCompat::backtrace(&error).is_some());
+    }
+
+    #[test]
+    fn variants_with_context_fields_are_unaffected() {
+        let error = Err::<(), _>(std::fmt::Error)
+            .context(FormatSnafu { value: 3 })
+            .unwrap_err();
+
+        assert!(matches!(error, Error::Format { value: 3, .. }));
+    }
+
+    mod single_variant {
+        use snafu::prelude::*;
+
+        #[derive(Debug, Snafu)]
+        enum Error {
+            #[snafu(auto_from)]
+            Parse {
+                #[snafu(source(from(std::num::ParseIntError, Box::new)))]
+                source: Box<std::num::ParseIntError>,
+            },
+
+            Other {
+                source: std::num::ParseIntError,
+            },
+        }
+
+        fn parse(text: &str) -> Result<i32, Error> {
+            Ok(text.parse::<i32>()?)
+        }
+
+        #[test]
+        fn variants_can_opt_in_individually() {
+            let error = parse("a").unwrap_err();
+
+            assert!(matches!(error, Error::Parse { .. }));
+        }
+
+        #[test]
+        fn other_variants_use_their_context_selectors() {
+            let error = "a".parse::<i32>().context(OtherSnafu).unwrap_err();
+
+            assert!(matches!(error, Error::Other { .. }));
+        }
+    }
+}
+
+mod structs {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(auto_from)]
+    struct Error {
+        source: std::num::ParseIntError,
+    }
+
+    fn parse(text: &str) -> Result<i32, Error> {
+        Ok(text.parse::<i32>()?)
+    }
+
+    #[test]
+    fn bare_question_mark_converts_the_source() {
+        let error = parse("a").unwrap_err();
+
+        assert_eq!(error.to_string(), "Error");
+    }
+
+    #[test]
+    fn the_context_selector_is_still_available() {
+        let error = "a".parse::<i32>().context(Snafu).unwrap_err();
+
+        assert_eq!(error.to_string(), "Error");
+    }
+}
@@ -45,7 +45,7 @@ fn is_compatible_with_std_backtrace_type() {
 
     let error = example().unwrap_err();
     let backtrace = ErrorCompat::backtrace(&error).unwrap();
-    expects_std_type(&backtrace);
+    expects_std_type(backtrace);
 }
 
 #[test]
@@ -127,7 +127,7 @@ mod whatever_nested {
     use snafu::{prelude::*, Whatever};
 
     fn inner_outer() -> Result<(), Whatever> {
-        not_a_whatever().with_whatever_context(|_| format!("Outer failure"))
+        not_a_whatever().with_whatever_context(|_| "Outer failure".to_string())
     }
 
     fn not_a_whatever() -> Result<(), Box<dyn std::error::Error>> {
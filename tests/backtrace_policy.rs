@@ -0,0 +1,39 @@
+use snafu::{
+    env::{self, BacktracePolicy},
+    prelude::*,
+    Backtrace, ErrorCompat,
+};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    BacktraceSometimes { backtrace: Option<Backtrace> },
+}
+
+// The policy is global, so every step is sequenced in a single test.
+#[test]
+fn policy_overrides_environment_variable() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "0");
+    std::env::set_var("RUST_BACKTRACE", "0");
+
+    assert_eq!(env::backtrace_policy(), BacktracePolicy::UseEnvVar);
+    let sometimes = BacktraceSometimesSnafu.build();
+    assert!(ErrorCompat::backtrace(&sometimes).is_none());
+
+    env::set_backtrace_policy(BacktracePolicy::AlwaysCapture);
+    assert_eq!(env::backtrace_policy(), BacktracePolicy::AlwaysCapture);
+    let sometimes = BacktraceSometimesSnafu.build();
+    assert!(ErrorCompat::backtrace(&sometimes).is_some());
+
+    let from_other_thread = std::thread::spawn(|| BacktraceSometimesSnafu.build())
+        .join()
+        .unwrap();
+    assert!(ErrorCompat::backtrace(&from_other_thread).is_some());
+
+    env::set_backtrace_policy(BacktracePolicy::NeverCapture);
+    let sometimes = BacktraceSometimesSnafu.build();
+    assert!(ErrorCompat::backtrace(&sometimes).is_none());
+
+    env::set_backtrace_policy(BacktracePolicy::UseEnvVar);
+    let sometimes = BacktraceSometimesSnafu.build();
+    assert!(ErrorCompat::backtrace(&sometimes).is_none());
+}
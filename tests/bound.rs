@@ -0,0 +1,79 @@
+use snafu::{prelude::*, Backtrace, ErrorCompat, IntoError};
+
+mod enums {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(bound(S: ErrorCompat))]
+    enum Error<S>
+    where
+        S: std::error::Error + 'static,
+    {
+        Wrap {
+            #[snafu(backtrace)]
+            source: S,
+        },
+    }
+
+    #[derive(Debug, Snafu)]
+    struct InnerError {
+        backtrace: Backtrace,
+    }
+
+    #[test]
+    fn extra_bound_allows_delegating_to_a_generic_source() {
+        let error = WrapSnafu.into_error(InnerSnafu.build());
+
+        assert!(ErrorCompat::backtrace(&error).is_some());
+    }
+}
+
+mod named_structs {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("wrapped {name}"), bound(where S: ErrorCompat))]
+    struct Error<S>
+    where
+        S: std::error::Error + 'static,
+    {
+        name: String,
+        #[snafu(backtrace)]
+        source: S,
+    }
+
+    #[derive(Debug, Snafu)]
+    struct InnerError {
+        backtrace: Backtrace,
+    }
+
+    #[test]
+    fn bounds_can_use_where_syntax() {
+        let error: Error<_> = Snafu { name: "alpha" }.into_error(InnerSnafu.build());
+
+        assert_eq!(error.to_string(), "wrapped alpha");
+        assert!(ErrorCompat::backtrace(&error).is_some());
+    }
+}
+
+mod tuple_structs {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(bound(E: ErrorCompat), bound(E: Send))]
+    struct Error<E>(E)
+    where
+        E: std::error::Error + 'static;
+
+    #[derive(Debug, Snafu)]
+    struct InnerError {
+        backtrace: Backtrace,
+    }
+
+    #[test]
+    fn bounds_can_be_repeated() {
+        let error = Error::from(InnerSnafu.build());
+
+        assert!(ErrorCompat::backtrace(&error).is_some());
+    }
+}
@@ -1,5 +1,7 @@
 // This test asserts that a boxed error trait object can be used as a source.
 
+#![allow(clippy::enum_variant_names)]
+
 use snafu::prelude::*;
 
 mod trait_object {
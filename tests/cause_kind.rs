@@ -0,0 +1,86 @@
+use snafu::{prelude::*, CauseKind, HasCauseKind, IntoError};
+
+mod enums {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(cause_kind = "transient")]
+        Timeout {
+            seconds: u64,
+        },
+
+        #[snafu(cause_kind = "permanent")]
+        NotFound {
+            source: std::io::Error,
+        },
+
+        Unexpected,
+    }
+
+    #[test]
+    fn transient_variants_are_transient() {
+        let error = TimeoutSnafu { seconds: 5u64 }.build();
+
+        assert_eq!(error.cause_kind(), CauseKind::Transient);
+    }
+
+    #[test]
+    fn permanent_variants_are_permanent() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        let error = NotFoundSnafu.into_error(io_error);
+
+        assert_eq!(error.cause_kind(), CauseKind::Permanent);
+    }
+
+    #[test]
+    fn variants_without_the_attribute_are_unknown() {
+        let error = UnexpectedSnafu.build();
+
+        assert_eq!(error.cause_kind(), CauseKind::Unknown);
+    }
+
+    #[test]
+    fn works_through_boxes_and_references() {
+        let error: Box<dyn HasCauseKind> = Box::new(TimeoutSnafu { seconds: 1u64 }.build());
+
+        fn kind_of(error: impl HasCauseKind) -> CauseKind {
+            error.cause_kind()
+        }
+
+        assert_eq!(kind_of(&error), CauseKind::Transient);
+    }
+}
+
+mod structs {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(cause_kind = "transient")]
+    struct Error;
+
+    #[test]
+    fn struct_reports_its_cause_kind() {
+        assert_eq!(Snafu.build().cause_kind(), CauseKind::Transient);
+    }
+}
+
+mod generics {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error<T>
+    where
+        T: std::fmt::Debug,
+    {
+        #[snafu(cause_kind = "permanent")]
+        Invalid { value: T },
+    }
+
+    #[test]
+    fn generic_errors_report_their_cause_kind() {
+        let error: Error<i32> = InvalidSnafu { value: 42 }.build();
+
+        assert_eq!(error.cause_kind(), CauseKind::Permanent);
+    }
+}
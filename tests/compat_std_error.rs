@@ -0,0 +1,80 @@
+// Run with `--no-default-features` to check the implementation that
+// is generated when `snafu::Error` is SNAFU's own trait.
+
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(compat_std_error)]
+enum EnumError {
+    #[snafu(display("Expected {expected}, got {actual}"))]
+    InvalidChecksum { expected: u32, actual: u32 },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(compat_std_error)]
+struct StructError {
+    code: i32,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(compat_std_error)]
+struct OpaqueError(EnumError);
+
+#[derive(Debug, Snafu)]
+#[snafu(compat_std_error)]
+enum GenericError<T>
+where
+    T: core::fmt::Debug,
+{
+    Wrapped { value: T },
+}
+
+fn assert_core_error<E: core::error::Error>(e: &E) -> String {
+    e.to_string()
+}
+
+fn assert_snafu_error<E: snafu::Error>(_: &E) {}
+
+#[test]
+fn implements_core_error() {
+    let e = InvalidChecksumSnafu {
+        expected: 1u32,
+        actual: 2u32,
+    }
+    .build();
+    assert_snafu_error(&e);
+    assert_eq!(assert_core_error(&e), "Expected 1, got 2");
+
+    let e = StructSnafu { code: 7 }.build();
+    assert_snafu_error(&e);
+    assert_eq!(assert_core_error(&e), "StructError");
+
+    let e = OpaqueError::from(e_enum());
+    assert_snafu_error(&e);
+    assert_eq!(assert_core_error(&e), "Expected 3, got 4");
+
+    let e: GenericError<i32> = WrappedSnafu { value: 42 }.build();
+    assert_snafu_error(&e);
+    assert_eq!(assert_core_error(&e), "Wrapped");
+}
+
+#[test]
+fn can_be_boxed_as_a_standard_error() {
+    let e: Box<dyn core::error::Error> = Box::new(StructSnafu { code: 1 }.build());
+    assert_eq!(e.to_string(), "StructError");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn is_the_same_implementation_as_snafu_error_with_std() {
+    let e: Box<dyn snafu::Error> = Box::new(e_enum());
+    assert!(e.is::<EnumError>());
+}
+
+fn e_enum() -> EnumError {
+    InvalidChecksumSnafu {
+        expected: 3u32,
+        actual: 4u32,
+    }
+    .build()
+}
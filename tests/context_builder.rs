@@ -0,0 +1,187 @@
+use snafu::{prelude::*, ContextBuilder};
+
+#[derive(Debug, Snafu)]
+#[snafu(context(builder))]
+enum Error {
+    #[snafu(display("{host}:{port} {user}@{database} ({retries} retries)"))]
+    Connect {
+        host: String,
+        port: u16,
+        user: String,
+        database: String,
+        retries: u8,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Value {value} is out of range"))]
+    OutOfRange {
+        value: i64,
+    },
+
+    #[snafu(display("Could not open {filename} in mode {mode:o}"))]
+    OpenConfig {
+        filename: String,
+        mode: u32,
+        source: std::io::Error,
+    },
+
+    Unavailable,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(context(builder), display("Quota of {limit} exceeded by {user}"))]
+struct QuotaError {
+    user: String,
+    limit: u64,
+}
+
+fn io_failure() -> Result<(), std::io::Error> {
+    Err(std::io::Error::other("refused"))
+}
+
+#[test]
+fn five_field_selector_can_be_built_incrementally() {
+    let builder = ConnectSnafu::builder().host("localhost");
+    let builder = builder.port(5432_u16);
+    let builder = builder.user("admin").database("production");
+    let selector = builder.retries(3_u8).build();
+
+    assert_eq!(selector.host, "localhost");
+    assert_eq!(selector.port, 5432);
+    assert_eq!(selector.user, "admin");
+    assert_eq!(selector.database, "production");
+    assert_eq!(selector.retries, 3);
+
+    let error = io_failure().context(selector).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "localhost:5432 admin@production (3 retries)"
+    );
+}
+
+#[test]
+fn fields_can_be_set_in_any_order_and_replaced() {
+    let selector = ConnectSnafuBuilder::new()
+        .retries(1_u8)
+        .database("staging")
+        .user("guest")
+        .port(80_u16)
+        .host("example.com")
+        .retries(5_u8)
+        .build();
+
+    let error = io_failure().context(selector).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "example.com:80 guest@staging (5 retries)"
+    );
+}
+
+#[test]
+fn leaf_selectors_can_be_built() {
+    let error = OutOfRangeSnafu::builder().value(-1).build().build();
+
+    assert!(matches!(error, Error::OutOfRange { value: -1 }));
+}
+
+#[test]
+#[should_panic(
+    expected = "the `port` field was not set when building the `ConnectSnafu` context selector"
+)]
+fn missing_fields_panic() {
+    let _selector: ConnectSnafu<&str, u16, &str, &str, u8> = ConnectSnafu::builder()
+        .host("localhost")
+        .user("admin")
+        .database("production")
+        .retries(3)
+        .build();
+}
+
+#[test]
+fn selectors_can_be_built_across_branches() {
+    for (read_only, expected) in [(true, 0o444), (false, 0o644)].iter().copied() {
+        let builder = OpenConfigSnafu::builder().filename("app.toml");
+        let builder = if read_only {
+            builder.mode(0o444_u32)
+        } else {
+            builder.mode(0o644_u32)
+        };
+
+        let error = builder.context(io_failure()).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::OpenConfig { ref filename, mode, .. } if filename == "app.toml" && mode == expected
+        ));
+    }
+}
+
+#[test]
+fn partial_builders_can_be_cloned_and_reused() {
+    let partial = OpenConfigSnafu::builder().filename("app.toml");
+
+    let first = partial.clone().mode(0o400_u32).build_selector();
+    let second = partial.mode(0o600_u32).build_selector();
+
+    assert_eq!(first.filename, "app.toml");
+    assert_eq!(first.mode, 0o400);
+    assert_eq!(second.filename, "app.toml");
+    assert_eq!(second.mode, 0o600);
+}
+
+#[test]
+fn context_finisher_passes_through_success() {
+    let result = OpenConfigSnafu::builder()
+        .filename("app.toml")
+        .mode(0o644_u32)
+        .context(Ok::<_, std::io::Error>(42));
+
+    assert_eq!(result.unwrap(), 42);
+}
+
+#[test]
+fn context_finisher_works_with_the_question_mark() {
+    fn open() -> Result<(), Error> {
+        let builder = OpenConfigSnafu::builder().filename("app.toml");
+        builder.mode(0o644_u32).context(io_failure())?;
+        Ok(())
+    }
+
+    assert_eq!(
+        open().unwrap_err().to_string(),
+        "Could not open app.toml in mode 644"
+    );
+}
+
+#[test]
+fn fail_finisher_creates_the_error() {
+    let result: Result<(), _> = OutOfRangeSnafu::builder().value(7).fail();
+
+    assert!(matches!(result, Err(Error::OutOfRange { value: 7 })));
+}
+
+#[test]
+fn struct_selectors_can_have_builders() {
+    let result: Result<(), _> = QuotaSnafu::builder().user("alice").limit(10_u64).fail();
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Quota of 10 exceeded by alice"
+    );
+}
+
+#[test]
+fn selectors_without_fields_have_no_builder() {
+    let error: Result<(), _> = UnavailableSnafu.fail();
+
+    assert!(matches!(error, Err(Error::Unavailable)));
+}
+
+#[test]
+#[should_panic(
+    expected = "the `mode` field was not set when building the `OpenConfigSnafu` context selector"
+)]
+fn finishers_panic_on_missing_fields() {
+    let _ = OpenConfigSnafu::<&str, u32>::builder()
+        .filename("app.toml")
+        .context(Ok::<(), std::io::Error>(()));
+}
@@ -0,0 +1,72 @@
+use snafu::{prelude::*, IntoError};
+
+#[derive(Debug, Snafu)]
+enum InnerError {
+    #[snafu(display("Level 3"))]
+    Leaf,
+
+    #[snafu(display("Level 2"))]
+    Middle { source: Box<InnerError> },
+
+    #[snafu(display("Level 1"))]
+    Top { source: Box<InnerError> },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Aggregate"))]
+struct AggregateError {
+    source: InnerError,
+    error_chain: Vec<Box<dyn snafu::Error + Send + Sync>>,
+}
+
+fn three_levels() -> Result<(), InnerError> {
+    let leaf = LeafSnafu.build();
+    let middle = MiddleSnafu.into_error(Box::new(leaf));
+    Err(TopSnafu.into_error(Box::new(middle)))
+}
+
+fn captured(depth: usize) -> AggregateError {
+    three_levels()
+        .context_chain(|error_chain| AggregateSnafu { error_chain }, depth)
+        .unwrap_err()
+}
+
+#[test]
+fn chain_is_limited_to_the_depth() {
+    assert_eq!(captured(0).error_chain.len(), 0);
+    assert_eq!(captured(1).error_chain.len(), 1);
+    assert_eq!(captured(2).error_chain.len(), 2);
+}
+
+#[test]
+fn chain_stops_at_the_last_source() {
+    assert_eq!(captured(10).error_chain.len(), 3);
+}
+
+#[test]
+fn chain_preserves_display_in_order() {
+    let e = captured(3);
+    let messages: Vec<_> = e.error_chain.iter().map(|e| e.to_string()).collect();
+
+    assert_eq!(messages, ["Level 1", "Level 2", "Level 3"]);
+}
+
+#[test]
+fn source_is_still_stored() {
+    let e = captured(1);
+
+    assert!(matches!(e.source, InnerError::Top { .. }));
+}
+
+#[test]
+fn closure_is_not_called_on_success() {
+    let value: Result<i32, InnerError> = Ok(42);
+    let result: Result<_, AggregateError> = value.context_chain(
+        |_error_chain| -> AggregateSnafu<Vec<Box<dyn snafu::Error + Send + Sync>>> {
+            panic!("This block will not be evaluated")
+        },
+        3,
+    );
+
+    assert_eq!(result.unwrap(), 42);
+}
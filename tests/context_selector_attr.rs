@@ -0,0 +1,66 @@
+mod enums {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(context_selector_attr(derive(PartialEq)))]
+    enum Error {
+        #[snafu(context_selector_attr(derive(Eq, Hash)))]
+        #[snafu(context_selector_attr(must_use = "handle this error"))]
+        Alpha {
+            id: i32,
+        },
+
+        Beta,
+
+        // The enum-wide attributes skip variants without a selector struct.
+        #[snafu(context(false))]
+        Gamma {
+            source: std::io::Error,
+        },
+    }
+
+    #[test]
+    fn enum_attributes_apply_to_every_selector() {
+        assert_eq!(BetaSnafu, BetaSnafu);
+        assert_eq!(AlphaSnafu { id: 1 }, AlphaSnafu { id: 1 });
+    }
+
+    #[test]
+    fn variant_attributes_are_added_to_the_enum_attributes() {
+        use std::collections::HashSet;
+
+        let mut selectors = HashSet::new();
+        selectors.insert(AlphaSnafu { id: 1 });
+        selectors.insert(AlphaSnafu { id: 1 });
+
+        assert_eq!(selectors.len(), 1);
+    }
+
+    #[test]
+    fn selectors_can_still_be_used() {
+        let error = AlphaSnafu { id: 42 }.build();
+        assert!(matches!(error, Error::Alpha { id: 42 }));
+
+        let error = Error::from(std::io::Error::new(std::io::ErrorKind::Other, "gone"));
+        assert!(matches!(error, Error::Gamma { .. }));
+    }
+}
+
+mod structs {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(context_selector_attr(derive(PartialEq)))]
+    #[snafu(context_selector_attr(cfg_attr(test, derive(Eq))))]
+    struct Error {
+        id: i32,
+    }
+
+    fn assert_eq_impl<T: Eq>(_: T) {}
+
+    #[test]
+    fn attributes_apply_to_the_selector() {
+        assert_eq!(Snafu { id: 1 }, Snafu { id: 1 });
+        assert_eq_impl(Snafu { id: 1 });
+    }
+}
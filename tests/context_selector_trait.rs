@@ -0,0 +1,70 @@
+use snafu::{prelude::*, ContextSelector, NoneError};
+use std::io;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Opening { source: io::Error, path: String },
+    Reading { source: io::Error },
+    Empty,
+}
+
+fn apply_context<C>(context: C) -> impl FnOnce(C::Source) -> Error
+where
+    C: ContextSelector<Error>,
+{
+    move |source| context.into_error(source)
+}
+
+fn not_found() -> io::Error {
+    io::ErrorKind::NotFound.into()
+}
+
+#[test]
+fn selectors_with_a_source_are_accepted() {
+    let error = apply_context(ReadingSnafu)(not_found());
+    assert!(matches!(error, Error::Reading { .. }));
+
+    let error = apply_context(OpeningSnafu { path: "/etc" })(not_found());
+    assert!(matches!(error, Error::Opening { path, .. } if path == "/etc"));
+}
+
+#[test]
+fn selectors_without_a_source_are_accepted() {
+    let error = apply_context(EmptySnafu)(NoneError);
+    assert!(matches!(error, Error::Empty));
+}
+
+#[test]
+fn from_fn_is_accepted() {
+    let context = snafu::from_fn(|source| Error::Reading { source });
+
+    let error = apply_context(context)(not_found());
+    assert!(matches!(error, Error::Reading { .. }));
+}
+
+mod generic {
+    use snafu::{prelude::*, ContextSelector};
+
+    #[derive(Debug, Snafu)]
+    struct Error<T>
+    where
+        T: std::fmt::Debug + std::fmt::Display,
+    {
+        value: T,
+        source: std::fmt::Error,
+    }
+
+    fn wrap<C, T>(context: C) -> Error<T>
+    where
+        C: ContextSelector<Error<T>, Source = std::fmt::Error>,
+        T: std::fmt::Debug + std::fmt::Display,
+    {
+        context.into_error(std::fmt::Error)
+    }
+
+    #[test]
+    fn selectors_for_generic_errors_are_accepted() {
+        let error: Error<i32> = wrap(Snafu { value: 42 });
+        assert_eq!(error.value, 42);
+    }
+}
@@ -0,0 +1,87 @@
+use core::ops::ControlFlow;
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(display("The value {value} is negative"))]
+struct NegativeError {
+    value: i32,
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("Summing the values failed"))]
+    Summing { source: NegativeError },
+
+    #[snafu(display("Summing the values failed after {count} values"))]
+    SummingWithCount { count: usize, source: NegativeError },
+
+    #[snafu(display("Could not parse {text}"))]
+    Parse {
+        text: String,
+        source: std::num::ParseIntError,
+    },
+}
+
+fn sum_positive(values: &[i32]) -> ControlFlow<NegativeError, i32> {
+    values.iter().try_fold(0, |acc, &value| {
+        if value < 0 {
+            ControlFlow::Break(NegativeSnafu { value }.build())
+        } else {
+            ControlFlow::Continue(acc + value)
+        }
+    })
+}
+
+#[test]
+fn continue_becomes_ok() {
+    let total = sum_positive(&[1, 2, 3])
+        .break_context(SummingSnafu)
+        .unwrap();
+
+    assert_eq!(total, 6);
+}
+
+#[test]
+fn break_becomes_a_contextual_error() {
+    let error = sum_positive(&[1, -2, 3])
+        .break_context(SummingSnafu)
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::Summing {
+            source: NegativeError { value: -2 }
+        }
+    ));
+}
+
+#[test]
+fn break_context_can_be_lazy() {
+    let error = sum_positive(&[-5])
+        .with_break_context(|e| SummingWithCountSnafu {
+            count: e.value.unsigned_abs() as usize,
+        })
+        .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Summing the values failed after 5 values"
+    );
+}
+
+#[test]
+fn result_errors_can_break_a_loop() {
+    fn parse_all(texts: &[&str]) -> ControlFlow<Error, i32> {
+        texts.iter().try_fold(0, |acc, &text| {
+            let value = text.parse::<i32>().context_break(ParseSnafu { text })?;
+            ControlFlow::Continue(acc + value)
+        })
+    }
+
+    assert!(matches!(parse_all(&["1", "2"]), ControlFlow::Continue(3)));
+
+    match parse_all(&["1", "x", "2"]) {
+        ControlFlow::Break(Error::Parse { text, .. }) => assert_eq!(text, "x"),
+        other => panic!("Expected a parse error, got {:?}", other),
+    }
+}
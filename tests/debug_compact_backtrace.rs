@@ -0,0 +1,170 @@
+use snafu::{prelude::*, Backtrace, Whatever};
+
+#[derive(Snafu)]
+#[snafu(debug(compact_backtrace))]
+enum Error {
+    Fetch {
+        id: u32,
+        r#type: &'static str,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Waited for {seconds} seconds"))]
+    Timeout {
+        #[snafu(backtrace)]
+        trace: Option<Backtrace>,
+        seconds: u64,
+    },
+
+    Shutdown,
+}
+
+#[derive(Snafu)]
+#[snafu(debug(compact_backtrace))]
+struct StructError {
+    name: String,
+    backtrace: Backtrace,
+}
+
+#[derive(Snafu)]
+#[snafu(debug(compact_backtrace))]
+enum GenericError<T> {
+    Invalid { value: T, backtrace: Backtrace },
+}
+
+#[test]
+fn backtrace_is_a_single_line() {
+    let error = Error::Fetch {
+        id: 42,
+        r#type: "user",
+        backtrace: Backtrace::disabled(),
+    };
+
+    assert_eq!(
+        format!("{:?}", error),
+        r#"Fetch { id: 42, type: "user", backtrace: Backtrace(<disabled>) }"#,
+    );
+}
+
+#[test]
+fn captured_backtrace_is_a_single_line() {
+    let error = Error::Fetch {
+        id: 42,
+        r#type: "user",
+        backtrace: Backtrace::force_capture(),
+    };
+
+    assert_eq!(
+        format!("{:?}", error),
+        r#"Fetch { id: 42, type: "user", backtrace: Backtrace(<captured>) }"#,
+    );
+}
+
+#[test]
+fn fields_are_shown_in_declaration_order() {
+    let error = Error::Timeout {
+        trace: None,
+        seconds: 30,
+    };
+
+    assert_eq!(
+        format!("{:?}", error),
+        "Timeout { trace: Backtrace(<disabled>), seconds: 30 }",
+    );
+}
+
+#[test]
+fn variants_without_fields_are_shown() {
+    assert_eq!(format!("{:?}", ShutdownSnafu.build()), "Shutdown");
+}
+
+#[test]
+fn alternate_form_includes_full_backtrace() {
+    let error = Error::Fetch {
+        id: 42,
+        r#type: "user",
+        backtrace: Backtrace::force_capture(),
+    };
+
+    let debug = format!("{:#?}", error);
+    assert!(!debug.contains("Backtrace(<captured>)"), "{}", debug);
+    assert!(
+        debug.contains("alternate_form_includes_full_backtrace"),
+        "{}",
+        debug,
+    );
+}
+
+#[test]
+fn structs_are_supported() {
+    let error = StructError {
+        name: "alpha".into(),
+        backtrace: Backtrace::disabled(),
+    };
+
+    assert_eq!(
+        format!("{:?}", error),
+        r#"StructError { name: "alpha", backtrace: Backtrace(<disabled>) }"#,
+    );
+}
+
+#[test]
+fn generic_errors_are_supported() {
+    let error = GenericError::Invalid {
+        value: 'x',
+        backtrace: Backtrace::disabled(),
+    };
+
+    assert_eq!(
+        format!("{:?}", error),
+        "Invalid { value: 'x', backtrace: Backtrace(<disabled>) }",
+    );
+}
+
+#[test]
+fn whatever_backtrace_is_a_single_line() {
+    fn example() -> Result<(), Whatever> {
+        whatever!("Something went wrong")
+    }
+
+    let error = example().unwrap_err();
+    let debug = format!("{:?}", error);
+
+    assert!(
+        debug.starts_with(
+            r#"Whatever { source: None, message: "Something went wrong", backtrace: Backtrace(<"#
+        ),
+        "{}",
+        debug,
+    );
+    let expected = format!(
+        "location: at tests{}debug_compact_backtrace.rs:",
+        std::path::MAIN_SEPARATOR,
+    );
+    assert!(debug.contains(&expected), "{}", debug);
+    assert!(!debug.contains('\n'), "{}", debug);
+}
+
+#[test]
+fn whatever_alternate_form_includes_full_backtrace() {
+    fn example() -> Result<(), Whatever> {
+        whatever!("Something went wrong")
+    }
+
+    let error = example().unwrap_err();
+    // `dbg!` formats its argument with `{:#?}`
+    let debug = format!("{:#?}", error);
+
+    assert!(debug.starts_with("Whatever {\n"), "{}", debug);
+    assert!(
+        debug.contains("    message: \"Something went wrong\",\n"),
+        "{}",
+        debug,
+    );
+    assert!(!debug.contains("Backtrace(<captured>)"), "{}", debug);
+    assert!(
+        debug.contains("whatever_alternate_form_includes_full_backtrace"),
+        "{}",
+        debug,
+    );
+}
@@ -0,0 +1,92 @@
+// The output is written to stderr, so each test runs itself again in
+// a child process and checks what the child printed.
+
+use snafu::prelude::*;
+use std::{env, process::Command};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("Could not load the configuration"))]
+    Loading { source: ParseError },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Invalid number on line {line}"))]
+struct ParseError {
+    line: u32,
+}
+
+const CHILD_ENV: &str = "SNAFU_DEBUG_ERROR_TEST_CHILD";
+
+fn loading_error() -> Error {
+    ParseSnafu { line: 3u32 }
+        .fail::<()>()
+        .context(LoadingSnafu)
+        .unwrap_err()
+}
+
+/// Returns `None` when running as the child, after calling `f`.
+fn stderr_of(test_name: &str, f: impl FnOnce()) -> Option<String> {
+    if env::var_os(CHILD_ENV).is_some() {
+        f();
+        return None;
+    }
+
+    let exe = env::current_exe().expect("Unable to locate the test binary");
+    let output = Command::new(exe)
+        .args([test_name, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD_ENV, "1")
+        .output()
+        .expect("Unable to run the test binary");
+    assert!(output.status.success(), "{:?}", output);
+
+    Some(String::from_utf8(output.stderr).expect("stderr was not UTF-8"))
+}
+
+#[test]
+fn prints_the_location_expression_and_chain() {
+    let line = line!() + 3;
+    let stderr = stderr_of("prints_the_location_expression_and_chain", || {
+        let error = loading_error();
+        let error = snafu::debug_error!(error);
+        assert_eq!(error.to_string(), "Could not load the configuration");
+    });
+
+    if let Some(stderr) = stderr {
+        let expected = format!(
+            "[{file}:{line}:21] error = Could not load the configuration\n\
+             \n\
+             Caused by this error:\n  \
+             1: Invalid number on line 3\n",
+            file = file!(),
+            line = line,
+        );
+        assert_eq!(stderr, expected);
+    }
+}
+
+#[test]
+fn does_not_consume_a_borrowed_error() {
+    let stderr = stderr_of("does_not_consume_a_borrowed_error", || {
+        let error = loading_error();
+        snafu::debug_error!(&error);
+        assert!(matches!(error, Error::Loading { .. }));
+    });
+
+    if let Some(stderr) = stderr {
+        assert!(stderr.contains("] &error = Could not load the configuration\n"));
+    }
+}
+
+#[test]
+fn accepts_boxed_trait_objects() {
+    let stderr = stderr_of("accepts_boxed_trait_objects", || {
+        let error: Box<dyn std::error::Error + Send + Sync> = Box::new(loading_error());
+        let error = snafu::debug_error!(error);
+        assert_eq!(error.to_string(), "Could not load the configuration");
+    });
+
+    if let Some(stderr) = stderr {
+        assert!(stderr.contains("Caused by this error:\n  1: Invalid number on line 3\n"));
+    }
+}
@@ -0,0 +1,100 @@
+mod outer {
+    #[snafu::defaults(
+        visibility(pub(crate)),
+        context(suffix(Ctx)),
+        display_prefix = "outer: "
+    )]
+    pub mod errors {
+        use snafu::prelude::*;
+
+        #[derive(Debug, Snafu)]
+        pub enum EnumError {
+            Alpha { id: u32 },
+        }
+
+        #[derive(Debug, Snafu)]
+        #[snafu(context(suffix(Snafu)), display_prefix = "own: ")]
+        pub enum OverriddenError {
+            Beta,
+        }
+
+        #[derive(Debug, Snafu)]
+        #[snafu(visibility(pub(crate)))]
+        pub struct StructError {
+            id: u32,
+        }
+
+        #[derive(Debug, Snafu)]
+        pub struct OpaqueError(EnumError);
+
+        pub mod nested {
+            use snafu::prelude::*;
+
+            #[derive(Debug, Snafu)]
+            pub enum NestedError {
+                Gamma,
+            }
+        }
+
+        #[snafu::defaults(context(suffix(false)), display_prefix = "inner: ")]
+        pub mod overriding {
+            use snafu::prelude::*;
+
+            #[derive(Debug, Snafu)]
+            pub enum InnerError {
+                Delta,
+            }
+        }
+
+        #[snafu::defaults]
+        pub mod inheriting {
+            use snafu::prelude::*;
+
+            #[derive(Debug, Snafu)]
+            pub enum InheritedError {
+                Epsilon,
+            }
+        }
+    }
+}
+
+use outer::errors::{self, inheriting, nested, overriding};
+
+#[test]
+fn defaults_are_applied_to_enums() {
+    let e = errors::AlphaCtx { id: 1u32 }.build();
+    assert_eq!(e.to_string(), "outer: Alpha");
+}
+
+#[test]
+fn attributes_on_the_error_override_defaults() {
+    let e = errors::BetaSnafu.build();
+    assert_eq!(e.to_string(), "own: Beta");
+}
+
+#[test]
+fn structs_are_unchanged() {
+    let e = errors::StructSnafu { id: 2u32 }.build();
+    assert_eq!(e.to_string(), "StructError");
+
+    let e = errors::OpaqueError::from(errors::AlphaCtx { id: 3u32 }.build());
+    assert_eq!(e.to_string(), "outer: Alpha");
+}
+
+#[test]
+fn nested_modules_receive_the_defaults() {
+    let e = nested::GammaCtx.build();
+    assert_eq!(e.to_string(), "outer: Gamma");
+}
+
+#[test]
+fn nested_defaults_override_their_parent() {
+    let e = overriding::Delta.build();
+    assert_eq!(e.to_string(), "inner: Delta");
+}
+
+#[test]
+fn nested_defaults_inherit_from_their_parent() {
+    let e = inheriting::EpsilonCtx.build();
+    assert_eq!(e.to_string(), "outer: Epsilon");
+}
@@ -0,0 +1,96 @@
+#![allow(deprecated)]
+
+use snafu::{prelude::*, IntoError};
+use std::error::Error as _;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Leaf,
+
+    WithSource {
+        source: std::io::Error,
+    },
+
+    #[cfg(feature = "rust_1_30_compat")]
+    r#Raw,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(whatever, display("{message}"))]
+struct Stringly {
+    message: String,
+    #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+    source: Option<Box<dyn std::error::Error>>,
+}
+
+fn address(error: Option<&dyn std::error::Error>) -> Option<*const u8> {
+    error.map(|e| e as *const dyn std::error::Error as *const u8)
+}
+
+#[test]
+fn cause_is_the_same_as_source() {
+    let io_error = std::io::Error::new(std::io::ErrorKind::Other, "oops");
+    let error = WithSourceSnafu.into_error(io_error);
+
+    assert!(error.cause().is_some());
+    assert_eq!(address(error.cause()), address(error.source()));
+}
+
+#[test]
+fn cause_is_none_without_a_source() {
+    let error = LeafSnafu.build();
+
+    assert!(error.cause().is_none());
+    assert!(error.source().is_none());
+}
+
+#[test]
+fn cause_is_the_same_as_source_for_stringly_typed_errors() {
+    let error = (|| -> Result<(), Stringly> {
+        whatever!(Err::<(), _>(LeafSnafu.build()), "outer");
+        Ok(())
+    })()
+    .unwrap_err();
+
+    assert!(error.cause().is_some());
+    assert_eq!(address(error.cause()), address(error.source()));
+}
+
+#[cfg(not(feature = "rust_1_30_compat"))]
+#[test]
+fn description_is_the_path_to_the_variant() {
+    let error = LeafSnafu.build();
+    let description = error.description();
+
+    // The exact spacing depends on how the compiler stringifies tokens
+    assert!(description.starts_with("Error"), "{}", description);
+    assert!(description.ends_with("Leaf"), "{}", description);
+}
+
+#[cfg(not(feature = "rust_1_30_compat"))]
+#[test]
+fn description_of_a_struct_is_the_struct_name_by_default() {
+    let error = (|| -> Result<(), Stringly> { whatever!("oops") })().unwrap_err();
+
+    assert_eq!(error.description(), "Stringly");
+}
+
+#[cfg(feature = "rust_1_30_compat")]
+#[test]
+fn description_is_the_variant_name() {
+    assert_eq!(LeafSnafu.build().description(), "Leaf");
+}
+
+#[cfg(feature = "rust_1_30_compat")]
+#[test]
+fn description_of_a_raw_identifier_does_not_include_the_prefix() {
+    assert_eq!(RawSnafu.build().description(), "Raw");
+}
+
+#[cfg(feature = "rust_1_30_compat")]
+#[test]
+fn description_of_a_struct_is_the_struct_name() {
+    let error = (|| -> Result<(), Stringly> { whatever!("oops") })().unwrap_err();
+
+    assert_eq!(error.description(), "Stringly");
+}
@@ -0,0 +1,68 @@
+use snafu::{prelude::*, ErrorCompat, Location};
+use std::{io, num::ParseIntError};
+
+snafu::derive_impl! {
+    /// Wraps a foreign I/O error
+    pub IoWrapperError {
+        source: io::Error,
+        message: String,
+    }
+    display = "foreign: {source}"
+}
+
+snafu::derive_impl! {
+    #[snafu(context(suffix(Ctx)))]
+    ParseWrapperError {
+        source: ParseIntError,
+        input: String,
+        #[snafu(implicit)]
+        location: Location,
+    }
+    display = "could not parse {input:?}",
+}
+
+fn open() -> Result<(), IoWrapperError> {
+    Err(io::Error::new(io::ErrorKind::NotFound, "gone"))
+        .context(IoWrapperSnafu { message: "opening" })
+}
+
+fn parse(input: &str) -> Result<i32, ParseWrapperError> {
+    input.parse().context(ParseWrapperCtx { input })
+}
+
+#[test]
+fn foreign_errors_can_be_wrapped_with_context() {
+    let error = open().unwrap_err();
+
+    assert_eq!(error.message, "opening");
+    assert_eq!(error.source.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn the_display_format_is_used() {
+    let error = open().unwrap_err();
+
+    assert_eq!(error.to_string(), "foreign: gone");
+}
+
+#[test]
+fn the_foreign_error_is_the_source() {
+    let error = parse("x").unwrap_err();
+    let chain: Vec<_> = error.iter_chain().map(ToString::to_string).collect();
+
+    assert_eq!(
+        chain,
+        ["could not parse \"x\"", "invalid digit found in string"]
+    );
+}
+
+#[test]
+fn snafu_attributes_are_applied() {
+    let base_line = line!();
+    let error = "x"
+        .parse::<i32>()
+        .context(ParseWrapperCtx { input: "x" })
+        .unwrap_err();
+
+    assert_eq!(error.location.line, base_line + 3, "{}", error.location);
+}
@@ -0,0 +1,79 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[repr(i32)]
+enum Error {
+    #[snafu(display("The item was not found"))]
+    NotFound = -2,
+
+    /// The resource is busy
+    Busy = -3,
+
+    Unknown = -99,
+}
+
+#[test]
+fn code_returns_the_discriminant() {
+    assert_eq!(NotFoundSnafu.build().code(), -2);
+    assert_eq!(BusySnafu.build().code(), -3);
+    assert_eq!(UnknownSnafu.build().code(), -99);
+}
+
+#[test]
+fn display_is_generated() {
+    assert_eq!(NotFoundSnafu.build().to_string(), "The item was not found");
+    assert_eq!(BusySnafu.build().to_string(), "The resource is busy");
+    assert_eq!(UnknownSnafu.build().to_string(), "Unknown");
+}
+
+#[test]
+fn selectors_work_with_ensure() {
+    fn example(busy: bool) -> Result<(), Error> {
+        ensure!(!busy, BusySnafu);
+        Ok(())
+    }
+
+    assert!(example(false).is_ok());
+    assert_eq!(example(true).unwrap_err().code(), -3);
+}
+
+#[test]
+fn implements_error() {
+    fn check<E: std::error::Error>(_: &E) {}
+    check(&NotFoundSnafu.build());
+}
+
+mod unsigned_repr {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    #[repr(u8)]
+    enum Error {
+        Low = 1,
+        High = 200,
+    }
+
+    #[test]
+    fn code_uses_the_repr_type() {
+        let code: u8 = HighSnafu.build().code();
+        assert_eq!(code, 200);
+        assert_eq!(LowSnafu.build().code(), 1);
+    }
+}
+
+mod implicit_discriminants {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        First = 10,
+        Second,
+    }
+
+    #[test]
+    fn code_defaults_to_i32() {
+        let code: i32 = SecondSnafu.build().code();
+        assert_eq!(code, 11);
+        assert_eq!(FirstSnafu.build().code(), 10);
+    }
+}
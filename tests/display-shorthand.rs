@@ -25,6 +25,19 @@ enum Error {
 
     /// Person {name} with ID {id} denied
     ShorthandArgumentsInDocComments { id: i32, name: &'static str },
+
+    #[snafu(display("Balance {balance:.3} for [{name:>6}]"))]
+    ShorthandArgumentsWithWidthAndPrecision { balance: f64, name: &'static str },
+
+    #[snafu(display("Balance [{balance:>width$.precision$}]"))]
+    WidthAndPrecisionFromFields {
+        balance: f64,
+        width: usize,
+        precision: usize,
+    },
+
+    #[snafu(display("ID [{0:>4}] for [{1:<5}]", id, name))]
+    IndexedArgumentsWithWidth { id: i32, name: &'static str },
 }
 
 #[test]
@@ -98,3 +111,34 @@ fn allows_shorthand_in_doc_comments() {
     .build();
     assert_eq!(error.to_string(), "Person Anna with ID 42 denied");
 }
+
+#[test]
+fn supports_width_and_precision() {
+    let error = ShorthandArgumentsWithWidthAndPrecisionSnafu {
+        balance: 1.23456,
+        name: "Anna",
+    }
+    .build();
+    assert_eq!(error.to_string(), "Balance 1.235 for [  Anna]");
+}
+
+#[test]
+fn supports_width_and_precision_from_fields() {
+    let error = WidthAndPrecisionFromFieldsSnafu {
+        balance: 1.23456,
+        width: 6usize,
+        precision: 1usize,
+    }
+    .build();
+    assert_eq!(error.to_string(), "Balance [   1.2]");
+}
+
+#[test]
+fn supports_indexed_arguments_with_width() {
+    let error = IndexedArgumentsWithWidthSnafu {
+        id: 42,
+        name: "Anna",
+    }
+    .build();
+    assert_eq!(error.to_string(), "ID [  42] for [Anna ]");
+}
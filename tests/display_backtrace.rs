@@ -0,0 +1,38 @@
+use snafu::{prelude::*, Backtrace, DisplayBacktrace, ErrorCompat};
+
+#[derive(Debug, Snafu)]
+struct Error {
+    backtrace: Backtrace,
+}
+
+#[test]
+#[cfg(not(feature = "backtraces-impl-backtrace-crate"))]
+fn matches_the_backtrace_display_output() {
+    let error = Snafu.build();
+    let backtrace = ErrorCompat::backtrace(&error).unwrap();
+
+    assert_eq!(
+        DisplayBacktrace(backtrace).to_string(),
+        backtrace.to_string(),
+    );
+}
+
+#[test]
+#[cfg(feature = "backtraces-impl-backtrace-crate")]
+fn matches_the_backtrace_crate_debug_output() {
+    let error = Snafu.build();
+    let backtrace = ErrorCompat::backtrace(&error).unwrap();
+
+    assert_eq!(
+        DisplayBacktrace(backtrace).to_string(),
+        format!("{:?}", backtrace),
+    );
+}
+
+#[test]
+fn can_be_used_by_report() {
+    let error = Snafu.build();
+    let report = snafu::Report::from_error(error).to_string();
+
+    assert!(report.starts_with("Error"), "{}", report);
+}
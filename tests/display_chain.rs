@@ -0,0 +1,117 @@
+use snafu::{display_chain, prelude::*, ChainDisplay, IntoError};
+use std::{error::Error as StdError, fmt, io};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("could not load the config"))]
+    LoadConfig { source: ReadError },
+
+    #[snafu(display("nothing went wrong"))]
+    Leaf,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("could not read {path}"))]
+struct ReadError {
+    path: String,
+    source: io::Error,
+}
+
+#[derive(Debug)]
+struct ForeignError(io::Error);
+
+impl fmt::Display for ForeignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the foreign operation failed")
+    }
+}
+
+impl StdError for ForeignError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn not_found() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no such file")
+}
+
+#[test]
+fn every_error_in_the_chain_is_displayed() {
+    let read = ReadSnafu { path: "app.toml" }.into_error(not_found());
+    let error = LoadConfigSnafu.into_error(read);
+
+    assert_eq!(
+        format!("{}", display_chain(&error)),
+        "could not load the config: could not read app.toml: no such file",
+    );
+}
+
+#[test]
+fn an_error_without_sources_is_displayed_alone() {
+    let error = LeafSnafu.build();
+
+    assert_eq!(format!("{}", display_chain(&error)), "nothing went wrong");
+}
+
+#[test]
+fn non_snafu_errors_are_supported() {
+    let error = ForeignError(not_found());
+
+    assert_eq!(
+        format!("{}", display_chain(&error)),
+        "the foreign operation failed: no such file",
+    );
+}
+
+#[test]
+fn boxed_errors_are_supported() {
+    let error: Box<dyn StdError> = Box::new(ForeignError(not_found()));
+
+    assert_eq!(
+        format!("{}", ChainDisplay::new(&*error)),
+        "the foreign operation failed: no such file",
+    );
+}
+
+#[test]
+fn can_be_formatted_more_than_once() {
+    let error = ForeignError(not_found());
+    let chain: ChainDisplay<'_> = display_chain(&error);
+
+    assert_eq!(chain.to_string(), format!("{}", chain));
+}
+
+#[derive(Debug, Snafu)]
+enum RecursiveError {
+    #[snafu(display("the operation failed"))]
+    Wrapped { source: Box<RecursiveError> },
+
+    #[snafu(display("the operation failed"))]
+    Root,
+}
+
+fn nested_identical_errors(depth: usize) -> RecursiveError {
+    (0..depth).fold(RootSnafu.build(), |error, _| {
+        WrappedSnafu.into_error(Box::new(error))
+    })
+}
+
+#[test]
+fn repeated_messages_are_truncated() {
+    let error = nested_identical_errors(20);
+
+    let mut expected = vec!["the operation failed"; snafu::MAX_REPEATED_MESSAGES];
+    expected.push("<repeated messages truncated>");
+
+    assert_eq!(display_chain(&error).to_string(), expected.join(": "));
+}
+
+#[test]
+fn repeated_messages_within_the_limit_are_kept() {
+    let error = nested_identical_errors(snafu::MAX_REPEATED_MESSAGES - 1);
+
+    let expected = ["the operation failed"; snafu::MAX_REPEATED_MESSAGES];
+
+    assert_eq!(display_chain(&error).to_string(), expected.join(": "));
+}
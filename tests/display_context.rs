@@ -0,0 +1,144 @@
+use snafu::{prelude::*, IntoError};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display_context)]
+    Retry { attempt: u8, host: String },
+
+    #[snafu(display("could not open the file"), display_context)]
+    Open {
+        path: String,
+        source: std::io::Error,
+    },
+
+    /// Could not connect to {host}
+    #[snafu(display_context)]
+    Connect { host: String, port: u16 },
+
+    #[snafu(display_context)]
+    Leaf {
+        code: i32,
+        backtrace: snafu::Backtrace,
+    },
+
+    #[snafu(display(with = show_closed), display_context)]
+    Closed { port: u16 },
+
+    #[snafu(display(template = MSG_RESET, host), display_context)]
+    Reset { host: String, r#type: &'static str },
+
+    #[snafu(display_context)]
+    Empty,
+}
+
+const MSG_RESET: &str = "{host} reset the connection";
+
+fn show_closed(f: &mut std::fmt::Formatter, port: &u16) -> std::fmt::Result {
+    write!(f, "port {} closed", port)
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display_prefix = "[net] ")]
+enum PrefixedError {
+    #[snafu(display("could not resolve"), display_context)]
+    Resolve { name: String },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display_context)]
+struct StructError {
+    id: u32,
+    source: std::io::Error,
+}
+
+fn io_error() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Other, "boom")
+}
+
+#[test]
+fn every_field_follows_the_variant_name() {
+    let error = RetrySnafu {
+        attempt: 3,
+        host: "example.com",
+    }
+    .build();
+
+    assert_eq!(
+        error.to_string(),
+        r#"Retry { attempt: 3, host: "example.com" }"#,
+    );
+}
+
+#[test]
+fn source_fields_are_skipped() {
+    let error = OpenSnafu { path: "a.txt" }.into_error(io_error());
+
+    assert_eq!(
+        error.to_string(),
+        r#"could not open the file { path: "a.txt" }"#,
+    );
+}
+
+#[test]
+fn fields_follow_a_doc_comment() {
+    let error = ConnectSnafu {
+        host: "example.com",
+        port: 80u16,
+    }
+    .build();
+
+    assert_eq!(
+        error.to_string(),
+        r#"Could not connect to example.com { host: "example.com", port: 80 }"#,
+    );
+}
+
+#[test]
+fn backtrace_fields_are_skipped() {
+    let error = LeafSnafu { code: 7 }.build();
+
+    assert_eq!(error.to_string(), "Leaf { code: 7 }");
+}
+
+#[test]
+fn fields_follow_a_display_function() {
+    let error = ClosedSnafu { port: 22u16 }.build();
+
+    assert_eq!(error.to_string(), "port 22 closed { port: 22 }");
+}
+
+#[test]
+fn fields_follow_a_display_template() {
+    let error = ResetSnafu {
+        host: "example.com",
+        r#type: "tcp",
+    }
+    .build();
+
+    assert_eq!(
+        error.to_string(),
+        r#"example.com reset the connection { host: "example.com", type: "tcp" }"#,
+    );
+}
+
+#[test]
+fn variants_without_fields_are_unchanged() {
+    assert_eq!(EmptySnafu.build().to_string(), "Empty");
+}
+
+#[test]
+fn combines_with_a_display_prefix() {
+    let error = ResolveSnafu { name: "db" }.build();
+
+    assert_eq!(
+        error.to_string(),
+        r#"[net] could not resolve { name: "db" }"#,
+    );
+}
+
+#[test]
+fn works_on_structs() {
+    let error = StructSnafu { id: 9u32 }.into_error(io_error());
+
+    assert_eq!(error.to_string(), "StructError { id: 9 }");
+}
@@ -0,0 +1,101 @@
+mod errors {
+    pub const DISPLAY_PREFIX: &str = "errors: ";
+
+    pub mod network {
+        use snafu::prelude::*;
+
+        #[derive(Debug, Snafu)]
+        #[snafu(visibility(pub))]
+        pub enum Error {
+            #[snafu(display_inherit(parent = crate::errors::DISPLAY_PREFIX))]
+            #[snafu(display("Could not connect to {host}"))]
+            Connect { host: String },
+
+            #[snafu(display_inherit(parent = super::DISPLAY_PREFIX))]
+            Timeout,
+
+            /// Lost the connection to {host}
+            #[snafu(display_inherit(parent = super::DISPLAY_PREFIX))]
+            Disconnected { host: String },
+
+            #[snafu(display_inherit(parent = super::DISPLAY_PREFIX))]
+            #[snafu(display(with = show_refused))]
+            Refused { port: u16 },
+
+            #[snafu(display_inherit(parent = super::DISPLAY_PREFIX))]
+            #[snafu(display(template = MSG_RESET, host))]
+            Reset { host: String },
+
+            #[snafu(display("Not inherited"))]
+            Unrelated,
+        }
+
+        const MSG_RESET: &str = "{host} reset the connection";
+
+        fn show_refused(f: &mut std::fmt::Formatter, port: &u16) -> std::fmt::Result {
+            write!(f, "port {} refused the connection", port)
+        }
+    }
+
+    pub mod disk {
+        use snafu::prelude::*;
+
+        #[derive(Debug, Snafu)]
+        #[snafu(display_inherit(parent = super::DISPLAY_PREFIX))]
+        #[snafu(display("disk {id} is full"), visibility(pub))]
+        pub struct Error {
+            pub id: u8,
+        }
+    }
+}
+
+use errors::{disk, network};
+
+#[test]
+fn prefix_is_written_before_a_display_format() {
+    let error = network::ConnectSnafu { host: "example" }.build();
+
+    assert_eq!(error.to_string(), "errors: Could not connect to example");
+}
+
+#[test]
+fn prefix_is_written_before_the_default_display() {
+    let error = network::TimeoutSnafu.build();
+
+    assert_eq!(error.to_string(), "errors: Timeout");
+}
+
+#[test]
+fn prefix_is_written_before_a_doc_comment() {
+    let error = network::DisconnectedSnafu { host: "example" }.build();
+
+    assert_eq!(error.to_string(), "errors: Lost the connection to example");
+}
+
+#[test]
+fn prefix_is_written_before_a_display_function() {
+    let error = network::RefusedSnafu { port: 80u16 }.build();
+
+    assert_eq!(error.to_string(), "errors: port 80 refused the connection");
+}
+
+#[test]
+fn prefix_is_written_before_a_display_template() {
+    let error = network::ResetSnafu { host: "example" }.build();
+
+    assert_eq!(error.to_string(), "errors: example reset the connection");
+}
+
+#[test]
+fn other_variants_are_unaffected() {
+    let error = network::UnrelatedSnafu.build();
+
+    assert_eq!(error.to_string(), "Not inherited");
+}
+
+#[test]
+fn prefix_is_written_for_structs() {
+    let error = disk::Snafu { id: 3 }.build();
+
+    assert_eq!(error.to_string(), "errors: disk 3 is full");
+}
@@ -0,0 +1,118 @@
+use snafu::{prelude::*, Location};
+
+#[derive(Debug, Snafu)]
+#[snafu(display_location)]
+enum Error {
+    #[snafu(display("could not connect to {host}"))]
+    Connect {
+        host: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    Timeout {
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("no location here"))]
+    Plain,
+
+    #[snafu(display_location(false))]
+    Quiet {
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display_context)]
+    Retry {
+        attempt: u8,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(transparent)]
+    Io {
+        source: std::io::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+}
+
+impl Error {
+    fn location(&self) -> String {
+        match self {
+            Error::Connect { location, .. }
+            | Error::Timeout { location }
+            | Error::Quiet { location }
+            | Error::Retry { location, .. }
+            | Error::Io { location, .. } => location.to_string(),
+            Error::Plain => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("could not save"), display_location)]
+struct SaveError {
+    #[snafu(implicit)]
+    location: Location,
+}
+
+#[test]
+fn location_follows_a_display_format() {
+    let error = ConnectSnafu { host: "example" }.build();
+
+    assert_eq!(
+        error.to_string(),
+        format!("could not connect to example (at {})", error.location()),
+    );
+}
+
+#[test]
+fn location_follows_the_default_display() {
+    let error = TimeoutSnafu.build();
+
+    assert_eq!(
+        error.to_string(),
+        format!("Timeout (at {})", error.location()),
+    );
+}
+
+#[test]
+fn variants_without_a_location_are_unchanged() {
+    assert_eq!(PlainSnafu.build().to_string(), "no location here");
+}
+
+#[test]
+fn variants_can_opt_out() {
+    assert_eq!(QuietSnafu.build().to_string(), "Quiet");
+}
+
+#[test]
+fn location_follows_the_context_fields() {
+    let error = RetrySnafu { attempt: 2u8 }.build();
+
+    assert_eq!(
+        error.to_string(),
+        format!("Retry {{ attempt: 2 }} (at {})", error.location()),
+    );
+}
+
+#[test]
+fn transparent_variants_are_unchanged() {
+    let error = Error::from(std::io::Error::new(std::io::ErrorKind::Other, "boom"));
+
+    assert!(!error.location().is_empty());
+    assert_eq!(error.to_string(), "boom");
+}
+
+#[test]
+fn location_is_shown_for_structs() {
+    let error = SaveSnafu.build();
+
+    assert_eq!(
+        error.to_string(),
+        format!("could not save (at {})", error.location),
+    );
+}
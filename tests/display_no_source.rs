@@ -0,0 +1,100 @@
+use snafu::{prelude::*, IntoError};
+
+#[derive(Debug, Snafu)]
+#[snafu(display("inner failure"))]
+struct InnerError;
+
+mod variant {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(display(no_source))]
+        Default { source: InnerError },
+
+        #[snafu(display("Loading {name} failed"), display(no_source))]
+        Explicit { name: String, source: InnerError },
+
+        /// Could not save {name}
+        #[snafu(display(no_source))]
+        DocComment { name: String, source: InnerError },
+    }
+
+    #[test]
+    fn default_display_does_not_include_source() {
+        let error = DefaultSnafu.into_error(InnerError);
+
+        assert_eq!(error.to_string(), "Default");
+    }
+
+    #[test]
+    fn explicit_display_does_not_include_source() {
+        let error = ExplicitSnafu { name: "config" }.into_error(InnerError);
+        let text = error.to_string();
+
+        assert_eq!(text, "Loading config failed");
+        assert!(!text.contains("inner failure"));
+    }
+
+    #[test]
+    fn doc_comment_display_does_not_include_source() {
+        let error = DocCommentSnafu { name: "config" }.into_error(InnerError);
+
+        assert_eq!(error.to_string(), "Could not save config");
+    }
+
+    #[test]
+    fn source_is_still_available() {
+        use std::error::Error as _;
+
+        let error = DefaultSnafu.into_error(InnerError);
+        let source = error.source().expect("Source must be present");
+
+        assert_eq!(source.to_string(), "inner failure");
+    }
+}
+
+mod enum_default {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display(no_source))]
+    enum Error {
+        #[snafu(display("Reading failed"))]
+        Read { source: InnerError },
+
+        #[snafu(transparent)]
+        Transparent { source: InnerError },
+    }
+
+    #[test]
+    fn applies_to_every_variant() {
+        let error = ReadSnafu.into_error(InnerError);
+
+        assert_eq!(error.to_string(), "Reading failed");
+    }
+
+    #[test]
+    fn transparent_variants_still_delegate_to_the_source() {
+        let error = Error::from(InnerError);
+
+        assert_eq!(error.to_string(), "inner failure");
+    }
+}
+
+mod named_struct {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("Struct failed"), display(no_source))]
+    struct Error {
+        source: InnerError,
+    }
+
+    #[test]
+    fn display_does_not_include_source() {
+        let error = Snafu.into_error(InnerError);
+
+        assert_eq!(error.to_string(), "Struct failed");
+    }
+}
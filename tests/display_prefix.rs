@@ -0,0 +1,103 @@
+use snafu::{prelude::*, IntoError};
+
+#[derive(Debug, Snafu)]
+#[snafu(display_prefix = "[storage] ")]
+enum Error {
+    #[snafu(display("could not open {path}"))]
+    Open {
+        path: String,
+        source: InnerError,
+    },
+
+    Closed,
+
+    /// the disk is full
+    Full,
+
+    #[snafu(display_prefix(false))]
+    #[snafu(display("storage is offline"))]
+    Offline,
+
+    #[snafu(display(with = show_locked))]
+    Locked {
+        owner: String,
+    },
+
+    #[snafu(display(template = MSG_QUOTA, quota))]
+    Quota {
+        quota: u64,
+    },
+
+    #[snafu(transparent)]
+    Io {
+        source: std::io::Error,
+    },
+}
+
+const MSG_QUOTA: &str = "quota of {quota} bytes exceeded";
+
+fn show_locked(f: &mut std::fmt::Formatter, owner: &String) -> std::fmt::Result {
+    write!(f, "locked by {}", owner)
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("inner failure"))]
+struct InnerError;
+
+#[test]
+fn prefix_is_written_before_a_display_format() {
+    let error = OpenSnafu { path: "data.db" }.into_error(InnerError);
+
+    assert_eq!(error.to_string(), "[storage] could not open data.db");
+}
+
+#[test]
+fn prefix_is_written_before_the_default_display() {
+    assert_eq!(ClosedSnafu.build().to_string(), "[storage] Closed");
+}
+
+#[test]
+fn prefix_is_written_before_a_doc_comment_display() {
+    assert_eq!(FullSnafu.build().to_string(), "[storage] the disk is full");
+}
+
+#[test]
+fn prefix_is_written_before_a_display_function() {
+    let error = LockedSnafu { owner: "alice" }.build();
+
+    assert_eq!(error.to_string(), "[storage] locked by alice");
+}
+
+#[test]
+fn prefix_is_written_before_a_display_template() {
+    let error = QuotaSnafu { quota: 10u64 }.build();
+
+    assert_eq!(error.to_string(), "[storage] quota of 10 bytes exceeded");
+}
+
+#[test]
+fn variants_can_opt_out_of_the_prefix() {
+    assert_eq!(OfflineSnafu.build().to_string(), "storage is offline");
+}
+
+#[test]
+fn transparent_variants_do_not_receive_the_prefix() {
+    let error = Error::from(std::io::Error::new(std::io::ErrorKind::Other, "disk gone"));
+
+    assert_eq!(error.to_string(), "disk gone");
+}
+
+#[test]
+fn prefix_appears_exactly_once() {
+    let error = OpenSnafu { path: "data.db" }.into_error(InnerError);
+
+    assert_eq!(error.to_string().matches("[storage] ").count(), 1);
+}
+
+#[test]
+fn prefix_is_not_added_to_the_source_text() {
+    let error = OpenSnafu { path: "data.db" }.into_error(InnerError);
+    let source = std::error::Error::source(&error).unwrap();
+
+    assert_eq!(source.to_string(), "inner failure");
+}
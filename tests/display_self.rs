@@ -0,0 +1,20 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("debugging {:?}", self))]
+    Positional,
+
+    #[snafu(display("debugging {self:?}"))]
+    Inline,
+
+    #[snafu(display("debugging {value:#?}", value = &self))]
+    Named,
+}
+
+#[test]
+fn self_can_be_formatted_with_debug() {
+    assert_eq!(PositionalSnafu.build().to_string(), "debugging Positional");
+    assert_eq!(InlineSnafu.build().to_string(), "debugging Inline");
+    assert_eq!(NamedSnafu.build().to_string(), "debugging Named");
+}
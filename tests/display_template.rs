@@ -0,0 +1,67 @@
+use snafu::prelude::*;
+
+mod messages {
+    pub const OPEN: &str = "Could not open {path}";
+    pub const RETRY: &str = "{operation} failed after {attempts} attempts";
+    pub const ESCAPED: &str = "{{literal}} {name} }} {missing}";
+}
+
+#[derive(Debug, Snafu)]
+enum ConfigError {
+    #[snafu(display(template = messages::OPEN, path))]
+    OpenConfig { path: String },
+
+    #[snafu(display(template = messages::RETRY, operation = "Loading", attempts))]
+    LoadConfig { attempts: u8 },
+}
+
+#[derive(Debug, Snafu)]
+enum CacheError {
+    #[snafu(display(template = messages::OPEN, path = filename.display()))]
+    OpenCache { filename: std::path::PathBuf },
+
+    #[snafu(display(template = messages::RETRY, operation = "Flushing", attempts = count))]
+    FlushCache { count: u32 },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display(template = messages::ESCAPED, name))]
+struct EscapedError {
+    name: &'static str,
+}
+
+#[test]
+fn two_enums_share_the_same_template() {
+    let config = OpenConfigSnafu {
+        path: "/etc/app.toml",
+    }
+    .build();
+    assert_eq!(config.to_string(), "Could not open /etc/app.toml");
+
+    let cache = OpenCacheSnafu {
+        filename: "/var/cache/app",
+    }
+    .build();
+    assert_eq!(cache.to_string(), "Could not open /var/cache/app");
+}
+
+#[test]
+fn named_arguments_can_be_any_expression() {
+    let config = LoadConfigSnafu { attempts: 3u8 }.build();
+    assert_eq!(config.to_string(), "Loading failed after 3 attempts");
+
+    let cache = FlushCacheSnafu { count: 5u32 }.build();
+    assert_eq!(cache.to_string(), "Flushing failed after 5 attempts");
+}
+
+#[test]
+fn braces_can_be_escaped_and_unknown_names_are_kept() {
+    let e = EscapedSnafu { name: "value" }.build();
+    assert_eq!(e.to_string(), "{literal} value } {missing}");
+}
+
+#[test]
+fn outer_formatting_options_do_not_leak_into_arguments() {
+    let e = LoadConfigSnafu { attempts: 3u8 }.build();
+    assert_eq!(format!("{:>40}", e), "Loading failed after 3 attempts");
+}
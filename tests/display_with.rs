@@ -0,0 +1,271 @@
+use snafu::prelude::*;
+use std::fmt;
+
+mod catalog {
+    use std::fmt;
+
+    pub fn missing_user(f: &mut fmt::Formatter, user_id: &u32, region: &str) -> fmt::Result {
+        write!(f, "[E100] user {user_id} does not exist in {region}")
+    }
+
+    pub fn quota(f: &mut fmt::Formatter, limit: &u64) -> fmt::Result {
+        write!(f, "[E200] quota of {limit} exceeded")
+    }
+}
+
+fn unit_message(f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("[E300] service unavailable")
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display(with = catalog::missing_user))]
+    MissingUser { user_id: u32, region: String },
+
+    #[snafu(display(with = catalog::quota))]
+    Quota { limit: u64 },
+
+    #[snafu(display(with = unit_message))]
+    Unavailable,
+
+    #[snafu(display("Inline message for {name}"))]
+    Inline { name: String },
+}
+
+#[test]
+fn calls_the_function_with_each_field_in_order() {
+    let error = MissingUserSnafu {
+        user_id: 42u32,
+        region: "eu",
+    }
+    .build();
+
+    assert_eq!(error.to_string(), "[E100] user 42 does not exist in eu");
+}
+
+#[test]
+fn works_with_single_field_variants() {
+    let error = QuotaSnafu { limit: 10u64 }.build();
+
+    assert_eq!(error.to_string(), "[E200] quota of 10 exceeded");
+}
+
+#[test]
+fn works_with_unit_variants() {
+    assert_eq!(
+        UnavailableSnafu.build().to_string(),
+        "[E300] service unavailable",
+    );
+}
+
+#[test]
+fn other_variants_keep_their_format_strings() {
+    let error = InlineSnafu { name: "alpha" }.build();
+
+    assert_eq!(error.to_string(), "Inline message for alpha");
+}
+
+mod source {
+    use super::*;
+
+    fn with_source(f: &mut fmt::Formatter, path: &String, source: &std::io::Error) -> fmt::Result {
+        write!(f, "could not read {path}: {source}")
+    }
+
+    fn without_source(f: &mut fmt::Formatter, path: &String) -> fmt::Result {
+        write!(f, "could not read {path}")
+    }
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(display(with = with_source))]
+        WithSource {
+            path: String,
+            source: std::io::Error,
+        },
+
+        #[snafu(display(no_source), display(with = without_source))]
+        WithoutSource {
+            path: String,
+            source: std::io::Error,
+        },
+    }
+
+    fn io_error() -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "gone")
+    }
+
+    #[test]
+    fn source_is_passed_to_the_function() {
+        let error = Err::<(), _>(io_error())
+            .context(WithSourceSnafu { path: "a.txt" })
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "could not read a.txt: gone");
+    }
+
+    #[test]
+    fn source_is_omitted_when_display_excludes_it() {
+        let error = Err::<(), _>(io_error())
+            .context(WithoutSourceSnafu { path: "a.txt" })
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "could not read a.txt");
+    }
+}
+
+mod structs {
+    use super::*;
+
+    fn describe(f: &mut fmt::Formatter, code: &i32) -> fmt::Result {
+        write!(f, "exit code {code}")
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display(with = describe))]
+    struct Error {
+        code: i32,
+    }
+
+    #[test]
+    fn structs_can_use_a_display_function() {
+        assert_eq!(Snafu { code: 3 }.build().to_string(), "exit code 3");
+    }
+}
+
+mod generics {
+    use super::*;
+
+    fn describe<T: fmt::Debug>(f: &mut fmt::Formatter, value: &T) -> fmt::Result {
+        write!(f, "bad value {value:?}")
+    }
+
+    #[derive(Debug, Snafu)]
+    enum Error<T>
+    where
+        T: fmt::Debug,
+    {
+        #[snafu(display(with = describe))]
+        Invalid { value: T },
+    }
+
+    #[test]
+    fn generic_functions_are_inferred() {
+        let error: Error<&str> = InvalidSnafu { value: "x" }.build();
+
+        assert_eq!(error.to_string(), r#"bad value "x""#);
+    }
+}
+
+mod fields {
+    use super::*;
+    use std::{path::PathBuf, time::Duration};
+
+    fn seconds(d: &Duration) -> String {
+        format!("{}s", d.as_secs())
+    }
+
+    fn hex(bytes: &[u8; 2]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(display("timed out after {timeout}"))]
+        Timeout {
+            #[snafu(display(with = seconds))]
+            timeout: Duration,
+        },
+
+        #[snafu(display("could not open {path}"))]
+        Open {
+            #[snafu(display(with = std::path::Path::display))]
+            path: PathBuf,
+        },
+
+        /// checksum {checksum} did not match
+        Checksum {
+            #[snafu(display(with = hex))]
+            checksum: [u8; 2],
+        },
+
+        #[snafu(display("checksum {checksum:?} did not match"))]
+        ChecksumDebug {
+            #[snafu(display(with = hex))]
+            checksum: [u8; 2],
+        },
+
+        #[snafu(display("timed out after {timeout}", timeout = timeout.as_millis()))]
+        Explicit {
+            #[snafu(display(with = seconds))]
+            timeout: Duration,
+        },
+    }
+
+    #[test]
+    fn shorthand_captures_use_the_field_function() {
+        let error = TimeoutSnafu {
+            timeout: Duration::from_secs(5),
+        }
+        .build();
+
+        assert_eq!(error.to_string(), "timed out after 5s");
+    }
+
+    #[test]
+    fn non_copy_fields_are_passed_by_reference() {
+        let error = OpenSnafu {
+            path: PathBuf::from("/tmp/a.txt"),
+        }
+        .build();
+
+        assert_eq!(error.to_string(), "could not open /tmp/a.txt");
+    }
+
+    #[test]
+    fn doc_comment_captures_use_the_field_function() {
+        let error = ChecksumSnafu {
+            checksum: [0xbe, 0xef],
+        }
+        .build();
+
+        assert_eq!(error.to_string(), "checksum beef did not match");
+    }
+
+    #[test]
+    fn format_specifiers_apply_to_the_transformed_value() {
+        let error = ChecksumDebugSnafu {
+            checksum: [0xbe, 0xef],
+        }
+        .build();
+
+        assert_eq!(error.to_string(), r#"checksum "beef" did not match"#);
+    }
+
+    #[test]
+    fn explicit_arguments_are_not_transformed() {
+        let error = ExplicitSnafu {
+            timeout: Duration::from_secs(5),
+        }
+        .build();
+
+        assert_eq!(error.to_string(), "timed out after 5000");
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("waited {waited}"))]
+    struct StructError {
+        #[snafu(display(with = seconds))]
+        waited: Duration,
+    }
+
+    #[test]
+    fn structs_can_transform_fields() {
+        let error = StructSnafu {
+            waited: Duration::from_secs(2),
+        }
+        .build();
+
+        assert_eq!(error.to_string(), "waited 2s");
+    }
+}
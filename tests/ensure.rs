@@ -20,3 +20,247 @@ fn accepts_trailing_commas() {
     }
     let _ = example(42);
 }
+
+#[test]
+fn check_evaluates_to_a_result() {
+    let results: Vec<Result<i32>> = [1, -1, 2]
+        .iter()
+        .map(|&a_long_piece_of_information| {
+            check!(
+                a_long_piece_of_information > 0,
+                AVeryLongVariantNameSnafu {
+                    a_long_piece_of_information,
+                },
+            )?;
+            Ok(a_long_piece_of_information * 2)
+        })
+        .collect();
+
+    assert!(matches!(results[0], Ok(2)));
+    assert!(matches!(
+        results[1],
+        Err(Error::AVeryLongVariantName {
+            a_long_piece_of_information: -1
+        })
+    ));
+    assert!(matches!(results[2], Ok(4)));
+}
+
+#[test]
+fn check_works_in_a_match_guard() {
+    fn classify(value: i32) -> &'static str {
+        match value {
+            v if check!(
+                v > 0,
+                AVeryLongVariantNameSnafu {
+                    a_long_piece_of_information: v
+                }
+            )
+            .is_ok() =>
+            {
+                "positive"
+            }
+            _ => "not positive",
+        }
+    }
+
+    assert_eq!(classify(1), "positive");
+    assert_eq!(classify(-1), "not positive");
+}
+
+#[test]
+fn ensure_or_evaluates_the_fallback_with_the_error() {
+    let mut valid = Vec::new();
+    let mut errors = Vec::new();
+
+    for &a_long_piece_of_information in &[1, -1, 2] {
+        ensure_or!(
+            a_long_piece_of_information > 0,
+            AVeryLongVariantNameSnafu {
+                a_long_piece_of_information,
+            },
+            |e| {
+                errors.push(e);
+                continue;
+            },
+        );
+        valid.push(a_long_piece_of_information);
+    }
+
+    assert_eq!(valid, [1, 2]);
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn ensure_or_accepts_a_fallback_without_the_error() {
+    let mut skipped = 0;
+
+    for &a_long_piece_of_information in &[1, -1, -2] {
+        ensure_or!(
+            a_long_piece_of_information > 0,
+            AVeryLongVariantNameSnafu {
+                a_long_piece_of_information,
+            },
+            skipped += 1,
+        );
+    }
+
+    assert_eq!(skipped, 2);
+}
+
+mod borrows {
+    use snafu::prelude::*;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        NotReady { key: String },
+        Missing { key: String },
+        Unparsable { source: std::num::ParseIntError },
+    }
+
+    struct Job {
+        ready: bool,
+    }
+
+    fn jobs() -> HashMap<String, Job> {
+        let mut jobs = HashMap::new();
+        jobs.insert("alpha".to_owned(), Job { ready: true });
+        jobs.insert("beta".to_owned(), Job { ready: false });
+        jobs
+    }
+
+    #[test]
+    fn predicate_borrows_end_before_the_selector_moves() {
+        fn example(jobs: &HashMap<String, Job>, key: String) -> Result<(), Error> {
+            ensure!(
+                matches!(jobs.get(&key), Some(job) if job.ready),
+                NotReadySnafu { key },
+            );
+            Ok(())
+        }
+
+        let jobs = jobs();
+        assert!(example(&jobs, "alpha".to_owned()).is_ok());
+        assert!(matches!(
+            example(&jobs, "beta".to_owned()),
+            Err(Error::NotReady { key }) if key == "beta",
+        ));
+    }
+
+    #[test]
+    fn predicate_mutable_borrows_end_before_the_selector_moves() {
+        fn example(jobs: &mut HashMap<String, Job>, key: String) -> Result<(), Error> {
+            ensure!(jobs.remove(&key).is_some(), MissingSnafu { key });
+            Ok(())
+        }
+
+        let mut jobs = jobs();
+        assert!(example(&mut jobs, "alpha".to_owned()).is_ok());
+        assert!(matches!(
+            example(&mut jobs, "alpha".to_owned()),
+            Err(Error::Missing { key }) if key == "alpha",
+        ));
+    }
+
+    #[test]
+    fn predicate_temporaries_may_use_the_question_mark_operator() {
+        fn example(jobs: &HashMap<String, Job>, key: String) -> Result<(), Error> {
+            ensure!(
+                jobs.get(&key.parse::<u8>().context(UnparsableSnafu)?.to_string())
+                    .is_some(),
+                MissingSnafu { key },
+            );
+            Ok(())
+        }
+
+        let mut jobs = jobs();
+        jobs.insert("1".to_owned(), Job { ready: true });
+
+        assert!(example(&jobs, "1".to_owned()).is_ok());
+        assert!(matches!(
+            example(&jobs, "2".to_owned()),
+            Err(Error::Missing { key }) if key == "2",
+        ));
+        assert!(matches!(
+            example(&jobs, "alpha".to_owned()),
+            Err(Error::Unparsable { .. }),
+        ));
+    }
+
+    #[test]
+    fn predicate_is_evaluated_exactly_once() {
+        fn example(calls: &mut u32, key: String) -> Result<(), Error> {
+            ensure!(
+                {
+                    *calls += 1;
+                    false
+                },
+                MissingSnafu { key },
+            );
+            Ok(())
+        }
+
+        let mut calls = 0;
+        assert!(example(&mut calls, "alpha".to_owned()).is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn check_predicate_borrows_end_before_the_selector_moves() {
+        let jobs = jobs();
+        let key = "beta".to_owned();
+
+        let result = check!(
+            matches!(jobs.get(&key), Some(job) if job.ready),
+            NotReadySnafu { key },
+        );
+
+        assert!(matches!(result, Err(Error::NotReady { key }) if key == "beta"));
+    }
+}
+
+mod location {
+    use snafu::{prelude::*, Location};
+
+    #[derive(Debug, Snafu)]
+    struct Error {
+        #[snafu(implicit)]
+        location: Location,
+    }
+
+    #[test]
+    fn implicit_location_is_the_macro_call_site() {
+        fn example() -> Result<(), Error> {
+            ensure!(false, Snafu);
+            Ok(())
+        }
+
+        let expected_line = line!() - 4;
+        let error = example().unwrap_err();
+
+        assert_eq!(error.location.file, file!());
+        assert_eq!(error.location.line, expected_line);
+    }
+
+    #[test]
+    fn check_implicit_location_is_the_macro_call_site() {
+        let result = check!(false, Snafu);
+        let expected_line = line!() - 1;
+        let error = result.unwrap_err();
+
+        assert_eq!(error.location.file, file!());
+        assert_eq!(error.location.line, expected_line);
+    }
+
+    #[test]
+    fn ensure_or_implicit_location_is_the_macro_call_site() {
+        let mut error = None;
+        ensure_or!(false, Snafu, |e| error = Some(e));
+        let expected_line = line!() - 1;
+        let error = error.unwrap();
+
+        assert_eq!(error.location.file, file!());
+        assert_eq!(error.location.line, expected_line);
+    }
+}
@@ -0,0 +1,74 @@
+// The failure path of `ensure!` is a single out-of-line function
+// that is shared between every use of the macro with the same
+// context selector and error types, instead of being expanded into
+// every call site. Look for its symbols in the symbol table of this
+// test binary to make sure that stays true.
+//
+// This needs `nm` from binutils, so it is ignored by default and run
+// explicitly in CI with `--ignored`.
+#![cfg(target_os = "linux")]
+
+use snafu::prelude::*;
+use std::{collections::BTreeSet, process::Command};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    OutOfRange { value: i32 },
+}
+
+fn check_one(value: i32) -> Result<(), Error> {
+    ensure!(value > 0, OutOfRangeSnafu { value });
+    ensure!(value < 100, OutOfRangeSnafu { value });
+    Ok(())
+}
+
+fn check_two(value: i32) -> Result<(), Error> {
+    ensure!(value % 2 == 0, OutOfRangeSnafu { value });
+    ensure!(value % 3 == 0, OutOfRangeSnafu { value });
+    Ok(())
+}
+
+fn ensure_failed_symbols() -> BTreeSet<String> {
+    let exe = std::env::current_exe().expect("Unable to locate the test binary");
+    let output = Command::new("nm")
+        .arg("--defined-only")
+        .arg(exe)
+        .output()
+        .unwrap_or_else(|e| panic!("Unable to run nm (from binutils) on the test binary: {}", e));
+    assert!(output.status.success(), "nm failed: {:?}", output);
+
+    // Match only the function itself by its mangled path segment
+    // (legacy `..NNname17h<hash>E` or v0 `..NN_name`), not closures
+    // or other items nested inside it.
+    let legacy = "15__ensure_failed17h";
+    let v0 = "15___ensure_failed";
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let _address = parts.next()?;
+            let kind = parts.next()?;
+            let name = parts.next()?;
+            Some((kind, name))
+        })
+        .filter(|(kind, _)| matches!(*kind, "t" | "T" | "W" | "w"))
+        .map(|(_, name)| name)
+        .filter(|name| name.contains(legacy) || name.ends_with(v0))
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+#[ignore = "requires nm from binutils; run with --ignored"]
+fn failure_path_is_not_duplicated_per_call_site() {
+    assert!(check_one(6).is_ok());
+    assert!(check_one(0).is_err());
+    assert!(check_one(100).is_err());
+    assert!(check_two(6).is_ok());
+    assert!(check_two(4).is_err());
+    assert!(check_two(3).is_err());
+
+    let symbols = ensure_failed_symbols();
+    assert_eq!(symbols.len(), 1, "{:#?}", symbols);
+}
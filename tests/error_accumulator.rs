@@ -0,0 +1,106 @@
+use snafu::{prelude::*, Backtrace, ErrorAccumulator, ErrorCompat, MultipleErrors};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("The name must not be empty"))]
+    EmptyName,
+
+    #[snafu(display("The port {port} is reserved"))]
+    ReservedPort { port: u16, backtrace: Backtrace },
+
+    #[snafu(display("The timeout must be positive"))]
+    NegativeTimeout,
+}
+
+fn validate(name: &str, port: u16, timeout: i32) -> Result<(), MultipleErrors<Error>> {
+    let mut errors = ErrorAccumulator::new();
+
+    errors.ensure(!name.is_empty(), EmptyNameSnafu);
+    errors.ensure(port >= 1024, ReservedPortSnafu { port });
+    if timeout < 0 {
+        errors.push(NegativeTimeoutSnafu.build());
+    }
+
+    errors.finish()
+}
+
+#[test]
+fn no_errors_is_ok() {
+    assert!(validate("server", 8080, 10).is_ok());
+}
+
+#[test]
+fn every_failure_is_reported() {
+    let error = validate("", 80, -1).unwrap_err();
+
+    assert!(matches!(
+        error.errors(),
+        [
+            Error::EmptyName,
+            Error::ReservedPort { port: 80, .. },
+            Error::NegativeTimeout,
+        ],
+    ));
+}
+
+#[test]
+fn display_is_a_numbered_list() {
+    let error = validate("", 80, -1).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "3 errors occurred:\n\
+         1. The name must not be empty\n\
+         2. The port 80 is reserved\n\
+         3. The timeout must be positive",
+    );
+}
+
+#[test]
+fn display_of_a_single_error() {
+    let error = validate("server", 8080, -1).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "1 error occurred:\n1. The timeout must be positive",
+    );
+}
+
+#[test]
+fn backtrace_is_the_first_available_backtrace() {
+    let error = validate("", 80, 10).unwrap_err();
+
+    assert!(ErrorCompat::backtrace(&error).is_some());
+
+    let error = validate("", 8080, -1).unwrap_err();
+
+    assert!(ErrorCompat::backtrace(&error).is_none());
+}
+
+#[test]
+fn ensure_returns_the_predicate() {
+    let mut errors = ErrorAccumulator::<Error>::default();
+
+    assert!(errors.ensure(true, EmptyNameSnafu));
+    assert!(errors.is_empty());
+    assert!(!errors.ensure(false, EmptyNameSnafu));
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn is_compatible_with_std_error_trait() {
+    fn expects_std_trait<E: std::error::Error>() {}
+
+    expects_std_trait::<MultipleErrors<Error>>();
+}
+
+#[test]
+fn errors_can_be_taken_back() {
+    let error = validate("", 80, 10).unwrap_err();
+
+    let names: Vec<_> = error.iter().map(|e| e.to_string()).collect();
+    assert_eq!(names.len(), 2);
+
+    let errors = error.into_errors();
+    assert_eq!(errors.len(), 2);
+}
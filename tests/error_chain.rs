@@ -36,9 +36,9 @@ fn chain_compat_iterates() {
 
     let errors: Vec<_> = ChainCompat::new(&error).collect();
 
-    assert_eq_debug(&errors[0], &error);
-    assert_eq_debug(&errors[1], &middle_error);
-    assert_eq_debug(&errors[2], &bottom_error);
+    assert_eq_debug(errors[0], &error);
+    assert_eq_debug(errors[1], &middle_error);
+    assert_eq_debug(errors[2], &bottom_error);
 }
 
 #[test]
@@ -51,9 +51,9 @@ fn errorcompat_chain_iterates() {
 
     let errors: Vec<_> = ErrorCompat::iter_chain(&error).collect();
 
-    assert_eq_debug(&errors[0], &error);
-    assert_eq_debug(&errors[1], &middle_error);
-    assert_eq_debug(&errors[2], &bottom_error);
+    assert_eq_debug(errors[0], &error);
+    assert_eq_debug(errors[1], &middle_error);
+    assert_eq_debug(errors[2], &bottom_error);
 }
 
 #[test]
@@ -70,3 +70,229 @@ fn can_downcast_chain_values() {
     assert_eq!(Some(&middle_error), errors[1].downcast_ref::<MiddleError>());
     assert_eq!(Some(&bottom_error), errors[2].downcast_ref::<LeafError>());
 }
+
+mod cycles {
+    use snafu::{ChainCompat, ChainTruncated, CleanedErrorText, Report, DEFAULT_CHAIN_LIMIT};
+    use std::{
+        error::Error,
+        fmt,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    /// A buggy error that returns itself as its source.
+    #[derive(Debug)]
+    struct SelfCycle;
+
+    impl fmt::Display for SelfCycle {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("self cycle")
+        }
+    }
+
+    impl Error for SelfCycle {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(self)
+        }
+    }
+
+    /// Two buggy errors that return each other as their sources.
+    #[derive(Debug)]
+    struct Ping;
+    #[derive(Debug)]
+    struct Pong;
+
+    static PING: Ping = Ping;
+    static PONG: Pong = Pong;
+
+    impl fmt::Display for Ping {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("ping")
+        }
+    }
+
+    impl fmt::Display for Pong {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("pong")
+        }
+    }
+
+    impl Error for Ping {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&PONG)
+        }
+    }
+
+    impl Error for Pong {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&PING)
+        }
+    }
+
+    /// A buggy error with data that returns itself as its source.
+    #[derive(Debug)]
+    struct NamedCycle {
+        name: &'static str,
+    }
+
+    impl fmt::Display for NamedCycle {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.name)
+        }
+    }
+
+    impl Error for NamedCycle {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(self)
+        }
+    }
+
+    /// A correct error whose source is stored at the same address and
+    /// has the same message.
+    #[derive(Debug)]
+    struct Wrapper {
+        inner: Inner,
+    }
+
+    #[derive(Debug)]
+    struct Inner {
+        code: u32,
+    }
+
+    impl fmt::Display for Wrapper {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Display::fmt(&self.inner, f)
+        }
+    }
+
+    impl fmt::Display for Inner {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "inner {}", self.code)
+        }
+    }
+
+    impl Error for Wrapper {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.inner)
+        }
+    }
+
+    impl Error for Inner {}
+
+    /// A correct error that stores its source at the same address and
+    /// counts how often it is displayed.
+    #[derive(Debug)]
+    struct Layer<T>(T);
+
+    static LAYER_DISPLAYS: AtomicUsize = AtomicUsize::new(0);
+
+    impl<T> fmt::Display for Layer<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            LAYER_DISPLAYS.fetch_add(1, Ordering::SeqCst);
+            f.write_str("layer")
+        }
+    }
+
+    impl<T: Error + 'static> Error for Layer<T> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn self_cycles_yield_the_error_and_then_the_sentinel() {
+        // The error passed in may have a different vtable than the
+        // one its `source` returns, so the cycle can be found one
+        // step late.
+        let error = NamedCycle { name: "cyc" };
+        let chain: Vec<_> = ChainCompat::new(&error).collect();
+
+        let (last, errors) = chain.split_last().unwrap();
+        assert!(matches!(errors.len(), 1 | 2), "{} errors", errors.len());
+        assert!(errors.iter().all(|e| e.to_string() == "cyc"));
+        let last = last.downcast_ref::<ChainTruncated>();
+        assert_eq!(last, Some(&ChainTruncated::Cycle));
+
+        let text = snafu::display_chain(&error).to_string();
+        assert!(
+            text == "cyc: <cycle detected>" || text == "cyc: cyc: <cycle detected>",
+            "{}",
+            text,
+        );
+    }
+
+    #[test]
+    fn sources_at_the_same_address_are_not_cycles() {
+        let error = Wrapper {
+            inner: Inner { code: 7 },
+        };
+        let chain: Vec<_> = ChainCompat::new(&error).map(|e| e.to_string()).collect();
+
+        assert_eq!(chain, ["inner 7", "inner 7"]);
+    }
+
+    #[test]
+    fn sources_at_the_same_address_are_not_formatted_to_find_cycles() {
+        let error = Layer(Layer(Layer(Layer(Layer(Layer(Inner { code: 7 }))))));
+        let chain: Vec<_> = ChainCompat::new(&error).map(|e| e.to_string()).collect();
+        let displays = LAYER_DISPLAYS.load(Ordering::SeqCst) - 6;
+
+        assert_eq!(chain.len(), 7);
+        assert_eq!(chain[6], "inner 7");
+        assert_eq!(displays, 0);
+    }
+
+    #[test]
+    fn self_cycles_end_with_a_sentinel() {
+        let chain: Vec<_> = ChainCompat::new(&SelfCycle)
+            .map(|e| e.to_string())
+            .collect();
+
+        assert_eq!(chain, ["self cycle", "<cycle detected>"]);
+    }
+
+    #[test]
+    fn longer_cycles_stop_at_the_limit() {
+        let chain: Vec<_> = ChainCompat::new(&PING).collect();
+
+        assert_eq!(chain.len(), DEFAULT_CHAIN_LIMIT + 1);
+        let last = chain.last().unwrap().downcast_ref::<ChainTruncated>();
+        assert_eq!(last, Some(&ChainTruncated::Limit));
+    }
+
+    #[test]
+    fn the_limit_can_be_configured() {
+        let chain: Vec<_> = ChainCompat::with_limit(&PING, 3)
+            .map(|e| e.to_string())
+            .collect();
+
+        assert_eq!(chain, ["ping", "pong", "ping", "<chain truncated>"]);
+    }
+
+    #[test]
+    fn cycles_take_priority_over_the_limit() {
+        let chain: Vec<_> = ChainCompat::with_limit(&SelfCycle, 1)
+            .map(|e| e.to_string())
+            .collect();
+
+        assert_eq!(chain, ["self cycle", "<cycle detected>"]);
+    }
+
+    #[test]
+    fn cleaned_error_text_terminates() {
+        let messages: Vec<_> = CleanedErrorText::new(&SelfCycle)
+            .map(|(_, text, _)| text)
+            .collect();
+
+        assert_eq!(messages, ["self cycle", "<cycle detected>"]);
+
+        let count = CleanedErrorText::with_limit(&PING, 5).count();
+        assert_eq!(count, 6);
+    }
+
+    #[test]
+    fn report_terminates() {
+        let report = Report::from_error(SelfCycle).to_string();
+
+        assert!(report.contains("<cycle detected>"), "{}", report);
+    }
+}
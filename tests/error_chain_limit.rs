@@ -0,0 +1,99 @@
+use snafu::{prelude::*, ChainCompat, FromString};
+
+#[derive(Debug, Snafu)]
+#[snafu(whatever, error_chain_limit = 3, display("{message}"))]
+struct LimitedError {
+    message: String,
+    #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+    source: Option<Box<dyn std::error::Error>>,
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(whatever, error_chain_limit = 1, display("{message}"))]
+    Generic {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error + Send + Sync>, Some)))]
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("leaf"))]
+struct LeafError;
+
+fn wrap_repeatedly(times: usize) -> LimitedError {
+    let mut error = LimitedError::with_source(Box::new(LeafError), "level 1".into());
+    for level in 2..=times {
+        error = LimitedError::with_source(Box::new(error), format!("level {}", level));
+    }
+    error
+}
+
+fn chain_text(error: &dyn std::error::Error) -> Vec<String> {
+    ChainCompat::new(error).map(ToString::to_string).collect()
+}
+
+#[test]
+fn chains_within_the_limit_are_kept_intact() {
+    let error = wrap_repeatedly(3);
+
+    assert_eq!(
+        chain_text(&error),
+        ["level 3", "level 2", "level 1", "leaf"]
+    );
+
+    let deepest = ChainCompat::new(&error).last().unwrap();
+    assert!(deepest.is::<LeafError>());
+}
+
+#[test]
+fn the_deepest_sources_beyond_the_limit_are_dropped() {
+    let error = wrap_repeatedly(4);
+
+    assert_eq!(
+        chain_text(&error),
+        ["level 4", "level 3", "level 2", "level 1 (and 1 more)"]
+    );
+}
+
+#[test]
+fn the_summary_counts_every_dropped_error() {
+    let error = LimitedError::with_source(Box::new(wrap_repeatedly(3)), "level 4".into());
+    let error = LimitedError::with_source(Box::new(error), "level 5".into());
+
+    assert_eq!(
+        chain_text(&error),
+        ["level 5", "level 4", "level 3", "level 2 (and 1 more)"]
+    );
+}
+
+#[test]
+fn chain_length_is_bounded_no_matter_how_often_errors_are_wrapped() {
+    let error = wrap_repeatedly(1000);
+    let chain = chain_text(&error);
+
+    assert_eq!(chain.len(), 4);
+    assert_eq!(chain[0], "level 1000");
+    assert_eq!(chain[3], "level 997 (and 1 more)");
+}
+
+#[test]
+fn errors_without_a_source_are_unaffected() {
+    let error = LimitedError::without_source("alone".into());
+
+    assert_eq!(chain_text(&error), ["alone"]);
+}
+
+#[test]
+fn thread_safe_sources_can_be_limited() {
+    let inner = LimitedError::without_source("inner".into());
+    let inner = LimitedError::with_source(Box::new(inner), "middle".into());
+    let inner = snafu::WhateverShared::from_whatever(snafu::Whatever::with_source(
+        Box::new(inner),
+        "outer".into(),
+    ));
+    let error = Error::with_source(Box::new(inner), "top".into());
+
+    assert_eq!(chain_text(&error), ["top", "outer (and 2 more)"]);
+}
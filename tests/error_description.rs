@@ -0,0 +1,94 @@
+use snafu::{prelude::*, ErrorDescription};
+
+mod enums {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(
+            display("Could not find the file {path}"),
+            error_description = "file not found"
+        )]
+        NotFound {
+            path: String,
+        },
+
+        #[snafu(error_description = "timed out")]
+        Timeout {
+            seconds: u64,
+        },
+
+        Unexpected,
+    }
+
+    #[test]
+    fn description_is_the_attribute_value() {
+        let error = NotFoundSnafu { path: "/tmp/a" }.build();
+
+        assert_eq!(ErrorDescription::description(&error), "file not found");
+    }
+
+    #[test]
+    fn description_differs_from_display() {
+        let error = NotFoundSnafu { path: "/tmp/a" }.build();
+
+        assert_eq!(error.to_string(), "Could not find the file /tmp/a");
+        assert_ne!(ErrorDescription::description(&error), error.to_string());
+    }
+
+    #[test]
+    fn variants_without_the_attribute_use_the_variant_name() {
+        let error = UnexpectedSnafu.build();
+
+        assert_eq!(ErrorDescription::description(&error), "Unexpected");
+    }
+
+    #[test]
+    fn works_through_boxes_and_references() {
+        let error: Box<dyn ErrorDescription> = Box::new(TimeoutSnafu { seconds: 1u64 }.build());
+
+        fn description_of(error: &impl ErrorDescription) -> String {
+            error.description().to_string()
+        }
+
+        assert_eq!(description_of(&&error), "timed out");
+    }
+}
+
+mod structs {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("The disk {name} is full"), error_description = "disk full")]
+    struct Error {
+        name: String,
+    }
+
+    #[test]
+    fn struct_reports_its_description() {
+        let error = Snafu { name: "sda" }.build();
+
+        assert_eq!(ErrorDescription::description(&error), "disk full");
+        assert_eq!(error.to_string(), "The disk sda is full");
+    }
+}
+
+mod generics {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error<T>
+    where
+        T: std::fmt::Debug,
+    {
+        #[snafu(error_description = "invalid value")]
+        Invalid { value: T },
+    }
+
+    #[test]
+    fn generic_errors_report_their_description() {
+        let error: Error<i32> = InvalidSnafu { value: 42 }.build();
+
+        assert_eq!(ErrorDescription::description(&error), "invalid value");
+    }
+}
@@ -0,0 +1,211 @@
+mod errors {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = enum_impls, visibility(pub))]
+    pub enum EnumError {
+        #[snafu(display("Could not open {path}"))]
+        Open {
+            path: String,
+            source: std::io::Error,
+        },
+
+        Closed,
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = struct_impls, visibility(pub))]
+    #[snafu(display("The value {value} is too large"))]
+    pub struct StructError {
+        value: i32,
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = opaque_impls)]
+    pub struct OpaqueError(EnumError);
+
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = generic_impls, visibility(pub))]
+    pub enum GenericError<T>
+    where
+        T: std::fmt::Display,
+    {
+        #[snafu(display("Unexpected value {value}"))]
+        Unexpected { value: T },
+    }
+
+    // The modules are created next to the error types
+    #[allow(unused_imports)]
+    use self::{enum_impls as _, generic_impls as _, opaque_impls as _, struct_impls as _};
+
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = crate::errors::impls::path_error, visibility(pub))]
+    pub enum PathError {
+        #[snafu(display("Could not parse {input}"))]
+        Parse {
+            input: String,
+            source: std::num::ParseIntError,
+        },
+
+        #[snafu(context(builder))]
+        Missing { name: String },
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = crate::errors::impls::path_struct_error)]
+    #[snafu(module(path_struct), display("The value {value} is negative"))]
+    pub struct PathStructError {
+        value: i32,
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = self::impls::path_opaque_error)]
+    pub struct PathOpaqueError(PathError);
+
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = crate::errors::impls::path_whatever_error)]
+    #[snafu(whatever, display("{message}"))]
+    pub struct PathWhateverError {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+    }
+
+    // Modules with the same name do not clash, because each macro is
+    // named after its error type
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = crate::errors::alpha::impls, visibility(pub))]
+    pub enum AlphaError {
+        Alpha,
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(error_impl = crate::errors::beta::impls, visibility(pub))]
+    pub enum BetaError {
+        Beta,
+    }
+
+    pub mod alpha {
+        pub mod impls {
+            crate::errors::alpha_error_impls!(crate::errors);
+        }
+    }
+
+    pub mod beta {
+        pub mod impls {
+            crate::errors::beta_error_impls!(crate::errors);
+        }
+    }
+
+    pub fn negative(value: i32) -> Result<(), PathStructError> {
+        ensure!(value >= 0, path_struct::PathStructSnafu { value });
+        Ok(())
+    }
+
+    // A module elsewhere receives the implementations by invoking
+    // the macro named after the error type
+    pub mod impls {
+        pub mod path_error {
+            crate::errors::path_error_impls!(crate::errors);
+        }
+
+        pub mod path_struct_error {
+            crate::errors::path_struct_error_impls!(crate::errors);
+        }
+
+        pub mod path_opaque_error {
+            crate::errors::path_opaque_error_impls!(crate::errors);
+        }
+
+        pub mod path_whatever_error {
+            crate::errors::path_whatever_error_impls!(crate::errors);
+        }
+    }
+}
+
+use errors::*;
+use snafu::{prelude::*, ErrorCompat};
+
+fn open() -> Result<(), EnumError> {
+    let error = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+    Err(error).context(OpenSnafu { path: "/tmp/data" })
+}
+
+#[test]
+fn enum_impls_are_generated() {
+    let error = open().unwrap_err();
+
+    assert_eq!(error.to_string(), "Could not open /tmp/data");
+    assert!(std::error::Error::source(&error).is_some());
+    assert!(ErrorCompat::backtrace(&error).is_none());
+    assert_eq!(ClosedSnafu.build().to_string(), "Closed");
+}
+
+#[test]
+fn struct_impls_are_generated() {
+    fn example(value: i32) -> Result<(), StructError> {
+        ensure!(value < 10, StructSnafu { value });
+        Ok(())
+    }
+
+    let error = example(42).unwrap_err();
+    assert_eq!(error.to_string(), "The value 42 is too large");
+}
+
+#[test]
+fn tuple_struct_impls_are_generated() {
+    let error = OpaqueError::from(ClosedSnafu.build());
+    assert_eq!(error.to_string(), "Closed");
+}
+
+#[test]
+fn generic_impls_are_generated() {
+    let error: GenericError<i32> = UnexpectedSnafu { value: 7 }.build();
+    assert_eq!(error.to_string(), "Unexpected value 7");
+}
+
+#[test]
+fn impls_are_generated_in_a_module_given_by_path() {
+    fn parse(input: &str) -> Result<i32, PathError> {
+        input.parse().context(ParseSnafu { input })
+    }
+
+    let error = parse("x").unwrap_err();
+    assert_eq!(error.to_string(), "Could not parse x");
+    assert!(std::error::Error::source(&error).is_some());
+
+    let error = MissingSnafu::builder()
+        .name("alpha")
+        .build_selector()
+        .build();
+    assert_eq!(error.to_string(), "Missing");
+}
+
+#[test]
+fn struct_impls_are_generated_in_a_module_given_by_path() {
+    let error = negative(-1).unwrap_err();
+    assert_eq!(error.to_string(), "The value -1 is negative");
+
+    let error = PathOpaqueError::from(MissingSnafu { name: "beta" }.build());
+    assert_eq!(error.to_string(), "Missing");
+
+    fn example() -> Result<(), PathWhateverError> {
+        whatever!("Something went wrong")
+    }
+    assert_eq!(example().unwrap_err().to_string(), "Something went wrong");
+}
+
+#[test]
+fn impl_modules_with_the_same_name_do_not_clash() {
+    assert_eq!(AlphaSnafu.build().to_string(), "Alpha");
+    assert_eq!(BetaSnafu.build().to_string(), "Beta");
+}
+
+#[test]
+fn error_types_stay_in_place_next_to_the_impl_modules() {
+    #[allow(unused_imports)]
+    use errors::impls::{path_error as _, path_struct_error as _};
+
+    let _: Option<errors::PathError> = None;
+    let _: Option<errors::PathStructError> = None;
+}
@@ -0,0 +1,82 @@
+use snafu::{prelude::*, ErrorKind, HasErrorKind, IntoError};
+
+mod enums {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(kind = "network")]
+        Connect {
+            host: String,
+        },
+
+        #[snafu(kind = "network")]
+        Timeout,
+
+        #[snafu(kind = "io")]
+        ReadConfig {
+            source: std::io::Error,
+        },
+
+        #[snafu(kind = "logic")]
+        InvalidState,
+
+        Unexpected,
+    }
+
+    #[test]
+    fn each_variant_reports_its_kind() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+
+        assert_eq!(ConnectSnafu { host: "a" }.build().kind(), "network");
+        assert_eq!(ReadConfigSnafu.into_error(io_error).kind(), "io");
+        assert_eq!(InvalidStateSnafu.build().kind(), "logic");
+    }
+
+    #[test]
+    fn variants_can_share_a_kind() {
+        let connect = ConnectSnafu { host: "a" }.build();
+        let timeout = TimeoutSnafu.build();
+
+        assert_eq!(connect.error_kind(), ErrorKind::Network);
+        assert_eq!(connect.error_kind(), timeout.error_kind());
+    }
+
+    #[test]
+    fn variants_without_the_attribute_are_unknown() {
+        let error = UnexpectedSnafu.build();
+
+        assert_eq!(error.error_kind(), ErrorKind::Unknown);
+        assert_eq!(error.kind(), "unknown");
+    }
+
+    #[test]
+    fn works_through_boxes_and_references() {
+        let error: Box<dyn HasErrorKind> = Box::new(TimeoutSnafu.build());
+
+        fn kind_of(error: impl HasErrorKind) -> &'static str {
+            error.kind()
+        }
+
+        assert_eq!(kind_of(&error), "network");
+    }
+}
+
+mod structs {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(kind = "io")]
+    struct Error;
+
+    #[test]
+    fn struct_reports_its_kind() {
+        assert_eq!(Snafu.build().kind(), "io");
+    }
+}
+
+#[test]
+fn kind_displays_its_name() {
+    assert_eq!(ErrorKind::Network.to_string(), "network");
+    assert_eq!(ErrorKind::Logic.as_str(), "logic");
+}
@@ -0,0 +1,77 @@
+use snafu::prelude::*;
+
+mod low {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(visibility(pub))]
+    pub enum Error {
+        Timeout { seconds: u64 },
+        Refused { source: std::io::Error },
+        NotFound { path: String, attempts: u8 },
+        Closed,
+        Other { message: String },
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    TimedOut { seconds: u64 },
+    Unreachable { source: std::io::Error },
+    Missing { path: String },
+    Disconnected,
+    Unknown { details: String },
+}
+
+fn convert(e: low::Error) -> Error {
+    snafu::error_map!(e: low::Error => Error {
+        Timeout { seconds } => TimedOut { seconds },
+        Refused { source } => Unreachable { source },
+        NotFound { path, .. } => Missing { path },
+        Closed => Disconnected,
+        Other { message } => Unknown { details: message.to_uppercase() }
+    })
+}
+
+#[test]
+fn converts_every_variant() {
+    use std::io;
+
+    assert!(matches!(
+        convert(low::TimeoutSnafu { seconds: 5u64 }.build()),
+        Error::TimedOut { seconds: 5 },
+    ));
+    assert!(matches!(
+        convert(low::Error::Refused {
+            source: io::Error::new(io::ErrorKind::ConnectionRefused, "refused"),
+        }),
+        Error::Unreachable { source } if source.kind() == io::ErrorKind::ConnectionRefused,
+    ));
+    assert!(matches!(
+        convert(low::NotFoundSnafu { path: "/a", attempts: 3u8 }.build()),
+        Error::Missing { path } if path == "/a",
+    ));
+    assert!(matches!(
+        convert(low::ClosedSnafu.build()),
+        Error::Disconnected,
+    ));
+    assert!(matches!(
+        convert(low::OtherSnafu { message: "oops" }.build()),
+        Error::Unknown { details } if details == "OOPS",
+    ));
+}
+
+#[test]
+fn accepts_a_parenthesized_expression() {
+    let errors = vec![low::ClosedSnafu.build()];
+
+    let converted = snafu::error_map!((errors.into_iter().next().unwrap()): low::Error => Error {
+        Timeout { seconds } => TimedOut { seconds },
+        Refused { source } => Unreachable { source },
+        NotFound { path, .. } => Missing { path },
+        Closed => Disconnected,
+        Other { message } => Unknown { details: message },
+    });
+
+    assert!(matches!(converted, Error::Disconnected));
+}
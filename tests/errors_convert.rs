@@ -0,0 +1,51 @@
+use snafu::{errors::convert, prelude::*};
+
+#[derive(Debug, Snafu)]
+#[snafu(display("The disk is full"))]
+struct DiskFullError;
+
+#[derive(Debug, Snafu)]
+enum SaveError {
+    #[snafu(context(false))]
+    DiskFull { source: DiskFullError },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("The value {value} is invalid"))]
+struct InvalidError {
+    value: i32,
+}
+
+#[test]
+fn wrap_converts_into_the_outer_error() {
+    let e: SaveError = convert::wrap(DiskFullError);
+
+    assert!(matches!(e, SaveError::DiskFull { .. }));
+}
+
+#[test]
+fn try_unwrap_recovers_the_same_type() {
+    let e = InvalidSnafu { value: 42 }.build();
+
+    let e = convert::try_unwrap::<InvalidError, _>(e).unwrap();
+    assert_eq!(e.value, 42);
+}
+
+#[test]
+fn try_unwrap_returns_other_types_unchanged() {
+    let e = InvalidSnafu { value: 42 }.build();
+
+    let e = convert::try_unwrap::<DiskFullError, _>(e).unwrap_err();
+    assert_eq!(e.value, 42);
+}
+
+#[test]
+fn convert_chain_converts_each_error() {
+    let errors = vec![DiskFullError, DiskFullError];
+
+    let errors: Vec<SaveError> = convert::convert_chain(errors);
+    assert_eq!(errors.len(), 2);
+    assert!(errors
+        .iter()
+        .all(|e| matches!(e, SaveError::DiskFull { .. })));
+}
@@ -0,0 +1,63 @@
+use snafu::{
+    env::{self, BacktracePolicy, FAKE_BACKTRACE},
+    prelude::*,
+    Backtrace, CompactBacktrace, DisplayBacktrace, ErrorCompat, Report,
+};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("the widget is missing"))]
+    Always { backtrace: Backtrace },
+
+    #[snafu(display("the gadget is missing"))]
+    Sometimes { backtrace: Option<Backtrace> },
+}
+
+fn displayed_backtrace(error: &Error) -> Option<String> {
+    ErrorCompat::backtrace(error).map(|bt| DisplayBacktrace(bt).to_string())
+}
+
+// The policy is global, so every step is sequenced in a single test.
+#[test]
+fn fake_backtraces_are_present_and_deterministic() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "0");
+    std::env::set_var("RUST_BACKTRACE", "0");
+
+    env::set_backtrace_policy(BacktracePolicy::Fake);
+    assert_eq!(env::backtrace_policy(), BacktracePolicy::Fake);
+
+    let always = AlwaysSnafu.build();
+    let sometimes = SometimesSnafu.build();
+
+    assert_eq!(
+        displayed_backtrace(&always).as_deref(),
+        Some(FAKE_BACKTRACE)
+    );
+    assert_eq!(
+        displayed_backtrace(&sometimes).as_deref(),
+        Some(FAKE_BACKTRACE)
+    );
+    assert_eq!(
+        format!(
+            "{:?}",
+            CompactBacktrace(ErrorCompat::backtrace(&always).unwrap())
+        ),
+        "Backtrace(<captured>)",
+    );
+
+    let report = format!(
+        "{}\nBacktrace:\n{}",
+        Report::from_error(&sometimes),
+        displayed_backtrace(&sometimes).unwrap(),
+    );
+    assert_eq!(
+        report,
+        "the gadget is missing\n\nBacktrace:\n<backtrace captured: 0 frames>",
+    );
+
+    env::set_backtrace_policy(BacktracePolicy::NeverCapture);
+    let sometimes = SometimesSnafu.build();
+    assert!(ErrorCompat::backtrace(&sometimes).is_none());
+
+    env::set_backtrace_policy(BacktracePolicy::UseEnvVar);
+}
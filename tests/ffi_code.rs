@@ -0,0 +1,105 @@
+use snafu::{prelude::*, FfiCode, FfiError, IntoError};
+use std::ffi::CStr;
+
+const BUSY: i32 = -3;
+
+mod enums {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(ffi_code(-2), display("could not find {name}"))]
+        NotFound { name: String },
+
+        #[snafu(ffi_code(BUSY), display("the device is busy"))]
+        Busy { source: std::io::Error },
+    }
+
+    fn round_trip(error: &FfiError) -> (i32, String) {
+        // Simulates a C host reading the message through the pointer.
+        let message = unsafe { CStr::from_ptr(error.message_ptr()) };
+        (error.code(), message.to_str().unwrap().to_owned())
+    }
+
+    #[test]
+    fn variants_are_converted_with_their_codes() {
+        let not_found = NotFoundSnafu { name: "sda" }.build().to_ffi();
+        assert_eq!(round_trip(&not_found), (-2, "could not find sda".into()));
+
+        let io_error = std::io::Error::new(std::io::ErrorKind::Other, "locked");
+        let busy = BusySnafu.into_error(io_error).to_ffi();
+        assert_eq!(round_trip(&busy), (-3, "the device is busy: locked".into()));
+    }
+
+    #[test]
+    fn messages_survive_a_trip_through_a_raw_pointer() {
+        let error = NotFoundSnafu { name: "sdb" }.build().to_ffi();
+
+        let raw = error.into_message().into_raw();
+        let message = unsafe { std::ffi::CString::from_raw(raw) };
+
+        assert_eq!(message.to_str(), Ok("could not find sdb"));
+    }
+
+    #[test]
+    fn nul_bytes_are_removed_from_the_message() {
+        let error = NotFoundSnafu { name: "s\0dc" }.build().to_ffi();
+
+        assert_eq!(error.message().to_str(), Ok("could not find sdc"));
+    }
+
+    #[test]
+    fn works_through_boxes_and_references() {
+        let error: Box<dyn FfiCode> = Box::new(NotFoundSnafu { name: "sdd" }.build());
+
+        fn code_of(error: impl FfiCode) -> i32 {
+            error.ffi_code()
+        }
+
+        assert_eq!(code_of(&error), -2);
+    }
+
+    #[test]
+    fn boxed_trait_objects_can_be_converted() {
+        let error: Box<dyn std::error::Error> = Box::new(NotFoundSnafu { name: "sde" }.build());
+        let ffi = FfiError::new(7, &*error);
+
+        assert_eq!(ffi.code(), 7);
+        assert_eq!(ffi.message().to_str(), Ok("could not find sde"));
+    }
+}
+
+mod structs {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(ffi_code(22), display("invalid argument {argument}"))]
+    struct Error {
+        argument: u32,
+    }
+
+    #[test]
+    fn structs_report_their_code() {
+        let error = Snafu { argument: 3u32 }.build().to_ffi();
+
+        assert_eq!(error.code(), 22);
+        assert_eq!(error.message().to_str(), Ok("invalid argument 3"));
+    }
+}
+
+mod generics {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error<T: std::fmt::Display> {
+        #[snafu(ffi_code(17), display("{value} already exists"))]
+        AlreadyExists { value: T },
+    }
+
+    #[test]
+    fn generic_errors_report_their_code() {
+        let error: Error<i32> = AlreadyExistsSnafu { value: 1 }.build();
+
+        assert_eq!(error.ffi_code(), 17);
+    }
+}
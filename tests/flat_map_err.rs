@@ -0,0 +1,64 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(display("status {code}"))]
+struct StatusError {
+    code: i32,
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("the device reported {description}"))]
+    Device { description: String },
+
+    #[snafu(display("the device reported unknown code {code}"))]
+    UnknownCode { code: i32 },
+}
+
+#[derive(Debug)]
+struct Described(&'static str);
+
+impl From<Described> for Error {
+    fn from(d: Described) -> Self {
+        DeviceSnafu { description: d.0 }.build()
+    }
+}
+
+fn describe(e: StatusError) -> Result<Described, Error> {
+    match e.code {
+        5 => Ok(Described("a paper jam")),
+        code => UnknownCodeSnafu { code }.fail(),
+    }
+}
+
+#[test]
+fn successful_conversion_is_used_as_the_error() {
+    let result: Result<(), StatusError> = StatusSnafu { code: 5 }.fail();
+    let error = result.flat_map_err(describe).unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::Device { ref description } if description == "a paper jam"
+    ));
+}
+
+#[test]
+fn failed_conversion_uses_the_fallback_error() {
+    let result: Result<(), StatusError> = StatusSnafu { code: 99 }.fail();
+    let error = result.flat_map_err(describe).unwrap_err();
+
+    assert!(matches!(error, Error::UnknownCode { code: 99 }));
+}
+
+#[test]
+fn success_is_unchanged() {
+    let result: Result<i32, StatusError> = Ok(3);
+    let mut called = false;
+    let result = result.flat_map_err(|e| {
+        called = true;
+        describe(e)
+    });
+
+    assert!(matches!(result, Ok(3)));
+    assert!(!called);
+}
@@ -0,0 +1,73 @@
+use snafu::prelude::*;
+use std::thread;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("The worker for job {job} did not complete"))]
+    Worker { job: i32, source: JoinError },
+
+    #[snafu(transparent)]
+    Job { source: JobError },
+}
+
+// Emulates the error returned when a spawned task cannot be joined.
+#[derive(Debug, Snafu)]
+struct JoinError;
+
+#[derive(Debug, Snafu)]
+#[snafu(display("The job failed with code {code}"))]
+struct JobError {
+    code: i32,
+}
+
+fn spawn_blocking<T>(f: impl FnOnce() -> T + Send + 'static) -> Result<T, JoinError>
+where
+    T: Send + 'static,
+{
+    thread::spawn(f).join().map_err(|_| JoinError)
+}
+
+#[test]
+fn success_is_unwrapped() {
+    let result: Result<_, Error> =
+        spawn_blocking(|| Ok::<_, JobError>(42)).flatten_context(WorkerSnafu { job: 1 });
+
+    assert_eq!(result.unwrap(), 42);
+}
+
+#[test]
+fn outer_error_receives_context() {
+    let nested: Result<Result<i32, JobError>, _> = JoinSnafu.fail();
+    let error = nested.flatten_context(WorkerSnafu { job: 2 }).unwrap_err();
+
+    assert!(matches!(error, Error::Worker { job: 2, .. }));
+    assert_eq!(error.to_string(), "The worker for job 2 did not complete");
+}
+
+#[test]
+fn inner_error_is_converted() {
+    let result = spawn_blocking(|| JobSnafu { code: 7 }.fail::<i32>());
+    let error = result.flatten_context(WorkerSnafu { job: 3 }).unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::Job {
+            source: JobError { code: 7 }
+        }
+    ));
+}
+
+#[test]
+fn inner_error_can_pass_through_unchanged() {
+    let nested: Result<Result<i32, Error>, JoinError> = Ok(Err(Error::Job {
+        source: JobError { code: 9 },
+    }));
+    let error = nested.flatten_context(WorkerSnafu { job: 4 }).unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::Job {
+            source: JobError { code: 9 }
+        }
+    ));
+}
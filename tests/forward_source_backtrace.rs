@@ -0,0 +1,144 @@
+use snafu::{prelude::*, Backtrace, ErrorCompat, IntoError};
+
+mod inner {
+    use snafu::{prelude::*, Backtrace};
+
+    #[derive(Debug, Snafu)]
+    #[snafu(visibility(pub))]
+    pub struct Error {
+        backtrace: Backtrace,
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(forward_source_backtrace)]
+enum Error {
+    Forwarded {
+        source: inner::Error,
+    },
+
+    CapturesItsOwn {
+        source: inner::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(forward_source_backtrace(false))]
+    OptedOut {
+        source: std::io::Error,
+    },
+
+    NoSource,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(forward_source_backtrace)]
+struct StructError {
+    source: inner::Error,
+}
+
+fn inner_error() -> inner::Error {
+    inner::Snafu.build()
+}
+
+fn same_backtrace(a: &impl ErrorCompat, b: &impl ErrorCompat) -> bool {
+    match (ErrorCompat::backtrace(a), ErrorCompat::backtrace(b)) {
+        (Some(a), Some(b)) => std::ptr::eq(a, b),
+        _ => false,
+    }
+}
+
+#[test]
+fn variants_with_a_source_use_its_backtrace() {
+    let error = ForwardedSnafu.into_error(inner_error());
+
+    match &error {
+        Error::Forwarded { source } => assert!(same_backtrace(&error, source)),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn variants_with_a_backtrace_field_capture_their_own() {
+    let error = CapturesItsOwnSnafu.into_error(inner_error());
+
+    assert!(ErrorCompat::backtrace(&error).is_some());
+    match &error {
+        Error::CapturesItsOwn { source, .. } => assert!(!same_backtrace(&error, source)),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn variants_can_opt_out() {
+    let io_error = std::io::Error::new(std::io::ErrorKind::Other, "boom");
+    let error = OptedOutSnafu.into_error(io_error);
+
+    assert!(ErrorCompat::backtrace(&error).is_none());
+}
+
+#[test]
+fn variants_without_a_source_are_unaffected() {
+    assert!(ErrorCompat::backtrace(&NoSourceSnafu.build()).is_none());
+}
+
+#[test]
+fn structs_use_the_source_backtrace() {
+    let error = StructSnafu.into_error(inner_error());
+
+    assert!(same_backtrace(&error, &error.source));
+}
+
+#[snafu::defaults(forward_source_backtrace)]
+mod defaulted {
+    use super::inner;
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(visibility(pub(super)))]
+    pub enum Error {
+        Forwarded {
+            source: inner::Error,
+        },
+
+        #[snafu(forward_source_backtrace(false))]
+        OptedOut {
+            source: std::io::Error,
+        },
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(visibility(pub(super)))]
+    pub struct StructError {
+        pub source: inner::Error,
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(visibility(pub(super)), forward_source_backtrace(false))]
+    pub struct OverriddenError {
+        source: std::io::Error,
+    }
+}
+
+#[test]
+fn module_defaults_apply_to_enums() {
+    let error = defaulted::ForwardedSnafu.into_error(inner_error());
+
+    match &error {
+        defaulted::Error::Forwarded { source } => assert!(same_backtrace(&error, source)),
+        _ => unreachable!(),
+    }
+
+    let io_error = std::io::Error::new(std::io::ErrorKind::Other, "boom");
+    let error = defaulted::OptedOutSnafu.into_error(io_error);
+    assert!(ErrorCompat::backtrace(&error).is_none());
+}
+
+#[test]
+fn module_defaults_apply_to_structs() {
+    let error = defaulted::StructSnafu.into_error(inner_error());
+    assert!(same_backtrace(&error, &error.source));
+
+    let io_error = std::io::Error::new(std::io::ErrorKind::Other, "boom");
+    let error = defaulted::OverriddenSnafu.into_error(io_error);
+    assert!(ErrorCompat::backtrace(&error).is_none());
+}
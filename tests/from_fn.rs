@@ -0,0 +1,82 @@
+use snafu::{prelude::*, IntoError, NoneError};
+use std::{io, mem};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Weird { source: io::Error, when: u64 },
+
+    Missing { name: String },
+}
+
+fn failing() -> io::Result<i32> {
+    Err(io::Error::new(io::ErrorKind::Other, "boom"))
+}
+
+#[test]
+fn closures_work_with_context() {
+    let when = 42;
+    let error = failing()
+        .context(snafu::from_fn(|source| Error::Weird { source, when }))
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Weird { when: 42, .. }));
+}
+
+#[test]
+fn closures_work_with_with_context() {
+    let error = failing()
+        .with_context(|e| {
+            let when = e.to_string().len() as u64;
+            snafu::from_fn(move |source| Error::Weird { source, when })
+        })
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Weird { when: 4, .. }));
+}
+
+#[test]
+fn closures_work_with_options() {
+    let name = String::from("config");
+    let error = None::<i32>
+        .context(snafu::from_fn(|NoneError| Error::Missing { name }))
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Missing { name } if name == "config"));
+}
+
+#[test]
+fn ok_values_do_not_call_the_closure() {
+    let value: Result<i32, Error> = Ok::<_, io::Error>(7).context(snafu::from_fn(|_| -> Error {
+        panic!("The closure should not be called")
+    }));
+
+    assert_eq!(value.unwrap(), 7);
+}
+
+#[test]
+fn cloneable_when_the_closure_is() {
+    let label = String::from("retry");
+    let selector = snafu::from_fn(move |source| Error::Weird {
+        source,
+        when: label.len() as u64,
+    });
+
+    let errors: Vec<Error> = (0..3)
+        .map(|_| selector.clone().into_error(failing().unwrap_err()))
+        .collect();
+
+    assert_eq!(errors.len(), 3);
+    assert!(errors
+        .iter()
+        .all(|e| matches!(e, Error::Weird { when: 5, .. })));
+}
+
+#[test]
+fn adds_no_size_over_the_closure() {
+    let when = 1_u64;
+    let closure = move |source| Error::Weird { source, when };
+    let closure_size = mem::size_of_val(&closure);
+    let selector = snafu::from_fn(closure);
+
+    assert_eq!(mem::size_of_val(&selector), closure_size);
+}
@@ -0,0 +1,117 @@
+use snafu::prelude::*;
+use std::{error::Error as _, fmt};
+
+#[derive(Debug)]
+struct Counter {
+    count: std::cell::Cell<u32>,
+}
+
+impl fmt::Display for Counter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "count is {}", self.count.get())
+    }
+}
+
+impl std::error::Error for Counter {}
+
+impl Counter {
+    fn new(count: u32) -> Self {
+        Self {
+            count: std::cell::Cell::new(count),
+        }
+    }
+
+    fn increment(&self) {
+        self.count.set(self.count.get() + 1);
+    }
+}
+
+mod context_selector {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(display("Failed for {name}: {message}"))]
+        Leaf {
+            name: String,
+            source: Counter,
+            #[snafu(from_source_display)]
+            message: String,
+        },
+    }
+
+    #[test]
+    fn snapshot_is_taken_during_construction() {
+        let error = Err::<(), _>(Counter::new(1))
+            .context(LeafSnafu { name: "alpha" })
+            .unwrap_err();
+
+        let Error::Leaf { source, .. } = &error;
+        source.increment();
+
+        let Error::Leaf { message, .. } = &error;
+        assert_eq!(message, "count is 1");
+        assert_eq!(error.to_string(), "Failed for alpha: count is 1");
+    }
+
+    #[test]
+    fn source_remains_attached() {
+        let error = Err::<(), _>(Counter::new(1))
+            .context(LeafSnafu { name: "alpha" })
+            .unwrap_err();
+
+        let source = error.source().expect("Source must be present");
+        assert_eq!(source.to_string(), "count is 1");
+    }
+}
+
+mod no_context {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(context(false), display("Wrapped: {message}"))]
+    struct Error {
+        source: Counter,
+        #[snafu(from_source_display)]
+        message: String,
+    }
+
+    #[test]
+    fn snapshot_is_taken_during_conversion() {
+        let error = Error::from(Counter::new(7));
+        error.source.increment();
+
+        assert_eq!(error.message, "count is 7");
+        assert_eq!(error.to_string(), "Wrapped: count is 7");
+    }
+
+    #[test]
+    fn source_remains_attached() {
+        let error = Error::from(Counter::new(7));
+
+        let source = error.source().expect("Source must be present");
+        assert_eq!(source.to_string(), "count is 7");
+    }
+}
+
+mod with_source_transformation {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(context(false))]
+        Boxed {
+            #[snafu(source(from(Counter, Box::new)))]
+            source: Box<dyn std::error::Error>,
+            #[snafu(from_source_display)]
+            message: String,
+        },
+    }
+
+    #[test]
+    fn snapshot_uses_the_transformed_source() {
+        let Error::Boxed { message, .. } = Error::from(Counter::new(3));
+
+        assert_eq!(message, "count is 3");
+    }
+}
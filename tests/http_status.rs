@@ -0,0 +1,87 @@
+#![cfg(feature = "http-status")]
+
+use snafu::{prelude::*, HttpStatus, IntoError};
+
+mod enums {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(http_status(NOT_FOUND))]
+        UserNotFound { id: u32 },
+
+        #[snafu(http_status(SERVICE_UNAVAILABLE))]
+        Database { source: std::io::Error },
+
+        #[snafu(http_status(599))]
+        UpstreamTimeout,
+    }
+
+    #[test]
+    fn named_statuses_use_the_constant() {
+        let error = UserNotFoundSnafu { id: 42u32 }.build();
+
+        assert_eq!(error.http_status(), snafu::http_status::NOT_FOUND);
+        assert_eq!(error.http_status(), 404);
+    }
+
+    #[test]
+    fn variants_with_sources_are_supported() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::Other, "down");
+        let error = DatabaseSnafu.into_error(io_error);
+
+        assert_eq!(error.http_status(), 503);
+    }
+
+    #[test]
+    fn numeric_statuses_are_used_directly() {
+        let error = UpstreamTimeoutSnafu.build();
+
+        assert_eq!(error.http_status(), 599);
+    }
+
+    #[test]
+    fn works_through_boxes_and_references() {
+        let error: Box<dyn HttpStatus> = Box::new(UpstreamTimeoutSnafu.build());
+
+        fn status_of(error: impl HttpStatus) -> u16 {
+            error.http_status()
+        }
+
+        assert_eq!(status_of(&error), 599);
+    }
+}
+
+mod structs {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(http_status(BAD_REQUEST))]
+    struct Error {
+        field: String,
+    }
+
+    #[test]
+    fn structs_report_their_status() {
+        let error = Snafu { field: "name" }.build();
+
+        assert_eq!(error.http_status(), 400);
+    }
+}
+
+mod generics {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error<T: std::fmt::Display> {
+        #[snafu(http_status(CONFLICT))]
+        AlreadyExists { value: T },
+    }
+
+    #[test]
+    fn generic_errors_report_their_status() {
+        let error: Error<i32> = AlreadyExistsSnafu { value: 1 }.build();
+
+        assert_eq!(error.http_status(), 409);
+    }
+}
@@ -0,0 +1,124 @@
+use snafu::{prelude::*, IntoError};
+
+fn next_request_id() -> u64 {
+    42
+}
+
+mod enums {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(display("Request {request_id} for {user} failed"))]
+        Leaf {
+            user: String,
+            #[snafu(ignore_field(with = next_request_id))]
+            request_id: u64,
+            #[snafu(ignore_field)]
+            retries: Vec<u8>,
+        },
+
+        WithSource {
+            source: std::io::Error,
+            #[snafu(ignore_field)]
+            checksum: u32,
+        },
+    }
+
+    #[test]
+    fn ignored_fields_are_filled_when_building() {
+        let error = LeafSnafu { user: "alice" }.build();
+
+        match error {
+            Error::Leaf {
+                user,
+                request_id,
+                retries,
+            } => {
+                assert_eq!(user, "alice");
+                assert_eq!(request_id, 42);
+                assert!(retries.is_empty());
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn ignored_fields_are_available_to_display() {
+        let error = LeafSnafu { user: "bob" }.build();
+
+        assert_eq!(error.to_string(), "Request 42 for bob failed");
+    }
+
+    #[test]
+    fn ignored_fields_are_filled_by_into_error() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::Other, "oops");
+        let error = WithSourceSnafu.into_error(io_error);
+
+        assert!(matches!(error, Error::WithSource { checksum: 0, .. }));
+    }
+}
+
+mod structs {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    struct Error {
+        name: String,
+        #[snafu(ignore_field)]
+        state: Option<u8>,
+    }
+
+    #[test]
+    fn ignored_fields_are_filled_for_structs() {
+        let error = Snafu { name: "x" }.build();
+
+        assert_eq!(error.name, "x");
+        assert_eq!(error.state, None);
+    }
+}
+
+mod context_false {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(context(false))]
+    struct Error {
+        source: std::io::Error,
+        #[snafu(ignore_field(with = next_request_id))]
+        request_id: u64,
+    }
+
+    fn failing() -> Result<(), Error> {
+        Err(std::io::Error::new(std::io::ErrorKind::Other, "oops"))?;
+        Ok(())
+    }
+
+    #[test]
+    fn ignored_fields_are_filled_by_from() {
+        let error = failing().unwrap_err();
+
+        assert_eq!(error.request_id, 42);
+    }
+}
+
+mod whatever {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(whatever, display("{message}"))]
+    struct Error {
+        message: String,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+        #[snafu(ignore_field(with = next_request_id))]
+        request_id: u64,
+    }
+
+    #[test]
+    fn ignored_fields_are_filled_for_whatever() {
+        let error = (|| -> Result<(), Error> { whatever!("oops") })().unwrap_err();
+
+        assert_eq!(error.request_id, 42);
+    }
+}
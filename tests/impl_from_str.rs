@@ -0,0 +1,200 @@
+use snafu::{prelude::*, FromStrError, Location};
+
+mod enums {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Snafu)]
+    #[snafu(impl_from_str)]
+    enum Error {
+        #[snafu(display("user {id} was not found in {group}"))]
+        UserNotFound {
+            id: u32,
+            group: String,
+        },
+
+        #[snafu(display("{{{count}}} items are {{locked}}"))]
+        Locked {
+            count: usize,
+        },
+
+        /// The disk is full
+        DiskFull,
+
+        Unknown,
+    }
+
+    #[test]
+    fn variants_round_trip_through_their_display() {
+        let errors = [
+            Error::UserNotFound {
+                id: 42,
+                group: "admins and friends".into(),
+            },
+            Error::Locked { count: 3 },
+            Error::DiskFull,
+            Error::Unknown,
+        ];
+
+        for error in errors {
+            assert_eq!(error.to_string().parse(), Ok(error));
+        }
+    }
+
+    #[test]
+    fn text_matching_no_variant_is_an_error() {
+        let error = "user x was not found in admins"
+            .parse::<Error>()
+            .unwrap_err();
+
+        assert_eq!(error, FromStrError::new("Error"));
+        assert_eq!(error.type_name(), "Error");
+        assert_eq!(
+            error.to_string(),
+            "the text does not match the display format of `Error`",
+        );
+
+        assert!("The disk is full!".parse::<Error>().is_err());
+    }
+}
+
+mod structs {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Snafu)]
+    #[snafu(impl_from_str, display("invalid port {port}"))]
+    struct Error {
+        port: u16,
+    }
+
+    #[test]
+    fn structs_round_trip_through_their_display() {
+        let error = Error { port: 8080 };
+        assert_eq!(error.to_string().parse(), Ok(error));
+
+        assert!("invalid port 99999".parse::<Error>().is_err());
+    }
+}
+
+mod implicit_and_ignored_fields {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(impl_from_str)]
+    enum Error {
+        #[snafu(display("request {id} timed out"))]
+        Timeout {
+            id: u64,
+            #[snafu(implicit)]
+            location: Location,
+            #[snafu(ignore_field)]
+            retries: u8,
+        },
+    }
+
+    #[test]
+    fn implicit_and_ignored_fields_are_generated() {
+        let error: Error = "request 7 timed out".parse().unwrap();
+
+        let Error::Timeout {
+            id,
+            location,
+            retries,
+        } = error;
+        assert_eq!(id, 7);
+        assert_eq!(location.file, file!());
+        assert_eq!(retries, 0);
+    }
+}
+
+mod prefixes {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Snafu)]
+    #[snafu(impl_from_str, display_prefix = "storage: ")]
+    enum Error {
+        #[snafu(display("{path} is missing"))]
+        Missing { path: String },
+    }
+
+    #[test]
+    fn the_display_prefix_is_matched() {
+        let error = Error::Missing {
+            path: "/tmp".into(),
+        };
+        assert_eq!(error.to_string(), "storage: /tmp is missing");
+        assert_eq!(error.to_string().parse(), Ok(error));
+
+        assert!("/tmp is missing".parse::<Error>().is_err());
+    }
+}
+
+mod parse_with {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Snafu)]
+    #[snafu(impl_from_str)]
+    enum Error {
+        #[snafu(
+            display("{} bytes left", left.iter().sum::<u32>()),
+            parse_with = parse_bytes_left
+        )]
+        BytesLeft { left: Vec<u32> },
+
+        #[snafu(display("the operation was cancelled"))]
+        Cancelled,
+    }
+
+    fn parse_bytes_left(s: &str) -> Option<Error> {
+        let total = s.strip_suffix(" bytes left")?.parse().ok()?;
+        Some(Error::BytesLeft { left: vec![total] })
+    }
+
+    #[test]
+    fn variants_can_be_parsed_with_a_function() {
+        let error = Error::BytesLeft { left: vec![1, 2] };
+
+        assert_eq!(
+            error.to_string().parse(),
+            Ok(Error::BytesLeft { left: vec![3] }),
+        );
+        assert_eq!("the operation was cancelled".parse(), Ok(Error::Cancelled),);
+    }
+}
+
+mod whatever {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(impl_from_str, whatever, display("{message}"))]
+    struct Error {
+        message: String,
+    }
+
+    #[test]
+    fn the_message_is_parsed() {
+        let error: Error = "anything at all".parse().unwrap();
+
+        assert_eq!(error.message, "anything at all");
+    }
+}
+
+mod generics {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Snafu)]
+    #[snafu(impl_from_str)]
+    enum Error<T>
+    where
+        T: std::fmt::Display,
+    {
+        #[snafu(display("{value} is out of range"))]
+        OutOfRange { value: T },
+    }
+
+    #[test]
+    fn generic_fields_are_parsed() {
+        let error: Error<i64> = "-5 is out of range".parse().unwrap();
+
+        assert_eq!(error, Error::OutOfRange { value: -5 });
+    }
+}
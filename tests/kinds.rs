@@ -0,0 +1,89 @@
+use snafu::{prelude::*, IntoError, UnknownCodeError};
+use std::convert::TryFrom;
+
+#[derive(Debug, Snafu)]
+#[snafu(kinds)]
+pub enum Error {
+    #[snafu(code = "not_found")]
+    NotFound {
+        name: String,
+    },
+
+    #[snafu(code = "io")]
+    Io {
+        source: std::io::Error,
+    },
+
+    Busy,
+}
+
+const ALL_KINDS: [ErrorKind; 3] = [ErrorKind::NotFound, ErrorKind::Io, ErrorKind::Busy];
+
+#[test]
+fn each_variant_has_a_kind() {
+    let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+
+    assert_eq!(
+        NotFoundSnafu { name: "a" }.build().kind(),
+        ErrorKind::NotFound,
+    );
+    assert_eq!(IoSnafu.into_error(io_error).kind(), ErrorKind::Io);
+    assert_eq!(BusySnafu.build().kind(), ErrorKind::Busy);
+}
+
+#[test]
+fn kinds_display_their_code() {
+    assert_eq!(ErrorKind::NotFound.code(), "not_found");
+    assert_eq!(ErrorKind::Io.to_string(), "io");
+    assert_eq!(ErrorKind::Busy.to_string(), "Busy");
+}
+
+#[test]
+fn codes_round_trip_through_kinds() {
+    for kind in ALL_KINDS {
+        let code = kind.to_string();
+
+        assert_eq!(code.parse::<ErrorKind>(), Ok(kind));
+        assert_eq!(ErrorKind::try_from(&*code), Ok(kind));
+        assert_eq!(code.parse::<ErrorKind>().unwrap().code(), code);
+    }
+}
+
+#[test]
+fn unknown_codes_are_rejected() {
+    let error = "NotFound".parse::<ErrorKind>().unwrap_err();
+
+    assert_eq!(error, UnknownCodeError::new("ErrorKind"));
+    assert_eq!(error.type_name(), "ErrorKind");
+    assert_eq!(
+        error.to_string(),
+        "the text is not the code of any `ErrorKind`"
+    );
+    assert!(ErrorKind::try_from("").is_err());
+}
+
+mod generics {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(kinds)]
+    enum RequestError<T>
+    where
+        T: std::fmt::Debug + std::fmt::Display,
+    {
+        #[snafu(display("rejected {value}"), code = "rejected")]
+        Rejected { value: T },
+
+        #[snafu(code = "timeout")]
+        Timeout,
+    }
+
+    #[test]
+    fn works_with_generic_errors() {
+        let error: RequestError<i32> = RejectedSnafu { value: 1 }.build();
+        assert_eq!(error.kind(), RequestErrorKind::Rejected);
+
+        let error: RequestError<i32> = TimeoutSnafu.build();
+        assert_eq!(error.kind().to_string(), "timeout");
+    }
+}
@@ -0,0 +1,66 @@
+use snafu::prelude::*;
+use std::thread;
+
+#[derive(Debug, Snafu)]
+enum EnumError {
+    #[snafu(display("Status {code}: {reason}"))]
+    Status { code: u16, reason: String },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Unavailable after {attempts} attempts"))]
+struct StructError {
+    attempts: u32,
+}
+
+snafu::lazy_static_error! {
+    static NOT_FOUND: EnumError = EnumError::Status {
+        code: 404,
+        reason: String::from("not found"),
+    };
+
+    /// Documentation is allowed
+    pub(crate) static UNAVAILABLE: StructError = StructSnafu { attempts: 3u32 }.build();
+}
+
+fn lookup() -> Result<(), &'static EnumError> {
+    Err(&NOT_FOUND)
+}
+
+#[test]
+fn static_fields_can_be_read() {
+    match &*NOT_FOUND {
+        EnumError::Status { code, reason } => {
+            assert_eq!(*code, 404);
+            assert_eq!(reason, "not found");
+        }
+    }
+
+    assert_eq!(UNAVAILABLE.attempts, 3);
+}
+
+#[test]
+fn static_can_be_returned_by_reference() {
+    let error = lookup().unwrap_err();
+
+    assert_eq!(error.to_string(), "Status 404: not found");
+}
+
+#[test]
+fn static_implements_display_and_debug() {
+    assert_eq!(UNAVAILABLE.to_string(), "Unavailable after 3 attempts");
+    assert_eq!(format!("{:?}", UNAVAILABLE), "StructError { attempts: 3 }");
+}
+
+#[test]
+fn static_is_initialized_once_for_all_threads() {
+    let addresses: Vec<usize> = (0..4)
+        .map(|_| thread::spawn(|| &*UNAVAILABLE as *const StructError as usize))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|handle| handle.join().expect("The thread panicked"))
+        .collect();
+
+    let local = &*UNAVAILABLE as *const StructError as usize;
+    assert!(addresses.iter().all(|&address| address == local));
+}
@@ -45,6 +45,7 @@ mod track_caller {
         location: Location,
     }
 
+    #[allow(dead_code)]
     #[derive(Debug, Snafu)]
     #[snafu(context(false))]
     struct WrapNoContext {
@@ -231,3 +232,71 @@ mod track_caller {
     // useful location for `context(false)` errors. Check back in the
     // future to see if there's a fix.
 }
+
+mod premade_whatever {
+    use snafu::{prelude::*, FromString, Whatever};
+
+    #[test]
+    fn whatever_macro_records_the_invocation() {
+        let base_line = line!();
+        fn x() -> Result<(), Whatever> {
+            whatever!("bang");
+        }
+        let whatever = x().unwrap_err();
+        let location = whatever.location();
+
+        assert!(location.file.ends_with("location.rs"), "{}", location);
+        assert_eq!(
+            location.line,
+            base_line + 2,
+            "Actual location: {}",
+            location
+        );
+        assert_eq!(location.column, 13, "Actual location: {}", location);
+    }
+
+    #[test]
+    fn whatever_macro_with_a_source_records_the_invocation() {
+        let base_line = line!();
+        fn x() -> Result<(), Whatever> {
+            whatever!("x".parse::<i32>(), "bang");
+            Ok(())
+        }
+        let whatever = x().unwrap_err();
+
+        assert_eq!(whatever.location().line, base_line + 2);
+    }
+
+    #[test]
+    fn whatever_context_records_the_call() {
+        let base_line = line!();
+        let whatever: Whatever = "x".parse::<i32>().whatever_context("bang").unwrap_err();
+
+        assert_eq!(whatever.location().line, base_line + 1);
+    }
+
+    #[test]
+    fn from_string_records_the_caller() {
+        let base_line = line!();
+        let without = Whatever::without_source("bang".into());
+        let with = Whatever::with_source(Box::new(without), "boom".into());
+
+        assert!(std::error::Error::source(&with).is_some());
+        assert_eq!(with.location().line, base_line + 2);
+    }
+
+    #[test]
+    fn debug_output_includes_the_location() {
+        let base_line = line!();
+        let whatever = Whatever::without_source("bang".into());
+
+        let sep = std::path::MAIN_SEPARATOR;
+        let expected = format!(
+            "location: at tests{}location.rs:{}:24 }}",
+            sep,
+            base_line + 1,
+        );
+        let debug = format!("{:?}", whatever);
+        assert!(debug.ends_with(&expected), "{}", debug);
+    }
+}
@@ -0,0 +1,53 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+#[snafu(display("database failure: {message}"))]
+struct DbError {
+    message: String,
+}
+
+impl From<ApiError> for DbError {
+    fn from(e: ApiError) -> Self {
+        DbError {
+            message: format!("code {}", e.code),
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+struct ApiError {
+    code: i32,
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Query { table: String, source: DbError },
+}
+
+#[test]
+fn the_error_is_converted_before_context_is_added() {
+    let result: Result<i32, ApiError> = ApiSnafu { code: 7 }.fail();
+    let error = result
+        .map_source(DbError::from)
+        .context(QuerySnafu { table: "users" })
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::Query { ref table, source: DbError { ref message } }
+            if table == "users" && message == "code 7"
+    ));
+}
+
+#[test]
+fn success_is_unchanged() {
+    let result: Result<i32, ApiError> = Ok(3);
+    let mut called = false;
+    let result = result.map_source(|e| {
+        called = true;
+        DbError::from(e)
+    });
+
+    assert!(matches!(result, Ok(3)));
+    assert!(!called);
+}
@@ -0,0 +1,84 @@
+use snafu::{prelude::*, IntoError, Location, MissingTypeName, NoneError};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("expected value of type {missing} was missing"))]
+    Missing {
+        #[snafu(implicit)]
+        missing: MissingTypeName,
+    },
+
+    #[snafu(display("no setting named {name} ({missing})"))]
+    MissingSetting {
+        name: String,
+        #[snafu(implicit)]
+        missing: MissingTypeName,
+        #[snafu(implicit)]
+        location: Location,
+    },
+}
+
+#[test]
+fn option_context_records_the_missing_type() {
+    let error = None::<String>.context(MissingSnafu).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "expected value of type alloc::string::String was missing",
+    );
+}
+
+#[test]
+fn option_with_context_records_the_missing_type() {
+    let error = None::<Vec<u8>>
+        .with_context(|| MissingSettingSnafu { name: "port" })
+        .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "no setting named port (alloc::vec::Vec<u8>)"
+    );
+}
+
+#[test]
+fn other_implicit_fields_still_record_the_caller() {
+    let line = line!() + 1;
+    let error = None::<u8>.context(MissingSettingSnafu { name: "port" });
+
+    match error.unwrap_err() {
+        Error::MissingSetting { location, .. } => {
+            assert_eq!(location.file, file!());
+            assert_eq!(location.line, line);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn errors_not_created_from_an_option_have_no_type_name() {
+    let error = MissingSnafu.build();
+    assert!(matches!(error, Error::Missing { missing } if missing.name().is_none()));
+
+    let error = MissingSnafu.into_error(NoneError);
+    assert!(matches!(error, Error::Missing { missing } if missing.name().is_none()));
+}
+
+#[test]
+fn none_error_can_be_displayed() {
+    assert_eq!(NoneError.to_string(), "a value was missing");
+    assert_eq!(format!("{:?}", NoneError), "NoneError");
+}
+
+#[test]
+fn unknown_type_names_are_displayed_as_unknown() {
+    fn ensure_positive(value: i32) -> Result<(), Error> {
+        ensure!(value > 0, MissingSnafu);
+        Ok(())
+    }
+
+    let error = ensure_positive(0).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected value of type <unknown> was missing",
+    );
+}
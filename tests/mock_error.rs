@@ -0,0 +1,96 @@
+#![cfg(feature = "testing")]
+
+use snafu::{mock_result, prelude::*, ErrorCompat, MockError};
+
+#[derive(Debug, Snafu)]
+#[snafu(display("could not save {name}"))]
+struct SaveError<E>
+where
+    E: std::error::Error + 'static,
+{
+    name: String,
+    source: E,
+}
+
+// Code under test, where `write` stands in for a real dependency.
+fn save<E>(name: &str, write: impl FnOnce() -> Result<(), E>) -> Result<(), SaveError<E>>
+where
+    E: std::error::Error + 'static,
+{
+    write().context(SaveSnafu { name })
+}
+
+fn chain(error: &dyn std::error::Error) -> Vec<String> {
+    snafu::ChainCompat::new(error)
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn can_be_injected_through_context() {
+    let error = save("config", || mock_result!(Err("disk full"))).unwrap_err();
+
+    assert_eq!(chain(&error), ["could not save config", "disk full"]);
+    assert_eq!(error.source, MockError::new("disk full"));
+}
+
+#[test]
+fn can_have_a_source_chain() {
+    let injected = MockError::new("disk full")
+        .caused_by(MockError::new("device unplugged").caused_by(MockError::new("cable cut")));
+
+    let error = save("config", || Err(injected.clone())).unwrap_err();
+
+    assert_eq!(
+        chain(&error),
+        [
+            "could not save config",
+            "disk full",
+            "device unplugged",
+            "cable cut",
+        ],
+    );
+    assert_eq!(error.source, injected);
+}
+
+#[test]
+fn successful_results_are_passed_through() {
+    assert!(save("config", || mock_result!(Ok(()))).is_ok());
+}
+
+#[test]
+fn implements_snafu_traits() {
+    fn check<E: std::error::Error + ErrorCompat + Clone + Send + Sync + 'static>() {}
+    check::<MockError>();
+
+    let error = MockError::new("outer").caused_by(MockError::new("inner"));
+    assert!(ErrorCompat::backtrace(&error).is_none());
+    assert_eq!(error.iter_chain().count(), 2);
+    assert_eq!(error.message(), "outer");
+}
+
+#[test]
+fn can_be_created_with_whatever() {
+    fn exercise(fail_inner: bool) -> Result<(), MockError> {
+        let inner: Result<(), MockError> = if fail_inner {
+            mock_result!(Err("inner"))
+        } else {
+            mock_result!(Ok(()))
+        };
+        whatever!(inner, "outer");
+        whatever!("no inner failure");
+    }
+
+    assert_eq!(chain(&exercise(true).unwrap_err()), ["outer", "inner"]);
+    assert_eq!(chain(&exercise(false).unwrap_err()), ["no inner failure"]);
+}
+
+#[test]
+fn copies_the_chain_of_another_error() {
+    let io = std::io::Error::new(std::io::ErrorKind::Other, "boom");
+    let error = save("config", || Err(io)).unwrap_err();
+
+    let copy = MockError::from_error(&error);
+
+    assert_eq!(chain(&copy), ["could not save config", "boom"]);
+}
@@ -100,3 +100,46 @@ fn can_set_module_visibility_pub_with_custom_name() {
 fn can_set_module_visibility_pub_crate_with_custom_name() {
     let _ = inner::custom_pub_crate::VariantSnafu { v: inner::Dummy2 }.build();
 }
+
+mod relative {
+    mod parent {
+        mod child {
+            use snafu::Snafu;
+
+            #[derive(Debug, Snafu)]
+            #[snafu(module, visibility(pub(super)))]
+            pub(super) enum Error {
+                PubSuper,
+
+                #[snafu(visibility(pub(in super::super)))]
+                PubInSuperSuper,
+
+                #[snafu(visibility(pub(self)))]
+                PubSelf,
+
+                #[snafu(visibility)]
+                Private,
+            }
+
+            #[test]
+            fn private_selectors_are_accessible_next_to_the_error() {
+                let _ = error::PubSelfSnafu.build();
+                let _ = error::PrivateSnafu.build();
+            }
+        }
+
+        #[test]
+        fn super_is_relative_to_the_error() {
+            let _ = child::error::PubSuperSnafu.build();
+        }
+
+        pub(super) fn build_in_super_super() {
+            let _ = child::error::PubInSuperSuperSnafu.build();
+        }
+    }
+
+    #[test]
+    fn in_super_path_is_relative_to_the_error() {
+        parent::build_in_super_super();
+    }
+}
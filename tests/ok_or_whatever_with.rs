@@ -0,0 +1,60 @@
+use snafu::{prelude::*, Whatever};
+use std::collections::HashMap;
+
+fn port(services: &HashMap<&str, u16>, name: &str) -> Result<u16, Whatever> {
+    let port = services
+        .get(name)
+        .copied()
+        .ok_or_whatever_with(|| format!("unknown service {}", name))?;
+    Ok(port)
+}
+
+fn address(services: &HashMap<&str, u16>, host: &str, name: &str) -> Result<String, Whatever> {
+    let port =
+        port(services, name).with_whatever_context(|_| format!("no address for {}", host))?;
+    Ok(format!("{}:{}", host, port))
+}
+
+fn services() -> HashMap<&'static str, u16> {
+    [("http", 80), ("https", 443)].iter().copied().collect()
+}
+
+#[test]
+fn some_is_returned_unchanged() {
+    assert_eq!(port(&services(), "https").unwrap(), 443);
+}
+
+#[test]
+fn none_becomes_a_whatever_with_the_message() {
+    let error = port(&services(), "gopher").unwrap_err();
+
+    assert_eq!(error.to_string(), "unknown service gopher");
+    assert!(std::error::Error::source(&error).is_none());
+}
+
+#[test]
+fn errors_can_be_wrapped_after_using_the_question_mark() {
+    let error = address(&services(), "example.com", "gopher").unwrap_err();
+
+    assert_eq!(error.to_string(), "no address for example.com");
+    let source = std::error::Error::source(&error).unwrap();
+    assert_eq!(source.to_string(), "unknown service gopher");
+}
+
+#[test]
+fn the_closure_is_not_called_for_some() {
+    let result = Some(1).ok_or_whatever_with(|| panic!("must not be called"));
+
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[test]
+fn the_error_type_does_not_need_to_be_named() {
+    let results: Vec<_> = [Some(1), None]
+        .iter()
+        .map(|v| v.ok_or_whatever_with(|| "missing".to_string()))
+        .collect();
+
+    assert!(results[0].is_ok());
+    assert_eq!(results[1].as_ref().unwrap_err().to_string(), "missing");
+}
@@ -18,6 +18,13 @@ mod inner {
         Ok(value)
     }
 
+    pub fn is_too_big(e: &(dyn std::error::Error + 'static)) -> bool {
+        matches!(
+            Error::downcast_inner_ref(e),
+            Some(InnerError::TooBig { .. })
+        )
+    }
+
     #[derive(Debug, Snafu)]
     enum InnerError {
         #[snafu(display("The value {count} is too big"))]
@@ -56,3 +63,68 @@ fn ensure_boxed() {
     let e = inner::boxed_inner(2).unwrap_err();
     assert!(e.to_string().contains("too big"));
 }
+
+mod transparent {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(opaque(transparent_source))]
+    pub struct Error(pub(crate) InnerError);
+
+    #[derive(Debug, Snafu)]
+    pub(crate) enum InnerError {
+        #[snafu(display("The value {count} is too big"))]
+        TooBig { count: i32 },
+    }
+
+    pub fn api(count: i32) -> Result<(), Error> {
+        TooBigSnafu { count }.fail().map_err(Into::into)
+    }
+}
+
+fn recover_count(e: &(dyn std::error::Error + 'static)) -> Option<i32> {
+    match transparent::Error::downcast_inner_ref(e)? {
+        transparent::InnerError::TooBig { count } => Some(*count),
+    }
+}
+
+#[test]
+fn inner_error_can_be_recovered_from_a_trait_object() {
+    let e = transparent::api(42).unwrap_err();
+    let boxed: Box<dyn std::error::Error> = Box::new(e);
+
+    assert_eq!(recover_count(&*boxed), Some(42));
+}
+
+#[test]
+fn downcasting_an_unrelated_error_returns_none() {
+    let e = inner::api().unwrap_err();
+
+    assert_eq!(recover_count(&e), None);
+}
+
+#[test]
+fn inner_error_can_be_recovered_without_transparent_source() {
+    let e = inner::api().unwrap_err();
+
+    assert!(inner::is_too_big(&e));
+}
+
+#[test]
+fn transparent_source_places_inner_error_in_the_chain() {
+    use std::error::Error as _;
+
+    let e = transparent::api(1).unwrap_err();
+    let source = e.source().expect("Must have a source");
+
+    assert!(source.is::<transparent::InnerError>());
+}
+
+#[test]
+fn source_is_delegated_without_transparent_source() {
+    use std::error::Error as _;
+
+    let e = inner::api().unwrap_err();
+
+    assert!(e.source().is_none());
+}
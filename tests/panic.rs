@@ -0,0 +1,61 @@
+use snafu::{panic, prelude::*};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("the worker {id} crashed"))]
+    Worker { id: u8, source: panic::Panicked },
+}
+
+#[test]
+fn returns_the_value_when_the_closure_does_not_panic() {
+    let value = panic::context_on_panic(|| 42, WorkerSnafu { id: 1 });
+
+    assert_eq!(value.unwrap(), 42);
+}
+
+#[test]
+fn captures_a_static_panic_message() {
+    let error =
+        panic::context_on_panic(|| -> i32 { panic!("out of cheese") }, WorkerSnafu { id: 1 })
+            .unwrap_err();
+
+    let Error::Worker { id, source } = &error;
+    assert_eq!(*id, 1);
+    assert_eq!(source.message(), "out of cheese");
+    assert_eq!(error.to_string(), "the worker 1 crashed");
+}
+
+#[test]
+fn captures_a_formatted_panic_message() {
+    let count = 3;
+    let error = panic::context_on_panic(
+        || -> i32 { panic!("{} is too many", count) },
+        WorkerSnafu { id: 2 },
+    )
+    .unwrap_err();
+
+    let source = std::error::Error::source(&error).unwrap();
+    assert_eq!(source.to_string(), "panicked: 3 is too many");
+}
+
+#[test]
+fn reports_a_placeholder_for_other_payloads() {
+    let error = panic::context_on_panic(
+        || -> i32 { std::panic::panic_any(42_u8) },
+        WorkerSnafu { id: 3 },
+    )
+    .unwrap_err();
+
+    let Error::Worker { source, .. } = &error;
+    assert_eq!(source.message(), "Box<dyn Any>");
+}
+
+#[test]
+fn the_panic_message_is_part_of_the_report() {
+    let error =
+        panic::context_on_panic(|| -> i32 { panic!("out of cheese") }, WorkerSnafu { id: 4 })
+            .unwrap_err();
+
+    let report = snafu::Report::from_error(error).to_string();
+    assert!(report.contains("out of cheese"), "{}", report);
+}
@@ -0,0 +1,72 @@
+use snafu::{prelude::*, IntoError};
+use std::{io, marker::PhantomData};
+
+#[derive(Debug, Snafu)]
+#[snafu(display("could not load the {} resource", std::any::type_name::<T>()))]
+struct LoadError<T> {
+    source: io::Error,
+    _phantom: PhantomData<T>,
+}
+
+type Marker<T> = PhantomData<fn() -> T>;
+
+#[derive(Debug, Snafu)]
+enum Error<T> {
+    #[snafu(display("resource {id} is missing"))]
+    Missing {
+        id: u32,
+        marker: std::marker::PhantomData<T>,
+    },
+
+    Aliased {
+        #[snafu(phantom_data)]
+        marker: Marker<T>,
+    },
+}
+
+#[derive(Debug)]
+struct User;
+
+fn not_found() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "gone")
+}
+
+#[test]
+fn phantom_field_is_not_part_of_the_context_selector() {
+    let error: LoadError<User> = LoadSnafu.into_error(not_found());
+
+    assert_eq!(
+        error.to_string(),
+        "could not load the phantom_data::User resource",
+    );
+    assert_eq!(error._phantom, PhantomData);
+}
+
+#[test]
+fn phantom_field_works_with_context() {
+    let result: Result<(), io::Error> = Err(not_found());
+    let error: LoadError<User> = result.context(LoadSnafu).unwrap_err();
+
+    assert_eq!(error.source.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn fully_qualified_phantom_field_is_populated() {
+    let error: Error<User> = MissingSnafu { id: 3u32 }.build();
+
+    assert!(matches!(
+        error,
+        Error::Missing {
+            id: 3,
+            marker: PhantomData,
+        }
+    ));
+    assert_eq!(error.to_string(), "resource 3 is missing");
+}
+
+#[test]
+fn aliased_phantom_field_is_populated_with_the_attribute() {
+    let error: Error<User> = AliasedSnafu.build();
+
+    assert!(matches!(error, Error::Aliased { .. }));
+}
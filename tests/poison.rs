@@ -0,0 +1,85 @@
+use snafu::{prelude::*, PoisonExt};
+use std::{
+    panic,
+    sync::{Mutex, PoisonError, RwLock},
+};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("The {name} lock was poisoned"))]
+    Poisoned {
+        name: String,
+        source: PoisonError<()>,
+    },
+}
+
+fn poison(hold_lock_and_panic: impl FnOnce()) {
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(hold_lock_and_panic));
+}
+
+fn increment(counter: &Mutex<i32>) -> Result<i32, Error> {
+    let mut count = counter
+        .lock()
+        .poison_context(PoisonedSnafu { name: "counter" })?;
+    *count += 1;
+    Ok(*count)
+}
+
+#[test]
+fn mutex_guard_is_available_when_not_poisoned() {
+    let counter = Mutex::new(41);
+
+    assert_eq!(increment(&counter).unwrap(), 42);
+    assert_eq!(*counter.lock().unwrap(), 42);
+}
+
+#[test]
+fn mutex_poisoning_is_wrapped() {
+    let counter = Mutex::new(41);
+    poison(|| {
+        let _guard = counter.lock();
+        panic!("Poisoning the lock");
+    });
+
+    let error = increment(&counter).unwrap_err();
+    assert!(matches!(&error, Error::Poisoned { name, .. } if name == "counter"));
+    assert_eq!(error.to_string(), "The counter lock was poisoned");
+}
+
+#[test]
+fn rwlock_read_and_write_guards_are_supported() {
+    let config = RwLock::new(String::from("alpha"));
+
+    {
+        let mut config = config
+            .write()
+            .with_poison_context(|| PoisonedSnafu { name: "config" })
+            .unwrap();
+        config.push_str("-beta");
+    }
+
+    let len = config
+        .read()
+        .poison_context(PoisonedSnafu { name: "config" })
+        .map(|config| config.len());
+    assert_eq!(len.unwrap(), 10);
+}
+
+#[test]
+fn rwlock_poisoning_is_wrapped() {
+    let config = RwLock::new(String::new());
+    poison(|| {
+        let _guard = config.write();
+        panic!("Poisoning the lock");
+    });
+
+    let read = config
+        .read()
+        .poison_context(PoisonedSnafu { name: "config" });
+    assert!(matches!(read, Err(Error::Poisoned { .. })));
+
+    let write = config
+        .write()
+        .with_poison_context(|| PoisonedSnafu { name: "config" });
+    assert!(matches!(write, Err(Error::Poisoned { .. })));
+}
@@ -0,0 +1,100 @@
+mod v1 {
+    use snafu::prelude::v1::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        TooSmall { value: i32 },
+        Parse { source: std::num::ParseIntError },
+        Missing,
+    }
+
+    fn parse(text: &str) -> Result<i32, Error> {
+        let value: i32 = text.parse().context(ParseSnafu)?;
+        ensure!(value > 0, TooSmallSnafu { value });
+        Ok(value)
+    }
+
+    #[test]
+    fn contains_the_original_items() {
+        assert_eq!(parse("4").unwrap(), 4);
+        assert!(matches!(parse("0"), Err(Error::TooSmall { value: 0 })));
+        assert!(matches!(parse("a"), Err(Error::Parse { .. })));
+        assert!(matches!(
+            None::<i32>.context(MissingSnafu),
+            Err(Error::Missing)
+        ));
+    }
+
+    #[test]
+    fn contains_the_whatever_macros() {
+        fn fails() -> Result<(), snafu::Whatever> {
+            ensure_whatever!(false, "ensured");
+            whatever!("unreachable")
+        }
+
+        assert_eq!(fails().unwrap_err().to_string(), "ensured");
+    }
+}
+
+mod v2 {
+    use snafu::prelude::v2::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        TooSmall { value: i32 },
+    }
+
+    #[test]
+    fn contains_the_newer_items() {
+        fn check_value(value: i32) -> Result<i32, Error> {
+            check!(value > 0, TooSmallSnafu { value })?;
+            Ok(value)
+        }
+
+        assert_eq!(check_value(3).unwrap(), 3);
+        assert!(matches!(
+            check_value(-1),
+            Err(Error::TooSmall { value: -1 })
+        ));
+    }
+}
+
+mod both {
+    use snafu::prelude::{v1::*, v2::*};
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        TooSmall { value: i32 },
+    }
+
+    #[test]
+    fn can_be_imported_together() {
+        fn check_value(value: i32) -> Result<i32, Error> {
+            ensure!(value > 0, TooSmallSnafu { value });
+            Ok(value)
+        }
+
+        assert!(check_value(0).is_err());
+        assert!(check!(true, TooSmallSnafu { value: 1 }).is_ok());
+        assert!(Some(1).context(TooSmallSnafu { value: 1 }).is_ok());
+    }
+}
+
+mod unversioned {
+    #[test]
+    fn is_the_newest_version() {
+        use snafu::prelude::*;
+
+        #[derive(Debug, Snafu)]
+        enum Error {
+            TooSmall { value: i32 },
+        }
+
+        fn check_value(value: i32) -> Result<i32, Error> {
+            check!(value > 0, TooSmallSnafu { value })?;
+            Ok(value)
+        }
+
+        assert!(check_value(0).is_err());
+    }
+}
@@ -105,3 +105,25 @@ fn has_a_backtrace() {
     let bt = ErrorCompat::backtrace(&e).expect("Must have a backtrace");
     assert!(bt.to_string().contains("has_a_backtrace"));
 }
+
+#[test]
+fn can_be_created_from_an_already_boxed_error() {
+    use std::error::Error as _;
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(UnderlyingError);
+    let e = Whatever::from_boxed(boxed, "Something else happened".into());
+    assert_eq!("Something else happened", e.to_string());
+
+    let src = e.source().expect("Must have a source");
+    assert!(src.downcast_ref::<UnderlyingError>().is_some());
+
+    let inner: Box<dyn std::error::Error> = Box::new(e);
+    let outer = Whatever::from_boxed(inner, "Outer error".into());
+    let chain: Vec<_> = snafu::ChainCompat::new(&outer)
+        .map(|e| e.to_string())
+        .collect();
+    assert_eq!(
+        chain,
+        ["Outer error", "Something else happened", "UnderlyingError"]
+    );
+}
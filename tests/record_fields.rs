@@ -0,0 +1,131 @@
+use snafu::{prelude::*, Backtrace, Location};
+
+fn recorded(
+    visit: impl FnOnce(&mut dyn FnMut(&'static str, &dyn std::fmt::Display)),
+) -> Vec<String> {
+    let mut fields = Vec::new();
+    visit(&mut |name, value| fields.push(format!("{}={}", name, value)));
+    fields
+}
+
+mod enums {
+    use super::*;
+    use snafu::FromString;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(record_fields)]
+    enum Error {
+        Request {
+            user_id: u32,
+            path: String,
+            r#type: &'static str,
+        },
+
+        Io {
+            filename: String,
+            source: std::io::Error,
+            backtrace: Backtrace,
+        },
+
+        Implicit {
+            attempt: u8,
+            #[snafu(implicit)]
+            location: Location,
+        },
+
+        Empty,
+
+        #[snafu(whatever, display("{message}"))]
+        Whatever {
+            message: String,
+        },
+    }
+
+    #[test]
+    fn fields_are_visited_in_declaration_order() {
+        let error = RequestSnafu {
+            user_id: 7u32,
+            path: "/home",
+            r#type: "GET",
+        }
+        .build();
+
+        let fields = recorded(|v| error.record_fields(v));
+        assert_eq!(fields, ["user_id=7", "path=/home", "type=GET"]);
+    }
+
+    #[test]
+    fn sources_and_backtraces_are_skipped() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::Other, "boom");
+        let error = Err::<(), _>(io_error)
+            .context(IoSnafu { filename: "a.txt" })
+            .unwrap_err();
+
+        let fields = recorded(|v| error.record_fields(v));
+        assert_eq!(fields, ["filename=a.txt"]);
+    }
+
+    #[test]
+    fn implicit_fields_are_skipped() {
+        let error = ImplicitSnafu { attempt: 3u8 }.build();
+
+        let fields = recorded(|v| error.record_fields(v));
+        assert_eq!(fields, ["attempt=3"]);
+    }
+
+    #[test]
+    fn variants_without_context_fields_visit_nothing() {
+        let error = EmptySnafu.build();
+        assert!(recorded(|v| error.record_fields(v)).is_empty());
+
+        let error = Error::without_source("oops".into());
+        assert!(recorded(|v| error.record_fields(v)).is_empty());
+    }
+}
+
+mod structs {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(record_fields)]
+    struct Error {
+        table: String,
+        row: usize,
+        source: std::fmt::Error,
+    }
+
+    #[test]
+    fn struct_fields_are_visited() {
+        let error = Err::<(), _>(std::fmt::Error)
+            .context(Snafu {
+                table: "users",
+                row: 12usize,
+            })
+            .unwrap_err();
+
+        let fields = recorded(|v| error.record_fields(v));
+        assert_eq!(fields, ["table=users", "row=12"]);
+    }
+}
+
+mod generics {
+    use super::*;
+    use std::fmt::{Debug, Display};
+
+    #[derive(Debug, Snafu)]
+    #[snafu(record_fields)]
+    enum Error<T>
+    where
+        T: Debug + Display,
+    {
+        Invalid { value: T },
+    }
+
+    #[test]
+    fn generic_fields_are_visited() {
+        let error: Error<f32> = InvalidSnafu { value: 1.5 }.build();
+
+        let fields = recorded(|v| error.record_fields(v));
+        assert_eq!(fields, ["value=1.5"]);
+    }
+}
@@ -0,0 +1,107 @@
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    NotFound { key: String },
+    Stale { cached: String, age: u64 },
+    PermissionDenied,
+}
+
+fn lookup(error: Error) -> Result<u32, Error> {
+    Err(error)
+}
+
+fn fetch(error: Error) -> Result<String, Error> {
+    Err(error)
+}
+
+fn zero_if_not_found(e: &Error) -> Option<u32> {
+    match e {
+        Error::NotFound { .. } => Some(0),
+        _ => None,
+    }
+}
+
+#[test]
+fn recover_replaces_matching_errors() {
+    let value = lookup(NotFoundSnafu { key: "a" }.build())
+        .recover(zero_if_not_found)
+        .unwrap();
+
+    assert_eq!(value + 1, 1);
+}
+
+#[test]
+fn recover_passes_through_other_errors() {
+    let error = lookup(PermissionDeniedSnafu.build())
+        .recover(zero_if_not_found)
+        .unwrap_err();
+
+    assert!(matches!(error, Error::PermissionDenied));
+}
+
+#[test]
+fn recover_keeps_successful_values() {
+    let value = Ok::<_, Error>(42).recover(|_| Some(0)).unwrap();
+
+    assert_eq!(value, 42);
+}
+
+#[test]
+fn recover_variant_moves_data_out_of_the_error() {
+    let value = fetch(
+        StaleSnafu {
+            cached: "yesterday",
+            age: 1u64,
+        }
+        .build(),
+    )
+    .recover_variant(snafu::variant!(Error::Stale { cached, .. } => cached))
+    .unwrap();
+
+    assert_eq!(value.to_uppercase(), "YESTERDAY");
+}
+
+#[test]
+fn recover_variant_passes_through_other_errors() {
+    let error = fetch(NotFoundSnafu { key: "a" }.build())
+        .recover_variant(snafu::variant!(Error::Stale { cached, .. } => cached))
+        .unwrap_err();
+
+    assert!(matches!(error, Error::NotFound { key } if key == "a"));
+}
+
+#[test]
+fn recover_variant_passes_through_errors_rejected_by_a_guard() {
+    let error = fetch(
+        StaleSnafu {
+            cached: "last year",
+            age: 365u64,
+        }
+        .build(),
+    )
+    .recover_variant(snafu::variant!(Error::Stale { cached, age } if age < 7 => cached))
+    .unwrap_err();
+
+    assert!(matches!(error, Error::Stale { age: 365, .. }));
+}
+
+#[test]
+fn recover_variant_accepts_several_arms() {
+    let recover = || {
+        snafu::variant! {
+            Error::Stale { cached, .. } => cached,
+            Error::NotFound { key } => format!("default for {}", key),
+        }
+    };
+
+    let value = fetch(NotFoundSnafu { key: "a" }.build())
+        .recover_variant(recover())
+        .unwrap();
+    assert_eq!(value, "default for a");
+
+    let error = fetch(PermissionDeniedSnafu.build())
+        .recover_variant(recover())
+        .unwrap_err();
+    assert!(matches!(error, Error::PermissionDenied));
+}
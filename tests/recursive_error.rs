@@ -46,3 +46,65 @@ fn implements_error() {
     check::<Error>();
     re_private().unwrap_err();
 }
+
+mod deeply_nested {
+    use snafu::{prelude::*, ChainCompat, Report};
+
+    #[derive(Debug, Snafu)]
+    enum EvalError {
+        #[snafu(display("Unknown variable {name}"))]
+        UnknownVariable { name: String },
+
+        #[snafu(display("Could not evaluate field {field}"))]
+        InField {
+            field: String,
+            #[snafu(source(from(EvalError, Box::new)))]
+            source: Box<EvalError>,
+        },
+    }
+
+    fn evaluate(depth: usize) -> Result<i32, EvalError> {
+        if depth == 0 {
+            UnknownVariableSnafu { name: "x" }.fail()
+        } else {
+            // No `Box::new` is needed at the recursion point.
+            evaluate(depth - 1).context(InFieldSnafu {
+                field: format!("f{depth}"),
+            })
+        }
+    }
+
+    const DEPTH: usize = 100;
+
+    #[test]
+    fn source_chain_terminates_at_the_innermost_error() {
+        let error = evaluate(DEPTH).unwrap_err();
+
+        let chain: Vec<_> = ChainCompat::new(&error).collect();
+        assert_eq!(chain.len(), DEPTH + 1);
+
+        let last = chain.last().unwrap();
+        assert_eq!(last.to_string(), "Unknown variable x");
+        assert!(last.source().is_none());
+    }
+
+    #[test]
+    fn report_of_a_deep_chain_lists_every_error() {
+        let error = evaluate(DEPTH).unwrap_err();
+        let report = Report::from_error(error).to_string();
+
+        let mut lines = report.lines();
+        assert_eq!(lines.next(), Some("Could not evaluate field f100"));
+        assert_eq!(
+            lines.last(),
+            Some(format!("{DEPTH:3}: Unknown variable x").as_str()),
+        );
+        assert_eq!(report.matches("Could not evaluate field").count(), DEPTH);
+    }
+
+    #[test]
+    fn each_error_only_displays_itself() {
+        let error = evaluate(DEPTH).unwrap_err();
+        assert_eq!(error.to_string(), "Could not evaluate field f100");
+    }
+}
@@ -207,7 +207,10 @@ fn nasty_hack_exit_code_eq(left: ExitCode, right: ExitCode) -> bool {
 
     let (left, right): (ExitCodeSize, ExitCodeSize) = unsafe {
         assert_eq!(mem::size_of::<ExitCodeSize>(), mem::size_of::<ExitCode>());
-        (mem::transmute(left), mem::transmute(right))
+        (
+            mem::transmute::<ExitCode, ExitCodeSize>(left),
+            mem::transmute::<ExitCode, ExitCodeSize>(right),
+        )
     };
 
     left == right
@@ -277,3 +280,78 @@ fn cleaning_nested_errors_removes_duplication() {
     assert_cleaning_step(&mut iter, "But I am only C", "");
     assert!(iter.next().is_none());
 }
+
+#[test]
+fn into_report_formats_the_whole_chain() {
+    use snafu::IntoReport;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("The disk is full"))]
+    struct DiskError;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("Could not save the file"))]
+    struct SaveError {
+        source: DiskError,
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("Could not finish the export"))]
+    struct ExportError {
+        source: SaveError,
+    }
+
+    let make_error = || ExportSnafu.into_error(SaveSnafu.into_error(DiskError));
+
+    let expected = "Could not finish the export\n\
+                    \n\
+                    Caused by these errors (recent errors listed first):\n  \
+                    1: Could not save the file\n  \
+                    2: The disk is full\n";
+
+    assert_eq!(make_error().into_report().to_string(), expected);
+    assert_eq!(snafu::into_report(make_error()).to_string(), expected);
+}
+
+#[test]
+fn cleaning_keeps_the_display_location() {
+    use snafu::Location;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("This is A: {source}"), display_location)]
+    struct A {
+        source: B,
+        #[snafu(implicit)]
+        location: Location,
+    }
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("But I am only B"), display_location)]
+    struct B {
+        #[snafu(implicit)]
+        location: Location,
+    }
+
+    let a = A {
+        source: B {
+            location: Location::new("b.rs", 3, 4),
+        },
+        location: Location::new("a.rs", 1, 2),
+    };
+    assert_eq!(
+        a.to_string(),
+        "This is A: But I am only B (at b.rs:3:4) (at a.rs:1:2)",
+    );
+
+    let mut iter = CleanedErrorText::new(&a);
+
+    let (_, text, cleaned) = iter.next().expect("Iterator unexpectedly exhausted");
+    assert_eq!(text, "This is A (at a.rs:1:2)");
+    assert!(cleaned);
+
+    assert_cleaning_step(&mut iter, "But I am only B (at b.rs:3:4)", "");
+    assert!(iter.next().is_none());
+
+    let msg = Report::from_error(a).to_string();
+    assert_contains!(needle: "This is A (at a.rs:1:2)", haystack: msg);
+}
@@ -0,0 +1,61 @@
+use snafu::{prelude::*, ErrorCompat, IntoError, Report};
+
+#[derive(Debug, Snafu)]
+#[snafu(display("the disk is full"))]
+struct InnerError;
+
+#[derive(Debug, Snafu)]
+#[snafu(display("could not save the file"))]
+struct MiddleError {
+    source: InnerError,
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("could not finish the request"))]
+struct OuterError {
+    source: MiddleError,
+}
+
+fn nested() -> OuterError {
+    OuterSnafu.into_error(MiddleSnafu.into_error(InnerError))
+}
+
+#[test]
+fn leaf_errors_are_a_single_line() {
+    assert_eq!(
+        ErrorCompat::to_report_string(&InnerError),
+        "the disk is full\n",
+    );
+}
+
+#[test]
+fn the_whole_chain_is_included() {
+    let expected = "could not finish the request\n\
+                    \n\
+                    Caused by these errors (recent errors listed first):\n  \
+                    1: could not save the file\n  \
+                    2: the disk is full\n";
+
+    assert_eq!(ErrorCompat::to_report_string(&nested()), expected);
+}
+
+#[test]
+fn matches_the_report_output() {
+    let error = nested();
+    let report = ErrorCompat::to_report_string(&error);
+
+    assert_eq!(report, Report::from_error(error).to_string());
+}
+
+#[test]
+fn boxed_trait_objects_can_use_the_free_function() {
+    let error: Box<dyn std::error::Error + Send + Sync> = Box::new(nested());
+
+    let expected = "could not finish the request\n\
+                    \n\
+                    Caused by these errors (recent errors listed first):\n  \
+                    1: could not save the file\n  \
+                    2: the disk is full\n";
+
+    assert_eq!(snafu::to_report_string(&*error), expected);
+}
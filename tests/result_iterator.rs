@@ -0,0 +1,86 @@
+use snafu::{prelude::*, ErrorAccumulator};
+
+#[derive(Debug, Snafu)]
+#[snafu(display("{value} is odd"))]
+struct OddError {
+    value: i32,
+}
+
+fn check(value: i32) -> Result<i32, OddError> {
+    ensure!(value % 2 == 0, OddSnafu { value });
+    Ok(value)
+}
+
+#[test]
+fn all_successes_are_collected() {
+    let values = [2, 4, 6].iter().map(|&v| check(v)).collect_all_errors();
+
+    assert_eq!(values.unwrap(), [2, 4, 6]);
+}
+
+#[test]
+fn all_errors_are_collected() {
+    let errors = [1, 2, 3, 4, 5]
+        .iter()
+        .map(|&v| check(v))
+        .collect_all_errors()
+        .unwrap_err();
+
+    let values: Vec<_> = errors.iter().map(|e| e.value).collect();
+    assert_eq!(values, [1, 3, 5]);
+}
+
+#[test]
+fn empty_iterators_are_ok() {
+    let values = std::iter::empty::<Result<i32, OddError>>().collect_all_errors();
+
+    assert_eq!(values.unwrap(), Vec::<i32>::new());
+}
+
+#[test]
+fn errors_can_be_reported_together() {
+    let errors = [1, 3]
+        .iter()
+        .map(|&v| check(v))
+        .collect_all_errors()
+        .unwrap_err();
+
+    let mut accumulator = ErrorAccumulator::new();
+    accumulator.extend(errors);
+    let error = accumulator.finish().unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "2 errors occurred:\n1. 1 is odd\n2. 3 is odd",
+    );
+}
+
+mod collect_errors {
+    use super::*;
+
+    #[test]
+    fn all_successes_are_returned() {
+        let (values, errors) = snafu::collect_errors([2, 4, 6].iter().map(|&v| check(v)));
+
+        assert_eq!(values, [2, 4, 6]);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn successes_and_errors_are_both_returned() {
+        let (values, errors) = snafu::collect_errors([1, 2, 3, 4, 5].iter().map(|&v| check(v)));
+
+        assert_eq!(values, [2, 4]);
+        let errors: Vec<_> = errors.iter().map(|e| e.value).collect();
+        assert_eq!(errors, [1, 3, 5]);
+    }
+
+    #[test]
+    fn all_errors_are_returned() {
+        let (values, errors) = snafu::collect_errors(vec![check(1), check(3)]);
+
+        assert!(values.is_empty());
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages, ["1 is odd", "3 is odd"]);
+    }
+}
@@ -46,7 +46,7 @@ fn implements_thread_safe_error() {
     check_send::<InnerError>();
     check_send::<Error>();
 
-    let t = thread::spawn(move || example());
+    let t = thread::spawn(example);
 
     let v = t.join().expect("Thread panicked");
     v.unwrap_err();
@@ -0,0 +1,119 @@
+use snafu::{prelude::*, Backtrace, ErrorCompat, GenerateImplicitData, SnafuCompat};
+use std::{error::Error as _, fmt};
+
+#[derive(Debug, SnafuCompat)]
+enum LegacyError {
+    Timeout {
+        seconds: u64,
+        backtrace: Backtrace,
+    },
+    Closed(u32, #[snafu(backtrace)] Backtrace),
+    Renamed {
+        #[snafu(backtrace)]
+        trace: Backtrace,
+    },
+    OptedOut {
+        #[snafu(backtrace(false))]
+        backtrace: String,
+    },
+    Unknown,
+}
+
+impl fmt::Display for LegacyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LegacyError::Timeout { seconds, .. } => write!(f, "timed out after {}s", seconds),
+            LegacyError::Closed(code, _) => write!(f, "closed with code {}", code),
+            LegacyError::Renamed { .. } => f.write_str("renamed"),
+            LegacyError::OptedOut { backtrace } => write!(f, "opted out: {}", backtrace),
+            LegacyError::Unknown => f.write_str("unknown"),
+        }
+    }
+}
+
+impl std::error::Error for LegacyError {}
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("Could not talk to the legacy system"))]
+    Legacy {
+        #[snafu(backtrace)]
+        source: LegacyError,
+    },
+}
+
+fn legacy_call(fail: bool) -> Result<u8, LegacyError> {
+    if fail {
+        Err(LegacyError::Timeout {
+            seconds: 5,
+            backtrace: Backtrace::generate(),
+        })
+    } else {
+        Ok(1)
+    }
+}
+
+#[test]
+fn named_backtrace_fields_are_returned() {
+    let error = legacy_call(true).unwrap_err();
+    assert!(ErrorCompat::backtrace(&error).is_some());
+}
+
+#[test]
+fn marked_backtrace_fields_are_returned() {
+    let error = LegacyError::Closed(3, Backtrace::generate());
+    assert!(ErrorCompat::backtrace(&error).is_some());
+
+    let error = LegacyError::Renamed {
+        trace: Backtrace::generate(),
+    };
+    assert!(ErrorCompat::backtrace(&error).is_some());
+}
+
+#[test]
+fn variants_without_backtraces_return_none() {
+    let error = LegacyError::OptedOut {
+        backtrace: "not really".into(),
+    };
+    assert!(ErrorCompat::backtrace(&error).is_none());
+    assert!(ErrorCompat::backtrace(&LegacyError::Unknown).is_none());
+}
+
+#[test]
+fn can_be_used_as_a_source_with_context() {
+    assert_eq!(legacy_call(false).context(LegacySnafu).unwrap(), 1);
+
+    let error = legacy_call(true).context(LegacySnafu).unwrap_err();
+
+    assert_eq!(error.to_string(), "Could not talk to the legacy system");
+    assert_eq!(error.source().unwrap().to_string(), "timed out after 5s");
+    assert!(ErrorCompat::backtrace(&error).is_some());
+}
+
+mod structs {
+    use super::*;
+
+    #[derive(Debug, SnafuCompat)]
+    struct Error<T> {
+        value: T,
+        backtrace: Option<Backtrace>,
+    }
+
+    impl<T> fmt::Display for Error<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("struct error")
+        }
+    }
+
+    impl<T: fmt::Debug> std::error::Error for Error<T> {}
+
+    #[test]
+    fn generic_structs_are_supported() {
+        let error = Error {
+            value: 1,
+            backtrace: None,
+        };
+        assert!(ErrorCompat::backtrace(&error).is_none());
+        assert_eq!(error.value, 1);
+    }
+}
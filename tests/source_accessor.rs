@@ -0,0 +1,108 @@
+use snafu::{prelude::*, IntoError};
+
+#[derive(Debug, Snafu)]
+#[snafu(display("The configuration is malformed"))]
+struct MalformedError;
+
+#[derive(Debug, Snafu)]
+#[snafu(display("The configuration is missing"))]
+struct MissingError;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    OpenConfig {
+        #[snafu(source(from(MalformedError, Box::new), accessor))]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    ReadConfig {
+        #[snafu(source(from(MissingError, |_| MalformedError)), source(accessor))]
+        source: MalformedError,
+    },
+
+    #[snafu(whatever, display("{message}"))]
+    Whatever {
+        message: String,
+        #[snafu(source(from(MalformedError, |e| Some(Box::new(e) as _)), accessor))]
+        source: Option<Box<dyn std::error::Error>>,
+    },
+}
+
+#[test]
+fn accessor_returns_the_original_source() {
+    let e = OpenConfigSnafu.into_error(MalformedError);
+
+    assert!(e.open_config_source().is_some());
+}
+
+#[test]
+fn accessor_returns_none_for_other_variants() {
+    let e = ReadConfigSnafu.into_error(MissingError);
+
+    assert!(e.open_config_source().is_none());
+}
+
+#[test]
+fn accessor_returns_none_when_the_conversion_changed_the_type() {
+    let e = ReadConfigSnafu.into_error(MissingError);
+
+    assert!(e.read_config_source().is_none());
+}
+
+#[test]
+fn accessor_works_with_whatever() {
+    let e: Error = Err::<(), _>(MalformedError)
+        .whatever_context("Oops")
+        .unwrap_err();
+
+    assert!(e.whatever_source().is_some());
+}
+
+mod structs {
+    use snafu::{prelude::*, IntoError};
+
+    #[derive(Debug, Snafu)]
+    #[snafu(display("The disk is full"))]
+    struct DiskFullError;
+
+    #[derive(Debug, Snafu)]
+    struct SaveError {
+        #[snafu(source(from(DiskFullError, Box::new), accessor))]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    }
+
+    #[test]
+    fn accessor_is_named_after_the_struct() {
+        let e = SaveSnafu.into_error(DiskFullError);
+
+        assert!(e.save_error_source().is_some());
+    }
+}
+
+mod visibility {
+    pub mod inner {
+        use snafu::prelude::*;
+
+        #[derive(Debug, Snafu)]
+        #[snafu(display("The disk is full"))]
+        pub struct DiskFullError;
+
+        #[derive(Debug, Snafu)]
+        #[snafu(visibility(pub))]
+        pub enum Error {
+            Saving {
+                #[snafu(source(from(DiskFullError, Box::new), accessor))]
+                source: Box<dyn std::error::Error + Send + Sync>,
+            },
+        }
+    }
+
+    #[test]
+    fn accessor_has_the_context_selector_visibility() {
+        use snafu::IntoError;
+
+        let e = inner::SavingSnafu.into_error(inner::DiskFullError);
+
+        assert!(e.saving_source().is_some());
+    }
+}
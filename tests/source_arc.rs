@@ -0,0 +1,123 @@
+use snafu::{prelude::*, Backtrace, ErrorCompat, IntoError};
+use std::{collections::HashMap, error::Error as _, num::ParseIntError, sync::Arc};
+
+#[derive(Debug, Clone, Snafu)]
+enum Error {
+    #[snafu(display("Could not parse {text:?}"))]
+    Parse {
+        text: String,
+        #[snafu(source(arc))]
+        source: Arc<ParseIntError>,
+        backtrace: Arc<Backtrace>,
+    },
+
+    #[snafu(display("Opaque failure"))]
+    Opaque {
+        #[snafu(source(arc))]
+        source: Arc<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+#[derive(Default)]
+struct Cache {
+    results: HashMap<String, Result<i32, Error>>,
+}
+
+impl Cache {
+    fn parse(&mut self, text: &str) -> Result<i32, Error> {
+        self.results
+            .entry(text.to_owned())
+            .or_insert_with(|| text.parse().context(ParseSnafu { text }))
+            .clone()
+    }
+}
+
+#[test]
+fn cached_errors_are_shared_between_callers() {
+    let mut cache = Cache::default();
+
+    let first = cache.parse("nope").unwrap_err();
+    let second = cache.parse("nope").unwrap_err();
+
+    match (&first, &second) {
+        (
+            Error::Parse {
+                source: a,
+                backtrace: a_bt,
+                ..
+            },
+            Error::Parse {
+                source: b,
+                backtrace: b_bt,
+                ..
+            },
+        ) => {
+            assert!(Arc::ptr_eq(a, b));
+            assert!(Arc::ptr_eq(a_bt, b_bt));
+        }
+        _ => panic!("Unexpected errors {:?} and {:?}", first, second),
+    }
+
+    assert_eq!(first.to_string(), r#"Could not parse "nope""#);
+    assert!(ErrorCompat::backtrace(&second).is_some());
+}
+
+#[test]
+fn source_looks_through_the_arc() {
+    let error = "nope".parse::<i32>().context(ParseSnafu { text: "nope" });
+    let error = error.unwrap_err();
+
+    let source = error.source().expect("Must have a source");
+    assert!(source.is::<ParseIntError>());
+}
+
+#[test]
+fn trait_object_sources_are_accepted_as_boxes() {
+    let original: Box<dyn std::error::Error + Send + Sync> =
+        "nope".parse::<i32>().unwrap_err().into();
+    let error = OpaqueSnafu.into_error(original);
+    let clone = error.clone();
+
+    let source = clone.source().expect("Must have a source");
+    assert!(source.is::<ParseIntError>());
+}
+
+mod optional_backtrace {
+    use super::*;
+
+    #[derive(Debug, Clone, Snafu)]
+    struct Error {
+        #[snafu(source(arc))]
+        source: Arc<ParseIntError>,
+        backtrace: Option<Arc<Backtrace>>,
+    }
+
+    #[test]
+    fn optional_shared_backtraces_can_be_cloned() {
+        let error = "nope".parse::<i32>().context(Snafu).unwrap_err();
+        let clone = error.clone();
+
+        assert_eq!(
+            ErrorCompat::backtrace(&error).is_some(),
+            ErrorCompat::backtrace(&clone).is_some(),
+        );
+    }
+}
+
+mod delegated_backtrace {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    struct Outer {
+        #[snafu(source(arc), backtrace)]
+        source: Arc<Error>,
+    }
+
+    #[test]
+    fn backtraces_can_be_delegated_through_the_arc() {
+        let inner = "nope".parse::<i32>().context(ParseSnafu { text: "nope" });
+        let outer = inner.context(OuterSnafu).unwrap_err();
+
+        assert!(ErrorCompat::backtrace(&outer).is_some());
+    }
+}
@@ -0,0 +1,103 @@
+// Run with `--no-default-features` to check the implementation that
+// is used when `snafu::Error` is SNAFU's own trait.
+
+use snafu::{prelude::*, ErrorCompat, IntoError};
+
+#[derive(Debug, Snafu)]
+#[snafu(display("the plugin registry has {registered} entries"))]
+struct NotRegistered {
+    registered: usize,
+}
+
+static NOT_REGISTERED: NotRegistered = NotRegistered { registered: 0 };
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("could not load {name}"))]
+    Load {
+        name: String,
+        source: &'static dyn snafu::Error,
+    },
+
+    #[snafu(display("could not unload {name}"))]
+    Unload {
+        name: String,
+        source: &'static (dyn snafu::Error + Send + Sync),
+    },
+
+    #[snafu(context(false))]
+    Unknown { source: &'static dyn snafu::Error },
+
+    #[snafu(transparent)]
+    Passthrough {
+        source: &'static (dyn snafu::Error + Send + Sync),
+    },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("the plugin host failed"))]
+struct HostError {
+    source: &'static (dyn snafu::Error + Sync),
+}
+
+fn source_of(error: &dyn snafu::Error) -> &(dyn snafu::Error + 'static) {
+    error.source().expect("Must have a source")
+}
+
+fn assert_is_the_static(source: &(dyn snafu::Error + 'static)) {
+    let source = source
+        .downcast_ref::<NotRegistered>()
+        .expect("Must be the registered error");
+
+    assert!(core::ptr::eq(source, &NOT_REGISTERED));
+}
+
+#[test]
+fn one_static_error_can_be_the_source_of_two_errors() {
+    let load = LoadSnafu { name: "alpha" }.into_error(&NOT_REGISTERED);
+    let unload = UnloadSnafu { name: "beta" }.into_error(&NOT_REGISTERED);
+
+    assert_eq!(load.to_string(), "could not load alpha");
+    assert_eq!(unload.to_string(), "could not unload beta");
+    assert_is_the_static(source_of(&load));
+    assert_is_the_static(source_of(&unload));
+}
+
+#[test]
+fn static_errors_can_be_converted_with_from() {
+    let error = Error::from(&NOT_REGISTERED as &'static dyn snafu::Error);
+
+    assert_is_the_static(source_of(&error));
+}
+
+#[test]
+fn static_errors_can_be_transparent() {
+    let error = Error::from(&NOT_REGISTERED as &'static (dyn snafu::Error + Send + Sync));
+
+    assert_eq!(error.to_string(), "the plugin registry has 0 entries");
+}
+
+#[test]
+fn static_errors_appear_in_the_chain() {
+    let error = HostSnafu.into_error(&NOT_REGISTERED);
+    let chain: Vec<_> = error.iter_chain().map(ToString::to_string).collect();
+
+    assert_eq!(
+        chain,
+        [
+            "the plugin host failed",
+            "the plugin registry has 0 entries"
+        ]
+    );
+}
+
+fn describe<E: snafu::Error>(error: E) -> String {
+    error.to_string()
+}
+
+#[test]
+fn references_to_errors_are_errors() {
+    let error: &'static dyn snafu::Error = &NOT_REGISTERED;
+
+    assert_eq!(describe(error), "the plugin registry has 0 entries");
+}
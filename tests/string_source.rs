@@ -0,0 +1,80 @@
+use snafu::{prelude::*, ChainCompat, StringSource};
+use std::{borrow::Cow, ffi::CStr};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("could not open the device"))]
+    Open {
+        #[snafu(source(from(String, StringSource::new)))]
+        source: StringSource,
+    },
+
+    #[snafu(display("could not close the device"))]
+    Close {
+        #[snafu(source(from(&'static str, StringSource::new)))]
+        source: StringSource,
+    },
+
+    #[snafu(display("could not reset the device"))]
+    Reset { source: StringSource },
+}
+
+// Stands in for an error message returned by a C library.
+fn ffi_open() -> Result<(), String> {
+    let message = CStr::from_bytes_with_nul(b"device busy\0").unwrap();
+    Err(message.to_string_lossy().into_owned())
+}
+
+fn ffi_close() -> Result<(), &'static str> {
+    Err("device gone")
+}
+
+#[test]
+fn an_ffi_message_is_a_link_in_the_chain() {
+    let error = ffi_open().context(OpenSnafu).unwrap_err();
+
+    let messages: Vec<_> = ChainCompat::new(&error).map(ToString::to_string).collect();
+    assert_eq!(messages, ["could not open the device", "device busy"]);
+}
+
+#[test]
+fn the_message_is_not_part_of_the_display() {
+    let error = ffi_open().context(OpenSnafu).unwrap_err();
+
+    assert_eq!(error.to_string(), "could not open the device");
+}
+
+#[test]
+fn borrowed_messages_can_be_sources() {
+    let error = ffi_close().context(CloseSnafu).unwrap_err();
+
+    let source = std::error::Error::source(&error).unwrap();
+    assert_eq!(source.to_string(), "device gone");
+}
+
+#[test]
+fn messages_can_be_converted_explicitly() {
+    let error = Err::<(), _>(StringSource::from("no power"))
+        .context(ResetSnafu)
+        .unwrap_err();
+
+    let source = std::error::Error::source(&error).unwrap();
+    assert_eq!(source.to_string(), "no power");
+}
+
+#[test]
+fn borrowed_messages_are_not_copied() {
+    let source = StringSource::new("static text");
+
+    assert!(matches!(source.into_inner(), Cow::Borrowed("static text")));
+}
+
+#[test]
+fn can_be_boxed_as_a_trait_object() {
+    let boxed: Box<dyn std::error::Error + Send + Sync> =
+        Box::new(StringSource::new(String::from("boxed")));
+    let converted: Box<dyn std::error::Error + Send + Sync> = StringSource::new("converted").into();
+
+    assert_eq!(boxed.to_string(), "boxed");
+    assert_eq!(converted.to_string(), "converted");
+}
@@ -294,3 +294,149 @@ mod send_and_sync {
         assert!(outer().is_err());
     }
 }
+
+mod converted_target {
+    use snafu::{prelude::*, Whatever};
+
+    #[derive(Debug, Snafu)]
+    struct UnderlyingError;
+
+    fn underlying(success: bool) -> Result<i32, UnderlyingError> {
+        ensure!(success, UnderlyingSnafu);
+        Ok(1)
+    }
+
+    #[derive(Debug, Snafu)]
+    enum LayerError {
+        #[snafu(display("Layer {id} is broken"))]
+        Broken { id: u8 },
+
+        #[snafu(context(false))]
+        Other { source: Whatever },
+    }
+
+    #[test]
+    fn plain_whatever_return_is_unchanged() {
+        fn exercise(success: bool) -> Result<i32, Whatever> {
+            if !success {
+                whatever!("This is a code {} error", 42);
+            }
+            Ok(1)
+        }
+
+        assert!(matches!(exercise(true), Ok(1)));
+        let e = exercise(false).unwrap_err();
+        assert_eq!(e.to_string(), "This is a code 42 error");
+    }
+
+    #[test]
+    fn explicit_target_is_converted_into_the_return_type() {
+        fn exercise(success: bool) -> Result<i32, LayerError> {
+            if !success {
+                whatever!(as Whatever, "This is a code {} error", 42);
+            }
+            BrokenSnafu { id: 0 }.fail()
+        }
+
+        assert!(matches!(exercise(true), Err(LayerError::Broken { id: 0 })));
+        let e = exercise(false).unwrap_err();
+        match e {
+            LayerError::Other { source } => {
+                assert_eq!(source.to_string(), "This is a code 42 error")
+            }
+            _ => panic!("Wrong variant: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn explicit_target_with_source_is_converted_into_the_return_type() {
+        fn exercise(success: bool) -> Result<i32, LayerError> {
+            let v = whatever!(as Whatever, underlying(success), "Something else happened {}", 42);
+            Ok(v + 1)
+        }
+
+        assert!(matches!(exercise(true), Ok(2)));
+        let e = exercise(false).unwrap_err();
+        match e {
+            LayerError::Other { source } => {
+                assert_eq!(source.to_string(), "Something else happened 42");
+                assert!(std::error::Error::source(&source).is_some());
+            }
+            _ => panic!("Wrong variant: {:?}", e),
+        }
+    }
+}
+
+mod extra_fields {
+    use snafu::prelude::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(whatever, display("{message} (exit code {exit_code})"))]
+    struct CliError {
+        message: String,
+        #[snafu(default = 1)]
+        exit_code: i32,
+        attempts: u32,
+        #[snafu(source(from(Box<dyn std::error::Error>, Some)))]
+        source: Option<Box<dyn std::error::Error>>,
+    }
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(whatever, display("{message}"))]
+        Whatever {
+            message: String,
+            #[snafu(default = String::from("unknown"))]
+            component: String,
+        },
+    }
+
+    #[test]
+    fn macro_fills_extra_fields_with_defaults() {
+        fn exercise() -> Result<(), CliError> {
+            whatever!("could not start");
+        }
+
+        let e = exercise().unwrap_err();
+        assert_eq!(e.exit_code, 1);
+        assert_eq!(e.attempts, 0);
+        assert_eq!(e.to_string(), "could not start (exit code 1)");
+    }
+
+    #[test]
+    fn macro_with_source_fills_extra_fields_with_defaults() {
+        fn exercise() -> Result<(), CliError> {
+            let r: Result<(), std::io::Error> = Err(std::io::ErrorKind::NotFound.into());
+            whatever!(r, "could not read config");
+            Ok(())
+        }
+
+        let e = exercise().unwrap_err();
+        assert_eq!(e.exit_code, 1);
+        assert!(std::error::Error::source(&e).is_some());
+    }
+
+    #[test]
+    fn extra_fields_can_be_set_when_constructed_directly() {
+        let e = CliError {
+            message: "usage error".into(),
+            exit_code: 2,
+            attempts: 3,
+            source: None,
+        };
+
+        assert_eq!(e.exit_code, 2);
+        assert_eq!(e.to_string(), "usage error (exit code 2)");
+    }
+
+    #[test]
+    fn enum_variants_fill_extra_fields_with_defaults() {
+        fn exercise() -> Result<(), Error> {
+            whatever!("oops");
+        }
+
+        match exercise().unwrap_err() {
+            Error::Whatever { component, .. } => assert_eq!(component, "unknown"),
+        }
+    }
+}
@@ -77,3 +77,27 @@ fn can_set_module_visibility_pub_with_custom_name() {
 fn can_set_module_visibility_pub_crate_with_custom_name() {
     let _ = inner::custom_pub_crate::PubCrateWithCustomModSnafu.build();
 }
+
+mod relative {
+    mod child {
+        use snafu::Snafu;
+
+        #[derive(Debug, Snafu)]
+        #[snafu(module, visibility(pub(super)))]
+        pub(super) struct PubSuperError;
+
+        #[derive(Debug, Snafu)]
+        #[snafu(module(in_self), visibility(pub(in self)))]
+        pub(super) struct PubInSelfError;
+
+        #[test]
+        fn self_is_relative_to_the_error() {
+            let _ = in_self::PubInSelfSnafu.build();
+        }
+    }
+
+    #[test]
+    fn super_is_relative_to_the_error() {
+        let _ = child::pub_super_error::PubSuperSnafu.build();
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "backtraces-symbolized")]
+
+use snafu::{backtrace::SymbolizedBacktrace, prelude::*};
+
+#[derive(Debug, Snafu)]
+enum Error {
+    Failed {
+        #[snafu(implicit)]
+        trace: SymbolizedBacktrace,
+    },
+}
+
+fn example() -> Result<(), Error> {
+    FailedSnafu.fail()
+}
+
+#[test]
+fn symbols_are_resolved_when_displayed() {
+    let Error::Failed { trace } = example().unwrap_err();
+    assert!(!trace.is_resolved());
+
+    let displayed = trace.to_string();
+    assert!(trace.is_resolved());
+    assert!(!displayed.is_empty());
+
+    // Displaying again reuses the resolved symbols
+    assert_eq!(trace.to_string(), displayed);
+}
+
+#[test]
+fn resolving_can_be_requested_early() {
+    let trace = SymbolizedBacktrace::capture();
+    assert!(!trace.is_resolved());
+
+    trace.resolve();
+    assert!(trace.is_resolved());
+}
+
+#[test]
+fn debug_does_not_resolve() {
+    let trace = SymbolizedBacktrace::capture();
+
+    assert_eq!(
+        format!("{:?}", trace),
+        "SymbolizedBacktrace { resolved: false, .. }"
+    );
+    assert!(!trace.is_resolved());
+}
+
+#[cfg(not(feature = "backtraces-impl-backtrace-crate"))]
+#[test]
+fn frames_are_not_available_from_other_implementations() {
+    use snafu::{Backtrace, ErrorCompat};
+
+    #[derive(Debug, Snafu)]
+    struct WithBacktrace {
+        backtrace: Backtrace,
+    }
+
+    let error = WithBacktraceSnafu.build();
+    let backtrace = ErrorCompat::backtrace(&error).unwrap();
+
+    assert!(snafu::backtrace::frames(backtrace).is_none());
+    assert!(snafu::backtrace::to_backtrace_crate(backtrace).is_none());
+}
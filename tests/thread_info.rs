@@ -0,0 +1,51 @@
+use snafu::{prelude::*, ThreadInfo};
+use std::thread;
+
+#[derive(Debug, Snafu)]
+enum Error {
+    #[snafu(display("{thread}"))]
+    Worker {
+        #[snafu(implicit)]
+        thread: ThreadInfo,
+    },
+}
+
+fn build_on_named_thread(name: &str) -> (Error, thread::ThreadId) {
+    thread::Builder::new()
+        .name(name.into())
+        .spawn(|| (WorkerSnafu.build(), thread::current().id()))
+        .expect("Could not spawn the thread")
+        .join()
+        .expect("The thread panicked")
+}
+
+#[test]
+fn each_error_records_the_thread_it_was_created_on() {
+    let (alpha, alpha_id) = build_on_named_thread("alpha");
+    let (beta, beta_id) = build_on_named_thread("beta");
+
+    let Error::Worker { thread: alpha } = alpha;
+    let Error::Worker { thread: beta } = beta;
+
+    assert_eq!(alpha.name(), Some("alpha"));
+    assert_eq!(alpha.id(), alpha_id);
+    assert_eq!(beta.name(), Some("beta"));
+    assert_eq!(beta.id(), beta_id);
+    assert_ne!(alpha, beta);
+}
+
+#[test]
+fn display_shows_the_name_and_id() {
+    let (error, id) = build_on_named_thread("worker-3");
+
+    assert_eq!(error.to_string(), format!("thread: worker-3 ({id:?})"));
+}
+
+#[test]
+fn unnamed_threads_are_displayed_as_unnamed() {
+    let (error, id) = thread::spawn(|| (WorkerSnafu.build(), thread::current().id()))
+        .join()
+        .expect("The thread panicked");
+
+    assert_eq!(error.to_string(), format!("thread: <unnamed> ({id:?})"));
+}
@@ -0,0 +1,78 @@
+use snafu::{prelude::*, ErrorCompat, TypedWhatever};
+use std::{error::Error as _, io};
+
+fn not_found() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no such file")
+}
+
+#[test]
+fn whatever_context_stores_the_concrete_source() {
+    fn inner() -> Result<(), TypedWhatever<io::Error>> {
+        Err(not_found()).whatever_context("Could not open the file")?;
+        Ok(())
+    }
+
+    let error = inner().unwrap_err();
+    assert_eq!(error.to_string(), "Could not open the file");
+
+    let source: &io::Error = error.typed_source().expect("Must have a source");
+    assert_eq!(source.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn error_source_returns_the_concrete_source() {
+    fn inner() -> Result<(), TypedWhatever<io::Error>> {
+        Err(not_found()).with_whatever_context(|e| format!("Failed with {}", e.kind()))
+    }
+
+    let error = inner().unwrap_err();
+    let source = error.source().expect("Must have a source");
+
+    assert!(source.is::<io::Error>());
+    assert_eq!(source.to_string(), "no such file");
+}
+
+#[test]
+fn works_with_whatever_macro() {
+    fn without_source() -> Result<(), TypedWhatever<io::Error>> {
+        whatever!("Something went wrong: {}", 42)
+    }
+
+    fn with_source() -> Result<(), TypedWhatever<io::Error>> {
+        whatever!(Err(not_found()), "Could not read");
+        Ok(())
+    }
+
+    let error = without_source().unwrap_err();
+    assert_eq!(error.message(), "Something went wrong: 42");
+    assert!(error.typed_source().is_none());
+
+    let error = with_source().unwrap_err();
+    assert_eq!(error.message(), "Could not read");
+    assert!(error.typed_source().is_some());
+}
+
+#[test]
+fn into_parts_returns_the_owned_source() {
+    fn inner() -> Result<(), TypedWhatever<io::Error>> {
+        Err(not_found()).whatever_context("Could not open the file")
+    }
+
+    let (message, source, _backtrace, _location) = inner().unwrap_err().into_parts();
+
+    assert_eq!(message, "Could not open the file");
+    assert_eq!(source.unwrap().kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn location_and_backtrace_are_recorded() {
+    let expected_line = line!() + 2;
+    fn inner() -> Result<(), TypedWhatever<io::Error>> {
+        whatever!("Something went wrong")
+    }
+
+    let error = inner().unwrap_err();
+    assert_eq!(error.location().line, expected_line);
+    assert!(ErrorCompat::backtrace(&error).is_some());
+    assert!(format!("{:?}", error).starts_with("TypedWhatever { source: None"));
+}
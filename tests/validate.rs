@@ -0,0 +1,104 @@
+use snafu::{prelude::*, IntoError};
+use std::path::{Path, PathBuf};
+
+fn not_empty(f: &Path) -> bool {
+    !f.as_os_str().is_empty()
+}
+
+mod enums {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    enum Error {
+        #[snafu(validate(field = filename, check = not_empty))]
+        Leaf { filename: PathBuf },
+
+        #[snafu(validate(
+            field = filename,
+            check = |f: &PathBuf| !f.as_os_str().is_empty(),
+            msg = "filename must not be empty"
+        ))]
+        #[snafu(validate(field = attempt, check = |a: &u8| *a < 10))]
+        WithSource {
+            filename: PathBuf,
+            attempt: u8,
+            source: std::io::Error,
+        },
+    }
+
+    fn io_error() -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::Other, "oops")
+    }
+
+    #[test]
+    fn valid_fields_build_the_error() {
+        let error = LeafSnafu { filename: "a.txt" }.build();
+
+        assert!(matches!(error, Error::Leaf { filename } if filename == Path::new("a.txt")));
+    }
+
+    #[test]
+    #[should_panic(expected = "validation of `filename` failed")]
+    fn invalid_fields_panic_when_building() {
+        let _ = LeafSnafu { filename: "" }.build();
+    }
+
+    #[test]
+    fn valid_fields_create_the_error_with_a_source() {
+        let error = WithSourceSnafu {
+            filename: "a.txt",
+            attempt: 3,
+        }
+        .into_error(io_error());
+
+        assert!(matches!(error, Error::WithSource { attempt: 3, .. }));
+    }
+
+    #[test]
+    #[should_panic(expected = "filename must not be empty")]
+    fn invalid_fields_panic_with_the_custom_message() {
+        let _ = WithSourceSnafu {
+            filename: "",
+            attempt: 3,
+        }
+        .into_error(io_error());
+    }
+
+    #[test]
+    #[should_panic(expected = "validation of `attempt` failed")]
+    fn every_check_is_run() {
+        let _ = WithSourceSnafu {
+            filename: "a.txt",
+            attempt: 10,
+        }
+        .into_error(io_error());
+    }
+}
+
+mod structs {
+    use super::*;
+
+    #[derive(Debug, Snafu)]
+    #[snafu(validate(field = filename, check = not_empty, msg = "empty filename"))]
+    struct Error {
+        filename: PathBuf,
+        source: std::io::Error,
+    }
+
+    fn open(filename: &str) -> Result<std::fs::File, Error> {
+        std::fs::File::open(filename).context(Snafu { filename })
+    }
+
+    #[test]
+    fn valid_fields_are_used_with_context() {
+        let error = open("/this/does/not/exist").unwrap_err();
+
+        assert_eq!(error.filename, PathBuf::from("/this/does/not/exist"));
+    }
+
+    #[test]
+    #[should_panic(expected = "empty filename")]
+    fn invalid_fields_panic_with_context() {
+        let _ = open("");
+    }
+}
@@ -38,3 +38,20 @@ mod outer {
 fn can_set_default_visibility() {
     let _ = self::outer::inner::PubCrateSnafu { id: 42 }.build();
 }
+
+mod relative {
+    mod child {
+        use snafu::prelude::*;
+
+        #[derive(Debug, Snafu)]
+        #[snafu(visibility(pub(super)))]
+        pub(super) enum Error {
+            PubSuper,
+        }
+    }
+
+    #[test]
+    fn can_set_visibility_relative_to_the_error() {
+        let _ = child::PubSuperSnafu.build();
+    }
+}
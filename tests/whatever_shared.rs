@@ -0,0 +1,103 @@
+use snafu::{prelude::*, ErrorCompat, Whatever, WhateverShared};
+use std::{
+    error::Error as _,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+fn assert_send_sync_clone<T: Send + Sync + Clone + 'static>() {}
+
+#[test]
+fn is_send_sync_and_clone() {
+    assert_send_sync_clone::<WhateverShared>();
+}
+
+#[test]
+fn works_with_whatever_macro() {
+    fn inner() -> Result<(), WhateverShared> {
+        whatever!("Something went wrong: {}", 42)
+    }
+
+    let error = inner().unwrap_err();
+    assert_eq!(error.to_string(), "Something went wrong: 42");
+    assert_eq!(error.message(), "Something went wrong: 42");
+    assert!(error.source().is_none());
+}
+
+#[test]
+fn works_with_whatever_context() {
+    fn inner() -> Result<i32, WhateverShared> {
+        let value = "x".parse::<i32>().whatever_context("Could not parse")?;
+        Ok(value)
+    }
+
+    let error = inner().unwrap_err();
+    assert_eq!(error.to_string(), "Could not parse");
+
+    let source = error.source().expect("Must have a source");
+    assert!(source.is::<std::num::ParseIntError>());
+}
+
+#[test]
+fn clones_share_the_same_data() {
+    fn inner() -> Result<(), WhateverShared> {
+        whatever!("Shared")
+    }
+
+    let error = inner().unwrap_err();
+    let clone = error.clone();
+
+    assert_eq!(error.message().as_ptr(), clone.message().as_ptr());
+    assert_eq!(
+        ErrorCompat::backtrace(&error).map(|b| b as *const _),
+        ErrorCompat::backtrace(&clone).map(|b| b as *const _),
+    );
+}
+
+#[test]
+fn can_be_created_from_whatever() {
+    fn inner() -> Result<(), Whatever> {
+        let _: i32 = "x".parse().whatever_context("Outer")?;
+        Ok(())
+    }
+
+    let error = WhateverShared::from_whatever(inner().unwrap_err());
+
+    assert_eq!(error.to_string(), "Outer");
+    assert!(ErrorCompat::backtrace(&error).is_some());
+
+    let source = error.source().expect("Must have a source");
+    assert_eq!(source.to_string(), "invalid digit found in string");
+}
+
+#[test]
+fn satisfies_multiple_waiters_of_a_deduplicated_operation() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let cached = Arc::new(Mutex::new(None::<Result<u8, WhateverShared>>));
+
+    let fetch = {
+        let runs = Arc::clone(&runs);
+        move || -> Result<u8, WhateverShared> {
+            runs.fetch_add(1, Ordering::SeqCst);
+            whatever!("The upstream service is down")
+        }
+    };
+
+    let waiters: Vec<_> = (0..5)
+        .map(|_| {
+            let cached = Arc::clone(&cached);
+            let fetch = fetch.clone();
+            thread::spawn(move || cached.lock().unwrap().get_or_insert_with(fetch).clone())
+        })
+        .collect();
+
+    for waiter in waiters {
+        let error = waiter.join().unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "The upstream service is down");
+    }
+
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,79 @@
+use snafu::{prelude::*, IntoError};
+
+#[derive(Debug, Snafu)]
+enum InnerError {
+    Alpha,
+}
+
+#[derive(Debug, Snafu)]
+enum OuterError {
+    #[snafu(display("Could not process user {user_id}"))]
+    Beta { user_id: i32, source: InnerError },
+}
+
+trait Processor {
+    type Error;
+
+    fn process(&self) -> Result<i32, Self::Error>;
+}
+
+struct Failing;
+
+impl Processor for Failing {
+    type Error = InnerError;
+
+    fn process(&self) -> Result<i32, Self::Error> {
+        AlphaSnafu.fail()
+    }
+}
+
+struct Succeeding;
+
+impl Processor for Succeeding {
+    type Error = InnerError;
+
+    fn process(&self) -> Result<i32, Self::Error> {
+        Ok(42)
+    }
+}
+
+fn generic_process<P, E>(processor: &P, user_id: i32) -> Result<i32, E>
+where
+    P: Processor,
+    BetaSnafu<i32>: IntoError<E, Source = P::Error>,
+    E: snafu::Error + snafu::ErrorCompat,
+{
+    with_context!(processor.process(), || BetaSnafu { user_id })
+}
+
+#[test]
+fn applies_the_context_on_error() {
+    let error: OuterError = generic_process(&Failing, 7).unwrap_err();
+
+    assert!(matches!(
+        error,
+        OuterError::Beta {
+            user_id: 7,
+            source: InnerError::Alpha,
+        }
+    ));
+    assert_eq!(error.to_string(), "Could not process user 7");
+}
+
+#[test]
+fn does_not_call_the_closure_on_success() {
+    let result: Result<i32, OuterError> =
+        with_context!(Succeeding.process(), || -> BetaSnafu<i32> {
+            panic!("This block will not be evaluated")
+        });
+
+    assert_eq!(result.unwrap(), 42);
+}
+
+#[test]
+fn accepts_trailing_commas() {
+    let result: Result<i32, OuterError> =
+        with_context!(Failing.process(), || BetaSnafu { user_id: 1 },);
+
+    result.unwrap_err();
+}